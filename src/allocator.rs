@@ -0,0 +1,393 @@
+//! Allocators for the entries of the chunks of a `Slab`.
+
+use crate::TryReserveError;
+use core::alloc::Layout;
+use core::cell::Cell;
+use core::mem::{ManuallyDrop, MaybeUninit};
+use core::ptr::{self, NonNull};
+
+/// An allocator for the arrays of entries of the chunks of a
+/// [`Slab`](struct.Slab.html).
+///
+/// Only the entries go through the allocator, so chunks can be carved from an
+/// arena, a bump allocator, or memory pinned to a NUMA node. The bookkeeping
+/// of the slab still lives on the global heap.
+///
+/// With the `allocator-api2` feature this is implemented for every
+/// `allocator_api2::alloc::Allocator` which is `Clone`.
+///
+/// # Safety
+///
+/// Memory returned by `allocate` must be valid for `layout` and must stay
+/// valid until it is passed to `deallocate`. Every clone of an allocator must
+/// be able to deallocate memory allocated by any other clone.
+///
+/// # Examples
+///
+/// ```
+/// # use pinned_slab::*;
+/// use std::alloc::Layout;
+/// use std::cell::Cell;
+/// use std::ptr::NonNull;
+/// use std::rc::Rc;
+///
+/// #[derive(Clone, Default)]
+/// struct Counting(Rc<Cell<usize>>);
+///
+/// unsafe impl ChunkAllocator for Counting {
+///     fn allocate(&self, layout: Layout) -> Option<NonNull<u8>> {
+///         self.0.set(self.0.get() + 1);
+///         Global.allocate(layout)
+///     }
+///
+///     unsafe fn deallocate(&self, ptr: NonNull<u8>, layout: Layout) {
+///         self.0.set(self.0.get() - 1);
+///         Global.deallocate(ptr, layout)
+///     }
+/// }
+///
+/// let counting = Counting::default();
+/// let mut slab = Slab::new_in(counting.clone());
+///
+/// slab.insert(1);
+/// assert_eq!(counting.0.get(), 1);
+///
+/// drop(slab);
+/// assert_eq!(counting.0.get(), 0);
+/// ```
+pub unsafe trait ChunkAllocator: Clone {
+    /// Allocate memory for `layout`, which is never zero-sized. Return `None`
+    /// if the allocation failed.
+    fn allocate(&self, layout: Layout) -> Option<NonNull<u8>>;
+
+    /// Deallocate the memory at `ptr`.
+    ///
+    /// # Safety
+    ///
+    /// `ptr` must have been returned by `allocate` on this allocator or one of
+    /// its clones, with the same `layout`.
+    unsafe fn deallocate(&self, ptr: NonNull<u8>, layout: Layout);
+}
+
+/// The global memory allocator, which is used by default.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Global;
+
+unsafe impl ChunkAllocator for Global {
+    fn allocate(&self, layout: Layout) -> Option<NonNull<u8>> {
+        // SAFETY: Callers never ask for zero-sized layouts.
+        NonNull::new(unsafe { alloc::alloc::alloc(layout) })
+    }
+
+    unsafe fn deallocate(&self, ptr: NonNull<u8>, layout: Layout) {
+        alloc::alloc::dealloc(ptr.as_ptr(), layout)
+    }
+}
+
+#[cfg(feature = "allocator-api2")]
+unsafe impl<A: allocator_api2::alloc::Allocator + Clone> ChunkAllocator for A {
+    fn allocate(&self, layout: Layout) -> Option<NonNull<u8>> {
+        allocator_api2::alloc::Allocator::allocate(self, layout)
+            .ok()
+            .map(NonNull::cast)
+    }
+
+    unsafe fn deallocate(&self, ptr: NonNull<u8>, layout: Layout) {
+        allocator_api2::alloc::Allocator::deallocate(self, ptr, layout)
+    }
+}
+
+/// The size of a page, which `PageAligned` aligns chunks to by default.
+pub const PAGE_SIZE: usize = 4096;
+
+/// The size of a transparent huge page on x86-64 and aarch64 Linux.
+pub const HUGE_PAGE_SIZE: usize = 2 * 1024 * 1024;
+
+/// A [`ChunkAllocator`](trait.ChunkAllocator.html) which aligns every chunk
+/// to a page boundary and rounds its size up to whole pages, so that no two
+/// chunks share a page.
+///
+/// This reduces TLB pressure for large slabs, since each chunk covers as few
+/// pages as possible. With [`huge`](#method.huge), chunks are aligned to
+/// 2 MB and, on Linux, the kernel is advised to back them with transparent
+/// huge pages. Use a chunk size which fills whole pages, or the rest of each
+/// page is wasted.
+///
+/// # Examples
+///
+/// ```
+/// # use pinned_slab::*;
+/// let mut slab = Slab::new_in(PageAligned::new());
+///
+/// let (_, value) = slab.insert(0u64);
+/// assert_eq!(value as *const u64 as usize % PAGE_SIZE, 0);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PageAligned<A: ChunkAllocator = Global> {
+    align: usize,
+    inner: A,
+}
+
+impl PageAligned {
+    /// Align chunks to [`PAGE_SIZE`](constant.PAGE_SIZE.html) with the global
+    /// allocator.
+    pub fn new() -> Self {
+        PageAligned::with_alignment(PAGE_SIZE, Global)
+    }
+
+    /// Align chunks to [`HUGE_PAGE_SIZE`](constant.HUGE_PAGE_SIZE.html) with
+    /// the global allocator, and advise the kernel to use huge pages for them
+    /// where that is supported.
+    pub fn huge() -> Self {
+        PageAligned::with_alignment(HUGE_PAGE_SIZE, Global)
+    }
+}
+
+impl Default for PageAligned {
+    fn default() -> Self {
+        PageAligned::new()
+    }
+}
+
+impl<A: ChunkAllocator> PageAligned<A> {
+    /// Align chunks allocated with `inner` to `align` bytes.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `align` is not a power of two.
+    pub fn with_alignment(align: usize, inner: A) -> Self {
+        assert!(
+            align.is_power_of_two(),
+            "the alignment must be a power of two"
+        );
+        PageAligned { align, inner }
+    }
+
+    /// Return the alignment of the chunks.
+    pub fn alignment(&self) -> usize {
+        self.align
+    }
+
+    /// Return the padded layout which is passed on to the inner allocator.
+    fn pad(&self, layout: Layout) -> Option<Layout> {
+        let layout = layout.align_to(self.align).ok()?;
+        Some(layout.pad_to_align())
+    }
+}
+
+unsafe impl<A: ChunkAllocator> ChunkAllocator for PageAligned<A> {
+    fn allocate(&self, layout: Layout) -> Option<NonNull<u8>> {
+        let layout = self.pad(layout)?;
+        let ptr = self.inner.allocate(layout)?;
+        if self.align >= HUGE_PAGE_SIZE {
+            advise_huge_pages(ptr, layout.size());
+        }
+        Some(ptr)
+    }
+
+    unsafe fn deallocate(&self, ptr: NonNull<u8>, layout: Layout) {
+        // `allocate` succeeded with the same layout, so padding it succeeds too.
+        let layout = self.pad(layout).unwrap();
+        self.inner.deallocate(ptr, layout)
+    }
+}
+
+/// Advise the kernel to back `len` bytes at `ptr` with transparent huge pages.
+/// This is only a hint, so errors are ignored.
+#[cfg(target_os = "linux")]
+fn advise_huge_pages(ptr: NonNull<u8>, len: usize) {
+    const MADV_HUGEPAGE: i32 = 14;
+
+    extern "C" {
+        fn madvise(addr: *mut u8, len: usize, advice: i32) -> i32;
+    }
+
+    // SAFETY: The range was just allocated, and `ptr` is page aligned.
+    unsafe { madvise(ptr.as_ptr(), len, MADV_HUGEPAGE) };
+}
+
+#[cfg(not(target_os = "linux"))]
+fn advise_huge_pages(_ptr: NonNull<u8>, _len: usize) {}
+
+/// A fixed region of memory which chunks are carved out of, for entries which
+/// must live in memory reserved by the caller, e.g. for DMA.
+///
+/// `&Region` is a [`ChunkAllocator`](trait.ChunkAllocator.html) which never
+/// touches the heap for the entries. Chunks are allocated one after another,
+/// and the region is reused once every chunk has been freed. The bookkeeping
+/// of the slab is still allocated on the global heap.
+///
+/// # Examples
+///
+/// ```
+/// # use pinned_slab::*;
+/// use std::mem::MaybeUninit;
+///
+/// let memory = Box::leak(Box::new([MaybeUninit::uninit(); 4096]));
+/// let region = Region::new(memory);
+/// let mut slab = Slab::<u64, 64, _>::with_growth_policy_in(GrowthPolicy::Fixed, &region);
+///
+/// for i in 0..512 {
+///     slab.insert(i);
+/// }
+/// assert_eq!(region.remaining(), 0);
+/// assert_eq!(slab.try_insert(512).err(), Some(512));
+///
+/// drop(slab);
+/// assert_eq!(region.remaining(), 4096);
+/// ```
+#[derive(Debug)]
+pub struct Region {
+    start: NonNull<u8>,
+    len: usize,
+    /// The number of bytes from `start` which may be in use.
+    used: Cell<usize>,
+    /// The number of allocations which haven't been freed yet.
+    live: Cell<usize>,
+}
+
+impl Region {
+    /// Construct a region from memory which lives forever.
+    pub fn new(memory: &'static mut [MaybeUninit<u8>]) -> Self {
+        let len = memory.len();
+        let start = NonNull::new(memory.as_mut_ptr().cast()).unwrap();
+        // SAFETY: The memory is borrowed mutably forever.
+        unsafe { Region::from_raw_parts(start, len) }
+    }
+
+    /// Construct a region of the `len` bytes at `start`.
+    ///
+    /// # Safety
+    ///
+    /// The memory must be valid for reads and writes of `len` bytes and must
+    /// not be accessed through any other pointer until the region and every
+    /// slab allocating from it have been dropped.
+    pub unsafe fn from_raw_parts(start: NonNull<u8>, len: usize) -> Self {
+        Region {
+            start,
+            len,
+            used: Cell::new(0),
+            live: Cell::new(0),
+        }
+    }
+
+    /// Return the size of the region in bytes.
+    pub fn capacity(&self) -> usize {
+        self.len
+    }
+
+    /// Return the number of bytes at the end of the region which haven't been
+    /// handed out yet.
+    pub fn remaining(&self) -> usize {
+        self.len - self.used.get()
+    }
+}
+
+unsafe impl ChunkAllocator for &Region {
+    fn allocate(&self, layout: Layout) -> Option<NonNull<u8>> {
+        let base = self.start.as_ptr() as usize;
+        let addr = base.checked_add(self.used.get())?;
+        let addr = addr.checked_add(layout.align() - 1)? & !(layout.align() - 1);
+        let used = addr - base + layout.size();
+        if used > self.len {
+            return None;
+        }
+
+        self.used.set(used);
+        self.live.set(self.live.get() + 1);
+        // SAFETY: `addr` lies within the region, which doesn't start at null.
+        Some(unsafe { NonNull::new_unchecked(self.start.as_ptr().add(addr - base)) })
+    }
+
+    unsafe fn deallocate(&self, ptr: NonNull<u8>, layout: Layout) {
+        self.live.set(self.live.get() - 1);
+        let offset = ptr.as_ptr() as usize - self.start.as_ptr() as usize;
+        if self.live.get() == 0 {
+            self.used.set(0);
+        } else if offset + layout.size() == self.used.get() {
+            self.used.set(offset);
+        }
+    }
+}
+
+/// An array of `MaybeUninit<T>` allocated with `A`. The array never moves,
+/// and the values in it are never dropped.
+pub(crate) struct RawArray<T, A: ChunkAllocator> {
+    ptr: NonNull<[MaybeUninit<T>]>,
+    alloc: A,
+}
+
+// SAFETY: `RawArray` owns its values like a `Box<[T], A>` would.
+unsafe impl<T: Send, A: ChunkAllocator + Send> Send for RawArray<T, A> {}
+unsafe impl<T: Sync, A: ChunkAllocator + Sync> Sync for RawArray<T, A> {}
+
+impl<T, A: ChunkAllocator> RawArray<T, A> {
+    pub fn try_new(len: usize, alloc: A) -> Result<Self, TryReserveError> {
+        let layout = Layout::array::<T>(len).map_err(|_| TryReserveError::CapacityOverflow)?;
+        let ptr = if layout.size() == 0 {
+            NonNull::dangling()
+        } else {
+            alloc
+                .allocate(layout)
+                .ok_or(TryReserveError::AllocError { layout })?
+                .cast()
+        };
+
+        Ok(RawArray {
+            ptr: NonNull::slice_from_raw_parts(ptr, len),
+            alloc,
+        })
+    }
+
+    /// Take the array apart into a pointer to its first entry, its length
+    /// and its allocator, without freeing it.
+    pub fn into_raw_parts(self) -> (NonNull<MaybeUninit<T>>, usize, A) {
+        let this = ManuallyDrop::new(self);
+        // SAFETY: `this` is never used or dropped again.
+        let alloc = unsafe { ptr::read(&this.alloc) };
+        (this.ptr.cast(), this.ptr.len(), alloc)
+    }
+
+    /// Reassemble an array taken apart with `into_raw_parts`.
+    ///
+    /// `ptr` must point to `len` entries allocated with `alloc` and
+    /// `Layout::array::<T>(len)`, or be dangling if that layout is empty.
+    pub unsafe fn from_raw_parts(ptr: NonNull<MaybeUninit<T>>, len: usize, alloc: A) -> Self {
+        RawArray {
+            ptr: NonNull::slice_from_raw_parts(ptr, len),
+            alloc,
+        }
+    }
+
+    pub fn allocator(&self) -> &A {
+        &self.alloc
+    }
+
+    pub fn as_slice(&self) -> &[MaybeUninit<T>] {
+        // SAFETY: The array is owned, and `MaybeUninit` doesn't need to be
+        // initialized.
+        unsafe { self.ptr.as_ref() }
+    }
+
+    /// Return a pointer to the first entry, which can be written through
+    /// without borrowing the other entries.
+    pub fn as_mut_ptr(&self) -> *mut MaybeUninit<T> {
+        self.ptr.cast().as_ptr()
+    }
+
+    /// The array itself never moves, but the caller must not move any values
+    /// which are pinned.
+    pub unsafe fn as_mut_slice(&mut self) -> &mut [MaybeUninit<T>] {
+        self.ptr.as_mut()
+    }
+}
+
+impl<T, A: ChunkAllocator> Drop for RawArray<T, A> {
+    fn drop(&mut self) {
+        let layout = Layout::array::<T>(self.ptr.len()).unwrap();
+        if layout.size() != 0 {
+            // SAFETY: The array was allocated with `alloc` and this layout.
+            unsafe { self.alloc.deallocate(self.ptr.cast(), layout) };
+        }
+    }
+}
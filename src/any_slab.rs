@@ -0,0 +1,198 @@
+//! A slab which stores values of several types.
+
+use crate::Slab;
+use std::any::{Any, TypeId};
+use std::collections::HashMap;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::marker::PhantomData;
+use std::pin::Pin;
+
+/// The key of a value of type `T` in an [`AnySlab`](struct.AnySlab.html).
+pub struct TypedKey<T> {
+    key: usize,
+    _marker: PhantomData<fn() -> T>,
+}
+
+impl<T> TypedKey<T> {
+    /// Return the key of the value within the slab of its type.
+    pub fn key(&self) -> usize {
+        self.key
+    }
+}
+
+impl<T> Clone for TypedKey<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for TypedKey<T> {}
+
+impl<T> PartialEq for TypedKey<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.key == other.key
+    }
+}
+
+impl<T> Eq for TypedKey<T> {}
+
+impl<T> Hash for TypedKey<T> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.key.hash(state);
+    }
+}
+
+impl<T> fmt::Debug for TypedKey<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("TypedKey").field(&self.key).finish()
+    }
+}
+
+/// The operations of a `Slab<T>` which don't depend on `T`.
+trait ErasedSlab {
+    fn len(&self) -> usize;
+    fn clear(&mut self);
+    fn as_any(&self) -> &dyn Any;
+    fn as_any_mut(&mut self) -> &mut dyn Any;
+}
+
+impl<T: 'static> ErasedSlab for Slab<T> {
+    fn len(&self) -> usize {
+        Slab::len(self)
+    }
+
+    fn clear(&mut self) {
+        Slab::clear(self)
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
+
+/// A slab which stores values of any number of types, e.g. the mailboxes of
+/// actors with different message types.
+///
+/// Each type gets its own [`Slab`](struct.Slab.html), so values are pinned
+/// and stored as compactly as in a slab of a single type. Keys are typed, so
+/// a key can only be used to look up values of the type it was returned for.
+///
+/// # Examples
+///
+/// ```
+/// # use pinned_slab::*;
+/// let mut slab = AnySlab::new();
+///
+/// let number = slab.insert(42u32);
+/// let name = slab.insert(String::from("mailbox"));
+/// assert_eq!(slab.len(), 2);
+///
+/// assert_eq!(*slab.get(number).unwrap(), 42);
+/// slab.get_pin_mut(name).unwrap().get_mut().push('!');
+/// assert_eq!(slab.remove(name), Some(String::from("mailbox!")));
+/// assert!(slab.get(name).is_none());
+/// ```
+#[derive(Default)]
+pub struct AnySlab {
+    slabs: HashMap<TypeId, Box<dyn ErasedSlab>>,
+}
+
+impl AnySlab {
+    /// Construct a new, empty `AnySlab`.
+    pub fn new() -> Self {
+        AnySlab::default()
+    }
+
+    /// Return the number of stored values of every type.
+    pub fn len(&self) -> usize {
+        self.slabs.values().map(|slab| slab.len()).sum()
+    }
+
+    /// Return `true` if there are no values stored in the slab.
+    pub fn is_empty(&self) -> bool {
+        self.slabs.values().all(|slab| slab.len() == 0)
+    }
+
+    /// Return `true` if a value is associated with the given key.
+    pub fn contains<T: 'static>(&self, key: TypedKey<T>) -> bool {
+        self.slab::<T>().is_some_and(|slab| slab.contains(key.key))
+    }
+
+    /// Insert a value in the slab of its type, returning its key.
+    pub fn insert<T: 'static>(&mut self, val: T) -> TypedKey<T> {
+        let (key, _) = self.slab_mut::<T>().insert(val);
+        TypedKey {
+            key,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Return a pinned reference to the value associated with the given key,
+    /// or `None` if there is no such value.
+    pub fn get<T: 'static>(&self, key: TypedKey<T>) -> Option<Pin<&T>> {
+        let val = self.slab::<T>()?.get(key.key)?;
+        // SAFETY: Values never move while they are in the slab.
+        Some(unsafe { Pin::new_unchecked(val) })
+    }
+
+    /// Return a pinned mutable reference to the value associated with the
+    /// given key, or `None` if there is no such value.
+    pub fn get_pin_mut<T: 'static>(&mut self, key: TypedKey<T>) -> Option<Pin<&mut T>> {
+        self.slabs
+            .get_mut(&TypeId::of::<T>())?
+            .as_any_mut()
+            .downcast_mut::<Slab<T>>()
+            .unwrap()
+            .get_pin_mut(key.key)
+    }
+
+    /// Remove and return the value associated with the given key, or `None`
+    /// if there is no such value.
+    ///
+    /// Values which aren't `Unpin` can't be moved out once they may have
+    /// been pinned, and are removed with
+    /// [`remove_in_place`](#method.remove_in_place) instead.
+    pub fn remove<T: Unpin + 'static>(&mut self, key: TypedKey<T>) -> Option<T> {
+        self.slab_mut::<T>().take(key.key)
+    }
+
+    /// Drop the value associated with the given key in place, returning
+    /// whether there was such a value.
+    pub fn remove_in_place<T: 'static>(&mut self, key: TypedKey<T>) -> bool {
+        self.slab_mut::<T>().remove_in_place(key.key)
+    }
+
+    /// Return the slab of the values of type `T`, if any have been inserted.
+    pub fn slab<T: 'static>(&self) -> Option<&Slab<T>> {
+        let slab = self.slabs.get(&TypeId::of::<T>())?;
+        Some(slab.as_any().downcast_ref().unwrap())
+    }
+
+    /// Drop every value of every type, keeping the allocated chunks.
+    pub fn clear(&mut self) {
+        self.slabs.values_mut().for_each(|slab| slab.clear());
+    }
+
+    fn slab_mut<T: 'static>(&mut self) -> &mut Slab<T> {
+        self.slabs
+            .entry(TypeId::of::<T>())
+            .or_insert_with(|| Box::new(Slab::<T>::new()))
+            .as_any_mut()
+            .downcast_mut()
+            .unwrap()
+    }
+}
+
+impl fmt::Debug for AnySlab {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("AnySlab")
+            .field("len", &self.len())
+            .field("types", &self.slabs.len())
+            .finish()
+    }
+}
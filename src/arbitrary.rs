@@ -0,0 +1,27 @@
+//! `Arbitrary` for `Slab`, available behind the `arbitrary` feature.
+
+use crate::{ChunkAllocator, Slab};
+use ::arbitrary::{Arbitrary, Result, Unstructured};
+
+/// Generates a slab by replaying a sequence of inserts and removes, so the
+/// slab has vacant keys on its free list like a slab which has been in use
+/// for a while.
+impl<'a, T, const N: usize, A> Arbitrary<'a> for Slab<T, N, A>
+where
+    T: Arbitrary<'a>,
+    A: ChunkAllocator + Default,
+{
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        let mut slab = Slab::default();
+        while u.arbitrary()? {
+            if slab.is_empty() || u.ratio(2u8, 3)? {
+                slab.insert(T::arbitrary(u)?);
+            } else {
+                let i = u.int_in_range(0..=slab.len() - 1)?;
+                let key = slab.keys().nth(i).unwrap();
+                slab.remove_in_place(key);
+            }
+        }
+        Ok(slab)
+    }
+}
@@ -0,0 +1,232 @@
+//! An append-only arena of pinned values.
+
+use crate::allocator::RawArray;
+use crate::{ChunkAllocator, Global, DEFAULT_CHUNK_SIZE};
+use alloc::vec::Vec;
+use core::cell::UnsafeCell;
+use core::fmt;
+use core::pin::Pin;
+use core::ptr;
+use core::slice;
+
+/// An arena which only allocates values, and drops them all at once.
+///
+/// Values are stored in chunks like in a [`Slab`](struct.Slab.html), but
+/// there are no keys, free list or occupancy bitmaps: each chunk is filled
+/// front to back. Values never move, so [`alloc`](#method.alloc) hands out
+/// pinned references which live as long as the arena.
+///
+/// # Examples
+///
+/// ```
+/// # use pinned_slab::*;
+/// let arena = Arena::new();
+/// let mut a = arena.alloc(String::from("a"));
+/// let b = arena.alloc(String::from("b"));
+///
+/// // Both values can be used at the same time.
+/// a.as_mut().get_mut().push_str(&b);
+/// assert_eq!(*a, "ab");
+/// assert_eq!(arena.len(), 2);
+/// ```
+pub struct Arena<T, const N: usize = DEFAULT_CHUNK_SIZE, A: ChunkAllocator = Global> {
+    inner: UnsafeCell<Inner<T, A>>,
+}
+
+struct Inner<T, A: ChunkAllocator> {
+    chunks: Vec<ArenaChunk<T, A>>,
+    /// The index of the chunk values are allocated in.
+    current: usize,
+    len: usize,
+    alloc: A,
+}
+
+struct ArenaChunk<T, A: ChunkAllocator> {
+    entries: RawArray<T, A>,
+    /// The number of initialized entries, which are at the front.
+    len: usize,
+}
+
+impl<T> Arena<T> {
+    /// Construct a new, empty `Arena`.
+    ///
+    /// This doesn't allocate until the first value is allocated.
+    pub fn new() -> Self {
+        Arena::new_in(Global)
+    }
+}
+
+impl<T, const N: usize, A: ChunkAllocator> Arena<T, N, A> {
+    /// Construct a new, empty `Arena` which allocates its chunks with `alloc`.
+    pub fn new_in(alloc: A) -> Self {
+        const { assert!(N > 0, "the chunk size must be greater than zero") };
+        Arena {
+            inner: UnsafeCell::new(Inner {
+                chunks: Vec::new(),
+                current: 0,
+                len: 0,
+                alloc,
+            }),
+        }
+    }
+
+    /// Return the number of allocated values.
+    pub fn len(&self) -> usize {
+        self.inner().len
+    }
+
+    /// Return `true` if no values have been allocated.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Return the number of values the arena can hold without allocating
+    /// another chunk, counting the allocated values.
+    pub fn capacity(&self) -> usize {
+        let inner = self.inner();
+        inner.len
+            + inner.chunks[inner.current.min(inner.chunks.len())..]
+                .iter()
+                .map(|chunk| chunk.entries.as_slice().len() - chunk.len)
+                .sum::<usize>()
+    }
+
+    /// Move `val` into the arena, returning a pinned reference to it which
+    /// lives as long as the arena.
+    #[allow(clippy::mut_from_ref)]
+    pub fn alloc(&self, val: T) -> Pin<&mut T> {
+        let ptr = self.reserve(1);
+        // SAFETY: The entry was reserved for this value, and values in the
+        // arena never move until it is cleared or dropped, which needs a
+        // unique borrow.
+        unsafe {
+            ptr.write(val);
+            self.inner_mut().len += 1;
+            Pin::new_unchecked(&mut *ptr)
+        }
+    }
+
+    /// Move every value of `iter` into the arena, next to each other,
+    /// returning them as a pinned slice.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use pinned_slab::*;
+    /// let arena: Arena<u32, 4> = Arena::new_in(Global);
+    /// arena.alloc(0);
+    ///
+    /// // The values don't fit in the rest of the first chunk, so they get
+    /// // a chunk of their own.
+    /// let values = arena.alloc_iter(1..=6);
+    /// assert_eq!(&*values, [1, 2, 3, 4, 5, 6]);
+    /// assert_eq!(arena.len(), 7);
+    /// ```
+    #[allow(clippy::mut_from_ref)]
+    pub fn alloc_iter<I>(&self, iter: I) -> Pin<&mut [T]>
+    where
+        I: IntoIterator<Item = T>,
+    {
+        // The iterator may allocate in the arena itself, so collect it first.
+        let mut values: Vec<T> = iter.into_iter().collect();
+        let n = values.len();
+        if n == 0 {
+            // SAFETY: An empty slice holds nothing to move.
+            return unsafe { Pin::new_unchecked(&mut []) };
+        }
+        let ptr = self.reserve(n);
+        // SAFETY: The entries were reserved for these values, and the values
+        // are moved out of `values` without dropping them there.
+        unsafe {
+            values.set_len(0);
+            ptr::copy_nonoverlapping(values.as_ptr(), ptr, n);
+            self.inner_mut().len += n;
+            Pin::new_unchecked(slice::from_raw_parts_mut(ptr, n))
+        }
+    }
+
+    /// Drop every value in the arena, keeping the allocated chunks for
+    /// reuse.
+    pub fn clear(&mut self) {
+        let inner = self.inner.get_mut();
+        inner.current = 0;
+        inner.len = 0;
+        for chunk in &mut inner.chunks {
+            let len = chunk.len;
+            chunk.len = 0;
+            // SAFETY: The first `len` entries are initialized, and no longer
+            // borrowed. They were dropped in place, so they were never moved.
+            unsafe {
+                let values =
+                    ptr::slice_from_raw_parts_mut(chunk.entries.as_mut_ptr() as *mut T, len);
+                ptr::drop_in_place(values);
+            }
+        }
+    }
+
+    /// Reserve `n` contiguous entries at the end of a chunk, returning a
+    /// pointer to the first one.
+    fn reserve(&self, n: usize) -> *mut T {
+        // SAFETY: No other reference to `Inner` is alive while this runs, and
+        // no user code is called with it.
+        let inner = unsafe { self.inner_mut() };
+        loop {
+            match inner.chunks.get_mut(inner.current) {
+                Some(chunk) if chunk.entries.as_slice().len() - chunk.len >= n => {
+                    let start = chunk.len;
+                    chunk.len += n;
+                    // SAFETY: The entries are within the chunk.
+                    return unsafe { chunk.entries.as_mut_ptr().add(start) as *mut T };
+                }
+                Some(_) => inner.current += 1,
+                None => {
+                    let size = n.max(N);
+                    let entries = match RawArray::try_new(size, inner.alloc.clone()) {
+                        Ok(entries) => entries,
+                        Err(crate::TryReserveError::AllocError { layout }) => {
+                            alloc::alloc::handle_alloc_error(layout)
+                        }
+                        Err(err) => panic!("{}", err),
+                    };
+                    inner.chunks.push(ArenaChunk { entries, len: 0 });
+                    inner.current = inner.chunks.len() - 1;
+                }
+            }
+        }
+    }
+
+    fn inner(&self) -> &Inner<T, A> {
+        // SAFETY: `Inner` is only borrowed mutably within methods which don't
+        // call back into the arena.
+        unsafe { &*self.inner.get() }
+    }
+
+    /// # Safety
+    ///
+    /// No other reference to `Inner` may be alive.
+    #[allow(clippy::mut_from_ref)]
+    unsafe fn inner_mut(&self) -> &mut Inner<T, A> {
+        &mut *self.inner.get()
+    }
+}
+
+impl<T, const N: usize, A: ChunkAllocator> Drop for Arena<T, N, A> {
+    fn drop(&mut self) {
+        self.clear();
+    }
+}
+
+impl<T, const N: usize, A: ChunkAllocator + Default> Default for Arena<T, N, A> {
+    fn default() -> Self {
+        Arena::new_in(A::default())
+    }
+}
+
+impl<T, const N: usize, A: ChunkAllocator> fmt::Debug for Arena<T, N, A> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Arena")
+            .field("len", &self.len())
+            .field("chunks", &self.inner().chunks.len())
+            .finish()
+    }
+}
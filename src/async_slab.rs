@@ -0,0 +1,363 @@
+//! A bounded slab whose inserts wait for a vacant slot.
+
+use crate::{BoundedSlab, Slab};
+use std::collections::VecDeque;
+use std::fmt;
+use std::future::Future;
+use std::ops;
+use std::pin::Pin;
+use std::sync::{Mutex, MutexGuard, PoisonError};
+use std::task::{Context, Poll, Waker};
+
+/// A slab which stores at most a fixed number of values, like a
+/// [`BoundedSlab`](struct.BoundedSlab.html), but whose inserts wait until a
+/// value is removed instead of failing.
+///
+/// The slab can be shared between tasks: every method takes `&self`, and the
+/// slab is behind a single lock. Waiting inserts are served in the order they
+/// started waiting. It only uses the `std::task` primitives, so it works with
+/// any executor.
+///
+/// # Examples
+///
+/// ```
+/// # use pinned_slab::*;
+/// use std::future::Future;
+/// use std::pin::pin;
+/// use std::task::{Context, Poll, Waker};
+///
+/// let slab = AsyncSlab::new(1);
+/// let mut cx = Context::from_waker(Waker::noop());
+///
+/// let first = slab.try_insert("first").unwrap();
+///
+/// let mut second = pin!(slab.insert("second"));
+/// assert!(second.as_mut().poll(&mut cx).is_pending());
+///
+/// assert_eq!(slab.remove(first), Some("first"));
+/// let second = match second.poll(&mut cx) {
+///     Poll::Ready(key) => key,
+///     Poll::Pending => unreachable!(),
+/// };
+/// assert_eq!(*slab.get(second).unwrap(), "second");
+/// ```
+pub struct AsyncSlab<T> {
+    state: Mutex<State<T>>,
+}
+
+struct State<T> {
+    slab: BoundedSlab<T>,
+    waiters: Slab<Waiter>,
+    /// The keys of the waiters which haven't been notified yet, oldest first.
+    queue: VecDeque<usize>,
+}
+
+struct Waiter {
+    waker: Option<Waker>,
+    /// Whether a slot was handed to this waiter, which took it off the queue.
+    notified: bool,
+}
+
+impl<T> AsyncSlab<T> {
+    /// Construct a new, empty `AsyncSlab` which stores at most `limit`
+    /// values.
+    pub fn new(limit: usize) -> Self {
+        AsyncSlab {
+            state: Mutex::new(State {
+                slab: BoundedSlab::new(limit),
+                waiters: Slab::new(),
+                queue: VecDeque::new(),
+            }),
+        }
+    }
+
+    /// Return the maximum number of values the slab will store.
+    pub fn limit(&self) -> usize {
+        self.lock().slab.limit()
+    }
+
+    /// Change the maximum number of values the slab will store, waking as
+    /// many waiting inserts as there are newly vacant slots.
+    pub fn set_limit(&self, limit: usize) {
+        let wakers = {
+            let mut state = self.lock();
+            state.slab.set_limit(limit);
+            let vacant = limit.saturating_sub(state.slab.len());
+            (0..vacant)
+                .map_while(|_| state.notify())
+                .collect::<Vec<_>>()
+        };
+        wakers.into_iter().for_each(Waker::wake);
+    }
+
+    /// Return the number of stored values.
+    pub fn len(&self) -> usize {
+        self.lock().slab.len()
+    }
+
+    /// Return `true` if there are no values stored in the slab.
+    pub fn is_empty(&self) -> bool {
+        self.lock().slab.is_empty()
+    }
+
+    /// Return `true` if a value is associated with the given key.
+    pub fn contains(&self, key: usize) -> bool {
+        self.lock().slab.contains(key)
+    }
+
+    /// Return a future which inserts `val` once the slab stores fewer than
+    /// [`limit`](#method.limit) values, and resolves to its key.
+    ///
+    /// Dropping the future before it resolves drops `val` without inserting
+    /// it.
+    pub fn insert(&self, val: T) -> AsyncInsert<'_, T> {
+        AsyncInsert {
+            slab: self,
+            val: Some(val),
+            waiter: None,
+        }
+    }
+
+    /// Insert a value in the slab without waiting, returning its key.
+    ///
+    /// If the slab is full, or other inserts are waiting, the value is handed
+    /// back instead.
+    pub fn try_insert(&self, val: T) -> Result<usize, T> {
+        let mut state = self.lock();
+        if !state.queue.is_empty() {
+            return Err(val);
+        }
+        state.slab.insert(val).map(|(key, _)| key)
+    }
+
+    /// Remove and return the value associated with the given key, or `None`
+    /// if there is no such value, waking the oldest waiting insert.
+    ///
+    /// The value is moved out, so it must be `Unpin`.
+    pub fn remove(&self, key: usize) -> Option<T>
+    where
+        T: Unpin,
+    {
+        let (val, waker) = {
+            let mut state = self.lock();
+            if !state.slab.contains(key) {
+                return None;
+            }
+            let val = state.slab.remove(key);
+            (val, state.notify())
+        };
+        if let Some(waker) = waker {
+            waker.wake();
+        }
+        Some(val)
+    }
+
+    /// Drop the value associated with the given key in place, waking the
+    /// oldest waiting insert, and return whether there was such a value.
+    pub fn remove_in_place(&self, key: usize) -> bool {
+        let waker = {
+            let mut state = self.lock();
+            if !state.slab.remove_in_place(key) {
+                return false;
+            }
+            state.notify()
+        };
+        if let Some(waker) = waker {
+            waker.wake();
+        }
+        true
+    }
+
+    /// Return a guard for the value associated with the given key, or `None`
+    /// if there is no such value.
+    ///
+    /// The whole slab is locked while the guard is alive.
+    pub fn get(&self, key: usize) -> Option<AsyncSlabRef<'_, T>> {
+        let state = self.lock();
+        if state.slab.contains(key) {
+            Some(AsyncSlabRef { state, key })
+        } else {
+            None
+        }
+    }
+
+    /// Consume the `AsyncSlab`, returning the underlying `Slab`.
+    pub fn into_inner(self) -> Slab<T> {
+        self.state
+            .into_inner()
+            .unwrap_or_else(PoisonError::into_inner)
+            .slab
+            .into_inner()
+    }
+
+    // The slab and the waiters stay consistent even if a panic poisons the
+    // lock, so poisoning is ignored.
+
+    fn lock(&self) -> MutexGuard<'_, State<T>> {
+        self.state.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+impl<T> State<T> {
+    /// Hand a slot to the oldest waiter, returning its waker.
+    fn notify(&mut self) -> Option<Waker> {
+        let key = self.queue.pop_front()?;
+        let waiter = &mut *self.waiters.get_pin_mut(key).unwrap();
+        waiter.notified = true;
+        waiter.waker.take()
+    }
+}
+
+impl<T> Default for AsyncSlab<T> {
+    /// Construct an `AsyncSlab` without a limit.
+    fn default() -> Self {
+        AsyncSlab::new(usize::MAX)
+    }
+}
+
+impl<T> fmt::Debug for AsyncSlab<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let state = self.lock();
+        f.debug_struct("AsyncSlab")
+            .field("len", &state.slab.len())
+            .field("limit", &state.slab.limit())
+            .field("waiting", &state.waiters.len())
+            .finish()
+    }
+}
+
+/// A future which inserts a value in an `AsyncSlab` once there is room.
+///
+/// See [`AsyncSlab::insert`](struct.AsyncSlab.html#method.insert).
+pub struct AsyncInsert<'a, T> {
+    slab: &'a AsyncSlab<T>,
+    val: Option<T>,
+    /// The key of the waiter, once the insert had to wait.
+    waiter: Option<usize>,
+}
+
+// The value is never pinned.
+impl<'a, T> Unpin for AsyncInsert<'a, T> {}
+
+impl<'a, T> Future for AsyncInsert<'a, T> {
+    type Output = usize;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<usize> {
+        let this = &mut *self;
+        let mut state = this.slab.lock();
+
+        // Without a slot of its own, an insert only gets in line.
+        let first = match this.waiter {
+            Some(waiter) => state.waiters[waiter].notified,
+            None => state.queue.is_empty(),
+        };
+        if first && !state.slab.is_full() {
+            if let Some(waiter) = this.waiter.take() {
+                state.waiters.remove(waiter);
+            }
+            let val = this
+                .val
+                .take()
+                .expect("`AsyncInsert` polled after completion");
+            let key = state.slab.insert(val).ok().unwrap().0;
+            return Poll::Ready(key);
+        }
+
+        match this.waiter {
+            Some(waiter) => {
+                let state = &mut *state;
+                let entry = &mut *state.waiters.get_pin_mut(waiter).unwrap();
+                if entry.notified {
+                    // Another insert took the slot first, so get back in
+                    // line at the front.
+                    entry.notified = false;
+                    state.queue.push_front(waiter);
+                }
+                match &entry.waker {
+                    Some(waker) if waker.will_wake(cx.waker()) => {}
+                    _ => entry.waker = Some(cx.waker().clone()),
+                }
+            }
+            None => {
+                let (waiter, _) = state.waiters.insert(Waiter {
+                    waker: Some(cx.waker().clone()),
+                    notified: false,
+                });
+                state.queue.push_back(waiter);
+                this.waiter = Some(waiter);
+            }
+        }
+        Poll::Pending
+    }
+}
+
+impl<'a, T> Drop for AsyncInsert<'a, T> {
+    fn drop(&mut self) {
+        let waiter = match self.waiter {
+            Some(waiter) => waiter,
+            None => return,
+        };
+        let waker = {
+            let mut state = self.slab.lock();
+            let notified = state.waiters.remove(waiter).notified;
+            if notified {
+                // Pass the slot on to the next waiter.
+                state.notify()
+            } else {
+                state.queue.retain(|&key| key != waiter);
+                None
+            }
+        };
+        if let Some(waker) = waker {
+            waker.wake();
+        }
+    }
+}
+
+impl<'a, T> fmt::Debug for AsyncInsert<'a, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("AsyncInsert")
+            .field("waiting", &self.waiter.is_some())
+            .finish()
+    }
+}
+
+/// A locked reference to a value of an `AsyncSlab`.
+///
+/// See [`AsyncSlab::get`](struct.AsyncSlab.html#method.get).
+pub struct AsyncSlabRef<'a, T> {
+    state: MutexGuard<'a, State<T>>,
+    key: usize,
+}
+
+impl<'a, T> AsyncSlabRef<'a, T> {
+    /// Return the key of the value.
+    pub fn key(&self) -> usize {
+        self.key
+    }
+
+    /// Return the value as a pinned mutable reference.
+    pub fn as_pin_mut(&mut self) -> Pin<&mut T> {
+        self.state.slab.get_pin_mut(self.key).unwrap()
+    }
+}
+
+impl<'a, T> ops::Deref for AsyncSlabRef<'a, T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.state.slab[self.key]
+    }
+}
+
+impl<'a, T: Unpin> ops::DerefMut for AsyncSlabRef<'a, T> {
+    fn deref_mut(&mut self) -> &mut T {
+        self.as_pin_mut().get_mut()
+    }
+}
+
+impl<'a, T: fmt::Debug> fmt::Debug for AsyncSlabRef<'a, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&**self, f)
+    }
+}
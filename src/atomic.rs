@@ -0,0 +1,529 @@
+//! A slab with lock-free inserts and removals.
+
+use crate::sync::{AtomicPtr, AtomicU32, AtomicU64, AtomicUsize, Ordering, UnsafeCell};
+use std::fmt;
+use std::mem::MaybeUninit;
+use std::ops;
+use std::pin::Pin;
+use std::process;
+use std::ptr;
+
+/// The number of slots of the first chunk. Every chunk after it holds twice
+/// as many slots as the one before.
+const FIRST_CHUNK: usize = 32;
+
+/// The number of chunks, enough for `u32::MAX` slots.
+const CHUNKS: usize = 28;
+
+// The state of a slot packs the flags below, the number of references to its
+// value, and the generation of the slot in the high half. The generation is
+// bumped whenever the slot is freed.
+
+/// The slot holds a value.
+const OCCUPIED: u64 = 1;
+/// The value of the slot has been removed, and is dropped once the last
+/// reference to it is released.
+const REMOVED: u64 = 2;
+/// One reference to the value of a slot. The slab itself holds one until the
+/// value is removed.
+const REF: u64 = 4;
+/// The flags and the number of references.
+const REFS_MASK: u64 = (1 << 32) - 1;
+/// More references than this abort the process, like `Arc` does.
+const MAX_REFS: u64 = 1 << 29;
+const GENERATION_SHIFT: u32 = 32;
+
+/// A slab which can be shared between threads without locks.
+///
+/// Readers never block on locks: [`get`](#method.get) only bumps a reference
+/// count on the slot of the key. Inserts take a vacant slot off a lock-free free list,
+/// and removals mark the slot removed. The value is dropped, and the slot
+/// reused, only once the last [`AtomicRef`](struct.AtomicRef.html) to it has
+/// been dropped, so readers never see a value being dropped or replaced under
+/// them.
+///
+/// Like in a [`Slab`](struct.Slab.html), values never move until they are
+/// dropped, so they can be handed out pinned. Only shared references are
+/// handed out, so use interior mutability to modify values.
+///
+/// # Reclamation and progress
+///
+/// Removed slots are reclaimed by counting the references to each slot, not
+/// with epochs or hazard pointers, so there is no global quiescence to wait
+/// for. This has some costs:
+///
+/// - `get` is lock-free but not wait-free. It bumps the count with a
+///   compare-and-swap loop, which retries while other threads change the
+///   state of the same slot, so readers of a hot key contend on its cache
+///   line.
+/// - A slot is only reused once its last `AtomicRef` is dropped, so a
+///   reference which is held on to keeps its slot, and the removed value in
+///   it, out of circulation for as long as it lives.
+///
+/// With the `epoch` feature, [`EpochSlab`](struct.EpochSlab.html) reclaims
+/// slots with epochs instead, and its reads are wait-free.
+///
+/// # Examples
+///
+/// ```
+/// # use pinned_slab::*;
+/// use std::sync::atomic::{AtomicUsize, Ordering};
+/// use std::thread;
+///
+/// let slab = AtomicSlab::new();
+/// let key = slab.insert(AtomicUsize::new(0));
+///
+/// thread::scope(|s| {
+///     for _ in 0..4 {
+///         s.spawn(|| slab.get(key).unwrap().fetch_add(1, Ordering::Relaxed));
+///     }
+/// });
+///
+/// let value = slab.get(key).unwrap();
+/// assert!(slab.remove(key));
+/// assert!(slab.get(key).is_none());
+///
+/// // The value stays alive until the last reference is dropped.
+/// assert_eq!(value.load(Ordering::Relaxed), 4);
+/// ```
+pub struct AtomicSlab<T> {
+    /// The chunk at index `i` holds `FIRST_CHUNK << i` slots, or is null if it
+    /// hasn't been allocated yet.
+    chunks: [AtomicPtr<Slot<T>>; CHUNKS],
+    /// The number of slots which have been handed out at least once.
+    fresh: AtomicUsize,
+    /// The top of the free list as `index + 1` in the low half, or zero if the
+    /// list is empty, with a counter in the high half against ABA.
+    free: AtomicU64,
+    len: AtomicUsize,
+}
+
+struct Slot<T> {
+    state: AtomicU64,
+    /// The index of the next slot of the free list plus one, or zero.
+    next_free: AtomicU32,
+    value: UnsafeCell<MaybeUninit<T>>,
+}
+
+// SAFETY: Values are shared between threads through `AtomicRef`, and dropped
+// by whichever thread releases them last.
+unsafe impl<T: Send> Send for AtomicSlab<T> {}
+unsafe impl<T: Send + Sync> Sync for AtomicSlab<T> {}
+
+impl<T> AtomicSlab<T> {
+    /// Construct a new, empty `AtomicSlab`.
+    ///
+    /// Like [`Slab::new`](struct.Slab.html#method.new), this does not
+    /// allocate.
+    pub fn new() -> Self {
+        AtomicSlab {
+            chunks: [(); CHUNKS].map(|_| AtomicPtr::new(ptr::null_mut())),
+            fresh: AtomicUsize::new(0),
+            free: AtomicU64::new(0),
+            len: AtomicUsize::new(0),
+        }
+    }
+
+    /// Return the number of stored values. Removed values which are still
+    /// referenced are counted too, until they are dropped.
+    ///
+    /// Other threads may insert or remove values at the same time, so this is
+    /// only a snapshot.
+    pub fn len(&self) -> usize {
+        self.len.load(Ordering::Relaxed)
+    }
+
+    /// Return `true` if there are no values stored in the slab.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Return `true` if a value is associated with the given key.
+    pub fn contains(&self, key: usize) -> bool {
+        self.slot(key).is_some_and(|slot| {
+            slot.state.load(Ordering::Acquire) & (OCCUPIED | REMOVED) == OCCUPIED
+        })
+    }
+
+    /// Insert a value in the slab, returning the key assigned to the value.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the slab already holds `u32::MAX - 1` slots.
+    pub fn insert(&self, val: T) -> usize {
+        self.insert_with_flags(val, OCCUPIED).0
+    }
+
+    /// Insert a value which is only owned by the single reference returned
+    /// with it, returning its key and the generation of its slot.
+    pub(crate) fn insert_unowned(&self, val: T) -> (usize, u32) {
+        self.insert_with_flags(val, OCCUPIED | REMOVED)
+    }
+
+    fn insert_with_flags(&self, val: T, flags: u64) -> (usize, u32) {
+        let key = self.pop_free().unwrap_or_else(|| self.fresh_slot());
+        let slot = self.slot(key).unwrap();
+
+        // SAFETY: The slot is vacant and off the free list, so no other
+        // thread accesses its value.
+        slot.value.with_mut(|value| unsafe { (*value).write(val) });
+        let generation = slot.state.load(Ordering::Relaxed) & !REFS_MASK;
+        slot.state
+            .store(generation | flags | REF, Ordering::Release);
+        self.len.fetch_add(1, Ordering::Relaxed);
+
+        (key, (generation >> GENERATION_SHIFT) as u32)
+    }
+
+    /// Return an upper bound on the keys which have been handed out.
+    pub(crate) fn key_bound(&self) -> usize {
+        self.fresh.load(Ordering::Acquire)
+    }
+
+    /// Return a reference to the value associated with the given key, or
+    /// `None` if there is no such value.
+    ///
+    /// This is lock-free, not wait-free: it retries while other threads
+    /// update the same slot. See the [type docs](struct.AtomicSlab.html).
+    pub fn get(&self, key: usize) -> Option<AtomicRef<'_, T>> {
+        if self.acquire(key, |state| state & (OCCUPIED | REMOVED) == OCCUPIED) {
+            Some(AtomicRef { slab: self, key })
+        } else {
+            None
+        }
+    }
+
+    /// Add a reference to the value at `key` if it is still referenced and
+    /// its slot is still in the given generation, returning whether it did.
+    pub(crate) fn acquire_generation(&self, key: usize, generation: u32) -> bool {
+        self.acquire(key, |state| {
+            state >> GENERATION_SHIFT == generation as u64
+                && state & OCCUPIED != 0
+                && state & REFS_MASK >= REF
+        })
+    }
+
+    /// Add a reference to the value at `key` if `valid` returns `true` for the
+    /// state of its slot, returning whether it did.
+    fn acquire<F>(&self, key: usize, valid: F) -> bool
+    where
+        F: Fn(u64) -> bool,
+    {
+        let slot = match self.slot(key) {
+            Some(slot) => slot,
+            None => return false,
+        };
+        let mut state = slot.state.load(Ordering::Acquire);
+        loop {
+            if !valid(state) {
+                return false;
+            }
+            if (state & REFS_MASK) / REF > MAX_REFS {
+                process::abort();
+            }
+            match slot.state.compare_exchange_weak(
+                state,
+                state + REF,
+                Ordering::Acquire,
+                Ordering::Acquire,
+            ) {
+                Ok(_) => return true,
+                Err(actual) => state = actual,
+            }
+        }
+    }
+
+    /// Add a reference to the value at `key`, which is already referenced.
+    pub(crate) fn retain(&self, key: usize) {
+        let slot = self.slot(key).unwrap();
+        let state = slot.state.fetch_add(REF, Ordering::Relaxed);
+        if (state & REFS_MASK) / REF > MAX_REFS {
+            process::abort();
+        }
+    }
+
+    /// Return the value at `key` if it isn't removed, without taking a
+    /// reference to it. This never retries.
+    ///
+    /// # Safety
+    ///
+    /// The caller must make sure that the value isn't dropped while the
+    /// returned reference is alive.
+    #[cfg(feature = "epoch")]
+    pub(crate) unsafe fn peek(&self, key: usize) -> Option<&T> {
+        let slot = self.slot(key)?;
+        if slot.state.load(Ordering::Acquire) & (OCCUPIED | REMOVED) != OCCUPIED {
+            return None;
+        }
+        Some(slot.value.with(|value| (*value).assume_init_ref()))
+    }
+
+    /// Return the value at `key`.
+    ///
+    /// # Safety
+    ///
+    /// The caller must hold a reference to the value.
+    pub(crate) unsafe fn value(&self, key: usize) -> &T {
+        let slot = self.slot(key).unwrap();
+        slot.value.with(|value| (*value).assume_init_ref())
+    }
+
+    /// Return the generation of the slot of `key`.
+    pub(crate) fn generation(&self, key: usize) -> u32 {
+        self.slot(key).map_or(0, |slot| {
+            (slot.state.load(Ordering::Acquire) >> GENERATION_SHIFT) as u32
+        })
+    }
+
+    /// Return the number of references to the value at `key`.
+    pub(crate) fn ref_count(&self, key: usize) -> usize {
+        self.slot(key).map_or(0, |slot| {
+            ((slot.state.load(Ordering::Acquire) & REFS_MASK) / REF) as usize
+        })
+    }
+
+    /// Remove the value associated with the given key, returning `true` if
+    /// there was one.
+    ///
+    /// The value is dropped right away, or once the last reference to it is
+    /// dropped, and only then is the key reused.
+    pub fn remove(&self, key: usize) -> bool {
+        if !self.mark_removed(key) {
+            return false;
+        }
+
+        self.release(key);
+        true
+    }
+
+    /// Mark the value at `key` removed, returning whether there was a value
+    /// which wasn't removed yet. The reference the slab held on the value is
+    /// left to the caller to release.
+    pub(crate) fn mark_removed(&self, key: usize) -> bool {
+        let slot = match self.slot(key) {
+            Some(slot) => slot,
+            None => return false,
+        };
+        let mut state = slot.state.load(Ordering::Acquire);
+        loop {
+            if state & (OCCUPIED | REMOVED) != OCCUPIED {
+                return false;
+            }
+            match slot.state.compare_exchange_weak(
+                state,
+                state | REMOVED,
+                Ordering::AcqRel,
+                Ordering::Acquire,
+            ) {
+                Ok(_) => return true,
+                Err(actual) => state = actual,
+            }
+        }
+    }
+
+    /// Drop one reference to the value at `key`, and free the slot if it was
+    /// the last reference to a removed value.
+    pub(crate) fn release(&self, key: usize) {
+        let slot = self.slot(key).unwrap();
+        let state = slot.state.fetch_sub(REF, Ordering::AcqRel);
+        if state & REFS_MASK != OCCUPIED | REMOVED | REF {
+            return;
+        }
+
+        // SAFETY: This was the last reference, and the value is removed, so
+        // no other thread can access it anymore.
+        slot.value
+            .with_mut(|value| unsafe { (*value).assume_init_drop() });
+        self.len.fetch_sub(1, Ordering::Relaxed);
+        let generation = (state >> GENERATION_SHIFT).wrapping_add(1);
+        slot.state
+            .store(generation << GENERATION_SHIFT, Ordering::Release);
+        self.push_free(key);
+    }
+
+    /// Return the slot of `key`, if its chunk has been allocated.
+    fn slot(&self, key: usize) -> Option<&Slot<T>> {
+        if key >= self.fresh.load(Ordering::Acquire) {
+            return None;
+        }
+        let (chunk, index) = locate(key);
+        let chunk = self.chunks[chunk].load(Ordering::Acquire);
+        if chunk.is_null() {
+            return None;
+        }
+
+        // SAFETY: Allocated chunks are only freed when the slab is dropped,
+        // and `index` is within the chunk.
+        Some(unsafe { &*chunk.add(index) })
+    }
+
+    /// Take a slot which has never been used before, allocating its chunk if
+    /// needed.
+    fn fresh_slot(&self) -> usize {
+        let key = self.fresh.fetch_add(1, Ordering::AcqRel);
+        if key >= u32::MAX as usize - 1 {
+            self.fresh.fetch_sub(1, Ordering::AcqRel);
+            panic!("too many slots");
+        }
+
+        let (chunk, _) = locate(key);
+        if self.chunks[chunk].load(Ordering::Acquire).is_null() {
+            let slots: Box<[Slot<T>]> = (0..FIRST_CHUNK << chunk)
+                .map(|_| Slot {
+                    state: AtomicU64::new(0),
+                    next_free: AtomicU32::new(0),
+                    value: UnsafeCell::new(MaybeUninit::uninit()),
+                })
+                .collect();
+            let new = Box::into_raw(slots) as *mut Slot<T>;
+            if self.chunks[chunk]
+                .compare_exchange(ptr::null_mut(), new, Ordering::AcqRel, Ordering::Acquire)
+                .is_err()
+            {
+                // Another thread allocated the chunk first.
+                // SAFETY: The chunk was never shared.
+                drop(unsafe {
+                    Box::from_raw(ptr::slice_from_raw_parts_mut(new, FIRST_CHUNK << chunk))
+                });
+            }
+        }
+
+        key
+    }
+
+    fn pop_free(&self) -> Option<usize> {
+        let mut head = self.free.load(Ordering::Acquire);
+        loop {
+            let key = (head as u32).checked_sub(1)? as usize;
+            let next = self.slot(key).unwrap().next_free.load(Ordering::Acquire);
+            let new = (head >> 32).wrapping_add(1) << 32 | next as u64;
+            match self
+                .free
+                .compare_exchange_weak(head, new, Ordering::AcqRel, Ordering::Acquire)
+            {
+                Ok(_) => return Some(key),
+                Err(actual) => head = actual,
+            }
+        }
+    }
+
+    fn push_free(&self, key: usize) {
+        let slot = self.slot(key).unwrap();
+        let mut head = self.free.load(Ordering::Acquire);
+        loop {
+            slot.next_free.store(head as u32, Ordering::Release);
+            let new = (head >> 32).wrapping_add(1) << 32 | (key as u64 + 1);
+            match self
+                .free
+                .compare_exchange_weak(head, new, Ordering::AcqRel, Ordering::Acquire)
+            {
+                Ok(_) => return,
+                Err(actual) => head = actual,
+            }
+        }
+    }
+}
+
+/// Return the index of the chunk holding `key` and the index of `key` within
+/// that chunk.
+fn locate(key: usize) -> (usize, usize) {
+    let chunk = (key / FIRST_CHUNK + 1).ilog2() as usize;
+    (chunk, key - FIRST_CHUNK * ((1 << chunk) - 1))
+}
+
+impl<T> Default for AtomicSlab<T> {
+    fn default() -> Self {
+        AtomicSlab::new()
+    }
+}
+
+impl<T> fmt::Debug for AtomicSlab<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("AtomicSlab")
+            .field("len", &self.len())
+            .finish()
+    }
+}
+
+impl<T> Drop for AtomicSlab<T> {
+    fn drop(&mut self) {
+        for (i, chunk) in self.chunks.iter_mut().enumerate() {
+            let chunk = chunk.load(Ordering::Relaxed);
+            if chunk.is_null() {
+                continue;
+            }
+
+            // SAFETY: The chunk was allocated as a boxed slice of this length.
+            let mut slots =
+                unsafe { Box::from_raw(ptr::slice_from_raw_parts_mut(chunk, FIRST_CHUNK << i)) };
+            for slot in slots.iter_mut() {
+                // No references are left, so every occupied slot is still
+                // owned by the slab.
+                if slot.state.load(Ordering::Relaxed) & OCCUPIED != 0 {
+                    slot.value
+                        .with_mut(|value| unsafe { (*value).assume_init_drop() });
+                }
+            }
+        }
+    }
+}
+
+/// A reference to a value of an `AtomicSlab`, which keeps the value alive
+/// even if it is removed.
+///
+/// The slot of the value isn't reused until the reference is dropped, so
+/// avoid holding on to it for long.
+///
+/// See [`AtomicSlab::get`](struct.AtomicSlab.html#method.get).
+pub struct AtomicRef<'a, T> {
+    slab: &'a AtomicSlab<T>,
+    key: usize,
+}
+
+// SAFETY: `AtomicRef` behaves like a `&T`, except that it may drop the value.
+unsafe impl<'a, T: Send + Sync> Send for AtomicRef<'a, T> {}
+unsafe impl<'a, T: Send + Sync> Sync for AtomicRef<'a, T> {}
+
+impl<'a, T> AtomicRef<'a, T> {
+    /// Return the key of the value.
+    pub fn key(&self) -> usize {
+        self.key
+    }
+
+    /// Return the value as a pinned reference.
+    pub fn as_pin(&self) -> Pin<&T> {
+        // SAFETY: Values never move until they are dropped.
+        unsafe { Pin::new_unchecked(&**self) }
+    }
+}
+
+impl<'a, T> ops::Deref for AtomicRef<'a, T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        // SAFETY: The reference keeps the value alive.
+        unsafe { self.slab.value(self.key) }
+    }
+}
+
+impl<'a, T> Clone for AtomicRef<'a, T> {
+    fn clone(&self) -> Self {
+        self.slab.retain(self.key);
+        AtomicRef {
+            slab: self.slab,
+            key: self.key,
+        }
+    }
+}
+
+impl<'a, T> Drop for AtomicRef<'a, T> {
+    fn drop(&mut self) {
+        self.slab.release(self.key);
+    }
+}
+
+impl<'a, T: fmt::Debug> fmt::Debug for AtomicRef<'a, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&**self, f)
+    }
+}
@@ -0,0 +1,53 @@
+//! `BorshSerialize` and `BorshDeserialize` for `Slab`, available behind the
+//! `borsh` feature.
+//!
+//! A slab is encoded densely: the number of values as a `u32`, followed by
+//! the key of each value as a `u64` and the value itself, in ascending key
+//! order. This is the only valid encoding of a slab, so keys out of order are
+//! rejected when deserializing, as are keys so large that there are far too
+//! few values for them.
+
+use crate::{is_plausible_key, ChunkAllocator, Slab, NIL};
+use ::borsh::io::{self, ErrorKind, Read, Write};
+use ::borsh::{BorshDeserialize, BorshSerialize};
+use core::convert::TryFrom;
+
+impl<T: BorshSerialize, const N: usize, A: ChunkAllocator> BorshSerialize for Slab<T, N, A> {
+    fn serialize<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        let len = u32::try_from(self.len())
+            .map_err(|_| io::Error::new(ErrorKind::InvalidData, "too many values"))?;
+        len.serialize(writer)?;
+        for (key, val) in self.iter() {
+            (key as u64).serialize(writer)?;
+            val.serialize(writer)?;
+        }
+        Ok(())
+    }
+}
+
+impl<T, const N: usize, A> BorshDeserialize for Slab<T, N, A>
+where
+    T: BorshDeserialize,
+    A: ChunkAllocator + Default,
+{
+    fn deserialize_reader<R: Read>(reader: &mut R) -> io::Result<Self> {
+        let len = u32::deserialize_reader(reader)?;
+        let mut slab = Slab::default();
+        for _ in 0..len {
+            let key = usize::try_from(u64::deserialize_reader(reader)?)
+                .map_err(|_| io::Error::new(ErrorKind::InvalidData, "key overflow"))?;
+            if slab.tail != NIL && key <= slab.tail {
+                return Err(io::Error::new(
+                    ErrorKind::InvalidData,
+                    "keys are not in ascending order",
+                ));
+            }
+            if !is_plausible_key(slab.len(), key) {
+                return Err(io::Error::new(ErrorKind::InvalidData, "key out of range"));
+            }
+            slab.occupy(key, T::deserialize_reader(reader)?);
+        }
+        slab.rebuild_free_list();
+        Ok(slab)
+    }
+}
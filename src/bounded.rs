@@ -0,0 +1,154 @@
+//! A `Slab` with a hard limit on the number of stored values.
+
+use crate::{trace, IterPinMut, Slab};
+use core::ops;
+use core::pin::Pin;
+
+/// A slab which refuses to store more than a fixed number of values.
+///
+/// This is useful for applying backpressure, e.g. to a table of connections.
+/// Every read-only method of [`Slab`](struct.Slab.html) is available through
+/// `Deref`.
+///
+/// # Examples
+///
+/// ```
+/// # use pinned_slab::*;
+/// let mut slab = BoundedSlab::new(2);
+///
+/// slab.insert("a").unwrap();
+/// let (b, _) = slab.insert("b").unwrap();
+/// assert_eq!(slab.insert("c"), Err("c"));
+///
+/// slab.remove(b);
+/// assert!(slab.insert("c").is_ok());
+/// assert_eq!(slab.len(), 2);
+/// ```
+#[derive(Debug, Clone)]
+pub struct BoundedSlab<T> {
+    slab: Slab<T>,
+    limit: usize,
+}
+
+impl<T> BoundedSlab<T> {
+    /// Construct a new, empty `BoundedSlab` which stores at most `limit`
+    /// values.
+    ///
+    /// Like [`Slab::new`](struct.Slab.html#method.new), this does not
+    /// allocate.
+    pub fn new(limit: usize) -> Self {
+        BoundedSlab {
+            slab: Slab::new(),
+            limit,
+        }
+    }
+
+    /// Give the slab a name, which identifies it in `tracing` events.
+    ///
+    /// See [`Slab::named`](struct.Slab.html#method.named).
+    pub fn named(self, name: &'static str) -> Self {
+        BoundedSlab {
+            slab: self.slab.named(name),
+            limit: self.limit,
+        }
+    }
+
+    /// Return the maximum number of values the slab will store.
+    pub fn limit(&self) -> usize {
+        self.limit
+    }
+
+    /// Change the maximum number of values the slab will store.
+    ///
+    /// Lowering the limit below [`len`](struct.Slab.html#method.len) doesn't
+    /// remove any values, but no new values can be inserted until enough
+    /// have been removed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use pinned_slab::*;
+    /// let mut slab = BoundedSlab::new(0);
+    /// assert!(slab.is_full());
+    ///
+    /// slab.set_limit(1);
+    /// assert!(slab.insert(1).is_ok());
+    /// assert!(slab.is_full());
+    /// ```
+    pub fn set_limit(&mut self, limit: usize) {
+        self.limit = limit;
+    }
+
+    /// Return `true` if no more values can be inserted.
+    pub fn is_full(&self) -> bool {
+        self.slab.len() >= self.limit
+    }
+
+    /// Insert a value in the slab, returning the key assigned to the value and
+    /// a reference to that value.
+    ///
+    /// If the slab already stores [`limit`](#method.limit) values, the value
+    /// is handed back instead.
+    pub fn insert(&mut self, val: T) -> Result<(usize, &T), T> {
+        if self.is_full() {
+            trace::limit_reached(
+                self.slab.name(),
+                self.slab.len(),
+                &format_args!("limit of {} values", self.limit),
+            );
+            return Err(val);
+        }
+
+        Ok(self.slab.insert(val))
+    }
+
+    /// Remove and return the value associated with the given key.
+    ///
+    /// See [`Slab::remove`](struct.Slab.html#method.remove).
+    ///
+    /// # Panics
+    ///
+    /// Panics if `key` is not associated with a value.
+    pub fn remove(&mut self, key: usize) -> T
+    where
+        T: Unpin,
+    {
+        self.slab.remove(key)
+    }
+
+    /// Drop the value associated with the given key in place, returning
+    /// whether there was such a value.
+    ///
+    /// See [`Slab::remove_in_place`](struct.Slab.html#method.remove_in_place).
+    pub fn remove_in_place(&mut self, key: usize) -> bool {
+        self.slab.remove_in_place(key)
+    }
+
+    /// Return a pinned mutable reference to the value associated with the
+    /// given key.
+    ///
+    /// See [`Slab::get_pin_mut`](struct.Slab.html#method.get_pin_mut).
+    pub fn get_pin_mut(&mut self, key: usize) -> Option<Pin<&mut T>> {
+        self.slab.get_pin_mut(key)
+    }
+
+    /// Return an iterator that yields pinned mutable references to each value.
+    ///
+    /// See [`Slab::iter_pin_mut`](struct.Slab.html#method.iter_pin_mut).
+    pub fn iter_pin_mut(&mut self) -> IterPinMut<'_, T> {
+        self.slab.iter_pin_mut()
+    }
+
+    /// Consume the `BoundedSlab`, returning the underlying `Slab`.
+    pub fn into_inner(self) -> Slab<T> {
+        self.slab
+    }
+}
+
+impl<T> ops::Deref for BoundedSlab<T> {
+    type Target = Slab<T>;
+
+    fn deref(&self) -> &Slab<T> {
+        &self.slab
+    }
+}
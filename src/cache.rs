@@ -0,0 +1,280 @@
+//! A slab which evicts its least recently used values, available behind the
+//! `cache` feature.
+
+use crate::list::KeyList;
+use crate::Slab;
+use std::fmt;
+use std::pin::Pin;
+use std::time::{Duration, Instant};
+
+/// A slab which stores at most a fixed number of values, and evicts the least
+/// recently used value to make room for a new one, e.g. as a connection cache.
+///
+/// The recency order is kept in an intrusive list over the keys, so every
+/// operation takes `O(1)` time. Values can also expire: with a time to live,
+/// [`evict_expired`](#method.evict_expired) removes every value which hasn't
+/// been used for that long.
+///
+/// Only [`get`](#method.get), [`get_pin_mut`](#method.get_pin_mut) and
+/// [`touch`](#method.touch) count as uses; [`peek`](#method.peek) doesn't.
+///
+/// # Examples
+///
+/// ```
+/// # use pinned_slab::*;
+/// let mut cache = LruSlab::new(2);
+///
+/// let (a, _) = cache.insert("a");
+/// let (b, _) = cache.insert("b");
+/// cache.get(a);
+///
+/// // `b` is the least recently used value.
+/// let (c, evicted) = cache.insert("c");
+/// assert_eq!(evicted, Some((b, "b")));
+/// assert_eq!(cache.peek(c), Some(&"c"));
+/// assert_eq!(cache.lru(), Some(a));
+/// ```
+pub struct LruSlab<T> {
+    slab: Slab<T>,
+    /// The keys from the most to the least recently used.
+    order: KeyList,
+    /// When the value of each key was last used, if the slab has a time to
+    /// live.
+    used: Vec<Option<Instant>>,
+    capacity: usize,
+    ttl: Option<Duration>,
+}
+
+impl<T> LruSlab<T> {
+    /// Construct a new, empty `LruSlab` which stores at most `capacity`
+    /// values.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `capacity` is zero.
+    pub fn new(capacity: usize) -> Self {
+        assert!(capacity > 0, "an LRU slab needs room for a value");
+        LruSlab {
+            slab: Slab::new(),
+            order: KeyList::new(),
+            used: Vec::new(),
+            capacity,
+            ttl: None,
+        }
+    }
+
+    /// Construct a new, empty `LruSlab` which stores at most `capacity`
+    /// values, and whose values expire once they haven't been used for `ttl`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `capacity` is zero.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use pinned_slab::*;
+    /// use std::time::{Duration, Instant};
+    ///
+    /// let mut cache = LruSlab::with_ttl(16, Duration::from_secs(60));
+    /// let (key, _) = cache.insert("connection");
+    ///
+    /// assert!(cache.evict_expired(Instant::now()).is_empty());
+    ///
+    /// let later = Instant::now() + Duration::from_secs(61);
+    /// assert_eq!(cache.evict_expired(later), [(key, "connection")]);
+    /// assert!(cache.is_empty());
+    /// ```
+    pub fn with_ttl(capacity: usize, ttl: Duration) -> Self {
+        LruSlab {
+            ttl: Some(ttl),
+            ..LruSlab::new(capacity)
+        }
+    }
+
+    /// Return the maximum number of values the slab will store.
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Return the time to live of the values, if they expire.
+    pub fn ttl(&self) -> Option<Duration> {
+        self.ttl
+    }
+
+    /// Return the number of stored values.
+    pub fn len(&self) -> usize {
+        self.slab.len()
+    }
+
+    /// Return `true` if there are no values stored in the slab.
+    pub fn is_empty(&self) -> bool {
+        self.slab.is_empty()
+    }
+
+    /// Return `true` if a value is associated with the given key.
+    pub fn contains(&self, key: usize) -> bool {
+        self.slab.contains(key)
+    }
+
+    /// Return the key of the least recently used value, which is evicted
+    /// next.
+    pub fn lru(&self) -> Option<usize> {
+        self.order.back()
+    }
+
+    /// Insert a value in the slab as the most recently used value, returning
+    /// its key.
+    ///
+    /// If the slab is full, the least recently used value is evicted first
+    /// and returned with its key, which may be reused for the new value.
+    pub fn insert(&mut self, val: T) -> (usize, Option<(usize, T)>) {
+        let evicted = if self.slab.len() >= self.capacity {
+            self.lru().map(|key| (key, self.remove(key).unwrap()))
+        } else {
+            None
+        };
+
+        let (key, _) = self.slab.insert(val);
+        self.push_front(key);
+        (key, evicted)
+    }
+
+    /// Return a reference to the value associated with the given key, marking
+    /// it as the most recently used value.
+    pub fn get(&mut self, key: usize) -> Option<&T> {
+        if !self.touch(key) {
+            return None;
+        }
+        self.slab.get(key)
+    }
+
+    /// Return a pinned mutable reference to the value associated with the
+    /// given key, marking it as the most recently used value.
+    ///
+    /// Evicted values are moved out of the cache, so it only hands out
+    /// pinned mutable references to values which may be moved anyway.
+    pub fn get_pin_mut(&mut self, key: usize) -> Option<Pin<&mut T>>
+    where
+        T: Unpin,
+    {
+        if !self.touch(key) {
+            return None;
+        }
+        self.slab.get_pin_mut(key)
+    }
+
+    /// Return a reference to the value associated with the given key, without
+    /// marking it as used.
+    pub fn peek(&self, key: usize) -> Option<&T> {
+        self.slab.get(key)
+    }
+
+    /// Mark the value associated with the given key as the most recently used
+    /// value, returning whether there is such a value.
+    pub fn touch(&mut self, key: usize) -> bool {
+        if !self.slab.contains(key) {
+            return false;
+        }
+        self.order.unlink(key);
+        self.push_front(key);
+        true
+    }
+
+    /// Remove and return the value associated with the given key, or `None`
+    /// if there is no such value.
+    pub fn remove(&mut self, key: usize) -> Option<T> {
+        if !self.slab.contains(key) {
+            return None;
+        }
+        self.order.unlink(key);
+        // SAFETY: The cache never pins values which aren't `Unpin`, see
+        // `get_pin_mut`, so they may be moved out.
+        Some(unsafe { self.slab.remove_unpinned(key) })
+    }
+
+    /// Remove the values which haven't been used for the time to live as of
+    /// `now`, returning them with their keys, least recently used first.
+    ///
+    /// This takes time proportional to the number of expired values. Nothing
+    /// expires if the slab has no time to live.
+    pub fn evict_expired(&mut self, now: Instant) -> Vec<(usize, T)> {
+        let ttl = match self.ttl {
+            Some(ttl) => ttl,
+            None => return Vec::new(),
+        };
+
+        let mut expired = Vec::new();
+        while let Some(key) = self.order.back() {
+            let used = self.used[key].unwrap();
+            if now.saturating_duration_since(used) < ttl {
+                break;
+            }
+            expired.push((key, self.remove(key).unwrap()));
+        }
+        expired
+    }
+
+    /// Drop every value in the slab.
+    pub fn clear(&mut self) {
+        self.slab.clear();
+        self.order.clear();
+    }
+
+    /// Return an iterator over the keys and values of the slab, most recently
+    /// used first, without marking them as used.
+    pub fn iter(&self) -> LruIter<'_, T> {
+        LruIter {
+            slab: self,
+            key: self.order.front(),
+            remaining: self.slab.len(),
+        }
+    }
+
+    /// Consume the `LruSlab`, returning the underlying `Slab`.
+    pub fn into_inner(self) -> Slab<T> {
+        self.slab
+    }
+
+    fn push_front(&mut self, key: usize) {
+        if self.ttl.is_some() {
+            if key >= self.used.len() {
+                self.used.resize(key + 1, None);
+            }
+            self.used[key] = Some(Instant::now());
+        }
+        self.order.push_front(key);
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for LruSlab<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_map().entries(self.iter()).finish()
+    }
+}
+
+/// An iterator over the values of an `LruSlab`, most recently used first.
+///
+/// See [`LruSlab::iter`](struct.LruSlab.html#method.iter).
+pub struct LruIter<'a, T> {
+    slab: &'a LruSlab<T>,
+    key: Option<usize>,
+    remaining: usize,
+}
+
+impl<'a, T> Iterator for LruIter<'a, T> {
+    type Item = (usize, &'a T);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let key = self.key?;
+        self.key = self.slab.order.next(key);
+        self.remaining -= 1;
+        Some((key, &self.slab.slab[key]))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+impl<'a, T> ExactSizeIterator for LruIter<'a, T> {}
@@ -0,0 +1,75 @@
+//! Values padded to a cache line, for slabs shared between threads.
+
+use core::ops;
+use core::pin::Pin;
+
+/// The size of the cache lines `CachePadded` aligns to.
+pub const CACHE_LINE_SIZE: usize = 64;
+
+/// A value aligned and padded to [`CACHE_LINE_SIZE`](constant.CACHE_LINE_SIZE.html)
+/// bytes.
+///
+/// Entries of a `Slab<CachePadded<T>>` never share a cache line, so threads
+/// which each mutate their own entry don't slow each other down with false
+/// sharing.
+///
+/// # Examples
+///
+/// ```
+/// # use pinned_slab::*;
+/// use std::sync::atomic::{AtomicUsize, Ordering};
+///
+/// let mut slab = Slab::new();
+/// let (a, _) = slab.insert(CachePadded::new(AtomicUsize::new(0)));
+/// let (b, _) = slab.insert(CachePadded::new(AtomicUsize::new(0)));
+///
+/// slab[a].fetch_add(1, Ordering::Relaxed);
+/// assert_eq!(slab[a].load(Ordering::Relaxed), 1);
+///
+/// let distance = &slab[b] as *const _ as usize - &slab[a] as *const _ as usize;
+/// assert_eq!(distance, CACHE_LINE_SIZE);
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[repr(align(64))]
+pub struct CachePadded<T> {
+    value: T,
+}
+
+impl<T> CachePadded<T> {
+    /// Pad `value` to a cache line.
+    pub const fn new(value: T) -> Self {
+        CachePadded { value }
+    }
+
+    /// Return the padded value.
+    pub fn into_inner(self) -> T {
+        self.value
+    }
+
+    /// Return a pinned mutable reference to the padded value.
+    pub fn as_pin_mut(self: Pin<&mut Self>) -> Pin<&mut T> {
+        // SAFETY: The value is structurally pinned, since it is never moved
+        // out of a pinned `CachePadded`.
+        unsafe { self.map_unchecked_mut(|padded| &mut padded.value) }
+    }
+}
+
+impl<T> From<T> for CachePadded<T> {
+    fn from(value: T) -> Self {
+        CachePadded::new(value)
+    }
+}
+
+impl<T> ops::Deref for CachePadded<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.value
+    }
+}
+
+impl<T> ops::DerefMut for CachePadded<T> {
+    fn deref_mut(&mut self) -> &mut T {
+        &mut self.value
+    }
+}
@@ -0,0 +1,135 @@
+//! Spare chunks which can be shared between slabs.
+
+use crate::{trace, Chunk, ChunkAllocator, Global, Slab};
+use alloc::vec::Vec;
+use core::fmt;
+
+/// A pool of spare chunk allocations for slabs of the same `T`.
+///
+/// Slabs which are created and destroyed frequently, e.g. one per client
+/// session, can take their chunks from a pool and give them back when they
+/// are done, instead of going through the global allocator every time.
+///
+/// Chunks are only reused by slabs which need a chunk of the same size. Every
+/// chunk is still freed by the allocator it was allocated with.
+///
+/// # Examples
+///
+/// ```
+/// # use pinned_slab::*;
+/// let mut pool = ChunkPool::new();
+///
+/// for session in 0..10 {
+///     let mut slab = Slab::new();
+///     slab.reserve_chunks_from(&mut pool, 1);
+///     slab.insert(session);
+///
+///     slab.clear_into_pool(&mut pool);
+///     assert_eq!(pool.len(), 1);
+/// }
+/// ```
+pub struct ChunkPool<T, A: ChunkAllocator = Global> {
+    chunks: Vec<Chunk<T, A>>,
+}
+
+impl<T> ChunkPool<T> {
+    /// Construct a new, empty `ChunkPool`.
+    pub fn new() -> Self {
+        ChunkPool { chunks: Vec::new() }
+    }
+}
+
+impl<T, A: ChunkAllocator> ChunkPool<T, A> {
+    /// Return the number of spare chunks in the pool.
+    pub fn len(&self) -> usize {
+        self.chunks.len()
+    }
+
+    /// Return `true` if there are no spare chunks in the pool.
+    pub fn is_empty(&self) -> bool {
+        self.chunks.is_empty()
+    }
+
+    /// Free every spare chunk in the pool.
+    pub fn clear(&mut self) {
+        self.chunks.clear();
+    }
+
+    /// Take a spare chunk with `size` entries out of the pool, or allocate a
+    /// new one if there is none.
+    fn take(&mut self, size: usize, alloc: &A) -> Chunk<T, A> {
+        match self.chunks.iter().rposition(|chunk| chunk.size() == size) {
+            Some(i) => self.chunks.swap_remove(i),
+            None => Chunk::new(size, alloc.clone()),
+        }
+    }
+}
+
+impl<T, A: ChunkAllocator> Default for ChunkPool<T, A> {
+    fn default() -> Self {
+        ChunkPool { chunks: Vec::new() }
+    }
+}
+
+impl<T, A: ChunkAllocator> fmt::Debug for ChunkPool<T, A> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ChunkPool")
+            .field("len", &self.chunks.len())
+            .finish()
+    }
+}
+
+impl<T, const N: usize, A: ChunkAllocator> Slab<T, N, A> {
+    /// Allocate `n` more chunks, taking them from `pool` where possible.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use pinned_slab::*;
+    /// let mut pool = ChunkPool::new();
+    /// Slab::<i32>::with_chunks(2).clear_into_pool(&mut pool);
+    ///
+    /// let mut slab: Slab<i32> = Slab::new();
+    /// slab.reserve_chunks_from(&mut pool, 3);
+    ///
+    /// assert!(pool.is_empty());
+    /// assert_eq!(slab.capacity(), DEFAULT_CHUNK_SIZE * 3);
+    /// ```
+    pub fn reserve_chunks_from(&mut self, pool: &mut ChunkPool<T, A>, n: usize) {
+        self.chunks.reserve(self.missing_slots(n));
+        for _ in 0..n {
+            let chunk = pool.take(self.growth.chunk_len(self.free_slot()), &self.alloc);
+            self.allocate_chunk(chunk);
+        }
+    }
+
+    /// Drop every value in the slab and move all of its chunks into `pool`.
+    ///
+    /// This leaves the slab without any capacity, like
+    /// [`clear_and_free`](#method.clear_and_free).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use pinned_slab::*;
+    /// let mut pool = ChunkPool::new();
+    /// let mut slab = Slab::new();
+    ///
+    /// slab.insert("hello");
+    /// slab.clear_into_pool(&mut pool);
+    ///
+    /// assert!(slab.is_empty());
+    /// assert_eq!(slab.capacity(), 0);
+    /// assert_eq!(pool.len(), 1);
+    /// ```
+    pub fn clear_into_pool(&mut self, pool: &mut ChunkPool<T, A>) {
+        self.clear();
+        self.next = 0;
+        for (index, chunk) in self.chunks.drain(..).enumerate() {
+            if let Some(chunk) = chunk {
+                trace::chunk_freed(self.name, index, chunk.size());
+                pool.chunks.push(chunk);
+            }
+        }
+    }
+}
@@ -0,0 +1,361 @@
+//! A drop-in replacement for the [`slab`](https://docs.rs/slab) crate's
+//! `Slab`.
+//!
+//! [`compat::Slab`](struct.Slab.html) has the same methods as `slab::Slab`,
+//! so code written against the `slab` crate compiles after changing its
+//! imports from `slab::` to `pinned_slab::compat::`. Methods which hand out
+//! mutable references or move values out of the slab are only available for
+//! `Unpin` types, since the values are pinned.
+//!
+//! # Examples
+//!
+//! ```
+//! use pinned_slab::compat::Slab;
+//!
+//! let mut slab = Slab::new();
+//!
+//! let hello = slab.insert("hello");
+//! let entry = slab.vacant_entry();
+//! let world = entry.key();
+//! entry.insert("world");
+//!
+//! *slab.get_mut(hello).unwrap() = "hi";
+//! assert_eq!(slab[hello], "hi");
+//! assert_eq!(slab.remove(world), "world");
+//! ```
+
+use crate::IterPinMut;
+use core::iter::FromIterator;
+use core::ops;
+use core::pin::Pin;
+
+/// A slab with the same methods as `slab::Slab`.
+///
+/// See the [module documentation](index.html).
+#[derive(Debug, Clone)]
+pub struct Slab<T> {
+    slab: crate::Slab<T>,
+}
+
+impl<T> Slab<T> {
+    /// Construct a new, empty `Slab`.
+    pub fn new() -> Self {
+        Slab {
+            slab: crate::Slab::new(),
+        }
+    }
+
+    /// Construct a new, empty `Slab` with room for at least `capacity`
+    /// values.
+    pub fn with_capacity(capacity: usize) -> Self {
+        Slab {
+            slab: crate::Slab::with_capacity(capacity),
+        }
+    }
+
+    /// Return the pinned slab this wraps.
+    pub fn into_inner(self) -> crate::Slab<T> {
+        self.slab
+    }
+
+    /// Return the number of values the slab can store without allocating.
+    pub fn capacity(&self) -> usize {
+        self.slab.capacity()
+    }
+
+    /// Reserve capacity for at least `additional` more values.
+    pub fn reserve(&mut self, additional: usize) {
+        self.slab.reserve(additional);
+    }
+
+    /// Reserve capacity for `additional` more values, allocating as little as
+    /// possible.
+    pub fn reserve_exact(&mut self, additional: usize) {
+        self.slab.reserve_exact(additional);
+    }
+
+    /// Free as much unused capacity as possible.
+    pub fn shrink_to_fit(&mut self) {
+        self.slab.shrink_to_fit();
+    }
+
+    /// Drop every value in the slab.
+    pub fn clear(&mut self) {
+        self.slab.clear();
+    }
+
+    /// Return the number of stored values.
+    pub fn len(&self) -> usize {
+        self.slab.len()
+    }
+
+    /// Return `true` if there are no values stored in the slab.
+    pub fn is_empty(&self) -> bool {
+        self.slab.is_empty()
+    }
+
+    /// Return an iterator over the keys and values of the slab.
+    pub fn iter(&self) -> crate::Iter<'_, T> {
+        self.slab.iter()
+    }
+
+    /// Return a reference to the value associated with the given key.
+    pub fn get(&self, key: usize) -> Option<&T> {
+        self.slab.get(key)
+    }
+
+    /// Return a reference to the value associated with the given key without
+    /// checking that it is occupied.
+    ///
+    /// # Safety
+    ///
+    /// `key` must be associated with a value.
+    pub unsafe fn get_unchecked(&self, key: usize) -> &T {
+        self.slab.get_unchecked(key)
+    }
+
+    /// Return the key of the value `present_element` refers to.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `present_element` isn't stored in the slab.
+    pub fn key_of(&self, present_element: &T) -> usize {
+        self.slab
+            .key_of_ptr(present_element)
+            .expect("element not in slab")
+    }
+
+    /// Insert a value in the slab, returning the key assigned to the value.
+    pub fn insert(&mut self, val: T) -> usize {
+        self.slab.insert(val).0
+    }
+
+    /// Return a handle to the vacant entry the next value will be inserted
+    /// into.
+    pub fn vacant_entry(&mut self) -> VacantEntry<'_, T> {
+        VacantEntry {
+            key: self.slab.next_key(),
+            slab: &mut self.slab,
+        }
+    }
+
+    /// Return `true` if a value is associated with the given key.
+    pub fn contains(&self, key: usize) -> bool {
+        self.slab.contains(key)
+    }
+}
+
+impl<T: Unpin> Slab<T> {
+    /// Remove and return the value associated with the given key, or `None`
+    /// if there is no such value.
+    pub fn try_remove(&mut self, key: usize) -> Option<T> {
+        if self.slab.contains(key) {
+            Some(self.slab.remove(key))
+        } else {
+            None
+        }
+    }
+
+    /// Remove and return the value associated with the given key.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `key` is not associated with a value.
+    pub fn remove(&mut self, key: usize) -> T {
+        self.slab.remove(key)
+    }
+
+    /// Return a draining iterator that removes and yields every value.
+    pub fn drain(&mut self) -> Drain<'_, T> {
+        Drain {
+            inner: self.slab.drain(),
+        }
+    }
+
+    /// Return an iterator over the keys and mutable references to the values
+    /// of the slab.
+    pub fn iter_mut(&mut self) -> IterMut<'_, T> {
+        IterMut {
+            inner: self.slab.iter_pin_mut(),
+        }
+    }
+
+    /// Return a mutable reference to the value associated with the given key.
+    pub fn get_mut(&mut self, key: usize) -> Option<&mut T> {
+        self.slab.get_pin_mut(key).map(Pin::into_inner)
+    }
+
+    /// Return mutable references to the values associated with two distinct
+    /// keys, or `None` if either key is vacant or they are equal.
+    pub fn get2_mut(&mut self, key1: usize, key2: usize) -> Option<(&mut T, &mut T)> {
+        let (val1, val2) = self.slab.get2_pin_mut(key1, key2)?;
+        Some((Pin::into_inner(val1), Pin::into_inner(val2)))
+    }
+
+    /// Return a mutable reference to the value associated with the given key
+    /// without checking that it is occupied.
+    ///
+    /// # Safety
+    ///
+    /// `key` must be associated with a value.
+    pub unsafe fn get_unchecked_mut(&mut self, key: usize) -> &mut T {
+        self.slab.get_unchecked_mut(key)
+    }
+
+    /// Retain only the values for which `f` returns `true`.
+    pub fn retain<F>(&mut self, mut f: F)
+    where
+        F: FnMut(usize, &mut T) -> bool,
+    {
+        self.slab.retain(|key, val| f(key, Pin::into_inner(val)))
+    }
+}
+
+impl<T> Default for Slab<T> {
+    fn default() -> Self {
+        Slab::new()
+    }
+}
+
+impl<T> From<crate::Slab<T>> for Slab<T> {
+    fn from(slab: crate::Slab<T>) -> Self {
+        Slab { slab }
+    }
+}
+
+impl<T> ops::Index<usize> for Slab<T> {
+    type Output = T;
+
+    fn index(&self, key: usize) -> &T {
+        &self.slab[key]
+    }
+}
+
+impl<T: Unpin> ops::IndexMut<usize> for Slab<T> {
+    fn index_mut(&mut self, key: usize) -> &mut T {
+        self.get_mut(key).expect("invalid key")
+    }
+}
+
+impl<T> FromIterator<(usize, T)> for Slab<T> {
+    /// Store every value at its key. If a key appears more than once, the
+    /// last value wins.
+    fn from_iter<I: IntoIterator<Item = (usize, T)>>(iter: I) -> Self {
+        let mut slab = crate::Slab::new();
+        for (key, val) in iter {
+            slab.remove_in_place(key);
+            slab.occupy(key, val);
+        }
+        slab.rebuild_free_list();
+        Slab { slab }
+    }
+}
+
+impl<T: Unpin> IntoIterator for Slab<T> {
+    type Item = (usize, T);
+    type IntoIter = crate::IntoIter<T>;
+
+    fn into_iter(self) -> crate::IntoIter<T> {
+        self.slab.into_iter()
+    }
+}
+
+impl<'a, T> IntoIterator for &'a Slab<T> {
+    type Item = (usize, &'a T);
+    type IntoIter = crate::Iter<'a, T>;
+
+    fn into_iter(self) -> crate::Iter<'a, T> {
+        self.iter()
+    }
+}
+
+impl<'a, T: Unpin> IntoIterator for &'a mut Slab<T> {
+    type Item = (usize, &'a mut T);
+    type IntoIter = IterMut<'a, T>;
+
+    fn into_iter(self) -> IterMut<'a, T> {
+        self.iter_mut()
+    }
+}
+
+/// A handle to a vacant entry of a [`Slab`](struct.Slab.html).
+///
+/// See [`Slab::vacant_entry`](struct.Slab.html#method.vacant_entry).
+#[derive(Debug)]
+pub struct VacantEntry<'a, T> {
+    slab: &'a mut crate::Slab<T>,
+    key: usize,
+}
+
+impl<'a, T> VacantEntry<'a, T> {
+    /// Return the key the value will be inserted at.
+    pub fn key(&self) -> usize {
+        self.key
+    }
+
+    /// Insert a value at the key of the entry, returning a reference to it.
+    pub fn insert(self, val: T) -> &'a mut T
+    where
+        T: Unpin,
+    {
+        let (key, _) = self.slab.insert(val);
+        debug_assert_eq!(key, self.key);
+        Pin::into_inner(self.slab.get_pin_mut(key).unwrap())
+    }
+}
+
+/// A mutable iterator over the values of a [`Slab`](struct.Slab.html).
+///
+/// See [`Slab::iter_mut`](struct.Slab.html#method.iter_mut).
+pub struct IterMut<'a, T> {
+    inner: IterPinMut<'a, T>,
+}
+
+impl<'a, T: Unpin> Iterator for IterMut<'a, T> {
+    type Item = (usize, &'a mut T);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (key, val) = self.inner.next()?;
+        Some((key, Pin::into_inner(val)))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<'a, T: Unpin> DoubleEndedIterator for IterMut<'a, T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        let (key, val) = self.inner.next_back()?;
+        Some((key, Pin::into_inner(val)))
+    }
+}
+
+impl<'a, T: Unpin> ExactSizeIterator for IterMut<'a, T> {}
+
+/// A draining iterator over the values of a [`Slab`](struct.Slab.html).
+///
+/// See [`Slab::drain`](struct.Slab.html#method.drain).
+pub struct Drain<'a, T> {
+    inner: crate::Drain<'a, T>,
+}
+
+impl<'a, T: Unpin> Iterator for Drain<'a, T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        self.inner.next().map(|(_, val)| val)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<'a, T: Unpin> DoubleEndedIterator for Drain<'a, T> {
+    fn next_back(&mut self) -> Option<T> {
+        self.inner.next_back().map(|(_, val)| val)
+    }
+}
+
+impl<'a, T: Unpin> ExactSizeIterator for Drain<'a, T> {}
@@ -0,0 +1,336 @@
+//! A slab whose clones and snapshots share their chunks until they are
+//! modified.
+
+use crate::{ChunkAllocator, Global, GrowthPolicy, Slab, DEFAULT_CHUNK_SIZE};
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+use core::fmt;
+use core::ops;
+use core::pin::Pin;
+
+/// A slab which shares its chunks with its clones, and only copies a chunk
+/// once a clone modifies it.
+///
+/// Cloning a `CowSlab` takes `O(chunks)` time instead of `O(capacity)`, which
+/// suits taking a snapshot of a large slab which then mostly changes in a few
+/// places. Modifying a value, inserting or removing copies the chunk of the
+/// key first if it is shared, which requires `T: Clone`.
+///
+/// Keys are assigned like in a [`Slab`](struct.Slab.html) with chunks of `N`
+/// entries, and values don't move while their chunk isn't shared. Once a
+/// shared chunk is copied, the values of this slab in that chunk are fresh
+/// clones at new addresses, while the old values stay where they are in the
+/// other clones.
+///
+/// # Examples
+///
+/// ```
+/// # use pinned_slab::*;
+/// let mut slab: CowSlab<String> = CowSlab::new();
+/// let a = slab.insert("a".to_string());
+/// let b = slab.insert("b".to_string());
+///
+/// let snapshot = slab.clone();
+/// *slab.get_pin_mut(a).unwrap() = "changed".to_string();
+/// slab.remove(b);
+///
+/// assert_eq!(snapshot[a], "a");
+/// assert_eq!(snapshot[b], "b");
+/// assert_eq!(slab[a], "changed");
+/// assert!(!slab.contains(b));
+/// ```
+pub struct CowSlab<T, const N: usize = DEFAULT_CHUNK_SIZE, A: ChunkAllocator = Global> {
+    /// Chunk `i` holds the keys `i * N..(i + 1) * N`, as the keys `0..N` of
+    /// a slab which never grows past one chunk.
+    chunks: Vec<Arc<Slab<T, N, A>>>,
+    alloc: A,
+    len: usize,
+    /// No chunk before this one has a vacant entry.
+    vacant: usize,
+}
+
+impl<T, const N: usize> CowSlab<T, N> {
+    /// Construct a new, empty `CowSlab`.
+    ///
+    /// This does not allocate.
+    pub fn new() -> Self {
+        CowSlab::new_in(Global)
+    }
+}
+
+impl<T, const N: usize, A: ChunkAllocator> CowSlab<T, N, A> {
+    /// Construct a new, empty `CowSlab` whose chunks are allocated with
+    /// `alloc`.
+    pub fn new_in(alloc: A) -> Self {
+        CowSlab {
+            chunks: Vec::new(),
+            alloc,
+            len: 0,
+            vacant: 0,
+        }
+    }
+
+    /// Return the number of stored values.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Return `true` if there are no values stored in the slab.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Return the number of values the slab can store without allocating.
+    pub fn capacity(&self) -> usize {
+        self.chunks.len() * N
+    }
+
+    /// Return the number of chunks which are shared with other clones, and
+    /// would be copied if they were modified.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use pinned_slab::*;
+    /// let mut slab: CowSlab<u32, 4> = CowSlab::new();
+    /// for i in 0..8 {
+    ///     slab.insert(i);
+    /// }
+    ///
+    /// let snapshot = slab.clone();
+    /// assert_eq!(slab.shared_chunks(), 2);
+    ///
+    /// slab.remove(5);
+    /// assert_eq!(slab.shared_chunks(), 1);
+    /// assert_eq!(snapshot.shared_chunks(), 1);
+    /// ```
+    pub fn shared_chunks(&self) -> usize {
+        self.chunks
+            .iter()
+            .filter(|chunk| Arc::strong_count(chunk) > 1)
+            .count()
+    }
+
+    /// Return `true` if a value is associated with the given key.
+    pub fn contains(&self, key: usize) -> bool {
+        self.get(key).is_some()
+    }
+
+    /// Return a reference to the value associated with the given key.
+    pub fn get(&self, key: usize) -> Option<&T> {
+        self.chunks.get(key / N)?.get(key % N)
+    }
+
+    /// Return a pinned reference to the value associated with the given key.
+    pub fn get_pin(&self, key: usize) -> Option<Pin<&T>> {
+        // SAFETY: Values never move while they are in a chunk.
+        self.get(key).map(|val| unsafe { Pin::new_unchecked(val) })
+    }
+
+    /// Return an iterator over the keys and values of the slab, in ascending
+    /// key order.
+    pub fn iter(&self) -> impl Iterator<Item = (usize, &T)> + '_ {
+        iter_chunks(&self.chunks)
+    }
+
+    /// Return a read-only snapshot of the values currently in the slab.
+    ///
+    /// The snapshot shares every chunk with the slab, so taking it takes
+    /// `O(chunks)` time, and the slab can still be modified while it is
+    /// alive. Modifying the slab copies the chunk it touches first, like for
+    /// a clone, so the snapshot keeps seeing the values as they were.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use pinned_slab::*;
+    /// let mut slab: CowSlab<u32> = CowSlab::new();
+    /// for i in 0..4 {
+    ///     slab.insert(i);
+    /// }
+    ///
+    /// let snapshot = slab.snapshot();
+    /// for (key, val) in snapshot.iter() {
+    ///     if val % 2 == 0 {
+    ///         slab.remove(key);
+    ///         slab.insert(val + 10);
+    ///     }
+    /// }
+    ///
+    /// assert_eq!(snapshot.iter().map(|(_, val)| *val).collect::<Vec<_>>(), [0, 1, 2, 3]);
+    /// assert_eq!(slab.iter().map(|(_, val)| *val).collect::<Vec<_>>(), [10, 1, 12, 3]);
+    /// ```
+    pub fn snapshot(&self) -> SlabSnapshot<T, N, A> {
+        SlabSnapshot {
+            chunks: self.chunks.clone(),
+            len: self.len,
+        }
+    }
+}
+
+impl<T: Clone, const N: usize, A: ChunkAllocator> CowSlab<T, N, A> {
+    /// Return a pinned mutable reference to the value associated with the
+    /// given key, copying its chunk first if it is shared.
+    pub fn get_pin_mut(&mut self, key: usize) -> Option<Pin<&mut T>> {
+        if !self.contains(key) {
+            return None;
+        }
+
+        Arc::make_mut(&mut self.chunks[key / N]).get_pin_mut(key % N)
+    }
+
+    /// Insert a value in the slab, copying the chunk it goes to first if it
+    /// is shared, and return the key assigned to it.
+    pub fn insert(&mut self, val: T) -> usize {
+        while self.vacant < self.chunks.len() && self.chunks[self.vacant].len() == N {
+            self.vacant += 1;
+        }
+        if self.vacant == self.chunks.len() {
+            let chunk = Slab::with_growth_policy_in(GrowthPolicy::Fixed, self.alloc.clone());
+            self.chunks.push(Arc::new(chunk));
+        }
+
+        let (key, _) = Arc::make_mut(&mut self.chunks[self.vacant]).insert(val);
+        self.len += 1;
+        self.vacant * N + key
+    }
+
+    /// Remove and return the value associated with the given key, copying
+    /// its chunk first if it is shared.
+    ///
+    /// The value is moved out, so it must be `Unpin`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `key` is not associated with a value.
+    pub fn remove(&mut self, key: usize) -> T
+    where
+        T: Unpin,
+    {
+        if !self.contains(key) {
+            panic!("invalid key");
+        }
+
+        let val = Arc::make_mut(&mut self.chunks[key / N]).remove(key % N);
+        self.len -= 1;
+        self.vacant = self.vacant.min(key / N);
+        val
+    }
+
+    /// Drop the value associated with the given key in place, copying its
+    /// chunk first if it is shared, and return whether there was such a
+    /// value.
+    pub fn remove_in_place(&mut self, key: usize) -> bool {
+        if !self.contains(key) {
+            return false;
+        }
+
+        Arc::make_mut(&mut self.chunks[key / N]).remove_in_place(key % N);
+        self.len -= 1;
+        self.vacant = self.vacant.min(key / N);
+        true
+    }
+}
+
+/// A read-only view of the values a [`CowSlab`](struct.CowSlab.html) held
+/// when [`snapshot`](struct.CowSlab.html#method.snapshot) was called.
+///
+/// The snapshot keeps the chunks it shares with the slab alive, and doesn't
+/// borrow the slab, so it can be iterated while the slab changes, or sent to
+/// another thread. Clones of a snapshot share the same chunks.
+pub struct SlabSnapshot<T, const N: usize = DEFAULT_CHUNK_SIZE, A: ChunkAllocator = Global> {
+    chunks: Vec<Arc<Slab<T, N, A>>>,
+    len: usize,
+}
+
+impl<T, const N: usize, A: ChunkAllocator> SlabSnapshot<T, N, A> {
+    /// Return the number of values in the snapshot.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Return `true` if there are no values in the snapshot.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Return `true` if a value was associated with the given key.
+    pub fn contains(&self, key: usize) -> bool {
+        self.get(key).is_some()
+    }
+
+    /// Return a reference to the value associated with the given key.
+    pub fn get(&self, key: usize) -> Option<&T> {
+        self.chunks.get(key / N)?.get(key % N)
+    }
+
+    /// Return an iterator over the keys and values of the snapshot, in
+    /// ascending key order.
+    pub fn iter(&self) -> impl Iterator<Item = (usize, &T)> + '_ {
+        iter_chunks(&self.chunks)
+    }
+}
+
+/// Return an iterator over the values of the chunks of a `CowSlab`.
+fn iter_chunks<T, const N: usize, A: ChunkAllocator>(
+    chunks: &[Arc<Slab<T, N, A>>],
+) -> impl Iterator<Item = (usize, &T)> + '_ {
+    chunks
+        .iter()
+        .enumerate()
+        .flat_map(|(i, chunk)| chunk.iter().map(move |(key, val)| (i * N + key, val)))
+}
+
+impl<T, const N: usize, A: ChunkAllocator> Clone for CowSlab<T, N, A> {
+    fn clone(&self) -> Self {
+        CowSlab {
+            chunks: self.chunks.clone(),
+            alloc: self.alloc.clone(),
+            len: self.len,
+            vacant: self.vacant,
+        }
+    }
+}
+
+impl<T, const N: usize> Default for CowSlab<T, N> {
+    fn default() -> Self {
+        CowSlab::new()
+    }
+}
+
+impl<T: fmt::Debug, const N: usize, A: ChunkAllocator> fmt::Debug for CowSlab<T, N, A> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_map().entries(self.iter()).finish()
+    }
+}
+
+impl<T, const N: usize, A: ChunkAllocator> ops::Index<usize> for CowSlab<T, N, A> {
+    type Output = T;
+
+    fn index(&self, key: usize) -> &T {
+        self.get(key).expect("invalid key")
+    }
+}
+
+impl<T, const N: usize, A: ChunkAllocator> Clone for SlabSnapshot<T, N, A> {
+    fn clone(&self) -> Self {
+        SlabSnapshot {
+            chunks: self.chunks.clone(),
+            len: self.len,
+        }
+    }
+}
+
+impl<T: fmt::Debug, const N: usize, A: ChunkAllocator> fmt::Debug for SlabSnapshot<T, N, A> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_map().entries(self.iter()).finish()
+    }
+}
+
+impl<T, const N: usize, A: ChunkAllocator> ops::Index<usize> for SlabSnapshot<T, N, A> {
+    type Output = T;
+
+    fn index(&self, key: usize) -> &T {
+        self.get(key).expect("invalid key")
+    }
+}
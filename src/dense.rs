@@ -0,0 +1,279 @@
+//! A slab which keeps its values in a dense array.
+
+use crate::NIL;
+use alloc::vec::Vec;
+use core::fmt;
+use core::iter::{Copied, Zip};
+use core::ops;
+use core::slice;
+
+#[derive(Debug, Clone, Copy)]
+enum Slot {
+    /// The index of the value in the dense array.
+    Occupied(usize),
+    /// The next vacant key.
+    Vacant(usize),
+}
+
+/// A slab which stores its values next to each other in a single `Vec`, with
+/// an index from keys to values, like `DenseSlotMap` from the `slotmap`
+/// crate.
+///
+/// Iterating over the values is as fast as iterating over a slice, no matter
+/// how many values have been removed. In exchange, values are **not**
+/// pinned: removing a value moves the last value into its place, so only use
+/// this when addresses don't need to be stable. Keys work like those of a
+/// [`Slab`](struct.Slab.html): they stay valid until their value is removed,
+/// and are then reused.
+///
+/// # Examples
+///
+/// ```
+/// # use pinned_slab::*;
+/// let mut slab = DenseSlab::new();
+///
+/// let a = slab.insert("a");
+/// let b = slab.insert("b");
+/// let c = slab.insert("c");
+/// slab.remove(a);
+///
+/// // The last value moved into the vacated place.
+/// assert_eq!(slab.values(), ["c", "b"]);
+/// assert_eq!(slab[c], "c");
+/// assert_eq!(slab.iter().collect::<Vec<_>>(), [(c, &"c"), (b, &"b")]);
+/// ```
+#[derive(Clone)]
+pub struct DenseSlab<T> {
+    values: Vec<T>,
+    /// The key of each value.
+    keys: Vec<usize>,
+    slots: Vec<Slot>,
+    /// The most recently vacated key.
+    next_free: usize,
+}
+
+impl<T> DenseSlab<T> {
+    /// Construct a new, empty `DenseSlab`.
+    pub fn new() -> Self {
+        DenseSlab::with_capacity(0)
+    }
+
+    /// Construct a new, empty `DenseSlab` with room for `capacity` values.
+    pub fn with_capacity(capacity: usize) -> Self {
+        DenseSlab {
+            values: Vec::with_capacity(capacity),
+            keys: Vec::with_capacity(capacity),
+            slots: Vec::with_capacity(capacity),
+            next_free: NIL,
+        }
+    }
+
+    /// Return the number of stored values.
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    /// Return `true` if there are no values stored in the slab.
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    /// Return `true` if a value is associated with the given key.
+    pub fn contains(&self, key: usize) -> bool {
+        self.index(key).is_some()
+    }
+
+    /// Insert a value in the slab, returning the key assigned to the value.
+    pub fn insert(&mut self, val: T) -> usize {
+        let index = self.values.len();
+        let key = match self.next_free {
+            NIL => {
+                self.slots.push(Slot::Occupied(index));
+                self.slots.len() - 1
+            }
+            key => {
+                if let Slot::Vacant(next) = self.slots[key] {
+                    self.next_free = next;
+                }
+                self.slots[key] = Slot::Occupied(index);
+                key
+            }
+        };
+        self.values.push(val);
+        self.keys.push(key);
+        key
+    }
+
+    /// Return a reference to the value associated with the given key.
+    pub fn get(&self, key: usize) -> Option<&T> {
+        Some(&self.values[self.index(key)?])
+    }
+
+    /// Return a mutable reference to the value associated with the given key.
+    pub fn get_mut(&mut self, key: usize) -> Option<&mut T> {
+        let index = self.index(key)?;
+        Some(&mut self.values[index])
+    }
+
+    /// Remove and return the value associated with the given key, or `None`
+    /// if there is no such value.
+    ///
+    /// The last value is moved into the place of the removed value.
+    pub fn remove(&mut self, key: usize) -> Option<T> {
+        let index = self.index(key)?;
+        let val = self.values.swap_remove(index);
+        self.keys.swap_remove(index);
+        if let Some(&moved) = self.keys.get(index) {
+            self.slots[moved] = Slot::Occupied(index);
+        }
+        self.slots[key] = Slot::Vacant(self.next_free);
+        self.next_free = key;
+        Some(val)
+    }
+
+    /// Drop every value in the slab.
+    pub fn clear(&mut self) {
+        self.values.clear();
+        self.keys.clear();
+        self.slots.clear();
+        self.next_free = NIL;
+    }
+
+    /// Return the values as a slice, in no particular order.
+    pub fn values(&self) -> &[T] {
+        &self.values
+    }
+
+    /// Return the values as a mutable slice, in the same order as
+    /// [`values`](#method.values).
+    pub fn values_mut(&mut self) -> &mut [T] {
+        &mut self.values
+    }
+
+    /// Return the keys of the values, in the same order as
+    /// [`values`](#method.values).
+    pub fn keys(&self) -> &[usize] {
+        &self.keys
+    }
+
+    /// Return an iterator over the keys and values of the slab, in the same
+    /// order as [`values`](#method.values).
+    pub fn iter(&self) -> DenseIter<'_, T> {
+        DenseIter {
+            inner: self.keys.iter().copied().zip(self.values.iter()),
+        }
+    }
+
+    /// Return an iterator over the keys and mutable references to the values
+    /// of the slab, in the same order as [`values`](#method.values).
+    pub fn iter_mut(&mut self) -> DenseIterMut<'_, T> {
+        DenseIterMut {
+            inner: self.keys.iter().copied().zip(self.values.iter_mut()),
+        }
+    }
+
+    fn index(&self, key: usize) -> Option<usize> {
+        match self.slots.get(key)? {
+            Slot::Occupied(index) => Some(*index),
+            Slot::Vacant(_) => None,
+        }
+    }
+}
+
+impl<T> Default for DenseSlab<T> {
+    fn default() -> Self {
+        DenseSlab::new()
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for DenseSlab<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_map().entries(self.iter()).finish()
+    }
+}
+
+impl<T> ops::Index<usize> for DenseSlab<T> {
+    type Output = T;
+
+    fn index(&self, key: usize) -> &T {
+        self.get(key).expect("invalid key")
+    }
+}
+
+impl<T> ops::IndexMut<usize> for DenseSlab<T> {
+    fn index_mut(&mut self, key: usize) -> &mut T {
+        self.get_mut(key).expect("invalid key")
+    }
+}
+
+impl<'a, T> IntoIterator for &'a DenseSlab<T> {
+    type Item = (usize, &'a T);
+    type IntoIter = DenseIter<'a, T>;
+
+    fn into_iter(self) -> DenseIter<'a, T> {
+        self.iter()
+    }
+}
+
+impl<'a, T> IntoIterator for &'a mut DenseSlab<T> {
+    type Item = (usize, &'a mut T);
+    type IntoIter = DenseIterMut<'a, T>;
+
+    fn into_iter(self) -> DenseIterMut<'a, T> {
+        self.iter_mut()
+    }
+}
+
+/// An iterator over the values of a `DenseSlab`.
+///
+/// See [`DenseSlab::iter`](struct.DenseSlab.html#method.iter).
+pub struct DenseIter<'a, T> {
+    inner: Zip<Copied<slice::Iter<'a, usize>>, slice::Iter<'a, T>>,
+}
+
+impl<'a, T> Iterator for DenseIter<'a, T> {
+    type Item = (usize, &'a T);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<'a, T> DoubleEndedIterator for DenseIter<'a, T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.inner.next_back()
+    }
+}
+
+impl<'a, T> ExactSizeIterator for DenseIter<'a, T> {}
+
+/// A mutable iterator over the values of a `DenseSlab`.
+///
+/// See [`DenseSlab::iter_mut`](struct.DenseSlab.html#method.iter_mut).
+pub struct DenseIterMut<'a, T> {
+    inner: Zip<Copied<slice::Iter<'a, usize>>, slice::IterMut<'a, T>>,
+}
+
+impl<'a, T> Iterator for DenseIterMut<'a, T> {
+    type Item = (usize, &'a mut T);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<'a, T> DoubleEndedIterator for DenseIterMut<'a, T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.inner.next_back()
+    }
+}
+
+impl<'a, T> ExactSizeIterator for DenseIterMut<'a, T> {}
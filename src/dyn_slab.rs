@@ -0,0 +1,249 @@
+//! A slab of trait objects which are stored inline where they fit.
+
+use crate::Slab;
+use alloc::boxed::Box;
+use core::fmt;
+use core::marker::PhantomPinned;
+use core::mem::{self, MaybeUninit};
+use core::pin::Pin;
+use core::ptr::{self, NonNull};
+
+/// The default number of bytes a value may take to be stored inline in a
+/// [`DynSlab`](struct.DynSlab.html).
+pub const DEFAULT_INLINE_SIZE: usize = 64;
+
+/// Insert a value into a [`DynSlab`](struct.DynSlab.html), coercing it to the
+/// trait object type of the slab, and return its key.
+///
+/// This is a safe wrapper around
+/// [`DynSlab::insert_with`](struct.DynSlab.html#method.insert_with).
+///
+/// # Examples
+///
+/// ```
+/// # use pinned_slab::*;
+/// use std::fmt::Display;
+///
+/// let mut slab: DynSlab<dyn Display> = DynSlab::new();
+/// let key = dyn_insert!(slab, 42);
+/// assert_eq!(slab.get(key).unwrap().to_string(), "42");
+/// ```
+///
+/// The value must coerce to `D`, so a sized `D` only accepts values of
+/// exactly that type:
+///
+/// ```compile_fail
+/// # use pinned_slab::*;
+/// let mut slab: DynSlab<u64> = DynSlab::new();
+/// let key = dyn_insert!(slab, 1u8);
+/// ```
+#[macro_export]
+macro_rules! dyn_insert {
+    ($slab:expr, $val:expr) => {
+        // SAFETY: The closure returns its argument through an implicit
+        // coercion to `*mut D`, which for raw pointers can only unsize them.
+        // Unlike an `as` cast, this fails to compile if `D` is sized and
+        // differs from the type of the value.
+        unsafe { $slab.insert_with($val, |ptr| -> *mut _ { ptr }) }
+    };
+}
+
+/// Storage for a value of up to `SIZE` bytes.
+#[repr(C, align(16))]
+struct Inline<const SIZE: usize>([MaybeUninit<u8>; SIZE]);
+
+struct Slot<D: ?Sized, const SIZE: usize> {
+    inline: Inline<SIZE>,
+    /// The value, which is either in `inline` or boxed. This is only `None`
+    /// while the slot is being filled or emptied.
+    value: Option<NonNull<D>>,
+    boxed: bool,
+    /// `value` may point into `inline`.
+    _pinned: PhantomPinned,
+}
+
+impl<D: ?Sized, const SIZE: usize> Slot<D, SIZE> {
+    /// Drop the value of the slot in place.
+    fn clear(self: Pin<&mut Self>) {
+        // SAFETY: The value is dropped in place, and the slot is never moved.
+        let this = unsafe { self.get_unchecked_mut() };
+        if let Some(value) = this.value.take() {
+            // SAFETY: The value was boxed or written to `inline` by
+            // `DynSlab::insert_with`, and is dropped only once.
+            unsafe {
+                if this.boxed {
+                    drop(Box::from_raw(value.as_ptr()));
+                } else {
+                    ptr::drop_in_place(value.as_ptr());
+                }
+            }
+        }
+    }
+}
+
+impl<D: ?Sized, const SIZE: usize> Drop for Slot<D, SIZE> {
+    fn drop(&mut self) {
+        // SAFETY: `drop` is called on the slot where it is pinned.
+        unsafe { Pin::new_unchecked(self) }.clear();
+    }
+}
+
+/// A slab of trait objects, e.g. `DynSlab<dyn Future<Output = ()>>`, which
+/// stores each value inline in its entry if it takes at most `SIZE` bytes,
+/// and boxes it otherwise.
+///
+/// This saves a `Box<dyn Trait>` allocation per value for small values,
+/// which would otherwise defeat the point of pooling them in a slab. Values
+/// are pinned just like in a [`Slab`](struct.Slab.html), whether they are
+/// inline or not.
+///
+/// Values are inserted with the [`dyn_insert!`](macro.dyn_insert.html) macro,
+/// which coerces them to `D`.
+///
+/// # Examples
+///
+/// ```
+/// # use pinned_slab::*;
+/// use std::fmt::Debug;
+///
+/// let mut slab: DynSlab<dyn Debug, 16> = DynSlab::new();
+/// let small = dyn_insert!(slab, 7u8);
+/// let large = dyn_insert!(slab, [0u64; 8]);
+///
+/// assert_eq!(slab.is_inline(small), Some(true));
+/// assert_eq!(slab.is_inline(large), Some(false));
+/// assert_eq!(format!("{:?}", slab.get(small).unwrap()), "7");
+///
+/// assert!(slab.remove(large));
+/// assert_eq!(slab.len(), 1);
+/// ```
+pub struct DynSlab<D: ?Sized, const SIZE: usize = DEFAULT_INLINE_SIZE> {
+    slab: Slab<Slot<D, SIZE>>,
+}
+
+// SAFETY: `DynSlab` owns its values like a `Slab<Box<D>>` would.
+unsafe impl<D: ?Sized + Send, const SIZE: usize> Send for DynSlab<D, SIZE> {}
+unsafe impl<D: ?Sized + Sync, const SIZE: usize> Sync for DynSlab<D, SIZE> {}
+
+impl<D: ?Sized, const SIZE: usize> DynSlab<D, SIZE> {
+    /// Construct a new, empty `DynSlab`.
+    pub fn new() -> Self {
+        DynSlab { slab: Slab::new() }
+    }
+
+    /// Return the number of stored values.
+    pub fn len(&self) -> usize {
+        self.slab.len()
+    }
+
+    /// Return `true` if there are no values stored in the slab.
+    pub fn is_empty(&self) -> bool {
+        self.slab.is_empty()
+    }
+
+    /// Return `true` if a value is associated with the given key.
+    pub fn contains(&self, key: usize) -> bool {
+        self.slab.contains(key)
+    }
+
+    /// Insert a value in the slab, returning its key. `coerce` must unsize a
+    /// pointer to the value into a pointer to `D`.
+    ///
+    /// Prefer the [`dyn_insert!`](macro.dyn_insert.html) macro, which is
+    /// safe.
+    ///
+    /// # Safety
+    ///
+    /// `coerce` must return its argument, unsized to `D`, e.g. with
+    /// `|ptr| ptr as *mut dyn Trait`.
+    pub unsafe fn insert_with<T, F>(&mut self, val: T, coerce: F) -> usize
+    where
+        F: FnOnce(*mut T) -> *mut D,
+    {
+        let fits =
+            mem::size_of::<T>() <= SIZE && mem::align_of::<T>() <= mem::align_of::<Inline<SIZE>>();
+        let (key, _) = self.slab.insert(Slot {
+            inline: Inline([MaybeUninit::uninit(); SIZE]),
+            value: None,
+            boxed: !fits,
+            _pinned: PhantomPinned,
+        });
+
+        // SAFETY: The slot is pinned, so a pointer into its inline storage
+        // stays valid until the slot is dropped.
+        let slot = self.slab.get_pin_mut(key).unwrap().get_unchecked_mut();
+        let ptr = if fits {
+            let ptr = slot.inline.0.as_mut_ptr() as *mut T;
+            ptr.write(val);
+            ptr
+        } else {
+            Box::into_raw(Box::new(val))
+        };
+        slot.value = Some(NonNull::new_unchecked(coerce(ptr)));
+        key
+    }
+
+    /// Return a pinned reference to the value associated with the given key,
+    /// or `None` if there is no such value.
+    pub fn get(&self, key: usize) -> Option<Pin<&D>> {
+        let value = self.slab.get(key)?.value?;
+        // SAFETY: The value lives as long as its slot, and never moves.
+        Some(unsafe { Pin::new_unchecked(value.as_ref()) })
+    }
+
+    /// Return a pinned mutable reference to the value associated with the
+    /// given key, or `None` if there is no such value.
+    pub fn get_pin_mut(&mut self, key: usize) -> Option<Pin<&mut D>> {
+        let mut value = self.slab.get(key)?.value?;
+        // SAFETY: The slab is borrowed mutably, so the value isn't borrowed
+        // anywhere else.
+        Some(unsafe { Pin::new_unchecked(value.as_mut()) })
+    }
+
+    /// Return whether the value associated with the given key is stored
+    /// inline, or `None` if there is no such value.
+    pub fn is_inline(&self, key: usize) -> Option<bool> {
+        Some(!self.slab.get(key)?.boxed)
+    }
+
+    /// Drop the value associated with the given key in place, returning
+    /// whether there was such a value.
+    pub fn remove(&mut self, key: usize) -> bool {
+        match self.slab.get_pin_mut(key) {
+            Some(slot) => {
+                slot.clear();
+                self.slab.remove_in_place(key);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Drop every value in the slab.
+    pub fn clear(&mut self) {
+        self.slab.clear();
+    }
+
+    /// Return an iterator over the keys and values of the slab.
+    pub fn iter(&self) -> impl Iterator<Item = (usize, Pin<&D>)> + '_ {
+        self.slab.iter().filter_map(|(key, slot)| {
+            // SAFETY: The value lives as long as its slot, and never moves.
+            let value = unsafe { Pin::new_unchecked(slot.value?.as_ref()) };
+            Some((key, value))
+        })
+    }
+}
+
+impl<D: ?Sized, const SIZE: usize> Default for DynSlab<D, SIZE> {
+    fn default() -> Self {
+        DynSlab::new()
+    }
+}
+
+impl<D: ?Sized + fmt::Debug, const SIZE: usize> fmt::Debug for DynSlab<D, SIZE> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_map()
+            .entries(self.iter().map(|(key, value)| (key, value.get_ref())))
+            .finish()
+    }
+}
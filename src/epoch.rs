@@ -0,0 +1,155 @@
+//! A slab with wait-free reads and epoch-based reclamation, available behind
+//! the `epoch` feature.
+
+use crate::AtomicSlab;
+use ::crossbeam_epoch::{self as epoch, Guard};
+use std::fmt;
+use std::pin::Pin;
+use std::sync::Arc;
+
+/// A slab which can be shared between threads, whose reads are wait-free.
+///
+/// Unlike [`AtomicSlab`](struct.AtomicSlab.html), which counts the
+/// references to each value, reads don't write to the slab at all. Instead
+/// they borrow from a [`crossbeam_epoch`](https://docs.rs/crossbeam-epoch)
+/// guard: [`get`](#method.get) is a few loads, with no retries, and the
+/// returned reference lives as long as the guard. Inserts take a vacant slot
+/// off a lock-free free list, and removals mark the slot removed. The value
+/// is dropped, and its slot reused, only once every thread which was pinned
+/// at the time of the removal has been unpinned.
+///
+/// Like in a [`Slab`](struct.Slab.html), values never move until they are
+/// dropped, so they are handed out pinned. Only shared references are handed
+/// out, so use interior mutability to modify values.
+///
+/// Removed values are dropped on whichever thread collects the garbage of
+/// the epoch, possibly after the slab itself has been dropped, so values
+/// must be `Send + Sync + 'static` to be removed. Guards must be pinned with
+/// [`crossbeam_epoch::pin`](https://docs.rs/crossbeam-epoch/0.9/crossbeam_epoch/fn.pin.html).
+///
+/// # Examples
+///
+/// ```
+/// # use pinned_slab::*;
+/// use std::sync::atomic::{AtomicUsize, Ordering};
+/// use std::thread;
+///
+/// let slab = EpochSlab::new();
+/// let key = slab.insert(AtomicUsize::new(0));
+///
+/// thread::scope(|s| {
+///     for _ in 0..4 {
+///         s.spawn(|| {
+///             let guard = crossbeam_epoch::pin();
+///             slab.get(key, &guard).unwrap().fetch_add(1, Ordering::Relaxed);
+///         });
+///     }
+/// });
+///
+/// let guard = crossbeam_epoch::pin();
+/// let value = slab.get(key, &guard).unwrap();
+/// assert!(slab.remove(key));
+/// assert!(slab.get(key, &guard).is_none());
+///
+/// // The value stays alive while the guard is pinned.
+/// assert_eq!(value.load(Ordering::Relaxed), 4);
+/// ```
+pub struct EpochSlab<T> {
+    /// The slots, which are shared with the deferred functions that free
+    /// them. The slab holds a single reference on each value, which is
+    /// released once the epoch has advanced past its removal.
+    slots: Arc<AtomicSlab<T>>,
+}
+
+impl<T> EpochSlab<T> {
+    /// Construct a new, empty `EpochSlab`.
+    ///
+    /// Like [`Slab::new`](struct.Slab.html#method.new), this does not
+    /// allocate.
+    pub fn new() -> Self {
+        EpochSlab {
+            slots: Arc::new(AtomicSlab::new()),
+        }
+    }
+
+    /// Return the number of stored values. Removed values which haven't been
+    /// dropped yet are counted too.
+    ///
+    /// Other threads may insert or remove values at the same time, so this is
+    /// only a snapshot.
+    pub fn len(&self) -> usize {
+        self.slots.len()
+    }
+
+    /// Return `true` if there are no values stored in the slab.
+    pub fn is_empty(&self) -> bool {
+        self.slots.is_empty()
+    }
+
+    /// Return `true` if a value is associated with the given key.
+    pub fn contains(&self, key: usize) -> bool {
+        self.slots.contains(key)
+    }
+
+    /// Insert a value in the slab, returning the key assigned to the value.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the slab already holds `u32::MAX - 1` slots.
+    pub fn insert(&self, val: T) -> usize {
+        self.slots.insert(val)
+    }
+
+    /// Return a pinned reference to the value associated with the given key,
+    /// or `None` if there is no such value.
+    ///
+    /// This is wait-free. The value isn't dropped until `guard` is unpinned,
+    /// even if it is removed in the meantime.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `guard` wasn't pinned with `crossbeam_epoch::pin`.
+    pub fn get<'g>(&'g self, key: usize, guard: &'g Guard) -> Option<Pin<&'g T>> {
+        assert!(
+            guard.collector() == Some(epoch::default_collector()),
+            "guard is not pinned with `crossbeam_epoch::pin`"
+        );
+
+        // SAFETY: Removed values are only dropped by functions deferred to
+        // the default collector, which don't run while `guard` is pinned, and
+        // values never move until they are dropped.
+        unsafe { self.slots.peek(key).map(|val| Pin::new_unchecked(val)) }
+    }
+
+    /// Remove the value associated with the given key, returning `true` if
+    /// there was one.
+    ///
+    /// The value is dropped, and the key reused, once every thread which is
+    /// pinned right now has been unpinned.
+    pub fn remove(&self, key: usize) -> bool
+    where
+        T: Send + Sync + 'static,
+    {
+        if !self.slots.mark_removed(key) {
+            return false;
+        }
+
+        let slots = self.slots.clone();
+        epoch::pin().defer(move || slots.release(key));
+        true
+    }
+}
+
+impl<T> Default for EpochSlab<T> {
+    fn default() -> Self {
+        EpochSlab::new()
+    }
+}
+
+impl<T> fmt::Debug for EpochSlab<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("EpochSlab")
+            .field("len", &self.len())
+            .finish()
+    }
+}
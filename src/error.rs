@@ -0,0 +1,38 @@
+use core::alloc::Layout;
+use core::error::Error;
+use core::fmt;
+
+/// The error type for [`Slab::try_reserve`](struct.Slab.html#method.try_reserve).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TryReserveError {
+    /// The requested capacity exceeds the maximum for the slab.
+    CapacityOverflow,
+    /// The allocator returned an error.
+    AllocError {
+        /// The layout of the allocation request that failed.
+        layout: Layout,
+    },
+}
+
+impl TryReserveError {
+    /// Return the error for a failed allocation of `len` values of type `X`.
+    pub(crate) fn array<X>(len: usize) -> Self {
+        match Layout::array::<X>(len) {
+            Ok(layout) => TryReserveError::AllocError { layout },
+            Err(_) => TryReserveError::CapacityOverflow,
+        }
+    }
+}
+
+impl fmt::Display for TryReserveError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TryReserveError::CapacityOverflow => f.write_str("capacity overflow"),
+            TryReserveError::AllocError { layout } => {
+                write!(f, "memory allocation of {} bytes failed", layout.size())
+            }
+        }
+    }
+}
+
+impl Error for TryReserveError {}
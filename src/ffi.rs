@@ -0,0 +1,122 @@
+//! A C interface to a slab of `void *` values, enabled by the `ffi` feature.
+//!
+//! The slab is an opaque [`PinnedSlab`](struct.PinnedSlab.html) handed out
+//! by `pinned_slab_new`, and keys are `size_t`s. Since entries never move, the
+//! pointer returned by `pinned_slab_get` stays valid until its key is removed
+//! or the slab is freed, so C callers can keep it between calls instead of
+//! looking the key up again. The functions are plain `extern "C"` functions
+//! which `cbindgen` turns into a header like this:
+//!
+//! ```c
+//! typedef struct PinnedSlab PinnedSlab;
+//!
+//! PinnedSlab *pinned_slab_new(void);
+//! void pinned_slab_free(PinnedSlab *slab);
+//! size_t pinned_slab_insert(PinnedSlab *slab, void *value);
+//! void **pinned_slab_get(const PinnedSlab *slab, size_t key);
+//! bool pinned_slab_remove(PinnedSlab *slab, size_t key, void **value);
+//! size_t pinned_slab_len(const PinnedSlab *slab);
+//! ```
+//!
+//! The slab never dereferences the stored pointers, so freeing whatever they
+//! point to is up to the caller.
+
+use crate::Slab;
+use alloc::boxed::Box;
+use core::cell::Cell;
+use core::ffi::c_void;
+use core::ptr;
+
+/// A slab of `void *` values, only used through a pointer from C.
+#[derive(Debug, Default)]
+pub struct PinnedSlab {
+    slab: Slab<Cell<*mut c_void>>,
+}
+
+/// Construct a new, empty slab, which must be freed with
+/// [`pinned_slab_free`](fn.pinned_slab_free.html).
+#[no_mangle]
+pub extern "C" fn pinned_slab_new() -> *mut PinnedSlab {
+    Box::into_raw(Box::default())
+}
+
+/// Free a slab. Does nothing if `slab` is null.
+///
+/// # Safety
+///
+/// `slab` must be null or come from
+/// [`pinned_slab_new`](fn.pinned_slab_new.html), and must not be used again.
+#[no_mangle]
+pub unsafe extern "C" fn pinned_slab_free(slab: *mut PinnedSlab) {
+    if !slab.is_null() {
+        drop(Box::from_raw(slab));
+    }
+}
+
+/// Insert a value in the slab, returning the key assigned to it.
+///
+/// # Safety
+///
+/// `slab` must be a live slab from [`pinned_slab_new`](fn.pinned_slab_new.html)
+/// which isn't being used by another thread.
+#[no_mangle]
+pub unsafe extern "C" fn pinned_slab_insert(slab: *mut PinnedSlab, value: *mut c_void) -> usize {
+    (*slab).slab.insert(Cell::new(value)).0
+}
+
+/// Return a pointer to the entry associated with the given key, or null if
+/// there is none.
+///
+/// The entry may be read and written through the pointer until the key is
+/// removed or the slab is freed, even while other values are inserted or
+/// removed.
+///
+/// # Safety
+///
+/// `slab` must be a live slab from [`pinned_slab_new`](fn.pinned_slab_new.html)
+/// which isn't being changed by another thread.
+#[no_mangle]
+pub unsafe extern "C" fn pinned_slab_get(slab: *const PinnedSlab, key: usize) -> *mut *mut c_void {
+    (*slab)
+        .slab
+        .get(key)
+        .map_or(ptr::null_mut(), |entry| entry.as_ptr())
+}
+
+/// Remove the value associated with the given key, writing it to `value`
+/// unless that is null. Return `false` if the key isn't associated with a
+/// value.
+///
+/// # Safety
+///
+/// `slab` must be a live slab from [`pinned_slab_new`](fn.pinned_slab_new.html)
+/// which isn't being used by another thread, and `value` must be null or
+/// valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn pinned_slab_remove(
+    slab: *mut PinnedSlab,
+    key: usize,
+    value: *mut *mut c_void,
+) -> bool {
+    let slab = &mut (*slab).slab;
+    if !slab.contains(key) {
+        return false;
+    }
+
+    let removed = slab.remove(key).into_inner();
+    if !value.is_null() {
+        *value = removed;
+    }
+    true
+}
+
+/// Return the number of stored values.
+///
+/// # Safety
+///
+/// `slab` must be a live slab from [`pinned_slab_new`](fn.pinned_slab_new.html)
+/// which isn't being changed by another thread.
+#[no_mangle]
+pub unsafe extern "C" fn pinned_slab_len(slab: *const PinnedSlab) -> usize {
+    (*slab).slab.len()
+}
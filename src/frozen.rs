@@ -0,0 +1,102 @@
+//! A read-only `Slab` which can be shared cheaply.
+
+use crate::{ChunkAllocator, Global, Slab, DEFAULT_CHUNK_SIZE};
+use alloc::sync::Arc;
+use core::ops;
+use core::pin::Pin;
+
+/// A read-only slab which can be cloned cheaply and shared between threads.
+///
+/// Every read-only method of [`Slab`](struct.Slab.html) is available through
+/// `Deref`. Clones share the same slab, so once the other clones are dropped
+/// the slab can be thawed to modify it again.
+///
+/// # Examples
+///
+/// ```
+/// # use pinned_slab::*;
+/// use std::thread;
+///
+/// let mut slab = Slab::new();
+/// let (key, _) = slab.insert("config");
+/// let frozen = slab.freeze();
+///
+/// thread::scope(|s| {
+///     for _ in 0..4 {
+///         let frozen = frozen.clone();
+///         s.spawn(move || assert_eq!(frozen[key], "config"));
+///     }
+/// });
+///
+/// let mut slab = frozen.thaw().unwrap();
+/// slab.remove(key);
+/// ```
+#[derive(Debug)]
+pub struct FrozenSlab<T, const N: usize = DEFAULT_CHUNK_SIZE, A: ChunkAllocator = Global> {
+    slab: Arc<Slab<T, N, A>>,
+}
+
+impl<T, const N: usize, A: ChunkAllocator> Slab<T, N, A> {
+    /// Make the slab read-only, so that it can be shared cheaply.
+    ///
+    /// See [`FrozenSlab`](struct.FrozenSlab.html).
+    pub fn freeze(self) -> FrozenSlab<T, N, A> {
+        FrozenSlab {
+            slab: Arc::new(self),
+        }
+    }
+}
+
+impl<T, const N: usize, A: ChunkAllocator> FrozenSlab<T, N, A> {
+    /// Return a pinned reference to the value associated with the given key.
+    ///
+    /// If the given key is not associated with a value, then `None` is
+    /// returned.
+    pub fn get_pin(&self, key: usize) -> Option<Pin<&T>> {
+        // SAFETY: The slab is never modified while it is frozen, and values
+        // never move anyway.
+        self.slab
+            .get(key)
+            .map(|val| unsafe { Pin::new_unchecked(val) })
+    }
+
+    /// Return `true` if no other clones of this slab exist, so it can be
+    /// thawed.
+    pub fn is_unique(&self) -> bool {
+        Arc::strong_count(&self.slab) == 1
+    }
+
+    /// Make the slab mutable again, if no other clones of it exist.
+    /// Otherwise the frozen slab is handed back.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use pinned_slab::*;
+    /// let frozen = Slab::<i32>::new().freeze();
+    /// let other = frozen.clone();
+    ///
+    /// let frozen = frozen.thaw().unwrap_err();
+    /// drop(other);
+    /// assert!(frozen.thaw().is_ok());
+    /// ```
+    pub fn thaw(self) -> Result<Slab<T, N, A>, Self> {
+        Arc::try_unwrap(self.slab).map_err(|slab| FrozenSlab { slab })
+    }
+}
+
+impl<T, const N: usize, A: ChunkAllocator> Clone for FrozenSlab<T, N, A> {
+    fn clone(&self) -> Self {
+        FrozenSlab {
+            slab: self.slab.clone(),
+        }
+    }
+}
+
+impl<T, const N: usize, A: ChunkAllocator> ops::Deref for FrozenSlab<T, N, A> {
+    type Target = Slab<T, N, A>;
+
+    fn deref(&self) -> &Slab<T, N, A> {
+        &self.slab
+    }
+}
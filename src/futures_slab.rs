@@ -0,0 +1,260 @@
+//! A keyed set of futures which are polled as they become ready.
+
+use crate::Slab;
+use std::collections::VecDeque;
+use std::fmt;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, PoisonError};
+use std::task::{Context, Poll, Wake, Waker};
+
+/// A set of futures, like `FuturesUnordered` from the `futures` crate, whose
+/// futures are stored pinned in a [`Slab`](struct.Slab.html) and can be
+/// cancelled by key.
+///
+/// Each future gets its own waker, which queues its key when woken, so
+/// [`poll_next`](#method.poll_next) only polls futures which are ready to make
+/// progress instead of every future in the slab.
+///
+/// Futures don't need to be `Unpin`, since they never move.
+///
+/// # Examples
+///
+/// ```
+/// # use pinned_slab::*;
+/// use std::future;
+/// use std::task::{Context, Poll, Waker};
+///
+/// let mut futures = FuturesSlab::new();
+/// let a = futures.insert(future::ready("a"));
+/// let b = futures.insert(future::ready("b"));
+/// let c = futures.insert(future::ready("c"));
+/// assert!(futures.cancel(b));
+///
+/// let mut cx = Context::from_waker(Waker::noop());
+/// let mut done = Vec::new();
+/// while let Poll::Ready(Some(output)) = futures.poll_next(&mut cx) {
+///     done.push(output);
+/// }
+/// assert_eq!(done, [(a, "a"), (c, "c")]);
+/// assert!(futures.is_empty());
+/// ```
+pub struct FuturesSlab<F> {
+    tasks: Slab<Task<F>>,
+    queue: Arc<ReadyQueue>,
+}
+
+struct Task<F> {
+    /// The future, which is dropped in place once it completes or is
+    /// cancelled.
+    future: Option<F>,
+    waker: Arc<TaskWaker>,
+}
+
+/// The keys of the futures which were woken, and the waker of the task
+/// polling the set.
+#[derive(Default)]
+struct ReadyQueue {
+    keys: Mutex<VecDeque<usize>>,
+    waker: Mutex<Option<Waker>>,
+}
+
+/// The waker of a single future.
+struct TaskWaker {
+    key: usize,
+    /// Whether the key is in the ready queue already.
+    queued: AtomicBool,
+    queue: Arc<ReadyQueue>,
+}
+
+impl<F: Future> FuturesSlab<F> {
+    /// Construct a new, empty `FuturesSlab`.
+    pub fn new() -> Self {
+        FuturesSlab {
+            tasks: Slab::new(),
+            queue: Arc::default(),
+        }
+    }
+
+    /// Return the number of futures which haven't completed yet.
+    pub fn len(&self) -> usize {
+        self.tasks.len()
+    }
+
+    /// Return `true` if there are no futures in the set.
+    pub fn is_empty(&self) -> bool {
+        self.tasks.is_empty()
+    }
+
+    /// Return `true` if the future of the given key hasn't completed or been
+    /// cancelled yet.
+    pub fn contains(&self, key: usize) -> bool {
+        self.tasks.contains(key)
+    }
+
+    /// Insert a future, returning its key. It is polled by the next call to
+    /// [`poll_next`](#method.poll_next).
+    pub fn insert(&mut self, future: F) -> usize {
+        let key = self.tasks.next_key();
+        let waker = Arc::new(TaskWaker {
+            key,
+            queued: AtomicBool::new(true),
+            queue: self.queue.clone(),
+        });
+        let (inserted, _) = self.tasks.insert(Task {
+            future: Some(future),
+            waker,
+        });
+        debug_assert_eq!(inserted, key);
+        self.queue.push(key);
+        key
+    }
+
+    /// Return the future of the given key, or `None` if there is no such
+    /// future.
+    pub fn get_pin_mut(&mut self, key: usize) -> Option<Pin<&mut F>> {
+        let task = self.tasks.get_pin_mut(key)?;
+        Task::future(task).as_pin_mut()
+    }
+
+    /// Drop the future of the given key without polling it again, returning
+    /// whether there was such a future.
+    pub fn cancel(&mut self, key: usize) -> bool {
+        match self.tasks.get_pin_mut(key) {
+            Some(task) => {
+                Task::future(task).set(None);
+                self.tasks.remove_in_place(key);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Poll the futures which were woken since they were last polled,
+    /// returning the key and output of the first one which completes.
+    ///
+    /// Return `Poll::Ready(None)` if the set is empty, and `Poll::Pending` if
+    /// no future completed. The waker of `cx` is woken when a future should be
+    /// polled again.
+    pub fn poll_next(&mut self, cx: &mut Context<'_>) -> Poll<Option<(usize, F::Output)>> {
+        self.queue.register(cx.waker());
+
+        // Each future may wake itself while it is polled, so yield once every
+        // future had a chance to be polled.
+        let mut budget = self.tasks.len();
+        while budget > 0 {
+            let key = match self.queue.pop() {
+                Some(key) => key,
+                None => break,
+            };
+            let task = match self.tasks.get_pin_mut(key) {
+                Some(task) => task,
+                // The future was cancelled after it was woken.
+                None => continue,
+            };
+            budget -= 1;
+
+            task.waker.queued.store(false, Ordering::Release);
+            let waker = Waker::from(task.waker.clone());
+            let mut future = Task::future(task);
+            let poll = match future.as_mut().as_pin_mut() {
+                Some(inner) => inner.poll(&mut Context::from_waker(&waker)),
+                None => continue,
+            };
+            if let Poll::Ready(output) = poll {
+                future.set(None);
+                self.tasks.remove_in_place(key);
+                return Poll::Ready(Some((key, output)));
+            }
+        }
+
+        if self.tasks.is_empty() {
+            Poll::Ready(None)
+        } else {
+            if budget == 0 && !self.queue.is_empty() {
+                cx.waker().wake_by_ref();
+            }
+            Poll::Pending
+        }
+    }
+}
+
+// The futures are pinned in the chunks of the slab, not in the set itself.
+impl<F> Unpin for FuturesSlab<F> {}
+
+impl<F> Task<F> {
+    /// Return the future of a pinned task.
+    fn future(self: Pin<&mut Self>) -> Pin<&mut Option<F>> {
+        // SAFETY: The future is pinned structurally: it is only dropped in
+        // place, and never moved out of the task.
+        unsafe { self.map_unchecked_mut(|task| &mut task.future) }
+    }
+}
+
+impl ReadyQueue {
+    fn push(&self, key: usize) {
+        self.keys
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .push_back(key);
+    }
+
+    fn pop(&self) -> Option<usize> {
+        self.keys
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .pop_front()
+    }
+
+    fn is_empty(&self) -> bool {
+        self.keys
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .is_empty()
+    }
+
+    fn register(&self, waker: &Waker) {
+        let mut current = self.waker.lock().unwrap_or_else(PoisonError::into_inner);
+        match &*current {
+            Some(current) if current.will_wake(waker) => {}
+            _ => *current = Some(waker.clone()),
+        }
+    }
+}
+
+impl Wake for TaskWaker {
+    fn wake(self: Arc<Self>) {
+        self.wake_by_ref();
+    }
+
+    fn wake_by_ref(self: &Arc<Self>) {
+        if self.queued.swap(true, Ordering::AcqRel) {
+            return;
+        }
+        self.queue.push(self.key);
+        let waker = self
+            .queue
+            .waker
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone();
+        if let Some(waker) = waker {
+            waker.wake();
+        }
+    }
+}
+
+impl<F: Future> Default for FuturesSlab<F> {
+    fn default() -> Self {
+        FuturesSlab::new()
+    }
+}
+
+impl<F> fmt::Debug for FuturesSlab<F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("FuturesSlab")
+            .field("len", &self.tasks.len())
+            .finish()
+    }
+}
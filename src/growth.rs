@@ -0,0 +1,118 @@
+//! Policies for the sizes of the chunks of a `Slab`.
+
+/// How the chunks of a [`Slab`](struct.Slab.html) grow as more capacity is
+/// needed.
+///
+/// The chunk size `N` of the slab is the size of the largest chunks. Small
+/// slabs waste less memory with a geometric policy, since their first chunks
+/// are smaller, while large slabs still allocate `N` entries at a time.
+///
+/// # Examples
+///
+/// ```
+/// # use pinned_slab::*;
+/// let mut slab = Slab::<usize>::with_growth_policy(GrowthPolicy::Geometric { first: 32 });
+///
+/// slab.insert(0);
+/// assert_eq!(slab.capacity(), 32);
+///
+/// for i in 1..100 {
+///     slab.insert(i);
+/// }
+/// assert_eq!(slab.capacity(), 32 + 64 + 128);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum GrowthPolicy {
+    /// Every chunk holds `N` entries.
+    #[default]
+    Fixed,
+    /// The first chunk holds `first` entries, and every chunk after it holds
+    /// twice as many entries as the one before, up to `N` entries.
+    Geometric {
+        /// The number of entries in the first chunk.
+        first: usize,
+    },
+}
+
+/// The sizes of the chunks of a slab, precomputed from its `GrowthPolicy` so
+/// that keys can be mapped to chunks in constant time.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Growth<const N: usize> {
+    policy: GrowthPolicy,
+    first: usize,
+    /// The number of chunks smaller than `N` entries.
+    doublings: usize,
+    /// The number of keys held by the chunks smaller than `N` entries.
+    end: usize,
+}
+
+impl<const N: usize> Growth<N> {
+    pub fn new(policy: GrowthPolicy) -> Self {
+        const { assert!(N > 0, "the chunk size must be greater than zero") };
+
+        let first = match policy {
+            GrowthPolicy::Fixed => N,
+            GrowthPolicy::Geometric { first } => first,
+        };
+        assert!(
+            first > 0 && first <= N,
+            "the first chunk size must be between 1 and the chunk size"
+        );
+
+        let mut doublings = 0;
+        let mut end = 0;
+        while first << doublings < N {
+            end += first << doublings;
+            doublings += 1;
+        }
+
+        Growth {
+            policy,
+            first,
+            doublings,
+            end,
+        }
+    }
+
+    pub fn policy(&self) -> GrowthPolicy {
+        self.policy
+    }
+
+    /// Return the number of chunks smaller than `N` entries.
+    pub fn doublings(&self) -> usize {
+        self.doublings
+    }
+
+    /// Return the number of entries of the chunk at `index`.
+    pub fn chunk_len(&self, index: usize) -> usize {
+        if index < self.doublings {
+            self.first << index
+        } else {
+            N
+        }
+    }
+
+    /// Return the first key of the chunk at `index`, saturating at
+    /// `usize::MAX`.
+    pub fn chunk_start(&self, index: usize) -> usize {
+        if index < self.doublings {
+            self.first * ((1 << index) - 1)
+        } else {
+            (index - self.doublings)
+                .saturating_mul(N)
+                .saturating_add(self.end)
+        }
+    }
+
+    /// Return the index of the chunk holding `key` and the index of `key`
+    /// within that chunk.
+    pub fn locate(&self, key: usize) -> (usize, usize) {
+        if key >= self.end {
+            let key = key - self.end;
+            (self.doublings + key / N, key % N)
+        } else {
+            let index = (key / self.first + 1).ilog2() as usize;
+            (index, key - self.chunk_start(index))
+        }
+    }
+}
@@ -0,0 +1,96 @@
+//! Keys which detect when their value has been removed.
+
+use crate::{ChunkAllocator, Slab};
+use core::pin::Pin;
+
+/// A key which only refers to the value it was created for.
+///
+/// Plain keys are reused once their value is removed, so a stale key may
+/// silently refer to a different value. A handle also records a stamp which
+/// is unique to its value, so after the value is removed the handle never
+/// refers to anything again, even if its key is reused.
+///
+/// # Examples
+///
+/// ```
+/// # use pinned_slab::*;
+/// let mut slab = Slab::new();
+///
+/// let (key, _) = slab.insert("old");
+/// let handle = slab.handle(key).unwrap();
+/// assert_eq!(slab.upgrade(handle).map(|val| *val), Some("old"));
+///
+/// slab.remove(key);
+/// let (reused, _) = slab.insert("new");
+/// assert_eq!(reused, handle.key());
+/// assert!(slab.upgrade(handle).is_none());
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Handle {
+    key: usize,
+    stamp: u64,
+}
+
+impl Handle {
+    /// Return the key of the value the handle was created for.
+    pub fn key(&self) -> usize {
+        self.key
+    }
+
+    /// Return the stamp of the value the handle was created for.
+    pub(crate) fn stamp(&self) -> u64 {
+        self.stamp
+    }
+}
+
+impl<T, const N: usize, A: ChunkAllocator> Slab<T, N, A> {
+    /// Return a handle to the value associated with the given key.
+    ///
+    /// If the given key is not associated with a value, then `None` is
+    /// returned.
+    pub fn handle(&self, key: usize) -> Option<Handle> {
+        Some(Handle {
+            key,
+            stamp: self.stamp_of(key)?,
+        })
+    }
+
+    /// Return `true` if the value `handle` was created for is still in the
+    /// slab.
+    pub fn contains_handle(&self, handle: Handle) -> bool {
+        self.stamp_of(handle.key) == Some(handle.stamp)
+    }
+
+    /// Return a pinned reference to the value `handle` was created for, or
+    /// `None` if it has been removed.
+    pub fn upgrade(&self, handle: Handle) -> Option<Pin<&T>> {
+        if !self.contains_handle(handle) {
+            return None;
+        }
+
+        // SAFETY: Values never move while they are in the slab.
+        Some(unsafe { Pin::new_unchecked(self.get_unchecked(handle.key)) })
+    }
+
+    /// Return a pinned mutable reference to the value `handle` was created
+    /// for, or `None` if it has been removed.
+    pub fn upgrade_pin_mut(&mut self, handle: Handle) -> Option<Pin<&mut T>> {
+        if !self.contains_handle(handle) {
+            return None;
+        }
+
+        // SAFETY: The value is occupied.
+        Some(unsafe { self.get_unchecked_pin_mut(handle.key) })
+    }
+
+    /// Return the stamp of the value at `key`, if it is occupied.
+    fn stamp_of(&self, key: usize) -> Option<u64> {
+        let (slab_key, entry_key) = self.growth.locate(key);
+        let chunk = self.chunk(slab_key)?;
+        if chunk.is_occupied(entry_key) {
+            Some(chunk.stamps[entry_key])
+        } else {
+            None
+        }
+    }
+}
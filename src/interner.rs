@@ -0,0 +1,181 @@
+//! A string interner which stores its strings in pinned chunks.
+
+use crate::{Arena, Global};
+use std::borrow::Borrow;
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::convert::TryFrom;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::str;
+
+/// The number of bytes of each chunk of interned strings. Longer strings get
+/// a chunk of their own.
+const CHUNK_BYTES: usize = 4096;
+
+/// An interned string, which can be resolved with the
+/// [`Interner`](struct.Interner.html) it was interned in.
+///
+/// Symbols are numbered from zero in the order their strings were first
+/// interned.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Symbol(u32);
+
+impl Symbol {
+    /// Return the number of the symbol.
+    pub fn index(self) -> usize {
+        self.0 as usize
+    }
+}
+
+/// A string interner, which stores each distinct string once and hands out
+/// small [`Symbol`](struct.Symbol.html)s for them, e.g. for the identifiers
+/// of a compiler.
+///
+/// Strings are copied into an [`Arena`](struct.Arena.html) of bytes, so they
+/// never move: the `&str`s handed out live as long as the interner, and stay
+/// valid while more strings are interned.
+///
+/// # Examples
+///
+/// ```
+/// # use pinned_slab::*;
+/// let interner = Interner::new();
+///
+/// let hello = interner.intern("hello");
+/// let world = interner.intern_str("world");
+/// assert_eq!(interner.intern("hello"), hello);
+///
+/// // `world` is still valid after more strings are interned.
+/// interner.intern("and more");
+/// assert_eq!(world, "world");
+/// assert_eq!(interner.resolve(hello), "hello");
+/// assert_eq!(interner.get("and more").map(Symbol::index), Some(2));
+/// ```
+pub struct Interner {
+    bytes: Arena<u8, CHUNK_BYTES>,
+    inner: RefCell<Inner>,
+}
+
+// SAFETY: The strings are owned by the arena, which moves along with the
+// interner.
+unsafe impl Send for Interner {}
+
+#[derive(Default)]
+struct Inner {
+    /// The string of each symbol.
+    strings: Vec<Interned>,
+    symbols: HashMap<Interned, Symbol>,
+}
+
+/// A string in the arena of an `Interner`, which is hashed and compared by
+/// its contents.
+#[derive(Clone, Copy)]
+struct Interned(*const str);
+
+impl Interned {
+    fn as_str(&self) -> &str {
+        // SAFETY: Interned strings live in the arena, which is never cleared
+        // and outlives every `Interned`.
+        unsafe { &*self.0 }
+    }
+}
+
+impl Borrow<str> for Interned {
+    fn borrow(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl PartialEq for Interned {
+    fn eq(&self, other: &Self) -> bool {
+        self.as_str() == other.as_str()
+    }
+}
+
+impl Eq for Interned {}
+
+impl Hash for Interned {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.as_str().hash(state);
+    }
+}
+
+impl Interner {
+    /// Construct a new, empty `Interner`.
+    pub fn new() -> Self {
+        Interner {
+            bytes: Arena::new_in(Global),
+            inner: RefCell::default(),
+        }
+    }
+
+    /// Return the number of distinct strings.
+    pub fn len(&self) -> usize {
+        self.inner.borrow().strings.len()
+    }
+
+    /// Return `true` if no strings have been interned.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Intern a string, returning its symbol.
+    ///
+    /// # Panics
+    ///
+    /// Panics if more than `u32::MAX` distinct strings are interned.
+    pub fn intern(&self, s: &str) -> Symbol {
+        if let Some(symbol) = self.get(s) {
+            return symbol;
+        }
+
+        let bytes = self.bytes.alloc_iter(s.bytes()).into_ref().get_ref();
+        // SAFETY: The bytes were copied from a `str`.
+        let interned = Interned(unsafe { str::from_utf8_unchecked(bytes) });
+        let mut inner = self.inner.borrow_mut();
+        let symbol = u32::try_from(inner.strings.len())
+            .map(Symbol)
+            .expect("too many interned strings");
+        inner.strings.push(interned);
+        inner.symbols.insert(interned, symbol);
+        symbol
+    }
+
+    /// Intern a string, returning the interned copy of it.
+    pub fn intern_str(&self, s: &str) -> &str {
+        self.resolve(self.intern(s))
+    }
+
+    /// Return the symbol of a string, if it has been interned.
+    pub fn get(&self, s: &str) -> Option<Symbol> {
+        self.inner.borrow().symbols.get(s).copied()
+    }
+
+    /// Return the string of a symbol.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the symbol wasn't returned by this interner.
+    pub fn resolve(&self, symbol: Symbol) -> &str {
+        let interned = self.inner.borrow().strings[symbol.index()];
+        // SAFETY: The string lives in the arena, which lives as long as
+        // `self`.
+        unsafe { &*interned.0 }
+    }
+}
+
+impl Default for Interner {
+    fn default() -> Self {
+        Interner::new()
+    }
+}
+
+impl fmt::Debug for Interner {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let inner = self.inner.borrow();
+        f.debug_list()
+            .entries(inner.strings.iter().map(Interned::as_str))
+            .finish()
+    }
+}
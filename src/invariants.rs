@@ -0,0 +1,258 @@
+//! Consistency checks for the internal state of `Slab`.
+
+use crate::{ChunkAllocator, KeyPolicy, Slab, NIL};
+use alloc::{format, string::String, vec::Vec};
+use core::fmt::Write;
+
+impl<T, const N: usize, A: ChunkAllocator> Slab<T, N, A> {
+    /// Check that the internal state of the slab is consistent, and panic
+    /// with a description of every inconsistency if it isn't.
+    ///
+    /// This is meant for tracking down bugs in `unsafe` code which uses the
+    /// slab. The counts of each chunk are always checked, in
+    /// `O(capacity / 64)` time. In builds with `debug_assertions`, the list
+    /// of occupied entries and the free list are walked as well, which takes
+    /// `O(capacity)` time.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the slab is inconsistent.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use pinned_slab::*;
+    /// let mut slab = Slab::new();
+    ///
+    /// slab.insert_many(0..100);
+    /// slab.remove(7);
+    /// slab.remove(42);
+    ///
+    /// slab.assert_invariants();
+    /// ```
+    pub fn assert_invariants(&self) {
+        let mut errors = Vec::new();
+        self.check_counts(&mut errors);
+        if cfg!(debug_assertions) && errors.is_empty() {
+            self.check_occupied_list(&mut errors);
+            self.check_free_list(&mut errors);
+        }
+
+        if !errors.is_empty() {
+            let mut msg = String::from("slab invariants violated:");
+            for error in &errors {
+                let _ = write!(msg, "\n- {}", error);
+            }
+            panic!("{}\n{}", msg, self.report());
+        }
+    }
+
+    /// Check the number of occupied entries of each chunk and of the slab,
+    /// and the ends of the list of occupied entries.
+    fn check_counts(&self, errors: &mut Vec<String>) {
+        let mut len = 0;
+        let mut reserved = 0;
+        let mut head = NIL;
+        let mut tail = NIL;
+        for (i, chunk) in self.chunks.iter().enumerate() {
+            let chunk = match chunk {
+                Some(chunk) => chunk,
+                None => continue,
+            };
+            let size = self.growth.chunk_len(i);
+            if chunk.size() != size {
+                errors.push(format!(
+                    "chunk {} has {} entries instead of {}",
+                    i,
+                    chunk.size(),
+                    size
+                ));
+                continue;
+            }
+            if !size.is_multiple_of(64) && chunk.occupied[size / 64] >> (size % 64) != 0 {
+                errors.push(format!("chunk {} has occupied bits past its end", i));
+            }
+            let occupied: usize = chunk.occupied.iter().map(|w| w.count_ones() as usize).sum();
+            if chunk
+                .occupied
+                .iter()
+                .zip(&chunk.reserved)
+                .any(|(o, r)| o & r != 0)
+            {
+                errors.push(format!(
+                    "chunk {} has entries both occupied and reserved",
+                    i
+                ));
+            }
+            let chunk_reserved: usize =
+                chunk.reserved.iter().map(|w| w.count_ones() as usize).sum();
+            if occupied != chunk.len {
+                errors.push(format!(
+                    "chunk {} has {} occupied entries but a length of {}",
+                    i, occupied, chunk.len
+                ));
+            }
+            if chunk.touched > size {
+                errors.push(format!(
+                    "chunk {} has touched {} of {} entries",
+                    i, chunk.touched, size
+                ));
+            } else if chunk.next_occupied(chunk.touched).is_some() {
+                errors.push(format!(
+                    "chunk {} has occupied entries past the {} touched ones",
+                    i, chunk.touched
+                ));
+            } else if chunk.taken_len() > chunk.touched {
+                errors.push(format!(
+                    "chunk {} has reserved entries past the {} touched ones",
+                    i, chunk.touched
+                ));
+            }
+            len += occupied;
+            reserved += chunk_reserved;
+
+            let start = self.growth.chunk_start(i);
+            if let Some(j) = chunk.next_occupied(0) {
+                head = head.min(start + j);
+            }
+            if let Some(j) = chunk.prev_occupied(size) {
+                tail = match tail {
+                    NIL => start + j,
+                    tail => tail.max(start + j),
+                };
+            }
+        }
+
+        if len != self.len {
+            errors.push(format!(
+                "the chunks hold {} values but the length is {}",
+                len, self.len
+            ));
+        }
+        if reserved != self.reserved {
+            errors.push(format!(
+                "the chunks hold {} reserved keys but {} are counted",
+                reserved, self.reserved
+            ));
+        }
+        if head != self.head {
+            errors.push(format!("the head is {} instead of {}", self.head, head));
+        }
+        if tail != self.tail {
+            errors.push(format!("the tail is {} instead of {}", self.tail, tail));
+        }
+        if self.contains(self.next) {
+            errors.push(format!("the next key {} is occupied", self.next));
+        }
+        if self.is_reserved(self.next) {
+            errors.push(format!("the next key {} is reserved", self.next));
+        }
+        let (slab_key, entry_key) = self.growth.locate(self.next);
+        if entry_key != 0 && self.chunk(slab_key).is_none() {
+            errors.push(format!(
+                "the next key {} is in the middle of an unallocated chunk",
+                self.next
+            ));
+        }
+    }
+
+    /// Walk the list of occupied entries from the head, checking that it is
+    /// sorted and doubly linked.
+    fn check_occupied_list(&self, errors: &mut Vec<String>) {
+        let mut prev = NIL;
+        let mut key = self.head;
+        let mut count = 0;
+        while key != NIL {
+            if count == self.len {
+                errors.push(format!(
+                    "the list of occupied entries is longer than {}",
+                    self.len
+                ));
+                return;
+            }
+            if prev != NIL && key <= prev {
+                errors.push(format!(
+                    "the list of occupied entries goes from {} to {}",
+                    prev, key
+                ));
+                return;
+            }
+            if !self.contains(key) {
+                errors.push(format!(
+                    "the list of occupied entries contains vacant key {}",
+                    key
+                ));
+                return;
+            }
+            let links = self.links(key);
+            if links.prev != prev {
+                errors.push(format!(
+                    "key {} links back to {} instead of {}",
+                    key, links.prev, prev
+                ));
+            }
+            prev = key;
+            key = links.next;
+            count += 1;
+        }
+        if count != self.len {
+            errors.push(format!(
+                "the list of occupied entries has {} keys instead of {}",
+                count, self.len
+            ));
+        }
+    }
+
+    /// Walk the free list from the next key, checking that it covers every
+    /// vacant entry which has been handed out before and isn't reserved
+    /// exactly once.
+    fn check_free_list(&self, errors: &mut Vec<String>) {
+        let free: usize = self
+            .chunks
+            .iter()
+            .flatten()
+            .map(|chunk| {
+                let reserved: usize = chunk.reserved.iter().map(|w| w.count_ones() as usize).sum();
+                chunk.touched - chunk.len - reserved
+            })
+            .sum();
+
+        match self.key_policy {
+            KeyPolicy::Lifo => {
+                let mut key = self.next;
+                let mut count = 0;
+                while self.is_free_listed(key) {
+                    if count == free {
+                        errors.push(format!("the free list is longer than {}", free));
+                        return;
+                    }
+                    key = self.links(key).next;
+                    count += 1;
+                }
+                if count != free {
+                    errors.push(format!(
+                        "the free list has {} keys but {} vacant entries have been handed out",
+                        count, free
+                    ));
+                }
+            }
+            KeyPolicy::LowestFirst => {
+                let lowest = self.lowest_vacant(0);
+                if self.next != lowest {
+                    errors.push(format!(
+                        "the next key is {} instead of the lowest vacant key {}",
+                        self.next, lowest
+                    ));
+                }
+            }
+            KeyPolicy::Monotonic => {
+                if self.tail != NIL && self.next <= self.tail {
+                    errors.push(format!(
+                        "the next key {} isn't above the largest key {}",
+                        self.next, self.tail
+                    ));
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,93 @@
+//! Policies for the order in which a `Slab` reuses vacant keys.
+
+use crate::{ChunkAllocator, Slab};
+
+/// The order in which a [`Slab`](struct.Slab.html) hands out vacant keys.
+///
+/// Whatever the policy, new chunks are only allocated once every allocated
+/// chunk is full, except with `Monotonic`.
+///
+/// # Examples
+///
+/// ```
+/// # use pinned_slab::*;
+/// let mut slab = Slab::new();
+/// slab.set_key_policy(KeyPolicy::LowestFirst);
+///
+/// for i in 0..5 {
+///     slab.insert(i);
+/// }
+/// slab.remove(3);
+/// slab.remove(1);
+///
+/// assert_eq!(slab.insert(5).0, 1);
+/// assert_eq!(slab.insert(6).0, 3);
+/// assert_eq!(slab.insert(7).0, 5);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum KeyPolicy {
+    /// Hand out the most recently removed key first. Its entry is the most
+    /// likely to still be in the cache.
+    #[default]
+    Lifo,
+    /// Hand out the lowest vacant key first. This keeps the keys dense, e.g.
+    /// for secondary maps which are indexed by key.
+    LowestFirst,
+    /// Never hand out a key twice, until the slab is cleared. Stale keys then
+    /// always refer to vacant entries, which helps when debugging. Switching
+    /// to this policy continues after the largest key handed out so far,
+    /// even if it has been removed again. New chunks
+    /// are allocated even if the old ones have vacant entries, so use
+    /// [`free_unused`](struct.Slab.html#method.free_unused) to free the
+    /// chunks which end up empty.
+    Monotonic,
+}
+
+impl<T, const N: usize, A: ChunkAllocator> Slab<T, N, A> {
+    /// Return the order in which the slab hands out vacant keys.
+    pub fn key_policy(&self) -> KeyPolicy {
+        self.key_policy
+    }
+
+    /// Change the order in which the slab hands out vacant keys.
+    ///
+    /// This takes `O(capacity)` time.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use pinned_slab::*;
+    /// let mut slab = Slab::new();
+    /// slab.set_key_policy(KeyPolicy::Monotonic);
+    ///
+    /// let (key, _) = slab.insert("hello");
+    /// slab.remove(key);
+    ///
+    /// assert_ne!(slab.insert("world").0, key);
+    /// ```
+    pub fn set_key_policy(&mut self, policy: KeyPolicy) {
+        self.key_policy = policy;
+        if policy == KeyPolicy::Monotonic {
+            self.next = self.issued;
+        }
+        self.rebuild_free_list();
+    }
+
+    /// Return the lowest vacant key of the allocated chunks which is greater
+    /// than or equal to `key`, or the first key of a new chunk if there is
+    /// none.
+    pub(crate) fn lowest_vacant(&self, key: usize) -> usize {
+        let (slab_key, mut entry_key) = self.growth.locate(key);
+
+        for i in slab_key..self.chunks.len() {
+            if let Some(chunk) = self.chunk(i) {
+                if let Some(j) = chunk.next_vacant(entry_key) {
+                    return self.growth.chunk_start(i) + j;
+                }
+            }
+            entry_key = 0;
+        }
+
+        self.growth.chunk_start(self.free_slot())
+    }
+}
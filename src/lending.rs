@@ -0,0 +1,99 @@
+//! A lending iterator, which hands out one pinned mutable reference at a
+//! time.
+
+use crate::{ChunkAllocator, Global, Slab, DEFAULT_CHUNK_SIZE, NIL};
+use core::pin::Pin;
+
+/// An iterator whose items may borrow from the iterator itself, so each item
+/// must be dropped before the next one is requested.
+///
+/// `Iterator` can't express this, since its items must stay valid while
+/// later ones are produced. Lending iterators aren't `for` loop compatible,
+/// and are driven with `while let` instead.
+pub trait LendingIterator {
+    /// The type of the items, which borrow the iterator for `'b`.
+    type Item<'b>
+    where
+        Self: 'b;
+
+    /// Advance the iterator and return the next item.
+    fn next(&mut self) -> Option<Self::Item<'_>>;
+}
+
+/// A lending iterator over pinned mutable references to the values of a
+/// `Slab`, in ascending key order.
+///
+/// Only one value is borrowed at a time, so between two calls to
+/// [`next`](trait.LendingIterator.html#tymethod.next) the whole slab can be
+/// read through [`slab`](#method.slab), which
+/// [`IterPinMut`](struct.IterPinMut.html) doesn't allow.
+///
+/// See [`Slab::lending_iter_mut`](struct.Slab.html#method.lending_iter_mut).
+pub struct LendingIterMut<
+    'a,
+    T: 'a,
+    const N: usize = DEFAULT_CHUNK_SIZE,
+    A: ChunkAllocator = Global,
+> {
+    slab: &'a mut Slab<T, N, A>,
+    /// The key of the next entry to lend.
+    next: usize,
+}
+
+impl<'a, T, const N: usize, A: ChunkAllocator> LendingIterMut<'a, T, N, A> {
+    /// Return a reference to the slab being iterated.
+    pub fn slab(&self) -> &Slab<T, N, A> {
+        self.slab
+    }
+}
+
+impl<'a, T, const N: usize, A: ChunkAllocator> LendingIterator for LendingIterMut<'a, T, N, A> {
+    type Item<'b>
+        = (usize, Pin<&'b mut T>)
+    where
+        Self: 'b;
+
+    fn next(&mut self) -> Option<Self::Item<'_>> {
+        let key = self.next;
+        if key == NIL {
+            return None;
+        }
+
+        self.next = self.slab.links(key).next;
+        self.slab.get_key_value_pin_mut(key)
+    }
+}
+
+impl<T, const N: usize, A: ChunkAllocator> Slab<T, N, A> {
+    /// Return a lending iterator over pinned mutable references to the
+    /// values, in ascending key order.
+    ///
+    /// Unlike [`iter_pin_mut`](#method.iter_pin_mut), each reference must be
+    /// dropped before the next one is taken, and in exchange the rest of the
+    /// slab can be read in between.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use pinned_slab::*;
+    /// let mut slab = Slab::new();
+    /// slab.insert_many([1, 2, 3]);
+    ///
+    /// let mut iter = slab.lending_iter_mut();
+    /// loop {
+    ///     let total: i32 = iter.slab().values().sum();
+    ///     match iter.next() {
+    ///         Some((_, mut val)) => *val = total - *val,
+    ///         None => break,
+    ///     }
+    /// }
+    ///
+    /// assert_eq!(slab.values().copied().collect::<Vec<_>>(), [5, 8, 13]);
+    /// ```
+    pub fn lending_iter_mut(&mut self) -> LendingIterMut<'_, T, N, A> {
+        LendingIterMut {
+            next: self.head,
+            slab: self,
+        }
+    }
+}
@@ -4,64 +4,803 @@
 //! be moved unless we first remove it from the pool.
 //!
 //! [`slab`]: https://github.com/carllerche/slab
+//!
+//! # `no_std`
+//!
+//! The slab itself only needs `alloc`. Without the default `std` feature the
+//! crate is `no_std`, and only the types which need threads, locks or hash
+//! maps are unavailable: `AnySlab`, `AsyncSlab`, `AtomicSlab`, `FuturesSlab`,
+//! `Interner`, `LocalSlab`, `LockedSlab`, `ShardedSlab`, `SlabArc` and
+//! `WakerSlab`.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
+
+mod allocator;
+#[cfg(feature = "std")]
+mod any_slab;
+#[cfg(feature = "arbitrary")]
+mod arbitrary;
+mod arena;
+#[cfg(feature = "std")]
+mod async_slab;
+#[cfg(feature = "std")]
+mod atomic;
+#[cfg(feature = "borsh")]
+mod borsh;
+mod bounded;
+#[cfg(feature = "cache")]
+mod cache;
+mod cache_padded;
+mod chunk_pool;
+pub mod compat;
+mod cow;
+mod dense;
+mod dyn_slab;
+#[cfg(feature = "epoch")]
+mod epoch;
+mod error;
+#[cfg(feature = "ffi")]
+pub mod ffi;
+mod frozen;
+#[cfg(feature = "std")]
+mod futures_slab;
+mod growth;
+mod handle;
+#[cfg(feature = "std")]
+mod interner;
+mod invariants;
+mod key_policy;
+mod lending;
+mod linked;
+mod list;
+#[cfg(feature = "std")]
+mod local;
+#[cfg(feature = "std")]
+mod locked;
+mod metrics;
+mod poison;
+mod pool;
+#[cfg(feature = "postcard")]
+mod postcard;
+#[cfg(feature = "proptest")]
+mod proptest;
+mod raw_parts;
+#[cfg(feature = "rayon")]
+mod rayon;
+#[cfg(feature = "std")]
+mod refcounted;
+mod reserve;
+#[cfg(feature = "rkyv")]
+mod rkyv;
+mod secondary;
+#[cfg(feature = "serde")]
+mod serde;
+#[cfg(feature = "std")]
+mod sharded;
+#[cfg(feature = "slab")]
+mod slab;
+mod slab_box;
+mod split;
+mod static_slab;
+mod stats;
+#[cfg(feature = "futures")]
+mod stream;
+mod sweep;
+#[cfg(feature = "std")]
+mod sync;
+mod tag;
+mod trace;
+#[cfg(feature = "std")]
+mod waker;
+mod zeroize;
+
+pub use crate::allocator::{
+    ChunkAllocator, Global, PageAligned, Region, HUGE_PAGE_SIZE, PAGE_SIZE,
+};
+#[cfg(feature = "std")]
+pub use crate::any_slab::{AnySlab, TypedKey};
+pub use crate::arena::Arena;
+#[cfg(feature = "std")]
+pub use crate::async_slab::{AsyncInsert, AsyncSlab, AsyncSlabRef};
+#[cfg(feature = "std")]
+pub use crate::atomic::{AtomicRef, AtomicSlab};
+pub use crate::bounded::BoundedSlab;
+#[cfg(feature = "cache")]
+pub use crate::cache::{LruIter, LruSlab};
+pub use crate::cache_padded::{CachePadded, CACHE_LINE_SIZE};
+pub use crate::chunk_pool::ChunkPool;
+pub use crate::cow::{CowSlab, SlabSnapshot};
+pub use crate::dense::{DenseIter, DenseIterMut, DenseSlab};
+pub use crate::dyn_slab::{DynSlab, DEFAULT_INLINE_SIZE};
+#[cfg(feature = "epoch")]
+pub use crate::epoch::EpochSlab;
+pub use crate::error::TryReserveError;
+pub use crate::frozen::FrozenSlab;
+#[cfg(feature = "std")]
+pub use crate::futures_slab::FuturesSlab;
+pub use crate::growth::GrowthPolicy;
+pub use crate::handle::Handle;
+#[cfg(feature = "std")]
+pub use crate::interner::{Interner, Symbol};
+pub use crate::key_policy::KeyPolicy;
+pub use crate::lending::{LendingIterMut, LendingIterator};
+pub use crate::linked::{LinkedIter, LinkedSlab};
+#[cfg(feature = "std")]
+pub use crate::local::{GlobalRegistry, LocalSlab};
+#[cfg(feature = "std")]
+pub use crate::locked::{LockedSlab, LockedWriteGuard};
+#[cfg(feature = "stats")]
+pub use crate::metrics::SlabMetrics;
+pub use crate::pool::{Pool, Reset};
+#[cfg(feature = "proptest")]
+pub use crate::proptest::slab_strategy;
+pub use crate::raw_parts::{RawChunk, RawLink, RawParts};
+#[cfg(feature = "rayon")]
+pub use crate::rayon::{ParIter, ParIterPinMut};
+#[cfg(feature = "std")]
+pub use crate::refcounted::{SlabArc, SlabWeak};
+pub use crate::reserve::ReservedKey;
+#[cfg(feature = "rkyv")]
+pub use crate::rkyv::{ArchivedSlab, ArchivedSlabError, SlabResolver};
+pub use crate::secondary::SecondaryMap;
+#[cfg(feature = "std")]
+pub use crate::sharded::{ShardedRef, ShardedRefMut, ShardedSlab};
+pub use crate::slab_box::SlabBox;
+pub use crate::static_slab::{StaticIter, StaticSlab};
+pub use crate::stats::{SlabReport, SlabStats, HISTOGRAM_BUCKETS};
+#[cfg(feature = "futures")]
+pub use crate::stream::IterStream;
+pub use crate::sweep::Sweep;
+#[cfg(feature = "std")]
+pub use crate::waker::WakerSlab;
+
+use crate::allocator::RawArray;
+use crate::growth::Growth;
+use crate::metrics::Counters;
+use alloc::{boxed::Box, vec::Vec};
+use core::fmt;
+use core::hash::{Hash, Hasher};
+use core::iter::{FusedIterator, IntoIterator, Iterator};
+use core::mem::{self, MaybeUninit};
+use core::ops::{self, Bound, RangeBounds};
+use core::pin::Pin;
+use core::ptr;
+
+/// The number of entries in each chunk of a `Slab` unless another chunk size is
+/// given.
+pub const DEFAULT_CHUNK_SIZE: usize = 1024;
 
-use arrayvec::ArrayVec;
-use std::iter::{IntoIterator, Iterator};
-use std::mem;
-use std::ops;
-use std::pin::Pin;
+/// Marks either end of the list of occupied entries.
+const NIL: usize = usize::MAX;
 
-/// The number of elements in each `Chunk`'s array. This can be removed once const
-/// generics are stable.
-pub const CHUNK_SIZE: usize = 1024;
+/// How far apart the keys of a deserialized slab may be on average before
+/// they are rejected, see `is_plausible_key`.
+#[cfg(any(feature = "borsh", feature = "rkyv", feature = "serde"))]
+const MAX_KEYS_PER_VALUE: usize = 1 << 16;
+
+/// Return `true` if a deserializer may store a value at `key` after storing
+/// `len` values.
+///
+/// Keys come from untrusted input there, so this rejects `NIL` and keys which
+/// are so large that the chunk table would grow out of proportion to the
+/// values read so far. It doesn't depend on the chunk size, so that archives
+/// can be validated without knowing it.
+#[cfg(any(feature = "borsh", feature = "rkyv", feature = "serde"))]
+fn is_plausible_key(len: usize, key: usize) -> bool {
+    key != NIL && key / MAX_KEYS_PER_VALUE <= len
+}
 
 /// The slab-allocator (also known as an object pool) struct.
-#[derive(Debug, Clone)]
-pub struct Slab<T> {
-    chunks: Vec<Chunk<T>>,
+///
+/// Values are stored in chunks of up to `N` entries each, which never move
+/// once allocated. The chunk size must be greater than zero. See
+/// [`GrowthPolicy`](enum.GrowthPolicy.html) for the sizes of the chunks.
+///
+/// Each chunk stores its values in a plain array of `T`, so the values of
+/// neighbouring keys are exactly `size_of::<T>()` bytes apart. Which entries
+/// are occupied, the free list, and the order of the occupied entries are
+/// kept in separate arrays, so iterating only reads the values it yields.
+///
+/// If `T` is zero-sized, e.g. for a `Slab<()>` which only hands out unique
+/// keys, the chunks never allocate any memory for values and the slab only
+/// allocates its bookkeeping.
+pub struct Slab<T, const N: usize = DEFAULT_CHUNK_SIZE, A: ChunkAllocator = Global> {
+    /// The chunk holding the keys `growth.chunk_start(i)..` is at index `i`,
+    /// or `None` if it has been freed or not allocated yet.
+    chunks: Vec<Option<Chunk<T, A>>>,
+    growth: Growth<N>,
+    key_policy: KeyPolicy,
+    alloc: A,
     len: usize,
     next: usize,
+    /// The smallest occupied key, or `NIL` if the slab is empty.
+    head: usize,
+    /// The largest occupied key, or `NIL` if the slab is empty.
+    tail: usize,
+    /// One past the largest key handed out since the slab was last cleared,
+    /// where `KeyPolicy::Monotonic` continues when it is switched to.
+    issued: usize,
+    /// The stamp of the last inserted value. See `Handle`.
+    stamp: u64,
+    /// The number of reserved keys. See `Slab::reserve_key`.
+    reserved: usize,
+    /// See `Slab::metrics`.
+    counters: Counters,
+    /// See `Slab::named`.
+    name: Option<&'static str>,
+}
+
+impl<T: Clone, const N: usize, A: ChunkAllocator> Clone for Slab<T, N, A> {
+    fn clone(&self) -> Self {
+        Slab {
+            chunks: self.chunks.clone(),
+            growth: self.growth,
+            key_policy: self.key_policy,
+            alloc: self.alloc.clone(),
+            len: self.len,
+            next: self.next,
+            head: self.head,
+            tail: self.tail,
+            issued: self.issued,
+            stamp: self.stamp,
+            reserved: self.reserved,
+            counters: self.counters.clone(),
+            name: self.name,
+        }
+    }
+
+    /// Make the slab a copy of `source`, reusing every allocated chunk which
+    /// has the same size as the chunk of `source` in its place.
+    ///
+    /// Values are dropped in place and the clones are written into the same
+    /// memory, so refreshing a copy of a large slab doesn't allocate once the
+    /// copy has all of its chunks.
+    fn clone_from(&mut self, source: &Self) {
+        /// Leaves the slab empty if cloning a value panics.
+        struct Guard<'a, T, const N: usize, A: ChunkAllocator>(&'a mut Slab<T, N, A>);
+
+        impl<'a, T, const N: usize, A: ChunkAllocator> Drop for Guard<'a, T, N, A> {
+            fn drop(&mut self) {
+                self.0.clear();
+            }
+        }
+
+        self.clear();
+        self.chunks.truncate(source.chunks.len());
+        self.chunks.resize_with(source.chunks.len(), || None);
+        let guard = Guard(self);
+        for (chunk, source) in guard.0.chunks.iter_mut().zip(&source.chunks) {
+            match (chunk, source) {
+                (Some(chunk), Some(source)) if chunk.size() == source.size() => {
+                    chunk.clone_from(source);
+                }
+                (chunk, source) => *chunk = source.clone(),
+            }
+        }
+        mem::forget(guard);
+
+        self.growth = source.growth;
+        self.key_policy = source.key_policy;
+        self.alloc = source.alloc.clone();
+        self.len = source.len;
+        self.next = source.next;
+        self.head = source.head;
+        self.tail = source.tail;
+        self.issued = source.issued;
+        self.stamp = source.stamp;
+        self.reserved = source.reserved;
+        self.counters = source.counters.clone();
+        self.name = source.name;
+    }
 }
 
-impl<T> Default for Slab<T> {
+impl<T, const N: usize, A: ChunkAllocator + Default> Default for Slab<T, N, A> {
     fn default() -> Self {
-        Slab::new()
+        Slab::with_growth_policy_in(GrowthPolicy::Fixed, A::default())
     }
 }
 
-#[derive(Debug, Clone)]
-struct Chunk<T> {
-    pub entries: Pin<Box<ArrayVec<[Entry<T>; CHUNK_SIZE]>>>,
+struct Chunk<T, A: ChunkAllocator = Global> {
+    /// The values of the chunk, allocated with `alloc`. Only the entries
+    /// marked in `occupied` are initialized. The entries never move, so they
+    /// can be handed out pinned.
+    entries: RawArray<T, A>,
+    /// The number of entries at the start of `entries` which have been handed
+    /// out. The rest have never been occupied.
+    pub touched: usize,
+    /// One bit per entry, set if the entry is occupied.
+    pub occupied: Box<[u64]>,
+    /// One bit per entry, set if the entry is vacant but its key is reserved.
+    /// See `Slab::reserve_key`.
+    pub reserved: Box<[u64]>,
+    /// The neighbours of each occupied entry. For vacant entries, `next` is
+    /// the next key of the free list instead.
+    pub links: Box<[Link]>,
+    /// The stamp of each occupied entry, unique among all values ever
+    /// inserted into the slab.
+    pub stamps: Box<[u64]>,
+    /// The tag of each occupied entry. See `Slab::tag`.
+    pub tags: Box<[u32]>,
     pub len: usize,
+    /// Where each vacant entry was vacated. See `Slab::freed_at`.
+    #[cfg(feature = "poison")]
+    pub freed_at: Box<[Option<&'static core::panic::Location<'static>>]>,
 }
 
-impl<T> Chunk<T> {
-    pub fn new() -> Self {
-        Chunk {
-            entries: Box::pin(ArrayVec::new()),
+/// The keys of the previous and next occupied entries. Together these form a
+/// doubly-linked list through every occupied entry, sorted by key.
+#[derive(Debug, Clone, Copy)]
+#[repr(C)]
+struct Link {
+    prev: usize,
+    next: usize,
+}
+
+impl<T, A: ChunkAllocator> Chunk<T, A> {
+    pub fn new(size: usize, alloc: A) -> Self {
+        match Chunk::try_new(size, alloc) {
+            Ok(chunk) => chunk,
+            Err(TryReserveError::AllocError { layout }) => alloc::alloc::handle_alloc_error(layout),
+            Err(err) => panic!("{}", err),
+        }
+    }
+
+    pub fn try_new(size: usize, alloc: A) -> Result<Self, TryReserveError> {
+        let link = Link {
+            prev: NIL,
+            next: NIL,
+        };
+
+        let words = size.div_ceil(64);
+        let mut occupied = Vec::new();
+        occupied
+            .try_reserve_exact(words)
+            .map_err(|_| TryReserveError::array::<u64>(words))?;
+        occupied.resize(words, 0);
+
+        let mut reserved = Vec::new();
+        reserved
+            .try_reserve_exact(words)
+            .map_err(|_| TryReserveError::array::<u64>(words))?;
+        reserved.resize(words, 0);
+
+        let mut links = Vec::new();
+        links
+            .try_reserve_exact(size)
+            .map_err(|_| TryReserveError::array::<Link>(size))?;
+        links.resize(size, link);
+
+        let mut stamps = Vec::new();
+        stamps
+            .try_reserve_exact(size)
+            .map_err(|_| TryReserveError::array::<u64>(size))?;
+        stamps.resize(size, 0);
+
+        let mut tags = Vec::new();
+        tags.try_reserve_exact(size)
+            .map_err(|_| TryReserveError::array::<u32>(size))?;
+        tags.resize(size, 0);
+
+        Ok(Chunk {
+            entries: RawArray::try_new(size, alloc)?,
+            touched: 0,
+            occupied: occupied.into_boxed_slice(),
+            reserved: reserved.into_boxed_slice(),
+            links: links.into_boxed_slice(),
+            stamps: stamps.into_boxed_slice(),
+            tags: tags.into_boxed_slice(),
             len: 0,
+            #[cfg(feature = "poison")]
+            freed_at: alloc::vec![None; size].into_boxed_slice(),
+        })
+    }
+
+    /// Return the number of entries of the chunk.
+    pub fn size(&self) -> usize {
+        self.entries().len()
+    }
+
+    pub fn entries(&self) -> &[MaybeUninit<T>] {
+        self.entries.as_slice()
+    }
+
+    /// Un-pins every entry, so the caller must not move occupied entries.
+    pub unsafe fn entries_mut(&mut self) -> &mut [MaybeUninit<T>] {
+        self.entries.as_mut_slice()
+    }
+
+    pub fn is_full(&self) -> bool {
+        self.touched == self.size()
+    }
+
+    pub fn is_occupied(&self, entry_key: usize) -> bool {
+        // SAFETY: There is a bit for every entry.
+        entry_key < self.size()
+            && unsafe { self.occupied.get_unchecked(entry_key / 64) } & (1 << (entry_key % 64)) != 0
+    }
+
+    pub fn is_reserved(&self, entry_key: usize) -> bool {
+        entry_key < self.size() && self.reserved[entry_key / 64] & (1 << (entry_key % 64)) != 0
+    }
+
+    pub fn set_reserved(&mut self, entry_key: usize, reserved: bool) {
+        let bit = 1 << (entry_key % 64);
+        if reserved {
+            self.reserved[entry_key / 64] |= bit;
+        } else {
+            self.reserved[entry_key / 64] &= !bit;
+        }
+    }
+
+    /// Return `true` if the chunk holds no values and no reserved keys, so it
+    /// can be freed.
+    pub fn is_unused(&self) -> bool {
+        self.len == 0 && self.reserved.iter().all(|&word| word == 0)
+    }
+
+    pub fn get(&self, entry_key: usize) -> Option<&T> {
+        if !self.is_occupied(entry_key) {
+            return None;
+        }
+
+        // SAFETY: Occupied entries are in bounds and initialized.
+        Some(unsafe { self.entries().get_unchecked(entry_key).assume_init_ref() })
+    }
+
+    /// Return a pointer to the occupied entry at `entry_key`, without
+    /// creating a reference to it.
+    pub fn as_ptr(&self, entry_key: usize) -> Option<*mut T> {
+        if !self.is_occupied(entry_key) {
+            return None;
+        }
+
+        // SAFETY: Occupied entries are in bounds.
+        Some(unsafe { self.entries.as_mut_ptr().add(entry_key) }.cast())
+    }
+
+    /// Un-pins the entry at `entry_key`, see `Slab::get_mut`.
+    pub unsafe fn get_mut(&mut self, entry_key: usize) -> Option<&mut T> {
+        if !self.is_occupied(entry_key) {
+            return None;
+        }
+
+        Some(
+            self.entries_mut()
+                .get_unchecked_mut(entry_key)
+                .assume_init_mut(),
+        )
+    }
+
+    /// Return the vacant entries of word `i` of the bitmap which aren't
+    /// reserved, ignoring the bits past the end of the chunk.
+    pub fn vacant_word(&self, i: usize) -> u64 {
+        let vacant = !(self.occupied[i] | self.reserved[i]);
+        match self.size() - i * 64 {
+            bits if bits < 64 => vacant & ((1 << bits) - 1),
+            _ => vacant,
+        }
+    }
+
+    /// Drop every occupied entry and mark all entries vacant, releasing the
+    /// reserved keys as well.
+    ///
+    /// The chunk is marked empty before anything is dropped, so if a value
+    /// panics while being dropped the rest are leaked instead.
+    #[track_caller]
+    pub fn clear(&mut self) {
+        /// Puts the bitmap back, cleared, even if dropping a value panics.
+        struct Guard<'a, T, A: ChunkAllocator> {
+            chunk: &'a mut Chunk<T, A>,
+            occupied: Box<[u64]>,
+        }
+
+        impl<'a, T, A: ChunkAllocator> Drop for Guard<'a, T, A> {
+            fn drop(&mut self) {
+                self.occupied.fill(0);
+                self.chunk.occupied = mem::take(&mut self.occupied);
+            }
+        }
+
+        let occupied = mem::take(&mut self.occupied);
+        let len = mem::replace(&mut self.touched, 0);
+        self.len = 0;
+        self.reserved.fill(0);
+        let guard = Guard {
+            chunk: self,
+            occupied,
+        };
+
+        // SAFETY: Dropping in place doesn't move the values, and the entries
+        // are marked vacant before they are dropped.
+        let entries = unsafe { guard.chunk.entries_mut() };
+        for (i, entry) in entries[..len].iter_mut().enumerate() {
+            if guard.occupied[i / 64] & (1 << (i % 64)) != 0 {
+                unsafe { entry.as_mut_ptr().drop_in_place() };
+            }
+        }
+        for i in 0..len {
+            if guard.occupied[i / 64] & (1 << (i % 64)) != 0 {
+                guard.chunk.wipe(i);
+                guard.chunk.poison(i);
+            }
+        }
+    }
+
+    /// Return the entries of the chunk as a slice, if none of them are vacant.
+    pub fn as_slice(&self) -> Option<&[T]> {
+        if self.len != self.touched {
+            return None;
+        }
+
+        // SAFETY: Every entry is initialized, and `MaybeUninit<T>` has the
+        // same layout as `T`.
+        Some(unsafe { core::slice::from_raw_parts(self.entries().as_ptr() as *const T, self.len) })
+    }
+
+    pub fn set_occupied(&mut self, entry_key: usize, occupied: bool) {
+        let bit = 1 << (entry_key % 64);
+        if occupied {
+            self.occupied[entry_key / 64] |= bit;
+        } else {
+            self.occupied[entry_key / 64] &= !bit;
+        }
+    }
+
+    /// Return the index of the first occupied entry at or after `entry_key`.
+    pub fn next_occupied(&self, entry_key: usize) -> Option<usize> {
+        let mut i = entry_key / 64;
+        let mut word = *self.occupied.get(i)? & (!0 << (entry_key % 64));
+
+        loop {
+            if word != 0 {
+                return Some(i * 64 + word.trailing_zeros() as usize);
+            }
+            i += 1;
+            word = *self.occupied.get(i)?;
+        }
+    }
+
+    /// Return the index of the first vacant entry at or after `entry_key`.
+    pub fn next_vacant(&self, entry_key: usize) -> Option<usize> {
+        let mut i = entry_key / 64;
+        if i >= self.occupied.len() {
+            return None;
+        }
+        let mut word = self.vacant_word(i) & (!0 << (entry_key % 64));
+
+        loop {
+            if word != 0 {
+                return Some(i * 64 + word.trailing_zeros() as usize);
+            }
+            i += 1;
+            if i == self.occupied.len() {
+                return None;
+            }
+            word = self.vacant_word(i);
+        }
+    }
+
+    /// Return the number of occupied entries in `start..end`.
+    pub fn count_occupied(&self, start: usize, end: usize) -> usize {
+        if start == 0 && end == self.size() {
+            return self.len;
+        }
+
+        let mut count = 0;
+        for i in start / 64..=(end - 1) / 64 {
+            let mut word = self.occupied[i];
+            if i == start / 64 {
+                word &= !0 << (start % 64);
+            }
+            if end - i * 64 < 64 {
+                word &= (1 << (end - i * 64)) - 1;
+            }
+            count += word.count_ones() as usize;
+        }
+
+        count
+    }
+
+    /// Return the number of entries up to and including the last one which
+    /// is occupied or reserved.
+    pub fn taken_len(&self) -> usize {
+        let reserved = self.reserved.iter().rposition(|&word| word != 0);
+        let reserved = reserved.map_or(0, |i| {
+            i * 64 + 64 - self.reserved[i].leading_zeros() as usize
+        });
+        let occupied = self.prev_occupied(self.size()).map_or(0, |j| j + 1);
+        occupied.max(reserved)
+    }
+
+    /// Return the index of the last occupied entry before `entry_key`.
+    pub fn prev_occupied(&self, entry_key: usize) -> Option<usize> {
+        let mut i = entry_key / 64;
+        let mut word = match entry_key % 64 {
+            0 => 0,
+            bits => self.occupied[i] & (!0 >> (64 - bits)),
+        };
+
+        loop {
+            if word != 0 {
+                return Some(i * 64 + 63 - word.leading_zeros() as usize);
+            }
+            i = i.checked_sub(1)?;
+            word = self.occupied[i];
+        }
+    }
+}
+
+impl<T, A: ChunkAllocator> Drop for Chunk<T, A> {
+    fn drop(&mut self) {
+        self.clear();
+        self.wipe_all();
+    }
+}
+
+impl<T: Clone, A: ChunkAllocator> Clone for Chunk<T, A> {
+    fn clone(&self) -> Self {
+        let mut chunk = Chunk::new(self.size(), self.entries.allocator().clone());
+        chunk.clone_from(self);
+        chunk
+    }
+
+    /// Drop the values of the chunk and clone those of `source` into the same
+    /// allocation, which must have the same size.
+    fn clone_from(&mut self, source: &Self) {
+        debug_assert_eq!(self.size(), source.size());
+        self.clear();
+        self.links.copy_from_slice(&source.links);
+        self.stamps.copy_from_slice(&source.stamps);
+        self.tags.copy_from_slice(&source.tags);
+        #[cfg(feature = "poison")]
+        self.freed_at.copy_from_slice(&source.freed_at);
+
+        // SAFETY: The chunk is empty, so nothing is pinned in it.
+        let entries = unsafe { self.entries_mut() };
+        for (i, entry) in entries[..source.touched].iter_mut().enumerate() {
+            if let Some(val) = source.get(i) {
+                *entry = MaybeUninit::new(val.clone());
+            }
         }
+
+        // Only mark the entries occupied once they're all cloned, so a
+        // panicking `clone` leaks the clones instead of dropping uninitialized
+        // entries.
+        self.touched = source.touched;
+        self.occupied.copy_from_slice(&source.occupied);
+        self.reserved.copy_from_slice(&source.reserved);
+        self.len = source.len;
     }
 }
 
-#[derive(Debug, Clone)]
-enum Entry<T> {
-    Occupied(T),
-    Vacant(usize),
+impl<T: fmt::Debug, A: ChunkAllocator> fmt::Debug for Chunk<T, A> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let occupied = (0..self.touched).filter_map(|i| Some((i, self.get(i)?)));
+        f.debug_map().entries(occupied).finish()
+    }
+}
+
+/// An iterator over the values stored in the `Slab`
+pub struct Iter<'a, T: 'a, const N: usize = DEFAULT_CHUNK_SIZE, A: ChunkAllocator = Global> {
+    chunks: &'a [Option<Chunk<T, A>>],
+    growth: Growth<N>,
+    /// The key of the next entry to yield from the front.
+    front: usize,
+    /// The key of the next entry to yield from the back.
+    back: usize,
+    /// The number of entries left to yield.
+    remaining: usize,
 }
 
 /// An iterator over the values stored in the `Slab`
-pub struct Iter<'a, T: 'a> {
-    chunks: std::slice::Iter<'a, Chunk<T>>,
-    entries: std::slice::Iter<'a, Entry<T>>,
-    curr: usize,
+pub struct IterMut<'a, T: 'a, const N: usize = DEFAULT_CHUNK_SIZE, A: ChunkAllocator = Global> {
+    /// The chunks which neither end of the iterator has reached yet.
+    chunks: core::slice::IterMut<'a, Option<Chunk<T, A>>>,
+    growth: Growth<N>,
+    /// The index of the first chunk in `chunks`.
+    chunks_start: usize,
+    front_chunk: Option<ChunkIterMut<'a, T>>,
+    back_chunk: Option<ChunkIterMut<'a, T>>,
+    front: usize,
+    back: usize,
+    remaining: usize,
+}
+
+/// The entries of a chunk that one end of an `IterMut` is yielding from.
+struct ChunkIterMut<'a, T: 'a> {
+    index: usize,
+    entries: core::slice::IterMut<'a, MaybeUninit<T>>,
+    links: &'a [Link],
+    /// The index of the front of `entries` within the chunk.
+    start: usize,
+    /// The index one past the back of `entries` within the chunk.
+    end: usize,
+}
+
+/// An owning iterator over the values stored in the `Slab`
+pub struct IntoIter<T, const N: usize = DEFAULT_CHUNK_SIZE, A: ChunkAllocator = Global> {
+    slab: Slab<T, N, A>,
+}
+
+/// An iterator over pinned mutable references to the values stored in the
+/// `Slab`
+pub struct IterPinMut<'a, T: 'a, const N: usize = DEFAULT_CHUNK_SIZE, A: ChunkAllocator = Global> {
+    inner: IterMut<'a, T, N, A>,
+}
+
+/// An iterator over the keys of the `Slab`
+///
+/// See [`Slab::keys`](struct.Slab.html#method.keys).
+pub struct Keys<'a, T: 'a, const N: usize = DEFAULT_CHUNK_SIZE, A: ChunkAllocator = Global> {
+    inner: Iter<'a, T, N, A>,
 }
 
 /// An iterator over the values stored in the `Slab`
-pub struct IterMut<'a, T: 'a> {
-    chunks: std::slice::IterMut<'a, Chunk<T>>,
-    entries: std::slice::IterMut<'a, Entry<T>>,
-    curr: usize,
+///
+/// See [`Slab::values`](struct.Slab.html#method.values).
+pub struct Values<'a, T: 'a, const N: usize = DEFAULT_CHUNK_SIZE, A: ChunkAllocator = Global> {
+    inner: Iter<'a, T, N, A>,
+}
+
+/// An iterator over pinned mutable references to the values stored in the
+/// `Slab`
+///
+/// See [`Slab::values_pin_mut`](struct.Slab.html#method.values_pin_mut).
+pub struct ValuesPinMut<'a, T: 'a, const N: usize = DEFAULT_CHUNK_SIZE, A: ChunkAllocator = Global>
+{
+    inner: IterPinMut<'a, T, N, A>,
+}
+
+/// An iterator over the vacant keys of the `Slab`
+///
+/// See [`Slab::vacant_keys`](struct.Slab.html#method.vacant_keys).
+pub struct VacantKeys<'a, T: 'a, const N: usize = DEFAULT_CHUNK_SIZE, A: ChunkAllocator = Global> {
+    chunks: &'a [Option<Chunk<T, A>>],
+    growth: Growth<N>,
+    /// The index of the chunk `word` was taken from.
+    chunk: usize,
+    /// The index of the bitmap word `word` was taken from, within the chunk.
+    word_index: usize,
+    /// The vacant entries of the current bitmap word which haven't been
+    /// yielded yet.
+    word: u64,
+    remaining: usize,
+}
+
+/// An iterator over the chunks of a fully occupied `Slab` as slices
+///
+/// See [`Slab::as_chunk_slices`](struct.Slab.html#method.as_chunk_slices).
+pub struct ChunkSlices<'a, T: 'a, const N: usize = DEFAULT_CHUNK_SIZE, A: ChunkAllocator = Global> {
+    chunks: core::slice::Iter<'a, Option<Chunk<T, A>>>,
+}
+
+/// A draining iterator for `Slab`
+///
+/// See [`Slab::drain`](struct.Slab.html#method.drain).
+pub struct Drain<'a, T: 'a, const N: usize = DEFAULT_CHUNK_SIZE, A: ChunkAllocator = Global> {
+    slab: &'a mut Slab<T, N, A>,
+}
+
+/// An iterator which removes and yields the entries matching a predicate.
+///
+/// See [`Slab::extract_if`](struct.Slab.html#method.extract_if).
+pub struct ExtractIf<'a, T: 'a, F, const N: usize = DEFAULT_CHUNK_SIZE, A: ChunkAllocator = Global>
+{
+    slab: &'a mut Slab<T, N, A>,
+    /// The key of the next entry to visit, or `NIL`.
+    front: usize,
+    pred: F,
+}
+
+/// A cursor over the occupied entries of a `Slab` which can remove the
+/// entry it points to.
+///
+/// See [`Slab::cursor_mut`](struct.Slab.html#method.cursor_mut).
+pub struct CursorMut<'a, T: 'a, const N: usize = DEFAULT_CHUNK_SIZE, A: ChunkAllocator = Global> {
+    slab: &'a mut Slab<T, N, A>,
+    key: Option<usize>,
 }
 
 impl<T> Slab<T> {
@@ -77,13 +816,148 @@ impl<T> Slab<T> {
     /// let slab: Slab<i32> = Slab::new();
     /// ```
     pub fn new() -> Self {
+        Slab::with_chunk_size()
+    }
+
+    /// Construct a new, empty `Slab` with room for at least `capacity` values.
+    ///
+    /// The capacity is rounded up to a multiple of `DEFAULT_CHUNK_SIZE`, and
+    /// every chunk needed for it is allocated up front.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the new capacity overflows a `usize`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use pinned_slab::*;
+    /// let slab: Slab<i32> = Slab::with_capacity(10);
+    /// assert_eq!(slab.capacity(), DEFAULT_CHUNK_SIZE);
+    /// ```
+    pub fn with_capacity(capacity: usize) -> Self {
+        let mut slab = Slab::new();
+        slab.reserve_exact(capacity);
+        slab
+    }
+
+    /// Construct a new, empty `Slab` with `n` chunks allocated up front.
+    ///
+    /// See [`ChunkPool`](struct.ChunkPool.html) to reuse the chunks of
+    /// other slabs instead.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use pinned_slab::*;
+    /// let slab: Slab<i32> = Slab::with_chunks(2);
+    /// assert_eq!(slab.capacity(), DEFAULT_CHUNK_SIZE * 2);
+    /// ```
+    pub fn with_chunks(n: usize) -> Self {
+        let mut slab = Slab::new();
+        slab.chunks.reserve_exact(n);
+        slab.allocate_chunks(n);
+        slab
+    }
+}
+
+impl<T, A: ChunkAllocator> Slab<T, DEFAULT_CHUNK_SIZE, A> {
+    /// Construct a new, empty `Slab` whose chunks are allocated with `alloc`.
+    ///
+    /// Like [`new`](#method.new), this does not allocate. See
+    /// [`ChunkAllocator`](trait.ChunkAllocator.html).
+    pub fn new_in(alloc: A) -> Self {
+        Slab::with_growth_policy_in(GrowthPolicy::Fixed, alloc)
+    }
+}
+
+impl<T, const N: usize> Slab<T, N> {
+    /// Construct a new, empty `Slab` with chunks of `N` entries.
+    ///
+    /// Like [`new`](#method.new), this does not allocate. Keys are still
+    /// assigned densely, but capacity is allocated `N` values at a time.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use pinned_slab::*;
+    /// let mut slab = Slab::<&str, 16>::with_chunk_size();
+    ///
+    /// slab.insert("hello");
+    /// assert_eq!(slab.capacity(), 16);
+    /// assert_eq!(Slab::<&str, 16>::CHUNK_SIZE, 16);
+    /// ```
+    pub fn with_chunk_size() -> Self {
+        Slab::with_growth_policy(GrowthPolicy::Fixed)
+    }
+
+    /// Construct a new, empty `Slab` whose chunks grow according to `policy`.
+    ///
+    /// Like [`new`](#method.new), this does not allocate.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the first chunk of a geometric policy is empty or larger
+    /// than `N`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use pinned_slab::*;
+    /// let mut slab = Slab::<usize, 256>::with_growth_policy(GrowthPolicy::Geometric { first: 32 });
+    ///
+    /// for i in 0..1000 {
+    ///     slab.insert(i);
+    /// }
+    ///
+    /// // The chunks hold 32, 64, and 128 entries, then 256 entries each.
+    /// assert_eq!(slab.chunk_count(), 7);
+    /// assert_eq!(slab.capacity(), 32 + 64 + 128 + 4 * 256);
+    /// assert_eq!(slab.chunk_range(1), 32..96);
+    /// ```
+    pub fn with_growth_policy(policy: GrowthPolicy) -> Self {
+        Slab::with_growth_policy_in(policy, Global)
+    }
+}
+
+impl<T, const N: usize, A: ChunkAllocator> Slab<T, N, A> {
+    /// The number of entries in each chunk, or in the largest chunks with a
+    /// geometric [`GrowthPolicy`](enum.GrowthPolicy.html).
+    pub const CHUNK_SIZE: usize = N;
+
+    /// Construct a new, empty `Slab` whose chunks grow according to `policy`
+    /// and are allocated with `alloc`.
+    ///
+    /// See [`with_growth_policy`](#method.with_growth_policy) and
+    /// [`ChunkAllocator`](trait.ChunkAllocator.html).
+    ///
+    /// # Panics
+    ///
+    /// Panics if the first chunk of a geometric policy is empty or larger
+    /// than `N`.
+    pub fn with_growth_policy_in(policy: GrowthPolicy, alloc: A) -> Self {
         Slab {
             chunks: Vec::new(),
+            growth: Growth::new(policy),
+            key_policy: KeyPolicy::Lifo,
+            alloc,
             len: 0,
             next: 0,
+            head: NIL,
+            tail: NIL,
+            issued: 0,
+            stamp: 0,
+            reserved: 0,
+            counters: Counters::new(),
+            name: None,
         }
     }
 
+    /// Return a reference to the allocator of the slab.
+    pub fn allocator(&self) -> &A {
+        &self.alloc
+    }
+
     /// Return the number of stored values.
     ///
     /// # Examples
@@ -137,19 +1011,11 @@ impl<T> Slab<T> {
         self.get(key).is_some()
     }
 
-    /// Return the number of values the slab can store without reallocating.
-    ///
-    /// This will always be a multiple of `CHUNK_SIZE`.
-    pub fn capacity(&self) -> usize {
-        self.chunks.len() * CHUNK_SIZE
-    }
-
-    /// Return an iterator over the slab.
+    /// Return `true` if `ptr` points to a value stored in the slab.
     ///
-    /// This function should generally be **avoided** as it is not efficient.
-    /// Iterators must iterate over every slot in the slab even if it is
-    /// vacant. As such, a slab with a capacity of 1 million but only one
-    /// stored value must still iterate the million slots.
+    /// This is useful for validating pointers handed out to foreign code
+    /// before turning them back into references. See
+    /// [`key_of_ptr`](#method.key_of_ptr).
     ///
     /// # Examples
     ///
@@ -157,31 +1023,26 @@ impl<T> Slab<T> {
     /// # use pinned_slab::*;
     /// let mut slab = Slab::new();
     ///
-    /// for i in 0..3 {
-    ///     slab.insert(i);
-    /// }
-    ///
-    /// let mut iterator = slab.iter();
+    /// let (key, val) = slab.insert(5);
+    /// let ptr = val as *const i32;
+    /// assert!(slab.contains_ptr(ptr));
     ///
-    /// assert_eq!(iterator.next(), Some((0, &0)));
-    /// assert_eq!(iterator.next(), Some((1, &1)));
-    /// assert_eq!(iterator.next(), Some((2, &2)));
-    /// assert_eq!(iterator.next(), None);
+    /// slab.remove(key);
+    /// assert!(!slab.contains_ptr(ptr));
+    /// assert!(!slab.contains_ptr(&5));
     /// ```
-    pub fn iter(&self) -> Iter<T> {
-        Iter {
-            chunks: self.chunks.iter(),
-            entries: [].iter(),
-            curr: 0,
-        }
+    pub fn contains_ptr(&self, ptr: *const T) -> bool {
+        self.key_of_ptr(ptr).is_some()
     }
 
-    /// Return an iterator that allows modifying each value.
+    /// Return the key of the value `ptr` points to.
+    ///
+    /// If `ptr` does not point to an occupied entry of the slab, then `None`
+    /// is returned. The pointer is never dereferenced, so it is fine to pass
+    /// dangling or otherwise invalid pointers. This runs in `O(chunks)`.
     ///
-    /// This function should generally be **avoided** as it is not efficient.
-    /// Iterators must iterate over every slot in the slab even if it is
-    /// vacant. As such, a slab with a capacity of 1 million but only one
-    /// stored value must still iterate the million slots.
+    /// Values of a zero-sized `T` all share one address, so this always
+    /// returns `None` for them.
     ///
     /// # Examples
     ///
@@ -189,171 +1050,237 @@ impl<T> Slab<T> {
     /// # use pinned_slab::*;
     /// let mut slab = Slab::new();
     ///
-    /// let (key1, _) = slab.insert(0);
-    /// let (key2, _) = slab.insert(1);
-    ///
-    /// for (key, val) in unsafe { slab.iter_mut() } {
-    ///     if key == key1 {
-    ///         *val += 2;
-    ///     }
-    /// }
+    /// slab.insert("hello");
+    /// let (key, val) = slab.insert("world");
+    /// let ptr = val as *const &str;
     ///
-    /// assert_eq!(slab[key1], 2);
-    /// assert_eq!(slab[key2], 1);
+    /// assert_eq!(slab.key_of_ptr(ptr), Some(key));
+    /// assert_eq!(slab.key_of_ptr(std::ptr::null()), None);
     /// ```
-    ///
-    /// # Safety
-    ///
-    /// This effectively un-pins every entry. The caller has to make sure
-    /// that this is definitely what they want to do, e.g. they won't invalidate
-    /// any pointers to these values.
-    pub unsafe fn iter_mut(&mut self) -> IterMut<T> {
-        IterMut {
-            chunks: self.chunks.iter_mut(),
-            entries: [].iter_mut(),
-            curr: 0,
+    pub fn key_of_ptr(&self, ptr: *const T) -> Option<usize> {
+        let entry_size = mem::size_of::<T>();
+        let addr = ptr as usize;
+
+        for (i, chunk) in self.chunks.iter().enumerate() {
+            let chunk = match chunk {
+                Some(chunk) => chunk,
+                None => continue,
+            };
+            let start = chunk.entries().as_ptr() as usize;
+            let end = start + chunk.touched * entry_size;
+            if addr < start || addr >= end {
+                continue;
+            }
+
+            let entry_key = (addr - start) / entry_size;
+            return match chunk.get(entry_key) {
+                Some(v) if ptr::eq(v, ptr) => Some(self.growth.chunk_start(i) + entry_key),
+                _ => None,
+            };
         }
+
+        None
     }
 
-    /// Return a reference to the value associated with the given key.
+    /// Return the number of values the slab can store without reallocating.
     ///
-    /// If the given key is not associated with a value, then `None` is
-    /// returned.
+    /// This is the total size of the allocated chunks, so with the default
+    /// [`GrowthPolicy`](enum.GrowthPolicy.html) it is a multiple of the chunk
+    /// size `N`.
+    pub fn capacity(&self) -> usize {
+        self.chunks.iter().flatten().map(Chunk::size).sum()
+    }
+
+    /// Reserve capacity for at least `additional` more values to be inserted
+    /// without allocating.
+    ///
+    /// Every chunk needed for the new capacity is allocated right away. The
+    /// list of chunks may over-allocate to avoid frequent reallocations, like
+    /// [`Vec::reserve`]. Does nothing if the capacity is already sufficient.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the new capacity overflows a `usize`.
     ///
     /// # Examples
     ///
     /// ```
     /// # use pinned_slab::*;
     /// let mut slab = Slab::new();
-    /// let (key, _) = slab.insert("hello");
+    /// slab.insert("hello");
     ///
-    /// assert_eq!(slab.get(key), Some(&"hello"));
-    /// assert_eq!(slab.get(123), None);
+    /// slab.reserve(DEFAULT_CHUNK_SIZE);
+    /// assert_eq!(slab.capacity(), 2 * DEFAULT_CHUNK_SIZE);
     /// ```
-    pub fn get(&self, key: usize) -> Option<&T> {
-        let slab_key = key / CHUNK_SIZE;
-        let entry_key = key % CHUNK_SIZE;
-
-        let slab = self.chunks.get(slab_key)?;
-        match slab.entries.get(entry_key) {
-            Some(&Entry::Occupied(ref val)) => Some(val),
-            _ => None,
-        }
+    pub fn reserve(&mut self, additional: usize) {
+        let missing = self.missing_chunks(additional).expect("capacity overflow");
+        self.chunks.reserve(self.missing_slots(missing));
+        self.allocate_chunks(missing);
     }
 
-    /// Return a mutable reference to the value associated with the given key.
+    /// Reserve capacity for at least `additional` more values to be inserted
+    /// without allocating.
     ///
-    /// If the given key is not associated with a value, then `None` is
-    /// returned.
+    /// Unlike [`reserve`](#method.reserve), the list of chunks will not
+    /// deliberately over-allocate. The capacity is still rounded up to whole
+    /// chunks.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the new capacity overflows a `usize`.
     ///
     /// # Examples
     ///
     /// ```
     /// # use pinned_slab::*;
     /// let mut slab = Slab::new();
-    /// let (key, _) = slab.insert("hello");
-    ///
-    /// unsafe {
-    ///     *slab.get_mut(key).unwrap() = "world";
-    /// }
+    /// slab.insert("hello");
     ///
-    /// assert_eq!(*unsafe { slab.get_mut(key) }.unwrap(), "world");
-    /// assert_eq!(unsafe { slab.get_mut(123) }, None);
+    /// slab.reserve_exact(DEFAULT_CHUNK_SIZE - 1);
+    /// assert_eq!(slab.capacity(), DEFAULT_CHUNK_SIZE);
     /// ```
-    ///
-    /// # Safety
-    ///
-    /// This effectively un-pins the entry at `key`. The caller has to make sure
-    /// that this is definitely what they want to do, e.g. they won't invalidate
-    /// any pointers to this value.
-    pub unsafe fn get_mut(&mut self, key: usize) -> Option<&mut T> {
-        let slab_key = key / CHUNK_SIZE;
-        let entry_key = key % CHUNK_SIZE;
-
-        let slab = self.chunks.get_mut(slab_key)?;
-        let entries = slab.entries.as_mut().get_unchecked_mut();
-        match entries.get_mut(entry_key) {
-            Some(&mut Entry::Occupied(ref mut val)) => Some(val),
-            _ => None,
-        }
+    pub fn reserve_exact(&mut self, additional: usize) {
+        let missing = self.missing_chunks(additional).expect("capacity overflow");
+        self.chunks.reserve_exact(self.missing_slots(missing));
+        self.allocate_chunks(missing);
     }
 
-    /// Insert a value in the slab, returning key assigned to the value and a
-    /// reference to that value.
+    /// Try to reserve capacity for at least `additional` more values to be
+    /// inserted without allocating.
     ///
-    /// The returned key can later be used to retrieve or remove the value using indexed
-    /// lookup and `remove`. Additional capacity is allocated if needed. See
-    /// [Capacity and reallocation](index.html#capacity-and-reallocation).
-    ///
-    /// # Panics
-    ///
-    /// Panics if the number of elements in the vector overflows a `usize`.
+    /// This behaves like [`reserve`](#method.reserve), except that an error
+    /// is returned instead of panicking or aborting if the capacity overflows
+    /// or the allocator reports a failure. Chunks which were allocated before
+    /// the failure are kept.
     ///
     /// # Examples
     ///
     /// ```
     /// # use pinned_slab::*;
     /// let mut slab = Slab::new();
-    /// let (key, value) = slab.insert("hello");
-    /// assert_eq!(*value, "hello");
-    /// assert_eq!(slab[key], "hello");
+    /// slab.insert("hello");
+    ///
+    /// assert_eq!(slab.try_reserve(10), Ok(()));
+    /// assert_eq!(slab.try_reserve(usize::MAX), Err(TryReserveError::CapacityOverflow));
     /// ```
-    /// Insert a value into the `Slab` and get the key for the value.
-    pub fn insert(&mut self, val: T) -> (usize, &T) {
-        let key = self.next;
+    pub fn try_reserve(&mut self, additional: usize) -> Result<(), TryReserveError> {
+        let result = self.try_allocate(additional);
+        if let Err(err) = &result {
+            trace::limit_reached(self.name, self.len, err);
+        }
+        result
+    }
 
-        (key, self.insert_at(key, val))
+    /// Allocate the chunks needed for `additional` more values. See
+    /// `try_reserve`.
+    fn try_allocate(&mut self, additional: usize) -> Result<(), TryReserveError> {
+        let missing = self
+            .missing_chunks(additional)
+            .ok_or(TryReserveError::CapacityOverflow)?;
+        let slots = self.missing_slots(missing);
+        self.chunks.try_reserve(slots).map_err(|_| {
+            TryReserveError::array::<Option<Chunk<T, A>>>(self.chunks.len() + slots)
+        })?;
+
+        for _ in 0..missing {
+            let chunk =
+                Chunk::try_new(self.growth.chunk_len(self.free_slot()), self.alloc.clone())?;
+            self.allocate_chunk(chunk);
+        }
+
+        Ok(())
     }
 
-    fn insert_at(&mut self, key: usize, val: T) -> &T {
-        self.len += 1;
+    /// Return the number of chunks which need to be allocated to insert
+    /// `additional` more values, or `None` if the capacity would overflow.
+    fn missing_chunks(&self, additional: usize) -> Option<usize> {
+        let required = self.len.checked_add(additional)?;
+        let mut capacity = self.capacity();
+
+        // Free slots are filled first, then new slots are pushed. Only the
+        // chunks before `doublings` are smaller than `N`.
+        let free = (0..self.chunks.len()).filter(|&i| self.chunks[i].is_none());
+        let smaller = self.chunks.len()..self.growth.doublings().max(self.chunks.len());
+        let mut missing = 0;
+        for index in free.chain(smaller) {
+            if capacity >= required {
+                return Some(missing);
+            }
+            capacity += self.growth.chunk_len(index);
+            missing += 1;
+        }
 
-        let slab_key = key / CHUNK_SIZE;
-        let entry_key = key % CHUNK_SIZE;
+        Some(missing + required.saturating_sub(capacity).div_ceil(N))
+    }
 
-        if slab_key == self.chunks.len() {
-            debug_assert_eq!(entry_key, 0);
-            self.chunks.push(Chunk::new());
+    /// Return the number of slots which need to be added to the list of chunks
+    /// to allocate `missing` more chunks.
+    fn missing_slots(&self, missing: usize) -> usize {
+        let free = self.chunks.iter().filter(|chunk| chunk.is_none()).count();
+        missing.saturating_sub(free)
+    }
+
+    fn allocate_chunks(&mut self, missing: usize) {
+        for _ in 0..missing {
+            let chunk = Chunk::new(self.growth.chunk_len(self.free_slot()), self.alloc.clone());
+            self.allocate_chunk(chunk);
         }
+    }
 
-        let slab = self.chunks.get_mut(slab_key).expect("invalid key");
-        slab.len += 1;
+    /// Return the index of the first free slot of the list of chunks, which
+    /// may be one past the end.
+    fn free_slot(&self) -> usize {
+        self.chunks
+            .iter()
+            .position(Option::is_none)
+            .unwrap_or(self.chunks.len())
+    }
 
-        // SAFETY: This will either push a new `Entry` on to the array, or
-        // replace a `Vacant` entry. In either case, this won't move other
-        // entries.
-        let entries = unsafe { slab.entries.as_mut().get_unchecked_mut() };
-        if entry_key == entries.len() {
-            entries.push(Entry::Occupied(val));
-            self.next = key + 1;
-            match entries.get(entries.len() - 1) {
-                Some(Entry::Occupied(ref v)) => v,
-                _ => unreachable!(),
-            }
-        } else {
-            let entry = &mut entries[entry_key];
-            let prev = mem::replace(entry, Entry::Occupied(val));
-            match prev {
-                Entry::Vacant(next) => {
-                    self.next = next;
-                }
-                _ => unreachable!(),
-            }
-            match entry {
-                Entry::Occupied(ref v) => v,
-                _ => unreachable!(),
-            }
+    /// Put `chunk` in the first free slot of the list of chunks.
+    fn allocate_chunk(&mut self, chunk: Chunk<T, A>) {
+        let index = self.free_slot();
+        match self.chunks.get_mut(index) {
+            Some(slot) => *slot = Some(chunk),
+            None => self.chunks.push(Some(chunk)),
         }
+        self.chunk_allocated(index);
     }
 
-    /// Remove and return the value associated with the given key.
-    ///
-    /// The key is then released and may be associated with future stored
-    /// values.
+    /// Count and trace the allocation of the chunk at `index`.
+    fn chunk_allocated(&mut self, index: usize) {
+        self.counters.chunk();
+        let chunk = self.chunks[index].as_ref().unwrap();
+        trace::chunk_allocated(self.name, index, chunk.size(), chunk.heap_size());
+    }
+
+    /// Free the chunk at `index`, if it is allocated.
+    fn free_chunk(&mut self, index: usize) {
+        if let Some(chunk) = self.chunks[index].take() {
+            trace::chunk_freed(self.name, index, chunk.size());
+        }
+    }
+
+    /// Return the chunk at `index`, if it is allocated.
+    fn chunk(&self, index: usize) -> Option<&Chunk<T, A>> {
+        self.chunks.get(index)?.as_ref()
+    }
+
+    /// Return the chunk at `index`, if it is allocated.
+    fn chunk_mut(&mut self, index: usize) -> Option<&mut Chunk<T, A>> {
+        self.chunks.get_mut(index)?.as_mut()
+    }
+
+    /// Return an iterator over the slab.
     ///
-    /// # Panics
+    /// Iterators follow a list threaded through the occupied entries in
+    /// ascending key order, so iterating takes `O(len)` time regardless of
+    /// the capacity of the slab.
     ///
-    /// Panics if `key` is not associated with a value.
+    /// Every iterator over the slab yields its entries in ascending key
+    /// order, or descending when iterated from the back, no matter in which
+    /// order they were inserted. This is part of the API, so it is fine to
+    /// rely on it, e.g. to resume an iteration with
+    /// [`iter_from`](#method.iter_from).
     ///
     /// # Examples
     ///
@@ -361,169 +1288,3237 @@ impl<T> Slab<T> {
     /// # use pinned_slab::*;
     /// let mut slab = Slab::new();
     ///
-    /// let (hello, _) = slab.insert("hello");
+    /// for i in 0..3 {
+    ///     slab.insert(i);
+    /// }
     ///
-    /// assert_eq!(slab.remove(hello), "hello");
-    /// assert!(!slab.contains(hello));
+    /// let mut iterator = slab.iter();
+    ///
+    /// assert_eq!(iterator.next(), Some((0, &0)));
+    /// assert_eq!(iterator.next(), Some((1, &1)));
+    /// assert_eq!(iterator.next(), Some((2, &2)));
+    /// assert_eq!(iterator.next(), None);
     /// ```
-    pub fn remove(&mut self, key: usize) -> T {
-        let slab_key = key / CHUNK_SIZE;
-        let entry_key = key % CHUNK_SIZE;
-
-        let chunk = self.chunks.get_mut(slab_key).expect("invalid key");
-
-        // SAFETY: By calling `remove` on this key, we're giving "permission" to
-        // un-pin the entry. Since `mem::replace` will only affect that entry,
-        // all other entries remain pinned.
-        let entries = unsafe { chunk.entries.as_mut().get_unchecked_mut() };
-        let prev = mem::replace(&mut entries[entry_key], Entry::Vacant(self.next));
-        match prev {
-            Entry::Occupied(val) => {
-                chunk.len -= 1;
-                self.len -= 1;
-                self.next = key;
-                val
-            }
-            _ => {
-                entries[key] = prev;
-                panic!("invalid key");
-            }
+    pub fn iter(&self) -> Iter<'_, T, N, A> {
+        Iter {
+            chunks: &self.chunks,
+            growth: self.growth,
+            front: self.head,
+            back: self.tail,
+            remaining: self.len,
         }
     }
 
-    /// Free any empty chunks.
-    pub fn free_unused(&mut self) {
-        self.chunks.retain(|slab| slab.len > 0)
-    }
-
-    /// Retain only the elements specified by the predicate.
+    /// Return an iterator that allows modifying each value.
+    ///
+    /// Iterators follow a list threaded through the occupied entries in
+    /// ascending key order, so iterating takes `O(len)` time regardless of
+    /// the capacity of the slab.
     ///
-    /// In other words, remove all elements `e` such that `f(usize, &mut e)`
-    /// returns false. This method operates in place and preserves the key
-    /// associated with the retained values.
+    /// Deprecated because handing out `&mut T` breaks the pinning guarantee
+    /// for every value; it is kept so existing callers still compile.
     ///
     /// # Examples
     ///
     /// ```
+    /// # #![allow(deprecated)]
     /// # use pinned_slab::*;
     /// let mut slab = Slab::new();
     ///
-    /// let (k1, _) = slab.insert(0);
-    /// let (k2, _) = slab.insert(1);
-    /// let (k3, _) = slab.insert(2);
+    /// let (key1, _) = slab.insert(0);
+    /// let (key2, _) = slab.insert(1);
     ///
-    /// unsafe {
-    ///    slab.retain(|key, val| key == k1 || *val == 1);
+    /// for (key, val) in unsafe { slab.iter_mut() } {
+    ///     if key == key1 {
+    ///         *val += 2;
+    ///     }
     /// }
     ///
-    /// assert!(slab.contains(k1));
-    /// assert!(slab.contains(k2));
-    /// assert!(!slab.contains(k3));
-    ///
-    /// assert_eq!(2, slab.len());
+    /// assert_eq!(slab[key1], 2);
+    /// assert_eq!(slab[key2], 1);
     /// ```
     ///
     /// # Safety
     ///
     /// This effectively un-pins every entry. The caller has to make sure
     /// that this is definitely what they want to do, e.g. they won't invalidate
-    /// any pointers to these values.
-    pub unsafe fn retain<F>(&mut self, mut f: F)
-    where
-        F: FnMut(usize, &mut T) -> bool,
-    {
-        for i in 0..self.chunks.len() {
-            for j in 0..CHUNK_SIZE {
-                let chunk = self.chunks.get_mut(i).unwrap();
-                let entry = chunk.entries.as_mut().get_unchecked_mut().get_mut(j);
-                let key = i * CHUNK_SIZE + j;
-                let keep = match entry {
-                    Some(Entry::Occupied(ref mut v)) => f(key, v),
-                    None => break,
-                    _ => true,
-                };
-
-                if !keep {
-                    self.remove(key);
-                }
-            }
-        }
+    /// any pointers to these values. For safe alternatives see
+    /// [`iter_pin_mut`](#method.iter_pin_mut) and
+    /// [`lending_iter_mut`](#method.lending_iter_mut).
+    #[deprecated(note = "use `iter_pin_mut` or `lending_iter_mut`, which keep the values pinned")]
+    pub unsafe fn iter_mut(&mut self) -> IterMut<'_, T, N, A> {
+        self.iter_pin_mut().inner
     }
-}
 
-impl<T> ops::Index<usize> for Slab<T> {
-    type Output = T;
+    /// Return an iterator that yields pinned mutable references to each value.
+    ///
+    /// This has the same performance characteristics as
+    /// [`iter`](#method.iter), but unlike the deprecated
+    /// [`iter_mut`](#method.iter_mut) it is safe to call since the values
+    /// stay pinned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use pinned_slab::*;
+    /// let mut slab = Slab::new();
+    ///
+    /// let (key1, _) = slab.insert(0);
+    /// let (key2, _) = slab.insert(1);
+    ///
+    /// for (key, mut val) in slab.iter_pin_mut() {
+    ///     if key == key1 {
+    ///         *val += 2;
+    ///     }
+    /// }
+    ///
+    /// assert_eq!(slab[key1], 2);
+    /// assert_eq!(slab[key2], 1);
+    /// ```
+    pub fn iter_pin_mut(&mut self) -> IterPinMut<'_, T, N, A> {
+        let inner = IterMut {
+            chunks: self.chunks.iter_mut(),
+            growth: self.growth,
+            chunks_start: 0,
+            front_chunk: None,
+            back_chunk: None,
+            front: self.head,
+            back: self.tail,
+            remaining: self.len,
+        };
 
-    fn index(&self, key: usize) -> &T {
-        match self.get(key) {
-            Some(&ref v) => v,
-            _ => panic!("invalid key"),
-        }
+        IterPinMut { inner }
     }
-}
-
-impl<'a, T> IntoIterator for &'a Slab<T> {
-    type Item = (usize, &'a T);
-    type IntoIter = Iter<'a, T>;
 
-    fn into_iter(self) -> Iter<'a, T> {
-        self.iter()
+    /// Return the number of chunk slots of the slab.
+    ///
+    /// Every key of the slab is below
+    /// [`chunk_range(chunk_count())`](#method.chunk_range)`.start`. Chunks
+    /// freed by [`free_unused`](#method.free_unused) in the middle of the
+    /// slab still count, see [`capacity`](#method.capacity) for the number of
+    /// allocated entries.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use pinned_slab::*;
+    /// let mut slab = Slab::new();
+    /// assert_eq!(slab.chunk_count(), 0);
+    ///
+    /// for i in 0..DEFAULT_CHUNK_SIZE + 1 {
+    ///     slab.insert(i);
+    /// }
+    /// assert_eq!(slab.chunk_count(), 2);
+    /// ```
+    pub fn chunk_count(&self) -> usize {
+        self.chunks.len()
     }
-}
-
-impl<'a, T> Iterator for Iter<'a, T> {
-    type Item = (usize, &'a T);
-
-    fn next(&mut self) -> Option<Self::Item> {
-        loop {
-            while let Some(entry) = self.entries.next() {
-                let curr = self.curr;
-                self.curr += 1;
-
-                if let Entry::Occupied(ref v) = *entry {
-                    return Some((curr, v));
-                }
-            }
 
-            // `self.entries.next()` was `None`...
-            if let Some(chunk) = self.chunks.next() {
-                self.entries = chunk.entries.iter();
-            } else {
-                return None;
-            }
-        }
+    /// Return an iterator over the entries stored in the chunk at `index`.
+    ///
+    /// The chunk at `index` holds the keys in
+    /// [`chunk_range(index)`](#method.chunk_range). If `index` is not less
+    /// than [`chunk_count`](#method.chunk_count), the iterator is empty.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use pinned_slab::*;
+    /// let mut slab = Slab::new();
+    ///
+    /// for i in 0..DEFAULT_CHUNK_SIZE + 1 {
+    ///     slab.insert(i);
+    /// }
+    ///
+    /// // Sweep the slab one chunk at a time
+    /// for index in 0..slab.chunk_count() {
+    ///     for (key, val) in slab.iter_chunk(index) {
+    ///         assert_eq!(key / DEFAULT_CHUNK_SIZE, index);
+    ///         assert_eq!(key, *val);
+    ///     }
+    /// }
+    ///
+    /// assert_eq!(slab.iter_chunk(1).len(), 1);
+    /// ```
+    pub fn iter_chunk(&self, index: usize) -> Iter<'_, T, N, A> {
+        self.iter_range(self.chunk_range(index))
     }
 
-    fn size_hint(&self) -> (usize, Option<usize>) {
-        (0, Some(self.chunks.len() * CHUNK_SIZE))
+    /// Return an iterator that yields pinned mutable references to the values
+    /// stored in the chunk at `index`.
+    ///
+    /// See [`iter_chunk`](#method.iter_chunk).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use pinned_slab::*;
+    /// let mut slab = Slab::new();
+    /// let (key, _) = slab.insert(1);
+    ///
+    /// for (_, mut val) in slab.iter_chunk_pin_mut(0) {
+    ///     *val += 1;
+    /// }
+    ///
+    /// assert_eq!(slab[key], 2);
+    /// ```
+    pub fn iter_chunk_pin_mut(&mut self, index: usize) -> IterPinMut<'_, T, N, A> {
+        self.iter_range_pin_mut(self.chunk_range(index))
     }
-}
-
-impl<'a, T> Iterator for IterMut<'a, T> {
-    type Item = (usize, &'a mut T);
-
-    fn next(&mut self) -> Option<Self::Item> {
-        loop {
-            while let Some(entry) = self.entries.next() {
-                let curr = self.curr;
-                self.curr += 1;
 
-                if let Entry::Occupied(ref mut v) = *entry {
-                    return Some((curr, v));
-                }
-            }
+    /// Return the values stored in the chunk at `index` as a contiguous
+    /// slice.
+    ///
+    /// The slice starts at key [`chunk_range(index)`](#method.chunk_range)`.start`
+    /// and covers every entry of
+    /// the chunk which has been handed out so far. If any of those entries is
+    /// vacant, or `index` is not less than
+    /// [`chunk_count`](#method.chunk_count), then `None` is returned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use pinned_slab::*;
+    /// let mut slab = Slab::new();
+    ///
+    /// for i in 0..DEFAULT_CHUNK_SIZE + 3 {
+    ///     slab.insert(i);
+    /// }
+    /// slab.remove(5);
+    ///
+    /// assert_eq!(slab.chunk_slice(0), None);
+    /// assert_eq!(slab.chunk_slice(1), Some(&[DEFAULT_CHUNK_SIZE, DEFAULT_CHUNK_SIZE + 1, DEFAULT_CHUNK_SIZE + 2][..]));
+    /// ```
+    pub fn chunk_slice(&self, index: usize) -> Option<&[T]> {
+        self.chunk(index)?.as_slice()
+    }
 
-            // `self.entries.next()` was `None`...
-            if let Some(chunk) = self.chunks.next() {
-                let entries = chunk.entries.as_mut();
-                self.entries = unsafe { entries.get_unchecked_mut().iter_mut() };
-            } else {
-                return None;
-            }
+    /// Return an iterator over the chunks of the slab as contiguous slices, if
+    /// no entry of the slab is vacant.
+    ///
+    /// This is the case as long as nothing has been removed from the slab (or
+    /// every removed key has been reused since) and no chunk has been freed. Each slice is the same as
+    /// [`chunk_slice`](#method.chunk_slice) would return for its chunk, so
+    /// the values are yielded in key order.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use pinned_slab::*;
+    /// let mut slab = Slab::new();
+    ///
+    /// for i in 0..DEFAULT_CHUNK_SIZE + 3 {
+    ///     slab.insert(i);
+    /// }
+    ///
+    /// let sum: usize = slab.as_chunk_slices().unwrap().map(|s| s.iter().sum::<usize>()).sum();
+    /// assert_eq!(sum, slab.values().sum());
+    ///
+    /// slab.remove(5);
+    /// assert!(slab.as_chunk_slices().is_none());
+    /// ```
+    pub fn as_chunk_slices(&self) -> Option<ChunkSlices<'_, T, N, A>> {
+        let dense =
+            |chunk: &Option<Chunk<T, A>>| chunk.as_ref().and_then(Chunk::as_slice).is_some();
+        if !self.chunks.iter().all(dense) {
+            return None;
+        }
+
+        Some(ChunkSlices {
+            chunks: self.chunks.iter(),
+        })
+    }
+
+    /// Return the range of keys held by the chunk at `index`, whether or not
+    /// it is allocated.
+    ///
+    /// With the default [`GrowthPolicy`](enum.GrowthPolicy.html) this is
+    /// `index * N..(index + 1) * N`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use pinned_slab::*;
+    /// let slab: Slab<i32> = Slab::new();
+    /// assert_eq!(slab.chunk_range(2), DEFAULT_CHUNK_SIZE * 2..DEFAULT_CHUNK_SIZE * 3);
+    /// ```
+    pub fn chunk_range(&self, index: usize) -> ops::Range<usize> {
+        let start = self.growth.chunk_start(index);
+        start..start.saturating_add(self.growth.chunk_len(index))
+    }
+
+    /// Return the growth policy of the slab.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use pinned_slab::*;
+    /// let slab: Slab<i32> = Slab::new();
+    /// assert_eq!(slab.growth_policy(), GrowthPolicy::Fixed);
+    /// ```
+    pub fn growth_policy(&self) -> GrowthPolicy {
+        self.growth.policy()
+    }
+
+    /// Return an iterator over the entries of the slab with keys in `range`.
+    ///
+    /// Only the chunks covering the range are visited, so this is much
+    /// cheaper than filtering the result of [`iter`](#method.iter) when the
+    /// range is small compared to the slab.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use pinned_slab::*;
+    /// let mut slab = Slab::new();
+    ///
+    /// for i in 0..10 {
+    ///     slab.insert(i);
+    /// }
+    /// slab.remove(4);
+    ///
+    /// let keys: Vec<_> = slab.iter_range(3..7).map(|(key, _)| key).collect();
+    /// assert_eq!(keys, vec![3, 5, 6]);
+    /// ```
+    pub fn iter_range<R: RangeBounds<usize>>(&self, range: R) -> Iter<'_, T, N, A> {
+        let (front, back, remaining) = self.occupied_range(range);
+        Iter {
+            chunks: &self.chunks,
+            growth: self.growth,
+            front,
+            back,
+            remaining,
+        }
+    }
+
+    /// Return an iterator over the entries of the slab with keys greater
+    /// than or equal to `key`, in ascending key order.
+    ///
+    /// This is the same as `iter_range(key..)`, and allows a scan of a large
+    /// slab to be split into pages: remember the last key a page saw and
+    /// resume from the key after it. Values inserted or removed between the
+    /// pages are seen or skipped depending on where their keys fall.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use pinned_slab::*;
+    /// let mut slab = Slab::new();
+    /// slab.insert_many(0..10);
+    ///
+    /// let page: Vec<_> = slab.iter_from(0).take(4).map(|(key, _)| key).collect();
+    /// assert_eq!(page, [0, 1, 2, 3]);
+    ///
+    /// slab.remove(4);
+    /// let resume = page.last().unwrap() + 1;
+    /// let page: Vec<_> = slab.iter_from(resume).take(4).map(|(key, _)| key).collect();
+    /// assert_eq!(page, [5, 6, 7, 8]);
+    /// ```
+    pub fn iter_from(&self, key: usize) -> Iter<'_, T, N, A> {
+        self.iter_range(key..)
+    }
+
+    /// Return an iterator that yields pinned mutable references to the values
+    /// with keys in `range`.
+    ///
+    /// See [`iter_range`](#method.iter_range).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use pinned_slab::*;
+    /// let mut slab = Slab::new();
+    ///
+    /// for i in 0..10 {
+    ///     slab.insert(i);
+    /// }
+    ///
+    /// for (_, mut val) in slab.iter_range_pin_mut(8..) {
+    ///     *val *= 10;
+    /// }
+    ///
+    /// assert_eq!(slab[7], 7);
+    /// assert_eq!(slab[8], 80);
+    /// assert_eq!(slab[9], 90);
+    /// ```
+    pub fn iter_range_pin_mut<R: RangeBounds<usize>>(
+        &mut self,
+        range: R,
+    ) -> IterPinMut<'_, T, N, A> {
+        let (front, back, remaining) = self.occupied_range(range);
+        let inner = IterMut {
+            chunks: self.chunks.iter_mut(),
+            growth: self.growth,
+            chunks_start: 0,
+            front_chunk: None,
+            back_chunk: None,
+            front,
+            back,
+            remaining,
+        };
+
+        IterPinMut { inner }
+    }
+
+    /// Return an iterator over the keys of the slab.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use pinned_slab::*;
+    /// let mut slab = Slab::new();
+    ///
+    /// let (a, _) = slab.insert("a");
+    /// let (b, _) = slab.insert("b");
+    ///
+    /// assert_eq!(slab.keys().collect::<Vec<_>>(), vec![a, b]);
+    /// ```
+    pub fn keys(&self) -> Keys<'_, T, N, A> {
+        Keys { inner: self.iter() }
+    }
+
+    /// Return an iterator over the values stored in the slab.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use pinned_slab::*;
+    /// let mut slab = Slab::new();
+    ///
+    /// slab.insert("a");
+    /// slab.insert("b");
+    ///
+    /// assert_eq!(slab.values().collect::<Vec<_>>(), vec![&"a", &"b"]);
+    /// ```
+    pub fn values(&self) -> Values<'_, T, N, A> {
+        Values { inner: self.iter() }
+    }
+
+    /// Return an iterator over pinned mutable references to the values stored
+    /// in the slab.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use pinned_slab::*;
+    /// let mut slab = Slab::new();
+    ///
+    /// let (key, _) = slab.insert(1);
+    ///
+    /// for mut val in slab.values_pin_mut() {
+    ///     *val += 1;
+    /// }
+    ///
+    /// assert_eq!(slab[key], 2);
+    /// ```
+    pub fn values_pin_mut(&mut self) -> ValuesPinMut<'_, T, N, A> {
+        ValuesPinMut {
+            inner: self.iter_pin_mut(),
+        }
+    }
+
+    /// Return an iterator over the keys of the slab which aren't associated
+    /// with a value, in ascending order.
+    ///
+    /// Only keys of allocated chunks are yielded, so there are
+    /// `capacity() - len()` of them.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use pinned_slab::*;
+    /// let mut slab = Slab::new();
+    ///
+    /// for i in 0..DEFAULT_CHUNK_SIZE {
+    ///     slab.insert(i);
+    /// }
+    /// slab.remove(3);
+    /// slab.remove(5);
+    ///
+    /// assert_eq!(slab.vacant_keys().collect::<Vec<_>>(), vec![3, 5]);
+    /// ```
+    pub fn vacant_keys(&self) -> VacantKeys<'_, T, N, A> {
+        VacantKeys {
+            chunks: &self.chunks,
+            growth: self.growth,
+            chunk: 0,
+            word_index: 0,
+            word: self.chunk(0).map_or(0, |chunk| chunk.vacant_word(0)),
+            remaining: self.capacity() - self.len - self.reserved,
+        }
+    }
+
+    /// Return the key that will be assigned to the next value inserted into
+    /// the slab.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use pinned_slab::*;
+    /// let mut slab = Slab::new();
+    ///
+    /// let key = slab.next_key();
+    /// assert_eq!(slab.insert("hello").0, key);
+    /// ```
+    pub fn next_key(&self) -> usize {
+        self.next
+    }
+
+    /// Move the values into a `Vec` indexed by key, with `None` at the vacant
+    /// keys.
+    ///
+    /// The `Vec` ends at the largest occupied key. The values are moved, so
+    /// they must be `Unpin`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use pinned_slab::*;
+    /// let mut slab: Slab<_> = Slab::from(vec!["a", "b", "c", "d"]);
+    /// slab.remove(1);
+    /// slab.remove(3);
+    ///
+    /// assert_eq!(slab.into_vec(), [Some("a"), None, Some("c")]);
+    /// ```
+    pub fn into_vec(self) -> Vec<Option<T>>
+    where
+        T: Unpin,
+    {
+        let mut vec = Vec::new();
+        if self.tail != NIL {
+            vec.resize_with(self.tail + 1, || None);
+        }
+        for (key, val) in self {
+            vec[key] = Some(val);
+        }
+        vec
+    }
+
+    /// Convert every value with `f`, which is given its key, into a new slab
+    /// with the same keys.
+    ///
+    /// The new slab has the same chunks, key policy, free list and reserved
+    /// keys, so keys held elsewhere stay valid and later inserts get the same
+    /// keys they would have gotten in this slab. The values are converted in
+    /// ascending key order. If `f` panics, the remaining values of both slabs
+    /// are dropped.
+    ///
+    /// `f` takes the values by value, which moves them out of their pinned
+    /// entries, so `T` must be `Unpin`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use pinned_slab::*;
+    /// let mut slab = Slab::new();
+    /// slab.insert_many(["a", "bb", "ccc"]);
+    /// slab.remove(1);
+    ///
+    /// let lens: Slab<usize> = slab.map(|_, val| val.len());
+    /// assert_eq!(lens[2], 3);
+    /// assert_eq!(lens.next_key(), 1);
+    /// ```
+    pub fn map<U, F>(self, mut f: F) -> Slab<U, N, A>
+    where
+        T: Unpin,
+        F: FnMut(usize, T) -> U,
+    {
+        match self.try_map(|key, val| Ok::<_, core::convert::Infallible>(f(key, val))) {
+            Ok(slab) => slab,
+            Err(err) => match err {},
+        }
+    }
+
+    /// Convert every value with `f` like [`map`](#method.map), stopping at
+    /// the first error.
+    ///
+    /// If `f` returns an error, it is returned and the remaining values of
+    /// both slabs are dropped.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use pinned_slab::*;
+    /// let mut slab = Slab::new();
+    /// slab.insert_many(["1", "2", "x"]);
+    ///
+    /// let parsed = slab.clone().try_map(|_, val| val.parse::<u32>());
+    /// assert!(parsed.is_err());
+    ///
+    /// slab.remove(2);
+    /// let parsed = slab.try_map(|_, val| val.parse::<u32>()).unwrap();
+    /// assert_eq!(parsed[1], 2);
+    /// ```
+    pub fn try_map<U, E, F>(mut self, mut f: F) -> Result<Slab<U, N, A>, E>
+    where
+        T: Unpin,
+        F: FnMut(usize, T) -> Result<U, E>,
+    {
+        let mut chunks = Vec::with_capacity(self.chunks.len());
+        for (i, chunk) in mem::take(&mut self.chunks).into_iter().enumerate() {
+            let mut old = match chunk {
+                Some(chunk) => chunk,
+                None => {
+                    chunks.push(None);
+                    continue;
+                }
+            };
+
+            let mut new = Chunk::new(old.size(), old.entries.allocator().clone());
+            new.touched = old.touched;
+            new.reserved.copy_from_slice(&old.reserved);
+            new.links.copy_from_slice(&old.links);
+            new.stamps.copy_from_slice(&old.stamps);
+            new.tags.copy_from_slice(&old.tags);
+            #[cfg(feature = "poison")]
+            new.freed_at.copy_from_slice(&old.freed_at);
+
+            let start = self.growth.chunk_start(i);
+            let mut next = old.next_occupied(0);
+            while let Some(j) = next {
+                // SAFETY: The slab is consumed, so moving its values out is
+                // like removing them. The entry is marked vacant right after,
+                // so it won't be read or dropped again.
+                let val = unsafe { old.entries()[j].as_ptr().read() };
+                old.set_occupied(j, false);
+                old.len -= 1;
+
+                let val = f(start + j, val)?;
+                // SAFETY: The new chunk isn't pinned anywhere yet.
+                unsafe { new.entries_mut()[j] = MaybeUninit::new(val) };
+                new.set_occupied(j, true);
+                new.len += 1;
+                next = old.next_occupied(j + 1);
+            }
+            chunks.push(Some(new));
+        }
+
+        Ok(Slab {
+            chunks,
+            growth: self.growth,
+            key_policy: self.key_policy,
+            alloc: self.alloc.clone(),
+            len: self.len,
+            next: self.next,
+            head: self.head,
+            tail: self.tail,
+            issued: self.issued,
+            stamp: self.stamp,
+            reserved: self.reserved,
+            counters: self.counters.clone(),
+            name: self.name,
+        })
+    }
+
+    /// Leak the slab, returning its keys and pinned references to its values
+    /// which live for the rest of the program, e.g. for global registries.
+    ///
+    /// None of the values are ever dropped, and the memory of the chunks is
+    /// never freed. Like [`Box::leak`], the references may have any lifetime
+    /// up to `'static`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use pinned_slab::*;
+    /// use std::pin::Pin;
+    ///
+    /// let mut slab = Slab::new();
+    /// slab.insert_many(["a", "b", "c"]);
+    ///
+    /// let leaked: Vec<(usize, Pin<&'static &str>)> = slab.leak().collect();
+    /// assert_eq!(*leaked[2].1, "c");
+    /// ```
+    pub fn leak<'a>(self) -> impl Iterator<Item = (usize, Pin<&'a T>)> + 'a
+    where
+        T: 'a,
+        A: 'a,
+    {
+        let slab: &'a Self = Box::leak(Box::new(self));
+        slab.iter().map(|(key, val)| {
+            // SAFETY: The slab is never dropped or accessed mutably again, so
+            // the values never move.
+            (key, unsafe { Pin::new_unchecked(val) })
+        })
+    }
+
+    /// Return a draining iterator that removes all entries from the slab and
+    /// yields the removed keys and values.
+    ///
+    /// When the iterator **is** dropped, all remaining values are dropped and
+    /// the slab is left empty, even if the iterator was not fully consumed.
+    /// If the iterator is leaked instead (e.g. with `mem::forget`), the slab
+    /// stays valid but may still contain the entries that weren't yielded.
+    ///
+    /// Yielding the values moves them, so this requires `T: Unpin`. To drop
+    /// every value in place instead, use [`clear`](#method.clear).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use pinned_slab::*;
+    /// let mut slab = Slab::new();
+    ///
+    /// let (a, _) = slab.insert(0);
+    /// let (b, _) = slab.insert(1);
+    ///
+    /// let drained: Vec<_> = slab.drain().collect();
+    ///
+    /// assert!(slab.is_empty());
+    /// assert_eq!(drained, vec![(a, 0), (b, 1)]);
+    /// ```
+    pub fn drain(&mut self) -> Drain<'_, T, N, A>
+    where
+        T: Unpin,
+    {
+        Drain { slab: self }
+    }
+
+    /// Return an iterator which removes and yields every entry for which the
+    /// predicate returns `true`.
+    ///
+    /// Entries for which the predicate returns `false` are left in place. If
+    /// the iterator is dropped before it is fully consumed, the entries it
+    /// hasn't visited yet are retained as well.
+    ///
+    /// The removed values are moved out, so `T` must be `Unpin`.
+    /// [`retain`](#method.retain) drops them in place instead.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use pinned_slab::*;
+    /// let mut slab = Slab::new();
+    ///
+    /// for i in 0..6 {
+    ///     slab.insert(i);
+    /// }
+    ///
+    /// let odds: Vec<_> = slab.extract_if(|_, val| *val % 2 == 1).collect();
+    ///
+    /// assert_eq!(odds, vec![(1, 1), (3, 3), (5, 5)]);
+    /// assert_eq!(slab.len(), 3);
+    /// ```
+    pub fn extract_if<F>(&mut self, pred: F) -> ExtractIf<'_, T, F, N, A>
+    where
+        T: Unpin,
+        F: FnMut(usize, Pin<&mut T>) -> bool,
+    {
+        ExtractIf {
+            front: self.head,
+            slab: self,
+            pred,
+        }
+    }
+
+    /// Return a cursor pointing to the first occupied entry of the slab.
+    ///
+    /// Unlike iterators, the cursor can remove the entry it points to and
+    /// insert new values while traversing the slab.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use pinned_slab::*;
+    /// let mut slab = Slab::new();
+    ///
+    /// for i in 0..6 {
+    ///     slab.insert(i);
+    /// }
+    ///
+    /// let mut cursor = slab.cursor_mut();
+    /// while let Some(val) = cursor.current() {
+    ///     if *val % 2 == 0 {
+    ///         cursor.remove_current();
+    ///     } else {
+    ///         cursor.move_next();
+    ///     }
+    /// }
+    ///
+    /// let vals: Vec<_> = slab.iter().map(|(_, v)| *v).collect();
+    /// assert_eq!(vals, vec![1, 3, 5]);
+    /// ```
+    pub fn cursor_mut(&mut self) -> CursorMut<'_, T, N, A> {
+        let key = Some(self.head).filter(|&key| key != NIL);
+        CursorMut { slab: self, key }
+    }
+
+    /// Return a reference to the value associated with the given key.
+    ///
+    /// If the given key is not associated with a value, then `None` is
+    /// returned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use pinned_slab::*;
+    /// let mut slab = Slab::new();
+    /// let (key, _) = slab.insert("hello");
+    ///
+    /// assert_eq!(slab.get(key), Some(&"hello"));
+    /// assert_eq!(slab.get(123), None);
+    /// ```
+    pub fn get(&self, key: usize) -> Option<&T> {
+        let (slab_key, entry_key) = self.growth.locate(key);
+
+        self.chunk(slab_key)?.get(entry_key)
+    }
+
+    /// Return a mutable reference to the value associated with the given key.
+    ///
+    /// If the given key is not associated with a value, then `None` is
+    /// returned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use pinned_slab::*;
+    /// let mut slab = Slab::new();
+    /// let (key, _) = slab.insert("hello");
+    ///
+    /// unsafe {
+    ///     *slab.get_mut(key).unwrap() = "world";
+    /// }
+    ///
+    /// assert_eq!(*unsafe { slab.get_mut(key) }.unwrap(), "world");
+    /// assert_eq!(unsafe { slab.get_mut(123) }, None);
+    /// ```
+    ///
+    /// # Safety
+    ///
+    /// This effectively un-pins the entry at `key`. The caller has to make sure
+    /// that this is definitely what they want to do, e.g. they won't invalidate
+    /// any pointers to this value.
+    pub unsafe fn get_mut(&mut self, key: usize) -> Option<&mut T> {
+        let (slab_key, entry_key) = self.growth.locate(key);
+
+        self.chunk_mut(slab_key)?.get_mut(entry_key)
+    }
+
+    /// Return a pinned mutable reference to the value associated with the
+    /// given key.
+    ///
+    /// If the given key is not associated with a value, then `None` is
+    /// returned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use pinned_slab::*;
+    /// let mut slab = Slab::new();
+    /// let (key, _) = slab.insert(1);
+    ///
+    /// *slab.get_pin_mut(key).unwrap() += 1;
+    ///
+    /// assert_eq!(slab[key], 2);
+    /// assert!(slab.get_pin_mut(123).is_none());
+    /// ```
+    pub fn get_pin_mut(&mut self, key: usize) -> Option<Pin<&mut T>> {
+        // SAFETY: The value is never moved out of the slab while it is
+        // occupied, so handing out a pinned reference to it is sound.
+        unsafe { self.get_mut(key).map(|v| Pin::new_unchecked(v)) }
+    }
+
+    /// Return a raw pointer to the value associated with the given key,
+    /// without creating a reference to it.
+    ///
+    /// If the given key is not associated with a value, then `None` is
+    /// returned.
+    ///
+    /// The pointer stays valid for reads while the key is occupied, even
+    /// while other values are inserted or removed and the slab grows. It is
+    /// invalidated once the value is removed, by `remove`, `clear`, `retain`
+    /// and the like, or the slab is dropped. Reading through it must not race
+    /// with writes to the value through [`get_pin_mut`](#method.get_pin_mut)
+    /// and the like.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use pinned_slab::*;
+    /// let mut slab = Slab::new();
+    /// let (key, _) = slab.insert(7);
+    /// let ptr = slab.as_ptr(key).unwrap();
+    ///
+    /// slab.insert_many(0..100);
+    /// assert_eq!(unsafe { *ptr }, 7);
+    /// assert_eq!(slab.as_ptr(123), None);
+    /// ```
+    pub fn as_ptr(&self, key: usize) -> Option<*const T> {
+        let (slab_key, entry_key) = self.growth.locate(key);
+
+        self.chunk(slab_key)?
+            .as_ptr(entry_key)
+            .map(<*mut T>::cast_const)
+    }
+
+    /// Return a raw mutable pointer to the value associated with the given
+    /// key, without creating a reference to it, e.g. to hand the address of a
+    /// buffer to the kernel.
+    ///
+    /// If the given key is not associated with a value, then `None` is
+    /// returned.
+    ///
+    /// The pointer stays valid for reads and writes while the key is
+    /// occupied, under the same conditions as [`as_ptr`](#method.as_ptr).
+    /// The value is pinned, so it must not be moved out through the pointer,
+    /// e.g. with `ptr::read` or `ptr::swap`, unless `T: Unpin`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use pinned_slab::*;
+    /// let mut slab = Slab::new();
+    /// let (key, _) = slab.insert([0u8; 4]);
+    /// let ptr = slab.as_mut_ptr(key).unwrap();
+    ///
+    /// slab.insert([1; 4]);
+    /// unsafe { (*ptr)[0] = 42 };
+    /// assert_eq!(slab[key], [42, 0, 0, 0]);
+    /// ```
+    pub fn as_mut_ptr(&mut self, key: usize) -> Option<*mut T> {
+        let (slab_key, entry_key) = self.growth.locate(key);
+
+        self.chunk(slab_key)?.as_ptr(entry_key)
+    }
+
+    /// Return the key and a reference to the value associated with the given
+    /// key.
+    ///
+    /// If the given key is not associated with a value, then `None` is
+    /// returned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use pinned_slab::*;
+    /// let mut slab = Slab::new();
+    /// let (key, _) = slab.insert("hello");
+    ///
+    /// assert_eq!(slab.get_key_value(key), Some((key, &"hello")));
+    /// assert_eq!(slab.get_key_value(123), None);
+    /// ```
+    pub fn get_key_value(&self, key: usize) -> Option<(usize, &T)> {
+        self.get(key).map(|v| (key, v))
+    }
+
+    /// Return the key and a pinned mutable reference to the value associated
+    /// with the given key.
+    ///
+    /// If the given key is not associated with a value, then `None` is
+    /// returned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use pinned_slab::*;
+    /// let mut slab = Slab::new();
+    /// let (key, _) = slab.insert(1);
+    ///
+    /// if let Some((k, mut v)) = slab.get_key_value_pin_mut(key) {
+    ///     *v += k;
+    /// }
+    ///
+    /// assert_eq!(slab[key], 1 + key);
+    /// ```
+    pub fn get_key_value_pin_mut(&mut self, key: usize) -> Option<(usize, Pin<&mut T>)> {
+        self.get_pin_mut(key).map(|v| (key, v))
+    }
+
+    /// Return the smallest key associated with a value, or `None` if the
+    /// slab is empty.
+    ///
+    /// The occupied entries are kept in a list sorted by key, so this takes
+    /// constant time.
+    ///
+    /// # Examples
+    ///
+    /// Dropping every value below a watermark:
+    ///
+    /// ```
+    /// # use pinned_slab::*;
+    /// let mut slab = Slab::new();
+    /// slab.insert_many(0..10);
+    ///
+    /// while let Some(key) = slab.min_key().filter(|&key| key < 4) {
+    ///     slab.remove(key);
+    /// }
+    ///
+    /// assert_eq!(slab.min_key(), Some(4));
+    /// assert_eq!(slab.len(), 6);
+    /// ```
+    pub fn min_key(&self) -> Option<usize> {
+        Some(self.head).filter(|&key| key != NIL)
+    }
+
+    /// Return the largest key associated with a value, or `None` if the
+    /// slab is empty.
+    ///
+    /// Like [`min_key`](#method.min_key), this takes constant time.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use pinned_slab::*;
+    /// let mut slab = Slab::new();
+    /// assert_eq!(slab.max_key(), None);
+    ///
+    /// slab.insert_many(0..10);
+    /// slab.remove(9);
+    /// assert_eq!(slab.max_key(), Some(8));
+    /// ```
+    pub fn max_key(&self) -> Option<usize> {
+        Some(self.tail).filter(|&key| key != NIL)
+    }
+
+    /// Return the smallest key and the value associated with it, or `None`
+    /// if the slab is empty.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use pinned_slab::*;
+    /// let mut slab = Slab::new();
+    /// slab.insert_many(["a", "b", "c"]);
+    /// slab.remove(0);
+    ///
+    /// assert_eq!(slab.first(), Some((1, &"b")));
+    /// ```
+    pub fn first(&self) -> Option<(usize, &T)> {
+        self.min_key().and_then(|key| self.get_key_value(key))
+    }
+
+    /// Return the largest key and the value associated with it, or `None`
+    /// if the slab is empty.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use pinned_slab::*;
+    /// let mut slab = Slab::new();
+    /// slab.insert_many(["a", "b", "c"]);
+    ///
+    /// assert_eq!(slab.last(), Some((2, &"c")));
+    /// ```
+    pub fn last(&self) -> Option<(usize, &T)> {
+        self.max_key().and_then(|key| self.get_key_value(key))
+    }
+
+    /// Return the smallest key and a pinned mutable reference to the value
+    /// associated with it, or `None` if the slab is empty.
+    pub fn first_pin_mut(&mut self) -> Option<(usize, Pin<&mut T>)> {
+        self.min_key()
+            .and_then(move |key| self.get_key_value_pin_mut(key))
+    }
+
+    /// Return the largest key and a pinned mutable reference to the value
+    /// associated with it, or `None` if the slab is empty.
+    pub fn last_pin_mut(&mut self) -> Option<(usize, Pin<&mut T>)> {
+        self.max_key()
+            .and_then(move |key| self.get_key_value_pin_mut(key))
+    }
+
+    /// Return the first key and value, in ascending key order, for which the
+    /// predicate returns `true`.
+    ///
+    /// Only the occupied entries are visited, by scanning the occupancy
+    /// bitmap of each chunk, and the search stops at the first match.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use pinned_slab::*;
+    /// let mut slab = Slab::new();
+    /// slab.insert_many(["apple", "banana", "cherry"]);
+    ///
+    /// assert_eq!(slab.find(|_, val| val.starts_with('b')), Some((1, &"banana")));
+    /// assert_eq!(slab.find(|_, val| val.is_empty()), None);
+    /// ```
+    pub fn find<F>(&self, mut f: F) -> Option<(usize, &T)>
+    where
+        F: FnMut(usize, &T) -> bool,
+    {
+        self.find_map(|key, val| if f(key, val) { Some((key, val)) } else { None })
+    }
+
+    /// Return the first key, in ascending key order, whose value the
+    /// predicate returns `true` for.
+    ///
+    /// Like [`find`](#method.find), but only returns the key.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use pinned_slab::*;
+    /// let mut slab = Slab::new();
+    /// slab.insert_many([1, 4, 9, 16]);
+    ///
+    /// assert_eq!(slab.position(|_, &val| val > 5), Some(2));
+    /// ```
+    pub fn position<F>(&self, mut f: F) -> Option<usize>
+    where
+        F: FnMut(usize, &T) -> bool,
+    {
+        self.find(|key, val| f(key, val)).map(|(key, _)| key)
+    }
+
+    /// Apply `f` to the keys and values in ascending key order, and return
+    /// the first result which isn't `None`.
+    ///
+    /// Like [`find`](#method.find), only the occupied entries are visited.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use pinned_slab::*;
+    /// let mut slab = Slab::new();
+    /// slab.insert_many(["one", "2", "three", "4"]);
+    ///
+    /// let first_number = slab.find_map(|key, val| Some((key, val.parse::<u32>().ok()?)));
+    /// assert_eq!(first_number, Some((1, 2)));
+    /// ```
+    pub fn find_map<'a, B, F>(&'a self, mut f: F) -> Option<B>
+    where
+        F: FnMut(usize, &'a T) -> Option<B>,
+    {
+        for (i, chunk) in self.chunks.iter().enumerate() {
+            let chunk = match chunk {
+                Some(chunk) if chunk.len > 0 => chunk,
+                _ => continue,
+            };
+
+            let start = self.growth.chunk_start(i);
+            let mut next = chunk.next_occupied(0);
+            while let Some(j) = next {
+                if let Some(found) = chunk.get(j).and_then(|val| f(start + j, val)) {
+                    return Some(found);
+                }
+                next = chunk.next_occupied(j + 1);
+            }
+        }
+
+        None
+    }
+
+    /// Return a reference to the value associated with the given key without
+    /// performing bounds checking.
+    ///
+    /// For a safe alternative see [`get`](#method.get).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use pinned_slab::*;
+    /// let mut slab = Slab::new();
+    /// let (key, _) = slab.insert(2);
+    ///
+    /// assert_eq!(unsafe { *slab.get_unchecked(key) }, 2);
+    /// ```
+    ///
+    /// # Safety
+    ///
+    /// Calling this method with a key that is not associated with a value is
+    /// undefined behavior.
+    #[track_caller]
+    pub unsafe fn get_unchecked(&self, key: usize) -> &T {
+        self.check_poison(key);
+        let (slab_key, entry_key) = self.growth.locate(key);
+        let chunk = self
+            .chunks
+            .get_unchecked(slab_key)
+            .as_ref()
+            .unwrap_unchecked();
+        chunk.entries().get_unchecked(entry_key).assume_init_ref()
+    }
+
+    /// Return a mutable reference to the value associated with the given key
+    /// without performing bounds checking.
+    ///
+    /// For a safe, pinned alternative see [`get_pin_mut`](#method.get_pin_mut).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use pinned_slab::*;
+    /// let mut slab = Slab::new();
+    /// let (key, _) = slab.insert(2);
+    ///
+    /// unsafe {
+    ///     *slab.get_unchecked_mut(key) = 3;
+    /// }
+    ///
+    /// assert_eq!(slab[key], 3);
+    /// ```
+    ///
+    /// # Safety
+    ///
+    /// Calling this method with a key that is not associated with a value is
+    /// undefined behavior. This also effectively un-pins the entry at `key`,
+    /// see [`get_mut`](#method.get_mut).
+    #[track_caller]
+    pub unsafe fn get_unchecked_mut(&mut self, key: usize) -> &mut T {
+        self.check_poison(key);
+        let (slab_key, entry_key) = self.growth.locate(key);
+        let chunk = self
+            .chunks
+            .get_unchecked_mut(slab_key)
+            .as_mut()
+            .unwrap_unchecked();
+        chunk
+            .entries_mut()
+            .get_unchecked_mut(entry_key)
+            .assume_init_mut()
+    }
+
+    /// Return a pinned mutable reference to the value associated with the
+    /// given key without performing bounds checking.
+    ///
+    /// For a safe alternative see [`get_pin_mut`](#method.get_pin_mut).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use pinned_slab::*;
+    /// let mut slab = Slab::new();
+    /// let (key, _) = slab.insert(2);
+    ///
+    /// unsafe {
+    ///     *slab.get_unchecked_pin_mut(key) += 1;
+    /// }
+    ///
+    /// assert_eq!(slab[key], 3);
+    /// ```
+    ///
+    /// # Safety
+    ///
+    /// Calling this method with a key that is not associated with a value is
+    /// undefined behavior.
+    #[track_caller]
+    pub unsafe fn get_unchecked_pin_mut(&mut self, key: usize) -> Pin<&mut T> {
+        Pin::new_unchecked(self.get_unchecked_mut(key))
+    }
+
+    /// Return two pinned mutable references to the values associated with the
+    /// two given keys simultaneously.
+    ///
+    /// If either key is not associated with a value, or if both keys are the
+    /// same, then `None` is returned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use pinned_slab::*;
+    /// let mut slab = Slab::new();
+    /// let (key1, _) = slab.insert(1);
+    /// let (key2, _) = slab.insert(2);
+    ///
+    /// let (mut a, mut b) = slab.get2_pin_mut(key1, key2).unwrap();
+    /// std::mem::swap(&mut *a, &mut *b);
+    ///
+    /// assert_eq!(slab[key1], 2);
+    /// assert_eq!(slab[key2], 1);
+    /// assert!(slab.get2_pin_mut(key1, key1).is_none());
+    /// ```
+    pub fn get2_pin_mut(&mut self, key1: usize, key2: usize) -> Option<(Pin<&mut T>, Pin<&mut T>)> {
+        let [a, b] = self.get_disjoint_pin_mut([key1, key2])?;
+        Some((a, b))
+    }
+
+    /// Return pinned mutable references to the values associated with each of
+    /// the given keys simultaneously.
+    ///
+    /// If any key is not associated with a value, or if any key appears more
+    /// than once, then `None` is returned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use pinned_slab::*;
+    /// let mut slab = Slab::new();
+    /// let keys = [slab.insert(1).0, slab.insert(2).0, slab.insert(3).0];
+    ///
+    /// for val in slab.get_disjoint_pin_mut(keys).unwrap().iter_mut() {
+    ///     **val *= 10;
+    /// }
+    ///
+    /// assert_eq!(slab[keys[2]], 30);
+    /// assert!(slab.get_disjoint_pin_mut([keys[0], keys[1], keys[0]]).is_none());
+    /// ```
+    pub fn get_disjoint_pin_mut<const K: usize>(
+        &mut self,
+        keys: [usize; K],
+    ) -> Option<[Pin<&mut T>; K]> {
+        for (i, &key) in keys.iter().enumerate() {
+            if !self.contains(key) || keys[..i].contains(&key) {
+                return None;
+            }
+        }
+
+        // The pointers are derived from the base pointers of the chunks, since
+        // borrowing a whole chunk mutably for each key would invalidate the
+        // pointers to the other keys in the same chunk.
+        let ptrs = keys.map(|key| self.as_mut_ptr(key).unwrap());
+        // SAFETY: Every key is occupied and distinct, so the references
+        // don't alias, and the values are pinned.
+        Some(ptrs.map(|ptr| unsafe { Pin::new_unchecked(&mut *ptr) }))
+    }
+
+    /// Insert a value in the slab, returning key assigned to the value and a
+    /// reference to that value.
+    ///
+    /// The returned key can later be used to retrieve or remove the value using indexed
+    /// lookup and `remove`. Additional capacity is allocated if needed. See
+    /// [Capacity and reallocation](index.html#capacity-and-reallocation).
+    /// Vacant keys are reused in the order given by the
+    /// [`KeyPolicy`](enum.KeyPolicy.html) of the slab.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the number of elements in the vector overflows a `usize`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use pinned_slab::*;
+    /// let mut slab = Slab::new();
+    /// let (key, value) = slab.insert("hello");
+    /// assert_eq!(*value, "hello");
+    /// assert_eq!(slab[key], "hello");
+    /// ```
+    /// Insert a value into the `Slab` and get the key for the value.
+    pub fn insert(&mut self, val: T) -> (usize, &T) {
+        let key = self.next;
+
+        (key, self.insert_at(key, val))
+    }
+
+    /// Try to insert a value in the slab, returning the key assigned to the
+    /// value and a pinned mutable reference to it.
+    ///
+    /// If a new chunk is needed and can't be allocated, the value is handed
+    /// back instead of panicking or aborting. See
+    /// [`try_reserve`](#method.try_reserve).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use pinned_slab::*;
+    /// let mut slab = Slab::new();
+    ///
+    /// let key = match slab.try_insert(1) {
+    ///     Ok((key, mut val)) => {
+    ///         *val += 1;
+    ///         key
+    ///     }
+    ///     Err(_) => panic!("out of memory"),
+    /// };
+    ///
+    /// assert_eq!(slab[key], 2);
+    /// ```
+    pub fn try_insert(&mut self, val: T) -> Result<(usize, Pin<&mut T>), T> {
+        if let Err(err) = self.try_allocate_next() {
+            trace::limit_reached(self.name, self.len, &err);
+            return Err(val);
+        }
+
+        let key = self.next;
+        self.insert_at(key, val);
+
+        // SAFETY: The value was just inserted, and it is never moved out of
+        // the slab while it is occupied.
+        Ok((key, unsafe { self.get_unchecked_pin_mut(key) }))
+    }
+
+    /// Insert a value in the slab without allocating, returning the key
+    /// assigned to the value and a pinned mutable reference to it.
+    ///
+    /// This only succeeds if there is a vacant entry in an already allocated
+    /// chunk, i.e. if [`len`](#method.len) plus the number of reserved keys
+    /// is less than [`capacity`](#method.capacity). Otherwise the value is
+    /// handed back.
+    ///
+    /// With [`KeyPolicy::Monotonic`](enum.KeyPolicy.html#variant.Monotonic)
+    /// the vacant entries below [`next_key`](#method.next_key) are never
+    /// reused, so this fails as soon as the chunk of the next key isn't
+    /// allocated, however many vacant entries the other chunks have.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use pinned_slab::*;
+    /// let mut slab = Slab::new();
+    /// assert_eq!(slab.insert_within_capacity(1).err(), Some(1));
+    ///
+    /// slab.reserve(1);
+    /// let (key, _) = slab.insert_within_capacity(1).unwrap();
+    /// assert_eq!(slab[key], 1);
+    /// ```
+    pub fn insert_within_capacity(&mut self, val: T) -> Result<(usize, Pin<&mut T>), T> {
+        // The other policies only point the next key at an unallocated chunk
+        // once the allocated chunks are full, and `Monotonic` never goes back
+        // to their vacant entries, so only the chunk of the next key matters.
+        if self.chunk(self.growth.locate(self.next).0).is_none() {
+            trace::limit_reached(self.name, self.len, &"no vacant entry within capacity");
+            return Err(val);
+        }
+
+        let key = self.next;
+        self.insert_at(key, val);
+
+        // SAFETY: The value was just inserted, and it is never moved out of
+        // the slab while it is occupied.
+        Ok((key, unsafe { self.get_unchecked_pin_mut(key) }))
+    }
+
+    /// Insert a value constructed in place by `init`, returning the key
+    /// assigned to the value and a pinned mutable reference to it.
+    ///
+    /// `init` is given the vacant entry and has to return a reference to it
+    /// once it is initialized, e.g. from
+    /// [`MaybeUninit::write`](https://doc.rust-lang.org/core/mem/union.MaybeUninit.html#method.write)
+    /// or by writing the fields through
+    /// [`as_mut_ptr`](https://doc.rust-lang.org/core/mem/union.MaybeUninit.html#method.as_mut_ptr)
+    /// and calling `assume_init_mut`. Large values can then be built without
+    /// first being moved through the stack. If `init` panics, the key stays
+    /// vacant.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `init` returns a reference to anything but the entry it was
+    /// given.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use pinned_slab::*;
+    /// use std::mem::MaybeUninit;
+    /// use std::ptr::addr_of_mut;
+    ///
+    /// struct Buffer {
+    ///     len: usize,
+    ///     data: [u8; 16 * 1024],
+    /// }
+    ///
+    /// let mut slab = Slab::new();
+    /// let (key, _) = slab.insert_with(|entry: &mut MaybeUninit<Buffer>| {
+    ///     let ptr = entry.as_mut_ptr();
+    ///     // SAFETY: Both fields are initialized before `assume_init_mut`.
+    ///     unsafe {
+    ///         addr_of_mut!((*ptr).len).write(0);
+    ///         addr_of_mut!((*ptr).data).write_bytes(0, 1);
+    ///         entry.assume_init_mut()
+    ///     }
+    /// });
+    ///
+    /// assert_eq!(slab[key].len, 0);
+    /// assert_eq!(slab[key].data[1000], 0);
+    /// ```
+    pub fn insert_with<F>(&mut self, init: F) -> (usize, Pin<&mut T>)
+    where
+        F: for<'a> FnOnce(&'a mut MaybeUninit<T>) -> &'a mut T,
+    {
+        let key = self.next;
+
+        // SAFETY: `init` returns a reference to the entry, which can't exist
+        // unless the entry is initialized.
+        unsafe {
+            self.insert_at_with(key, |entry| {
+                let ptr = entry.as_mut_ptr();
+                assert!(
+                    ptr::eq(init(entry), ptr),
+                    "insert_with must return a reference to its entry"
+                );
+            });
+        }
+
+        // SAFETY: The value was just inserted, and it is never moved out of
+        // the slab while it is occupied.
+        (key, unsafe { self.get_unchecked_pin_mut(key) })
+    }
+
+    /// Insert a value whose bytes are all zero, without moving it through the
+    /// stack, returning the key assigned to the value and a pinned mutable
+    /// reference to it.
+    ///
+    /// # Safety
+    ///
+    /// All zero bytes must be a valid `T`, as for
+    /// [`mem::zeroed`](https://doc.rust-lang.org/core/mem/fn.zeroed.html).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use pinned_slab::*;
+    /// let mut slab: Slab<[u64; 2048]> = Slab::new();
+    ///
+    /// // SAFETY: Zero is a valid `u64`.
+    /// let (key, mut buf) = unsafe { slab.insert_zeroed() };
+    /// buf[7] = 7;
+    ///
+    /// assert_eq!(slab[key][..8], [0, 0, 0, 0, 0, 0, 0, 7]);
+    /// ```
+    pub unsafe fn insert_zeroed(&mut self) -> (usize, Pin<&mut T>) {
+        self.insert_with(|entry| {
+            entry.as_mut_ptr().write_bytes(0, 1);
+            entry.assume_init_mut()
+        })
+    }
+
+    /// Insert every value of `values` under consecutive keys, returning the
+    /// range of keys assigned to them.
+    ///
+    /// Unlike [`insert`](#method.insert), this never reuses vacant keys. The
+    /// values are stored after every key which has been handed out before,
+    /// filling whole chunks at a time, which is much faster than inserting
+    /// them one by one.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use pinned_slab::*;
+    /// let mut slab = Slab::new();
+    ///
+    /// let (key, _) = slab.insert(0);
+    /// slab.remove(key);
+    ///
+    /// let keys = slab.insert_many(1..=3000);
+    /// assert_eq!(keys, 1..3001);
+    /// assert_eq!(slab[2000], 2000);
+    /// assert_eq!(slab.len(), 3000);
+    /// assert_eq!(slab.insert(0).0, 0);
+    /// ```
+    pub fn insert_many<I>(&mut self, values: I) -> ops::Range<usize>
+    where
+        I: IntoIterator<Item = T>,
+    {
+        /// Points the next key away from the inserted keys once the values
+        /// are inserted, even if the iterator panics.
+        struct Guard<'a, T, const N: usize, A: ChunkAllocator> {
+            slab: &'a mut Slab<T, N, A>,
+            start: usize,
+            end: usize,
+            /// The last key of the free list, if it isn't empty.
+            last_free: usize,
+        }
+
+        impl<'a, T, const N: usize, A: ChunkAllocator> Drop for Guard<'a, T, N, A> {
+            fn drop(&mut self) {
+                let inserted = self.start..self.end;
+                let slab = &mut *self.slab;
+                if self.last_free != NIL && inserted.contains(&slab.links(self.last_free).next) {
+                    let fresh = slab.fresh_key();
+                    slab.links_mut(self.last_free).next = fresh;
+                }
+                slab.next = match slab.key_policy {
+                    KeyPolicy::Lifo if inserted.contains(&slab.next) => slab.fresh_key(),
+                    KeyPolicy::LowestFirst if inserted.contains(&slab.next) => {
+                        slab.lowest_vacant(self.end)
+                    }
+                    KeyPolicy::Monotonic => slab.next.max(self.end),
+                    _ => slab.next,
+                };
+                slab.issued = slab.issued.max(self.end);
+            }
+        }
+
+        let mut start = self.touched_end();
+        if self.key_policy == KeyPolicy::Monotonic {
+            start = start.max(self.next);
+        }
+
+        let mut last_free = NIL;
+        if self.key_policy == KeyPolicy::Lifo {
+            let mut key = self.next;
+            while self.is_free_listed(key) {
+                last_free = key;
+                key = self.links(key).next;
+            }
+        }
+
+        let mut values = values.into_iter().peekable();
+        let mut guard = Guard {
+            slab: self,
+            start,
+            end: start,
+            last_free,
+        };
+        while values.peek().is_some() {
+            let slab = &mut *guard.slab;
+            let (slab_key, first) = slab.growth.locate(guard.end);
+            if slab_key >= slab.chunks.len() {
+                slab.chunks.resize_with(slab_key + 1, || None);
+            }
+            if slab.chunks[slab_key].is_none() {
+                let chunk = Chunk::new(slab.growth.chunk_len(slab_key), slab.alloc.clone());
+                slab.chunks[slab_key] = Some(chunk);
+                slab.chunk_allocated(slab_key);
+            }
+            let chunk_start = guard.end - first;
+
+            // The first value has already been peeked, so it is safe to link
+            // its key before it is written.
+            match slab.tail {
+                NIL => slab.head = guard.end,
+                tail => slab.links_mut(tail).next = guard.end,
+            }
+
+            let chunk = slab.chunks[slab_key].as_mut().unwrap();
+            for (j, val) in (first..chunk.size()).zip(values.by_ref()) {
+                // SAFETY: The entry has never been handed out, so this doesn't
+                // move any other entries.
+                unsafe { chunk.entries_mut()[j] = MaybeUninit::new(val) };
+                chunk.set_occupied(j, true);
+                slab.stamp += 1;
+                chunk.stamps[j] = slab.stamp;
+                chunk.tags[j] = 0;
+                chunk.links[j] = Link {
+                    prev: slab.tail,
+                    next: NIL,
+                };
+                if j > first {
+                    chunk.links[j - 1].next = chunk_start + j;
+                }
+                chunk.len += 1;
+                chunk.touched = j + 1;
+                slab.tail = chunk_start + j;
+                slab.len += 1;
+                slab.counters.insert(false, slab.len);
+                guard.end += 1;
+            }
+        }
+
+        start..guard.end
+    }
+
+    /// Return `true` if `key` is vacant, not reserved and has been handed out
+    /// before, so it may be on the free list.
+    fn is_free_listed(&self, key: usize) -> bool {
+        let (slab_key, entry_key) = self.growth.locate(key);
+        self.chunk(slab_key).is_some_and(|chunk| {
+            entry_key < chunk.touched
+                && !chunk.is_occupied(entry_key)
+                && !chunk.is_reserved(entry_key)
+        })
+    }
+
+    fn insert_at(&mut self, key: usize, val: T) -> &T {
+        // SAFETY: The entry is initialized right away.
+        unsafe {
+            self.insert_at_with(key, |entry| {
+                entry.write(val);
+            })
+        }
+    }
+
+    /// Hand out the vacant `key`, letting `init` initialize its entry in
+    /// place. If `init` panics, the key stays vacant.
+    ///
+    /// The caller must make sure `init` initializes the entry unless it
+    /// panics.
+    unsafe fn insert_at_with(&mut self, key: usize, init: impl FnOnce(&mut MaybeUninit<T>)) -> &T {
+        let (slab_key, entry_key) = self.allocate_key(key);
+        let slab = self.chunks[slab_key].as_mut().unwrap();
+        // The key policies never hand out reserved keys, but occupying one
+        // would let `fulfill` overwrite the value, so make sure.
+        assert!(!slab.is_reserved(entry_key), "key {} is reserved", key);
+
+        // This will either hand out a new entry of the array, or overwrite a
+        // vacant entry. In either case, this won't move other entries.
+        init(&mut slab.entries_mut()[entry_key]);
+
+        self.len += 1;
+        self.stamp += 1;
+        self.counters.insert(entry_key < slab.touched, self.len);
+        slab.len += 1;
+        slab.set_occupied(entry_key, true);
+        slab.stamps[entry_key] = self.stamp;
+        slab.tags[entry_key] = 0;
+
+        self.advance_next(key);
+        self.link(key);
+
+        // SAFETY: The entry was initialized above.
+        self.get_unchecked(key)
+    }
+
+    /// Allocate the chunk of `key` if needed, returning the index of the
+    /// chunk and of the entry.
+    fn allocate_key(&mut self, key: usize) -> (usize, usize) {
+        let (slab_key, entry_key) = self.growth.locate(key);
+
+        if slab_key >= self.chunks.len() {
+            self.chunks.resize_with(slab_key + 1, || None);
+        }
+
+        if self.chunks[slab_key].is_none() {
+            debug_assert_eq!(entry_key, 0);
+            self.chunks[slab_key] = Some(Chunk::new(
+                self.growth.chunk_len(slab_key),
+                self.alloc.clone(),
+            ));
+            self.chunk_allocated(slab_key);
+        }
+
+        (slab_key, entry_key)
+    }
+
+    /// Point the next key past `key`, which was the next key and has just
+    /// been occupied or reserved.
+    fn advance_next(&mut self, key: usize) {
+        self.issued = self.issued.max(key + 1);
+        let (slab_key, entry_key) = self.growth.locate(key);
+        let slab = self.chunks[slab_key].as_mut().unwrap();
+
+        let next = if entry_key >= slab.touched {
+            slab.touched = entry_key + 1;
+            if slab.is_full() {
+                NIL
+            } else {
+                key + 1
+            }
+        } else {
+            slab.links[entry_key].next
+        };
+
+        self.next = match self.key_policy {
+            KeyPolicy::Lifo if next == NIL => self.fresh_key(),
+            KeyPolicy::Lifo => next,
+            KeyPolicy::LowestFirst => self.lowest_vacant(key + 1),
+            KeyPolicy::Monotonic => key + 1,
+        };
+    }
+
+    /// Remove and return the value associated with the given key.
+    ///
+    /// The key is then released and may be associated with future stored
+    /// values.
+    ///
+    /// Returning the value moves it out of its entry, which is only allowed
+    /// for `Unpin` values, since the slab hands out pinned references to
+    /// them. Other values are removed with
+    /// [`remove_in_place`](#method.remove_in_place).
+    ///
+    /// # Panics
+    ///
+    /// Panics if `key` is not associated with a value.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use pinned_slab::*;
+    /// let mut slab = Slab::new();
+    ///
+    /// let (hello, _) = slab.insert("hello");
+    ///
+    /// assert_eq!(slab.remove(hello), "hello");
+    /// assert!(!slab.contains(hello));
+    /// ```
+    #[track_caller]
+    pub fn remove(&mut self, key: usize) -> T
+    where
+        T: Unpin,
+    {
+        // SAFETY: `T` is `Unpin`, so the value may be moved even though it
+        // may have been pinned.
+        unsafe { self.remove_unpinned(key) }
+    }
+
+    /// Remove and return the value associated with the given key, like
+    /// [`remove`](#method.remove) but for any `T`.
+    ///
+    /// # Safety
+    ///
+    /// The value must not have been pinned, unless `T: Unpin`.
+    #[track_caller]
+    pub(crate) unsafe fn remove_unpinned(&mut self, key: usize) -> T {
+        if !self.contains(key) {
+            self.invalid_key(key);
+        }
+        let (slab_key, entry_key) = self.growth.locate(key);
+        let chunk = self.chunk_mut(slab_key).unwrap();
+
+        // SAFETY: The caller makes sure the value may be moved. The entry is
+        // marked vacant right after, so it won't be read or dropped again.
+        let val = unsafe { chunk.entries()[entry_key].as_ptr().read() };
+        chunk.len -= 1;
+        chunk.set_occupied(entry_key, false);
+        chunk.wipe(entry_key);
+        chunk.poison(entry_key);
+        self.len -= 1;
+
+        self.release(key);
+
+        val
+    }
+
+    /// Drop the value associated with the given key in place, returning
+    /// whether there was such a value.
+    ///
+    /// Unlike [`remove`](#method.remove), the value is never moved, so this
+    /// works for values which aren't `Unpin`, e.g. futures which have been
+    /// polled through a pinned reference.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use pinned_slab::*;
+    /// use std::marker::PhantomPinned;
+    ///
+    /// let mut slab = Slab::new();
+    /// let (key, _) = slab.insert(PhantomPinned);
+    ///
+    /// assert!(slab.remove_in_place(key));
+    /// assert!(!slab.remove_in_place(key));
+    /// assert!(slab.is_empty());
+    /// ```
+    #[track_caller]
+    pub fn remove_in_place(&mut self, key: usize) -> bool {
+        if !self.contains(key) {
+            return false;
+        }
+
+        let (slab_key, entry_key) = self.growth.locate(key);
+        self.drop_entry(slab_key, entry_key);
+        true
+    }
+
+    /// Remove and return the value associated with the given key, if there
+    /// is one.
+    ///
+    /// Like [`remove`](#method.remove), but returns `None` instead of
+    /// panicking if the key is vacant.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use pinned_slab::*;
+    /// let mut slab = Slab::new();
+    /// let (key, _) = slab.insert("hello");
+    ///
+    /// assert_eq!(slab.take(key), Some("hello"));
+    /// assert_eq!(slab.take(key), None);
+    /// ```
+    pub fn take(&mut self, key: usize) -> Option<T>
+    where
+        T: Unpin,
+    {
+        if !self.contains(key) {
+            return None;
+        }
+
+        Some(self.remove(key))
+    }
+
+    /// Replace the value associated with the given key, returning the old
+    /// value.
+    ///
+    /// The old value is moved out like by [`remove`](#method.remove), so `T`
+    /// must be `Unpin`, and the new value takes its entry, so it is pinned
+    /// from then on and [`Handle`](struct.Handle.html)s to the old value
+    /// don't refer to it.
+    ///
+    /// If the key is vacant, `val` is handed back as `Err` instead. That is
+    /// why this returns a `Result` rather than an `Option`, which would have
+    /// to drop `val` in that case.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use pinned_slab::*;
+    /// let mut slab = Slab::new();
+    /// let (key, _) = slab.insert("hello");
+    ///
+    /// assert_eq!(slab.replace(key, "world"), Ok("hello"));
+    /// assert_eq!(slab[key], "world");
+    /// assert_eq!(slab.replace(123, "nope"), Err("nope"));
+    /// ```
+    pub fn replace(&mut self, key: usize, val: T) -> Result<T, T>
+    where
+        T: Unpin,
+    {
+        if !self.contains(key) {
+            return Err(val);
+        }
+        self.stamp += 1;
+        let stamp = self.stamp;
+        let (slab_key, entry_key) = self.growth.locate(key);
+        let chunk = self.chunk_mut(slab_key).unwrap();
+
+        // SAFETY: The entry is occupied, and `T` is `Unpin`, so the old value
+        // may be moved out even though it may have been pinned. Only its
+        // entry is read and written.
+        let old = unsafe {
+            let entry = &mut chunk.entries_mut()[entry_key];
+            mem::replace(entry, MaybeUninit::new(val)).assume_init()
+        };
+        chunk.stamps[entry_key] = stamp;
+        chunk.tags[entry_key] = 0;
+        self.counters.remove(1);
+        self.counters.insert(true, self.len);
+
+        Ok(old)
+    }
+
+    /// Remove and return the values associated with the given keys, in
+    /// ascending key order.
+    ///
+    /// The keys are sorted first, so that each chunk is only looked up once.
+    ///
+    /// # Panics
+    ///
+    /// Panics if any key is not associated with a value or appears more than
+    /// once. Nothing is removed in that case.
+    ///
+    /// Like [`remove`](#method.remove), this moves the values, so it requires
+    /// `T: Unpin`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use pinned_slab::*;
+    /// let mut slab = Slab::new();
+    /// slab.insert_many(0..10);
+    ///
+    /// let removed = slab.remove_many([7, 2, 5]);
+    /// assert_eq!(removed, [(2, 2), (5, 5), (7, 7)]);
+    /// assert_eq!(slab.len(), 7);
+    /// assert!(!slab.contains(5));
+    /// ```
+    #[track_caller]
+    pub fn remove_many<I>(&mut self, keys: I) -> Vec<(usize, T)>
+    where
+        T: Unpin,
+        I: IntoIterator<Item = usize>,
+    {
+        let mut keys: Vec<usize> = keys.into_iter().collect();
+        keys.sort_unstable();
+        for (i, &key) in keys.iter().enumerate() {
+            if !self.contains(key) {
+                self.invalid_key(key);
+            }
+            if i > 0 && keys[i - 1] == key {
+                panic!("invalid key");
+            }
+        }
+
+        let mut removed = Vec::with_capacity(keys.len());
+        let growth = self.growth;
+        for group in keys.chunk_by(|&a, &b| growth.locate(a).0 == growth.locate(b).0) {
+            let (slab_key, _) = growth.locate(group[0]);
+            let chunk = self.chunks[slab_key].as_mut().unwrap();
+            for &key in group {
+                let entry_key = key - growth.chunk_start(slab_key);
+                // SAFETY: See `remove`.
+                let val = unsafe { chunk.entries()[entry_key].as_ptr().read() };
+                chunk.set_occupied(entry_key, false);
+                chunk.wipe(entry_key);
+                chunk.poison(entry_key);
+                removed.push((key, val));
+            }
+            chunk.len -= group.len();
+        }
+        self.len -= keys.len();
+
+        // Released in descending order, so that the lowest keys are reused
+        // first.
+        for &key in keys.iter().rev() {
+            self.release(key);
+        }
+
+        removed
+    }
+
+    /// Remove the vacated `key` from the list of occupied entries and make it
+    /// available again according to the key policy.
+    fn release(&mut self, key: usize) {
+        self.counters.remove(1);
+        self.unlink(key);
+        self.free_key(key);
+    }
+
+    /// Vacate the occupied entry `entry_key` of the chunk at `slab_key`, and
+    /// drop its value where it is.
+    #[track_caller]
+    fn drop_entry(&mut self, slab_key: usize, entry_key: usize) {
+        let chunk = self.chunks[slab_key].as_mut().unwrap();
+        chunk.len -= 1;
+        chunk.set_occupied(entry_key, false);
+        self.len -= 1;
+        self.release(self.growth.chunk_start(slab_key) + entry_key);
+
+        let chunk = self.chunks[slab_key].as_mut().unwrap();
+        // SAFETY: The value is dropped where it is, so it never moves. The
+        // entry is already vacant, so if dropping panics the slab is left
+        // consistent and the value won't be dropped again.
+        unsafe {
+            ptr::drop_in_place(chunk.entries_mut()[entry_key].as_mut_ptr());
+        }
+        chunk.wipe(entry_key);
+        chunk.poison(entry_key);
+    }
+
+    /// Make the vacant `key` available again according to the key policy.
+    fn free_key(&mut self, key: usize) {
+        match self.key_policy {
+            KeyPolicy::Lifo => {
+                self.links_mut(key).next = self.next;
+                self.next = key;
+            }
+            KeyPolicy::LowestFirst => {
+                // The next key is only in a new chunk if every allocated
+                // chunk was full.
+                if key < self.next || self.chunk(self.growth.locate(self.next).0).is_none() {
+                    self.next = key;
+                }
+            }
+            KeyPolicy::Monotonic => {}
+        }
+    }
+
+    /// Allocate the chunk of the next key, if it isn't allocated yet.
+    fn try_allocate_next(&mut self) -> Result<(), TryReserveError> {
+        let (slab_key, _) = self.growth.locate(self.next);
+        if self.chunk(slab_key).is_some() {
+            return Ok(());
+        }
+
+        if slab_key >= self.chunks.len() {
+            let slots = slab_key + 1 - self.chunks.len();
+            self.chunks.try_reserve(slots).map_err(|_| {
+                TryReserveError::array::<Option<Chunk<T, A>>>(self.chunks.len() + slots)
+            })?;
+            self.chunks.resize_with(slab_key + 1, || None);
+        }
+
+        let chunk = Chunk::try_new(self.growth.chunk_len(slab_key), self.alloc.clone())?;
+        self.chunks[slab_key] = Some(chunk);
+        self.chunk_allocated(slab_key);
+        Ok(())
+    }
+
+    /// Return the smallest key which has never been handed out, preferring
+    /// allocated chunks over free slots.
+    fn fresh_key(&self) -> usize {
+        let allocated = self
+            .chunks
+            .iter()
+            .enumerate()
+            .find_map(|(i, chunk)| match chunk {
+                Some(chunk) if !chunk.is_full() => Some(self.growth.chunk_start(i) + chunk.touched),
+                _ => None,
+            });
+
+        allocated.unwrap_or_else(|| self.growth.chunk_start(self.free_slot()))
+    }
+
+    /// Return the smallest occupied key which is greater than or equal to
+    /// `key`.
+    fn next_occupied(&self, key: usize) -> Option<usize> {
+        let (slab_key, mut entry_key) = self.growth.locate(key);
+
+        for i in slab_key..self.chunks.len() {
+            if let Some(chunk) = self.chunk(i).filter(|chunk| chunk.len > 0) {
+                if let Some(j) = chunk.next_occupied(entry_key) {
+                    return Some(self.growth.chunk_start(i) + j);
+                }
+            }
+            entry_key = 0;
+        }
+
+        None
+    }
+
+    /// Return the largest occupied key which is less than `key`.
+    fn prev_occupied(&self, key: usize) -> Option<usize> {
+        if key >= self.growth.chunk_start(self.chunks.len()) {
+            return Some(self.tail).filter(|&key| key != NIL);
+        }
+
+        let (slab_key, entry_key) = self.growth.locate(key);
+        if let Some(chunk) = self.chunk(slab_key) {
+            if let Some(j) = chunk.prev_occupied(entry_key) {
+                return Some(self.growth.chunk_start(slab_key) + j);
+            }
+        }
+
+        for i in (0..slab_key).rev() {
+            if let Some(chunk) = self.chunk(i).filter(|chunk| chunk.len > 0) {
+                let start = self.growth.chunk_start(i);
+                return chunk.prev_occupied(chunk.size()).map(|j| start + j);
+            }
+        }
+
+        None
+    }
+
+    /// Return the first and last occupied keys within `range` and the number
+    /// of occupied entries between them.
+    fn occupied_range<R: RangeBounds<usize>>(&self, range: R) -> (usize, usize, usize) {
+        let start = match range.start_bound() {
+            Bound::Included(&start) => start,
+            Bound::Excluded(&start) => start.saturating_add(1),
+            Bound::Unbounded => 0,
+        };
+        let end = match range.end_bound() {
+            Bound::Included(&end) => end.saturating_add(1),
+            Bound::Excluded(&end) => end,
+            Bound::Unbounded => usize::MAX,
+        };
+
+        let (front, back) = match (self.next_occupied(start), self.prev_occupied(end)) {
+            (Some(front), Some(back)) if start < end && front <= back => (front, back),
+            _ => return (NIL, NIL, 0),
+        };
+
+        let (first, front_entry) = self.growth.locate(front);
+        let (last, back_entry) = self.growth.locate(back);
+        let mut count = 0;
+        for i in first..=last {
+            if let Some(chunk) = self.chunk(i) {
+                let start = if i == first { front_entry } else { 0 };
+                let end = if i == last {
+                    back_entry + 1
+                } else {
+                    chunk.size()
+                };
+                count += chunk.count_occupied(start, end);
+            }
+        }
+
+        (front, back, count)
+    }
+
+    fn links(&self, key: usize) -> Link {
+        let (slab_key, entry_key) = self.growth.locate(key);
+        self.chunk(slab_key).unwrap().links[entry_key]
+    }
+
+    fn links_mut(&mut self, key: usize) -> &mut Link {
+        let (slab_key, entry_key) = self.growth.locate(key);
+        &mut self.chunk_mut(slab_key).unwrap().links[entry_key]
+    }
+
+    /// Insert `key` into the list of occupied entries.
+    fn link(&mut self, key: usize) {
+        // Most insertions append to the end of the list, so check that first
+        // before searching the occupancy bitmaps.
+        let prev = if self.tail == NIL || key > self.tail {
+            self.tail
+        } else {
+            self.prev_occupied(key).unwrap_or(NIL)
+        };
+        let next = match prev {
+            NIL => self.head,
+            prev => self.links(prev).next,
+        };
+
+        *self.links_mut(key) = Link { prev, next };
+        match prev {
+            NIL => self.head = key,
+            prev => self.links_mut(prev).next = key,
+        }
+        match next {
+            NIL => self.tail = key,
+            next => self.links_mut(next).prev = key,
+        }
+    }
+
+    /// Remove `key` from the list of occupied entries.
+    fn unlink(&mut self, key: usize) {
+        let Link { prev, next } = self.links(key);
+        match prev {
+            NIL => self.head = next,
+            prev => self.links_mut(prev).next = next,
+        }
+        match next {
+            NIL => self.tail = prev,
+            next => self.links_mut(next).prev = prev,
+        }
+    }
+
+    /// Drop every value in the slab, keeping the allocated chunks for reuse.
+    ///
+    /// Keys are handed out from zero again afterwards. If dropping a value
+    /// panics, the slab is still left empty, but some values may be leaked.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use pinned_slab::*;
+    /// let mut slab = Slab::new();
+    ///
+    /// for i in 0..3 {
+    ///     slab.insert(i);
+    /// }
+    /// slab.clear();
+    ///
+    /// assert!(slab.is_empty());
+    /// assert_eq!(slab.capacity(), DEFAULT_CHUNK_SIZE);
+    /// assert_eq!(slab.insert(3).0, 0);
+    /// ```
+    #[track_caller]
+    pub fn clear(&mut self) {
+        /// Clears the remaining chunks if dropping a value panics.
+        struct Guard<'a, T, A: ChunkAllocator>(
+            core::iter::Flatten<core::slice::IterMut<'a, Option<Chunk<T, A>>>>,
+        );
+
+        impl<'a, T, A: ChunkAllocator> Drop for Guard<'a, T, A> {
+            fn drop(&mut self) {
+                self.0.by_ref().for_each(Chunk::clear);
+            }
+        }
+
+        self.counters.remove(self.len);
+        self.len = 0;
+        self.reserved = 0;
+        self.issued = 0;
+        self.next = self
+            .growth
+            .chunk_start(self.chunks.iter().position(Option::is_some).unwrap_or(0));
+        self.head = NIL;
+        self.tail = NIL;
+
+        let mut guard = Guard(self.chunks.iter_mut().flatten());
+        for chunk in guard.0.by_ref() {
+            chunk.clear();
+        }
+    }
+
+    /// Drop every value in the slab and free all of its chunks.
+    ///
+    /// See [`clear`](#method.clear).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use pinned_slab::*;
+    /// let mut slab = Slab::new();
+    ///
+    /// slab.insert("hello");
+    /// slab.clear_and_free();
+    ///
+    /// assert!(slab.is_empty());
+    /// assert_eq!(slab.capacity(), 0);
+    /// ```
+    pub fn clear_and_free(&mut self) {
+        self.clear();
+        self.next = 0;
+        for index in 0..self.chunks.len() {
+            self.free_chunk(index);
+        }
+        self.chunks = Vec::new();
+    }
+
+    /// Free every empty chunk of the slab.
+    ///
+    /// Every key stays valid. Chunks in the middle of the slab leave a free
+    /// slot behind in the list of chunks, which is filled again once the
+    /// allocated chunks run out of vacant entries. Trailing free slots are
+    /// removed like in [`shrink_to_fit`](#method.shrink_to_fit).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use pinned_slab::*;
+    /// let mut slab = Slab::new();
+    ///
+    /// for i in 0..DEFAULT_CHUNK_SIZE * 3 {
+    ///     slab.insert(i);
+    /// }
+    /// for key in DEFAULT_CHUNK_SIZE..DEFAULT_CHUNK_SIZE * 2 {
+    ///     slab.remove(key);
+    /// }
+    ///
+    /// slab.free_unused();
+    /// assert_eq!(slab.capacity(), DEFAULT_CHUNK_SIZE * 2);
+    /// assert_eq!(slab[DEFAULT_CHUNK_SIZE * 2], DEFAULT_CHUNK_SIZE * 2);
+    /// assert_eq!(slab.insert(0).0, DEFAULT_CHUNK_SIZE);
+    /// ```
+    pub fn free_unused(&mut self) {
+        for index in 0..self.chunks.len() {
+            if self.chunk(index).is_some_and(Chunk::is_unused) {
+                self.free_chunk(index);
+            }
+        }
+
+        self.shrink_to_fit();
+    }
+
+    /// Free the empty chunks at the end of the slab, and shrink the list of
+    /// chunks as much as possible.
+    ///
+    /// Every key stays valid. Vacant keys in the freed chunks aren't handed
+    /// out anymore until the slab grows again, and afterwards the remaining
+    /// vacant keys are reused in ascending order. Use
+    /// [`free_unused`](#method.free_unused) to free empty chunks in the middle
+    /// of the slab as well.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use pinned_slab::*;
+    /// let mut slab = Slab::new();
+    ///
+    /// for i in 0..DEFAULT_CHUNK_SIZE * 2 {
+    ///     slab.insert(i);
+    /// }
+    /// for key in DEFAULT_CHUNK_SIZE - 1..DEFAULT_CHUNK_SIZE * 2 {
+    ///     slab.remove(key);
+    /// }
+    ///
+    /// slab.shrink_to_fit();
+    /// assert_eq!(slab.capacity(), DEFAULT_CHUNK_SIZE);
+    /// assert_eq!(slab[3], 3);
+    /// assert_eq!(slab.insert(0).0, DEFAULT_CHUNK_SIZE - 1);
+    /// ```
+    pub fn shrink_to_fit(&mut self) {
+        while self
+            .chunks
+            .last()
+            .is_some_and(|chunk| chunk.as_ref().is_none_or(Chunk::is_unused))
+        {
+            self.free_chunk(self.chunks.len() - 1);
+            self.chunks.pop();
+        }
+        self.chunks.shrink_to_fit();
+
+        self.rebuild_free_list();
+    }
+
+    /// Move every value into the lowest keys of the slab and free the chunks
+    /// which end up empty.
+    ///
+    /// Afterwards the values occupy exactly the keys `0..len()`, apart from
+    /// the keys reserved with [`reserve_key`](#method.reserve_key), which
+    /// are skipped. For every
+    /// value which is moved, `f` is called with its old key, its new key and
+    /// the value itself, so references to the value elsewhere can be fixed up.
+    /// Values are only moved if `T` is `Unpin`, since moving them breaks the
+    /// pinning guarantee otherwise. [`Handle`](struct.Handle.html)s to moved
+    /// values don't refer to them anymore.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use pinned_slab::*;
+    /// let mut slab = Slab::new();
+    ///
+    /// for i in 0..DEFAULT_CHUNK_SIZE * 3 {
+    ///     slab.insert(i);
+    /// }
+    /// slab.extract_if(|key, _| key % 100 != 0).for_each(drop);
+    ///
+    /// let mut moved = Vec::new();
+    /// slab.compact(|old, new, val| {
+    ///     assert_eq!(old, *val);
+    ///     moved.push((old, new));
+    /// });
+    ///
+    /// assert_eq!(moved[0], (3000, 1));
+    /// assert_eq!(slab.keys().collect::<Vec<_>>(), (0..slab.len()).collect::<Vec<_>>());
+    /// assert_eq!(slab.capacity(), DEFAULT_CHUNK_SIZE);
+    /// ```
+    pub fn compact<F>(&mut self, mut f: F)
+    where
+        T: Unpin,
+        F: FnMut(usize, usize, &mut T),
+    {
+        /// Rebuilds the free list, which `occupy` bypasses, even if `f`
+        /// panics.
+        struct Guard<'a, T, const N: usize, A: ChunkAllocator>(&'a mut Slab<T, N, A>);
+
+        impl<'a, T, const N: usize, A: ChunkAllocator> Drop for Guard<'a, T, N, A> {
+            fn drop(&mut self) {
+                self.0.rebuild_free_list();
+            }
+        }
+
+        let guard = Guard(self);
+        let slab = &mut *guard.0;
+        for key in 0..slab.len + slab.reserved {
+            if slab.contains(key) || slab.is_reserved(key) {
+                continue;
+            }
+
+            // There are `len` values and reserved keys and `key` is vacant,
+            // so the last value is above `key` unless only reserved keys are.
+            let old = slab.tail;
+            if old == NIL || old < key {
+                break;
+            }
+            let val = slab.remove(old);
+            slab.occupy(key, val);
+            slab.counters.relocate();
+
+            // SAFETY: `key` was just occupied.
+            f(old, key, unsafe { slab.get_unchecked_mut(key) });
+        }
+        mem::forget(guard);
+
+        self.free_unused();
+    }
+
+    /// Store `val` at the vacant `key`, allocating its chunk if needed.
+    ///
+    /// This bypasses the free list, so it has to be rebuilt afterwards.
+    fn occupy(&mut self, key: usize, val: T) {
+        let (slab_key, entry_key) = self.growth.locate(key);
+
+        if slab_key >= self.chunks.len() {
+            self.chunks.resize_with(slab_key + 1, || None);
+        }
+
+        let len = self.growth.chunk_len(slab_key);
+        if self.chunks[slab_key].is_none() {
+            self.chunks[slab_key] = Some(Chunk::new(len, self.alloc.clone()));
+            self.chunk_allocated(slab_key);
+        }
+        let chunk = self.chunks[slab_key].as_mut().unwrap();
+        chunk.len += 1;
+        chunk.set_occupied(entry_key, true);
+        self.stamp += 1;
+        chunk.stamps[entry_key] = self.stamp;
+        chunk.tags[entry_key] = 0;
+
+        // Any entries below it which haven't been handed out before become
+        // vacant entries.
+        chunk.touched = chunk.touched.max(entry_key + 1);
+
+        // SAFETY: This only writes to the vacant entry at `entry_key`.
+        unsafe { chunk.entries_mut()[entry_key] = MaybeUninit::new(val) };
+
+        self.len += 1;
+        self.counters.insert(false, self.len);
+        self.issued = self.issued.max(key + 1);
+        self.link(key);
+    }
+
+    /// Return one past the last key of the last chunk which has handed out
+    /// any keys, or 0 if there is none.
+    fn touched_end(&self) -> usize {
+        self.chunks
+            .iter()
+            .enumerate()
+            .rev()
+            .find_map(|(i, chunk)| {
+                let chunk = chunk.as_ref().filter(|chunk| chunk.touched > 0)?;
+                Some(self.growth.chunk_start(i) + chunk.touched)
+            })
+            .unwrap_or(0)
+    }
+
+    /// Thread the free list through every vacant entry which has been handed
+    /// out before, in ascending order, and pick the next key according to the
+    /// key policy.
+    ///
+    /// Vacant entries after the last occupied or reserved entry of each chunk
+    /// are treated as never handed out, so they are used up in order
+    /// afterwards.
+    fn rebuild_free_list(&mut self) {
+        for chunk in self.chunks.iter_mut().flatten() {
+            chunk.touched = chunk.taken_len();
+        }
+
+        let mut next = self.fresh_key();
+
+        for (i, chunk) in self.chunks.iter_mut().enumerate().rev() {
+            let chunk = match chunk {
+                Some(chunk) => chunk,
+                None => continue,
+            };
+            for j in (0..chunk.touched).rev() {
+                if !chunk.is_occupied(j) && !chunk.is_reserved(j) {
+                    chunk.links[j].next = next;
+                    next = self.growth.chunk_start(i) + j;
+                }
+            }
+        }
+
+        self.next = match self.key_policy {
+            KeyPolicy::Lifo => next,
+            KeyPolicy::LowestFirst => self.lowest_vacant(0),
+            KeyPolicy::Monotonic => {
+                // New chunks are only entered at their first key, so skip the
+                // rest of the next key's chunk if it has been freed.
+                let (slab_key, entry_key) = self.growth.locate(self.next);
+                if entry_key != 0 && self.chunk(slab_key).is_none() {
+                    self.growth.chunk_start(slab_key + 1)
+                } else {
+                    self.next
+                }
+            }
+        };
+    }
+
+    /// Retain only the elements specified by the predicate.
+    ///
+    /// In other words, remove all elements `e` such that `f(key, e)` returns
+    /// false. The predicate gets a pinned reference to each value, in
+    /// ascending key order. This method operates in place and preserves the
+    /// key associated with the retained values.
+    ///
+    /// Only the occupied entries are visited, by scanning the occupancy
+    /// bitmap of each chunk, and rejected values are dropped where they are,
+    /// so `retain` takes time in the number of values rather than the
+    /// capacity of the slab.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use pinned_slab::*;
+    /// let mut slab = Slab::new();
+    ///
+    /// let (k1, _) = slab.insert(0);
+    /// let (k2, _) = slab.insert(1);
+    /// let (k3, _) = slab.insert(2);
+    ///
+    /// slab.retain(|key, val| key == k1 || *val == 1);
+    ///
+    /// assert!(slab.contains(k1));
+    /// assert!(slab.contains(k2));
+    /// assert!(!slab.contains(k3));
+    ///
+    /// assert_eq!(2, slab.len());
+    /// ```
+    #[track_caller]
+    pub fn retain<F>(&mut self, mut f: F)
+    where
+        F: FnMut(usize, Pin<&mut T>) -> bool,
+    {
+        for slab_key in 0..self.chunks.len() {
+            let start = self.growth.chunk_start(slab_key);
+            let mut next = match self.chunk(slab_key) {
+                Some(chunk) if chunk.len > 0 => chunk.next_occupied(0),
+                _ => None,
+            };
+
+            while let Some(entry_key) = next {
+                let chunk = self.chunks[slab_key].as_mut().unwrap();
+                // SAFETY: The entry is occupied, and values never move while
+                // they are in the slab.
+                let val = unsafe {
+                    Pin::new_unchecked(&mut *chunk.entries_mut()[entry_key].as_mut_ptr())
+                };
+                if !f(start + entry_key, val) {
+                    self.drop_entry(slab_key, entry_key);
+                }
+                next = self.chunks[slab_key]
+                    .as_ref()
+                    .unwrap()
+                    .next_occupied(entry_key + 1);
+            }
+        }
+    }
+}
+
+impl<'a, T, const N: usize, A: ChunkAllocator> CursorMut<'a, T, N, A> {
+    /// Return the key of the entry the cursor points to, or `None` if the
+    /// cursor has moved past the last occupied entry.
+    pub fn key(&self) -> Option<usize> {
+        self.key
+    }
+
+    /// Return a pinned mutable reference to the entry the cursor points to.
+    pub fn current(&mut self) -> Option<Pin<&mut T>> {
+        let key = self.key?;
+        // SAFETY: `self.key` always refers to an occupied entry.
+        Some(unsafe { self.slab.get_unchecked_pin_mut(key) })
+    }
+
+    /// Move the cursor to the next occupied entry.
+    ///
+    /// If there are no more occupied entries, the cursor stops pointing to
+    /// anything and `current` returns `None`.
+    pub fn move_next(&mut self) {
+        if let Some(key) = self.key {
+            self.key = Some(self.slab.links(key).next).filter(|&key| key != NIL);
+        }
+    }
+
+    /// Remove the entry the cursor points to and move the cursor to the next
+    /// occupied entry.
+    ///
+    /// Returns the removed value, or `None` if the cursor doesn't point to an
+    /// entry. The value is moved out of the slab, so `T` must be `Unpin`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use pinned_slab::*;
+    /// let mut slab = Slab::new();
+    /// let (a, _) = slab.insert("a");
+    /// let (b, _) = slab.insert("b");
+    ///
+    /// let mut cursor = slab.cursor_mut();
+    /// assert_eq!(cursor.remove_current(), Some("a"));
+    /// assert_eq!(cursor.key(), Some(b));
+    ///
+    /// assert!(!slab.contains(a));
+    /// ```
+    pub fn remove_current(&mut self) -> Option<T>
+    where
+        T: Unpin,
+    {
+        let key = self.key?;
+        self.move_next();
+        Some(self.slab.remove(key))
+    }
+
+    /// Insert a value into the slab without moving the cursor, returning the
+    /// key assigned to the value.
+    ///
+    /// The new entry will be visited by the cursor later on only if its key
+    /// is greater than the key of the current entry.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use pinned_slab::*;
+    /// let mut slab = Slab::new();
+    /// slab.insert(0);
+    ///
+    /// let mut cursor = slab.cursor_mut();
+    /// let key = cursor.insert(1);
+    /// cursor.move_next();
+    ///
+    /// assert_eq!(cursor.key(), Some(key));
+    /// ```
+    pub fn insert(&mut self, val: T) -> usize {
+        self.slab.insert(val).0
+    }
+}
+
+impl<'a, T, const N: usize, A: ChunkAllocator> Iterator for VacantKeys<'a, T, N, A> {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<usize> {
+        if self.remaining == 0 {
+            return None;
+        }
+
+        while self.word == 0 {
+            self.word_index += 1;
+            if self.word_index * 64 >= self.growth.chunk_len(self.chunk) {
+                self.chunk += 1;
+                self.word_index = 0;
+            }
+            // Freed chunks don't have any vacant entries to hand out.
+            self.word = match &self.chunks[self.chunk] {
+                Some(chunk) => chunk.vacant_word(self.word_index),
+                None => 0,
+            };
+        }
+
+        let key = self.growth.chunk_start(self.chunk)
+            + self.word_index * 64
+            + self.word.trailing_zeros() as usize;
+        self.word &= self.word - 1;
+        self.remaining -= 1;
+        Some(key)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+impl<'a, T, const N: usize, A: ChunkAllocator> Iterator for ChunkSlices<'a, T, N, A> {
+    type Item = &'a [T];
+
+    fn next(&mut self) -> Option<&'a [T]> {
+        self.chunks.next()?.as_ref().and_then(Chunk::as_slice)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.chunks.size_hint()
+    }
+}
+
+impl<'a, T, const N: usize, A: ChunkAllocator> DoubleEndedIterator for ChunkSlices<'a, T, N, A> {
+    fn next_back(&mut self) -> Option<&'a [T]> {
+        self.chunks.next_back()?.as_ref().and_then(Chunk::as_slice)
+    }
+}
+
+impl<'a, T: Unpin, const N: usize, A: ChunkAllocator> Iterator for Drain<'a, T, N, A> {
+    type Item = (usize, T);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self.slab.head {
+            NIL => None,
+            key => Some((key, self.slab.remove(key))),
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.slab.len, Some(self.slab.len))
+    }
+}
+
+impl<'a, T: Unpin, const N: usize, A: ChunkAllocator> DoubleEndedIterator for Drain<'a, T, N, A> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        match self.slab.tail {
+            NIL => None,
+            key => Some((key, self.slab.remove(key))),
+        }
+    }
+}
+
+impl<'a, T, const N: usize, A: ChunkAllocator> Drop for Drain<'a, T, N, A> {
+    fn drop(&mut self) {
+        self.slab.clear();
+    }
+}
+
+impl<'a, T, F, const N: usize, A: ChunkAllocator> Iterator for ExtractIf<'a, T, F, N, A>
+where
+    T: Unpin,
+    F: FnMut(usize, Pin<&mut T>) -> bool,
+{
+    type Item = (usize, T);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.front != NIL {
+            let key = self.front;
+            self.front = self.slab.links(key).next;
+
+            // SAFETY: The list only contains occupied keys.
+            let val = unsafe { self.slab.get_unchecked_pin_mut(key) };
+            if (self.pred)(key, val) {
+                return Some((key, self.slab.remove(key)));
+            }
+        }
+
+        None
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, Some(self.slab.len))
+    }
+}
+
+/// Inserts the values with the keys `0..vec.len()`.
+///
+/// # Examples
+///
+/// ```
+/// # use pinned_slab::*;
+/// let slab: Slab<_> = Slab::from(vec!["a", "b"]);
+///
+/// assert_eq!(slab[1], "b");
+/// assert_eq!(slab.next_key(), 2);
+/// ```
+impl<T, const N: usize, A: ChunkAllocator + Default> From<Vec<T>> for Slab<T, N, A> {
+    fn from(vec: Vec<T>) -> Self {
+        let mut slab = Slab::default();
+        slab.reserve_exact(vec.len());
+        slab.insert_many(vec);
+        slab
+    }
+}
+
+/// Slabs are equal if they store equal values at the same keys, no matter
+/// their capacity or which keys they will hand out next.
+///
+/// # Examples
+///
+/// ```
+/// # use pinned_slab::*;
+/// let mut a: Slab<_> = Slab::from(vec![0, 1, 2]);
+/// let mut b = Slab::new();
+/// b.insert(0);
+/// b.insert(5);
+/// b.insert(2);
+///
+/// assert_ne!(a, b);
+/// b.remove(1);
+/// a.remove(1);
+/// assert_eq!(a, b);
+/// assert_eq!(a, [(0, 0), (2, 2)]);
+/// ```
+impl<T, U, const N: usize, const M: usize, A, B> PartialEq<Slab<U, M, B>> for Slab<T, N, A>
+where
+    T: PartialEq<U>,
+    A: ChunkAllocator,
+    B: ChunkAllocator,
+{
+    fn eq(&self, other: &Slab<U, M, B>) -> bool {
+        self.len == other.len
+            && self
+                .iter()
+                .zip(other.iter())
+                .all(|((k1, v1), (k2, v2))| k1 == k2 && v1 == v2)
+    }
+}
+
+impl<T: Eq, const N: usize, A: ChunkAllocator> Eq for Slab<T, N, A> {}
+
+/// Compares the keys and values of the slab, in ascending key order.
+impl<T, U, const N: usize, A: ChunkAllocator> PartialEq<[(usize, U)]> for Slab<T, N, A>
+where
+    T: PartialEq<U>,
+{
+    fn eq(&self, other: &[(usize, U)]) -> bool {
+        self.len == other.len()
+            && self
+                .iter()
+                .zip(other)
+                .all(|((k1, v1), (k2, v2))| k1 == *k2 && v1 == v2)
+    }
+}
+
+/// Compares the keys and values of the slab, in ascending key order.
+impl<T, U, const N: usize, const K: usize, A> PartialEq<[(usize, U); K]> for Slab<T, N, A>
+where
+    T: PartialEq<U>,
+    A: ChunkAllocator,
+{
+    fn eq(&self, other: &[(usize, U); K]) -> bool {
+        *self == other[..]
+    }
+}
+
+/// Hashes the keys and values of the slab, consistently with `PartialEq`.
+impl<T: Hash, const N: usize, A: ChunkAllocator> Hash for Slab<T, N, A> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        state.write_usize(self.len);
+        for (key, val) in self {
+            key.hash(state);
+            val.hash(state);
+        }
+    }
+}
+
+/// Shows the number of values, the capacity and the occupied entries as a
+/// map from keys to values. The alternate form `{:#?}` also shows the key
+/// policy, the growth policy, the next key and how many entries of each
+/// chunk are occupied, with `None` for chunks which aren't allocated.
+///
+/// # Examples
+///
+/// ```
+/// # use pinned_slab::*;
+/// let mut slab = Slab::new();
+/// slab.insert("a");
+/// slab.insert("b");
+/// slab.insert("c");
+/// slab.remove(1);
+///
+/// assert_eq!(
+///     format!("{:?}", slab),
+///     r#"Slab { len: 2, capacity: 1024, entries: {0: "a", 2: "c"} }"#
+/// );
+/// assert!(format!("{:#?}", slab).contains("2/1024"));
+/// ```
+impl<T: fmt::Debug, const N: usize, A: ChunkAllocator> fmt::Debug for Slab<T, N, A> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        struct Entries<'a, T, const N: usize, A: ChunkAllocator>(&'a Slab<T, N, A>);
+
+        impl<'a, T: fmt::Debug, const N: usize, A: ChunkAllocator> fmt::Debug for Entries<'a, T, N, A> {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.debug_map().entries(self.0.iter()).finish()
+            }
+        }
+
+        /// Shows how many entries of a chunk are occupied.
+        struct Occupancy(usize, usize);
+
+        impl fmt::Debug for Occupancy {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                write!(f, "{}/{}", self.0, self.1)
+            }
+        }
+
+        let verbose = f.alternate();
+        let mut s = f.debug_struct("Slab");
+        if let Some(name) = self.name {
+            s.field("name", &name);
+        }
+        s.field("len", &self.len)
+            .field("capacity", &self.capacity());
+        if verbose {
+            let chunks: Vec<_> = self
+                .chunks
+                .iter()
+                .map(|chunk| {
+                    chunk
+                        .as_ref()
+                        .map(|chunk| Occupancy(chunk.len, chunk.size()))
+                })
+                .collect();
+            s.field("key_policy", &self.key_policy)
+                .field("growth_policy", &self.growth_policy())
+                .field("next_key", &self.next)
+                .field("chunks", &chunks);
+        }
+        s.field("entries", &Entries(self)).finish()
+    }
+}
+
+impl<T, const N: usize, A: ChunkAllocator> ops::Index<usize> for Slab<T, N, A> {
+    type Output = T;
+
+    fn index(&self, key: usize) -> &T {
+        match self.get(key) {
+            Some(v) => v,
+            _ => self.invalid_key(key),
+        }
+    }
+}
+
+impl<'a, T, const N: usize, A: ChunkAllocator> IntoIterator for &'a Slab<T, N, A> {
+    type Item = (usize, &'a T);
+    type IntoIter = Iter<'a, T, N, A>;
+
+    fn into_iter(self) -> Iter<'a, T, N, A> {
+        self.iter()
+    }
+}
+
+impl<'a, T, const N: usize, A: ChunkAllocator> IntoIterator for &'a mut Slab<T, N, A> {
+    type Item = (usize, Pin<&'a mut T>);
+    type IntoIter = IterPinMut<'a, T, N, A>;
+
+    fn into_iter(self) -> IterPinMut<'a, T, N, A> {
+        self.iter_pin_mut()
+    }
+}
+
+impl<T: Unpin, const N: usize, A: ChunkAllocator> IntoIterator for Slab<T, N, A> {
+    type Item = (usize, T);
+    type IntoIter = IntoIter<T, N, A>;
+
+    fn into_iter(self) -> IntoIter<T, N, A> {
+        IntoIter { slab: self }
+    }
+}
+
+impl<'a, T, const N: usize, A: ChunkAllocator> Iterator for Iter<'a, T, N, A> {
+    type Item = (usize, &'a T);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.remaining == 0 {
+            return None;
+        }
+
+        let key = self.front;
+        let (slab_key, entry_key) = self.growth.locate(key);
+        let chunk = self.chunks[slab_key].as_ref().unwrap();
+        self.front = chunk.links[entry_key].next;
+        self.remaining -= 1;
+
+        // SAFETY: The list only contains occupied keys.
+        Some((key, unsafe { chunk.entries()[entry_key].assume_init_ref() }))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+impl<'a, T, const N: usize, A: ChunkAllocator> DoubleEndedIterator for Iter<'a, T, N, A> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.remaining == 0 {
+            return None;
+        }
+
+        let key = self.back;
+        let (slab_key, entry_key) = self.growth.locate(key);
+        let chunk = self.chunks[slab_key].as_ref().unwrap();
+        self.back = chunk.links[entry_key].prev;
+        self.remaining -= 1;
+
+        // SAFETY: The list only contains occupied keys.
+        Some((key, unsafe { chunk.entries()[entry_key].assume_init_ref() }))
+    }
+}
+
+impl<'a, T> ChunkIterMut<'a, T> {
+    fn new<A: ChunkAllocator>(index: usize, chunk: &'a mut Chunk<T, A>) -> Self {
+        let touched = chunk.touched;
+        // SAFETY: The entries are only handed out pinned.
+        let entries = unsafe { &mut *(chunk.entries_mut() as *mut [MaybeUninit<T>]) };
+
+        ChunkIterMut {
+            index,
+            end: touched,
+            entries: entries[..touched].iter_mut(),
+            links: &chunk.links,
+            start: 0,
+        }
+    }
+
+    fn take_front(&mut self, entry_key: usize) -> &'a mut T {
+        let entry = self.entries.nth(entry_key - self.start).unwrap();
+        self.start = entry_key + 1;
+
+        // SAFETY: Only occupied keys are taken.
+        unsafe { entry.assume_init_mut() }
+    }
+
+    fn take_back(&mut self, entry_key: usize) -> &'a mut T {
+        let entry = self.entries.nth_back(self.end - 1 - entry_key).unwrap();
+        self.end = entry_key;
+
+        // SAFETY: Only occupied keys are taken.
+        unsafe { entry.assume_init_mut() }
+    }
+}
+
+impl<'a, T, const N: usize, A: ChunkAllocator> IterMut<'a, T, N, A> {
+    /// Return an iterator over the occupied entries of a single chunk, which
+    /// is the chunk at `index` in its slab.
+    #[cfg(feature = "rayon")]
+    fn for_chunk(growth: Growth<N>, index: usize, slot: &'a mut Option<Chunk<T, A>>) -> Self {
+        let base = growth.chunk_start(index);
+        let (front, back, remaining) = match slot {
+            Some(chunk) => (
+                chunk.next_occupied(0).map_or(NIL, |j| base + j),
+                chunk.prev_occupied(chunk.size()).map_or(NIL, |j| base + j),
+                chunk.len,
+            ),
+            None => (NIL, NIL, 0),
+        };
+
+        IterMut {
+            chunks: core::slice::from_mut(slot).iter_mut(),
+            growth,
+            chunks_start: index,
+            front_chunk: None,
+            back_chunk: None,
+            front,
+            back,
+            remaining,
+        }
+    }
+
+    /// Return `true` if neither end of the iterator has reached the chunk at
+    /// `index` yet.
+    fn is_untouched(&self, index: usize) -> bool {
+        index >= self.chunks_start && index < self.chunks_start + self.chunks.len()
+    }
+
+    /// Return the entries of the chunk at `index` for the front of the
+    /// iterator.
+    fn front_chunk(&mut self, index: usize) -> &mut ChunkIterMut<'a, T> {
+        if self.front_chunk.as_ref().map(|c| c.index) != Some(index) {
+            // The list is sorted, so the front only ever moves forward. If the
+            // chunk isn't in `chunks` anymore then the back must have it.
+            self.front_chunk = if self.is_untouched(index) {
+                let chunk = self.chunks.nth(index - self.chunks_start).unwrap();
+                let chunk = chunk.as_mut().unwrap();
+                self.chunks_start = index + 1;
+                Some(ChunkIterMut::new(index, chunk))
+            } else {
+                self.back_chunk.take()
+            };
+        }
+
+        self.front_chunk.as_mut().unwrap()
+    }
+
+    /// Return the entries of the chunk at `index` for the back of the
+    /// iterator.
+    fn back_chunk(&mut self, index: usize) -> &mut ChunkIterMut<'a, T> {
+        if self.back_chunk.as_ref().map(|c| c.index) != Some(index) {
+            self.back_chunk = if self.is_untouched(index) {
+                let chunks_end = self.chunks_start + self.chunks.len();
+                let chunk = self.chunks.nth_back(chunks_end - 1 - index).unwrap();
+                let chunk = chunk.as_mut().unwrap();
+                Some(ChunkIterMut::new(index, chunk))
+            } else {
+                self.front_chunk.take()
+            };
+        }
+
+        self.back_chunk.as_mut().unwrap()
+    }
+}
+
+impl<'a, T, const N: usize, A: ChunkAllocator> Iterator for IterMut<'a, T, N, A> {
+    type Item = (usize, &'a mut T);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.remaining == 0 {
+            return None;
+        }
+
+        let key = self.front;
+        let (slab_key, entry_key) = self.growth.locate(key);
+        let chunk = self.front_chunk(slab_key);
+        let val = chunk.take_front(entry_key);
+        self.front = chunk.links[entry_key].next;
+        self.remaining -= 1;
+
+        Some((key, val))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+impl<'a, T, const N: usize, A: ChunkAllocator> DoubleEndedIterator for IterMut<'a, T, N, A> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.remaining == 0 {
+            return None;
+        }
+
+        let key = self.back;
+        let (slab_key, entry_key) = self.growth.locate(key);
+        let chunk = self.back_chunk(slab_key);
+        let val = chunk.take_back(entry_key);
+        self.back = chunk.links[entry_key].prev;
+        self.remaining -= 1;
+
+        Some((key, val))
+    }
+}
+
+impl<'a, T, const N: usize, A: ChunkAllocator> Iterator for IterPinMut<'a, T, N, A> {
+    type Item = (usize, Pin<&'a mut T>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (key, val) = self.inner.next()?;
+        // SAFETY: The values are never moved out of the slab.
+        Some((key, unsafe { Pin::new_unchecked(val) }))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<'a, T, const N: usize, A: ChunkAllocator> DoubleEndedIterator for IterPinMut<'a, T, N, A> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        let (key, val) = self.inner.next_back()?;
+        // SAFETY: The values are never moved out of the slab.
+        Some((key, unsafe { Pin::new_unchecked(val) }))
+    }
+}
+
+impl<'a, T, const N: usize, A: ChunkAllocator> Iterator for Keys<'a, T, N, A> {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<usize> {
+        self.inner.next().map(|(key, _)| key)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<'a, T, const N: usize, A: ChunkAllocator> DoubleEndedIterator for Keys<'a, T, N, A> {
+    fn next_back(&mut self) -> Option<usize> {
+        self.inner.next_back().map(|(key, _)| key)
+    }
+}
+
+impl<'a, T, const N: usize, A: ChunkAllocator> Iterator for Values<'a, T, N, A> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<&'a T> {
+        self.inner.next().map(|(_, val)| val)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<'a, T, const N: usize, A: ChunkAllocator> DoubleEndedIterator for Values<'a, T, N, A> {
+    fn next_back(&mut self) -> Option<&'a T> {
+        self.inner.next_back().map(|(_, val)| val)
+    }
+}
+
+impl<'a, T, const N: usize, A: ChunkAllocator> Iterator for ValuesPinMut<'a, T, N, A> {
+    type Item = Pin<&'a mut T>;
+
+    fn next(&mut self) -> Option<Pin<&'a mut T>> {
+        self.inner.next().map(|(_, val)| val)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<'a, T, const N: usize, A: ChunkAllocator> DoubleEndedIterator for ValuesPinMut<'a, T, N, A> {
+    fn next_back(&mut self) -> Option<Pin<&'a mut T>> {
+        self.inner.next_back().map(|(_, val)| val)
+    }
+}
+
+impl<T: Unpin, const N: usize, A: ChunkAllocator> Iterator for IntoIter<T, N, A> {
+    type Item = (usize, T);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self.slab.head {
+            NIL => None,
+            key => Some((key, self.slab.remove(key))),
         }
     }
 
     fn size_hint(&self) -> (usize, Option<usize>) {
-        (0, Some(self.chunks.len() * CHUNK_SIZE))
+        (self.slab.len, Some(self.slab.len))
+    }
+}
+
+impl<T: Unpin, const N: usize, A: ChunkAllocator> DoubleEndedIterator for IntoIter<T, N, A> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        match self.slab.tail {
+            NIL => None,
+            key => Some((key, self.slab.remove(key))),
+        }
     }
 }
+
+impl<'a, T, const N: usize, A: ChunkAllocator> ExactSizeIterator for Iter<'a, T, N, A> {}
+
+impl<'a, T, const N: usize, A: ChunkAllocator> ExactSizeIterator for IterMut<'a, T, N, A> {}
+
+impl<'a, T, const N: usize, A: ChunkAllocator> ExactSizeIterator for IterPinMut<'a, T, N, A> {}
+
+impl<'a, T, const N: usize, A: ChunkAllocator> ExactSizeIterator for Keys<'a, T, N, A> {}
+
+impl<'a, T, const N: usize, A: ChunkAllocator> ExactSizeIterator for Values<'a, T, N, A> {}
+
+impl<'a, T, const N: usize, A: ChunkAllocator> ExactSizeIterator for ValuesPinMut<'a, T, N, A> {}
+
+impl<'a, T, const N: usize, A: ChunkAllocator> ExactSizeIterator for VacantKeys<'a, T, N, A> {}
+
+impl<'a, T, const N: usize, A: ChunkAllocator> ExactSizeIterator for ChunkSlices<'a, T, N, A> {}
+
+impl<'a, T: Unpin, const N: usize, A: ChunkAllocator> ExactSizeIterator for Drain<'a, T, N, A> {}
+
+impl<T: Unpin, const N: usize, A: ChunkAllocator> ExactSizeIterator for IntoIter<T, N, A> {}
+
+impl<'a, T, const N: usize, A: ChunkAllocator> FusedIterator for Iter<'a, T, N, A> {}
+
+impl<'a, T, const N: usize, A: ChunkAllocator> FusedIterator for IterMut<'a, T, N, A> {}
+
+impl<'a, T, const N: usize, A: ChunkAllocator> FusedIterator for IterPinMut<'a, T, N, A> {}
+
+impl<'a, T, const N: usize, A: ChunkAllocator> FusedIterator for Keys<'a, T, N, A> {}
+
+impl<'a, T, const N: usize, A: ChunkAllocator> FusedIterator for Values<'a, T, N, A> {}
+
+impl<'a, T, const N: usize, A: ChunkAllocator> FusedIterator for ValuesPinMut<'a, T, N, A> {}
+
+impl<'a, T, const N: usize, A: ChunkAllocator> FusedIterator for VacantKeys<'a, T, N, A> {}
+
+impl<'a, T, const N: usize, A: ChunkAllocator> FusedIterator for ChunkSlices<'a, T, N, A> {}
+
+impl<'a, T: Unpin, const N: usize, A: ChunkAllocator> FusedIterator for Drain<'a, T, N, A> {}
+
+impl<'a, T, F, const N: usize, A: ChunkAllocator> FusedIterator for ExtractIf<'a, T, F, N, A>
+where
+    T: Unpin,
+    F: FnMut(usize, Pin<&mut T>) -> bool,
+{
+}
+
+impl<T: Unpin, const N: usize, A: ChunkAllocator> FusedIterator for IntoIter<T, N, A> {}
@@ -0,0 +1,279 @@
+//! A slab whose values are kept in a doubly linked list.
+
+use crate::list::KeyList;
+use crate::Slab;
+use core::fmt;
+use core::pin::Pin;
+
+/// A slab whose values are linked in a user-defined order, like a
+/// `LinkedList` whose nodes can be looked up by key.
+///
+/// The links are managed by the slab, so values don't need to hold pointers
+/// to each other, and every operation takes `O(1)` time. Values are pinned
+/// just like in a [`Slab`](struct.Slab.html), and relinking them never moves
+/// them.
+///
+/// # Examples
+///
+/// ```
+/// # use pinned_slab::*;
+/// let mut list = LinkedSlab::new();
+///
+/// let b = list.push_back("b");
+/// let a = list.push_front("a");
+/// let d = list.push_back("d");
+/// list.insert_after(b, "c");
+/// assert_eq!(list.iter_in_order().map(|(_, v)| *v).collect::<String>(), "abcd");
+///
+/// list.move_to_back(a);
+/// assert_eq!(list.unlink(d), Some("d"));
+/// assert_eq!(list.iter_in_order().map(|(_, v)| *v).collect::<String>(), "bca");
+/// ```
+pub struct LinkedSlab<T> {
+    slab: Slab<T>,
+    list: KeyList,
+}
+
+impl<T> LinkedSlab<T> {
+    /// Construct a new, empty `LinkedSlab`.
+    pub fn new() -> Self {
+        LinkedSlab {
+            slab: Slab::new(),
+            list: KeyList::new(),
+        }
+    }
+
+    /// Return the number of stored values.
+    pub fn len(&self) -> usize {
+        self.slab.len()
+    }
+
+    /// Return `true` if there are no values stored in the slab.
+    pub fn is_empty(&self) -> bool {
+        self.slab.is_empty()
+    }
+
+    /// Return `true` if a value is associated with the given key.
+    pub fn contains(&self, key: usize) -> bool {
+        self.slab.contains(key)
+    }
+
+    /// Return the key of the first value in the list.
+    pub fn front(&self) -> Option<usize> {
+        self.list.front()
+    }
+
+    /// Return the key of the last value in the list.
+    pub fn back(&self) -> Option<usize> {
+        self.list.back()
+    }
+
+    /// Return the key of the value after the given key in the list.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `key` is not associated with a value.
+    pub fn next(&self, key: usize) -> Option<usize> {
+        assert!(self.slab.contains(key), "invalid key");
+        self.list.next(key)
+    }
+
+    /// Return the key of the value before the given key in the list.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `key` is not associated with a value.
+    pub fn prev(&self, key: usize) -> Option<usize> {
+        assert!(self.slab.contains(key), "invalid key");
+        self.list.prev(key)
+    }
+
+    /// Insert a value at the front of the list, returning its key.
+    pub fn push_front(&mut self, val: T) -> usize {
+        let (key, _) = self.slab.insert(val);
+        self.list.push_front(key);
+        key
+    }
+
+    /// Insert a value at the back of the list, returning its key.
+    pub fn push_back(&mut self, val: T) -> usize {
+        let (key, _) = self.slab.insert(val);
+        self.list.push_back(key);
+        key
+    }
+
+    /// Insert a value right after the value of `at` in the list, returning
+    /// its key.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `at` is not associated with a value.
+    pub fn insert_after(&mut self, at: usize, val: T) -> usize {
+        assert!(self.slab.contains(at), "invalid key");
+        let (key, _) = self.slab.insert(val);
+        self.list.insert_after(at, key);
+        key
+    }
+
+    /// Insert a value right before the value of `at` in the list, returning
+    /// its key.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `at` is not associated with a value.
+    pub fn insert_before(&mut self, at: usize, val: T) -> usize {
+        assert!(self.slab.contains(at), "invalid key");
+        let (key, _) = self.slab.insert(val);
+        self.list.insert_before(at, key);
+        key
+    }
+
+    /// Move the value of the given key to the front of the list.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `key` is not associated with a value.
+    pub fn move_to_front(&mut self, key: usize) {
+        assert!(self.slab.contains(key), "invalid key");
+        self.list.unlink(key);
+        self.list.push_front(key);
+    }
+
+    /// Move the value of the given key to the back of the list.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `key` is not associated with a value.
+    pub fn move_to_back(&mut self, key: usize) {
+        assert!(self.slab.contains(key), "invalid key");
+        self.list.unlink(key);
+        self.list.push_back(key);
+    }
+
+    /// Unlink the value of the given key from the list and remove it from the
+    /// slab, or return `None` if there is no such value.
+    ///
+    /// The value is moved out, so it must be `Unpin`.
+    pub fn unlink(&mut self, key: usize) -> Option<T>
+    where
+        T: Unpin,
+    {
+        if !self.slab.contains(key) {
+            return None;
+        }
+        self.list.unlink(key);
+        Some(self.slab.remove(key))
+    }
+
+    /// Unlink the value of the given key from the list and drop it in place,
+    /// returning whether there was such a value.
+    pub fn unlink_in_place(&mut self, key: usize) -> bool {
+        if !self.slab.contains(key) {
+            return false;
+        }
+        self.list.unlink(key);
+        self.slab.remove_in_place(key)
+    }
+
+    /// Remove and return the first value in the list.
+    pub fn pop_front(&mut self) -> Option<T>
+    where
+        T: Unpin,
+    {
+        self.unlink(self.list.front()?)
+    }
+
+    /// Remove and return the last value in the list.
+    pub fn pop_back(&mut self) -> Option<T>
+    where
+        T: Unpin,
+    {
+        self.unlink(self.list.back()?)
+    }
+
+    /// Return a reference to the value associated with the given key.
+    pub fn get(&self, key: usize) -> Option<&T> {
+        self.slab.get(key)
+    }
+
+    /// Return a pinned mutable reference to the value associated with the
+    /// given key.
+    pub fn get_pin_mut(&mut self, key: usize) -> Option<Pin<&mut T>> {
+        self.slab.get_pin_mut(key)
+    }
+
+    /// Drop every value in the slab.
+    pub fn clear(&mut self) {
+        self.slab.clear();
+        self.list.clear();
+    }
+
+    /// Return an iterator over the keys and values in list order.
+    pub fn iter_in_order(&self) -> LinkedIter<'_, T> {
+        LinkedIter {
+            slab: self,
+            front: self.list.front(),
+            back: self.list.back(),
+            remaining: self.slab.len(),
+        }
+    }
+
+    /// Consume the `LinkedSlab`, returning the underlying `Slab`.
+    pub fn into_inner(self) -> Slab<T> {
+        self.slab
+    }
+}
+
+impl<T> Default for LinkedSlab<T> {
+    fn default() -> Self {
+        LinkedSlab::new()
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for LinkedSlab<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_map().entries(self.iter_in_order()).finish()
+    }
+}
+
+/// An iterator over the values of a `LinkedSlab` in list order.
+///
+/// See [`LinkedSlab::iter_in_order`](struct.LinkedSlab.html#method.iter_in_order).
+pub struct LinkedIter<'a, T> {
+    slab: &'a LinkedSlab<T>,
+    front: Option<usize>,
+    back: Option<usize>,
+    remaining: usize,
+}
+
+impl<'a, T> Iterator for LinkedIter<'a, T> {
+    type Item = (usize, &'a T);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.remaining == 0 {
+            return None;
+        }
+        let key = self.front?;
+        self.front = self.slab.list.next(key);
+        self.remaining -= 1;
+        Some((key, &self.slab.slab[key]))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+impl<'a, T> DoubleEndedIterator for LinkedIter<'a, T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.remaining == 0 {
+            return None;
+        }
+        let key = self.back?;
+        self.back = self.slab.list.prev(key);
+        self.remaining -= 1;
+        Some((key, &self.slab.slab[key]))
+    }
+}
+
+impl<'a, T> ExactSizeIterator for LinkedIter<'a, T> {}
@@ -0,0 +1,113 @@
+//! An intrusive doubly linked list over the keys of a slab.
+
+use crate::NIL;
+use alloc::vec::Vec;
+
+/// A doubly linked list of keys, with the links stored in a `Vec` indexed by
+/// key, so every operation takes `O(1)` time.
+///
+/// Each key may be in the list at most once.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct KeyList {
+    nodes: Vec<Node>,
+    head: usize,
+    tail: usize,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Node {
+    prev: usize,
+    next: usize,
+}
+
+impl KeyList {
+    pub fn new() -> Self {
+        KeyList {
+            nodes: Vec::new(),
+            head: NIL,
+            tail: NIL,
+        }
+    }
+
+    pub fn front(&self) -> Option<usize> {
+        some(self.head)
+    }
+
+    pub fn back(&self) -> Option<usize> {
+        some(self.tail)
+    }
+
+    /// Return the key after `key`, which must be in the list.
+    pub fn next(&self, key: usize) -> Option<usize> {
+        some(self.nodes[key].next)
+    }
+
+    /// Return the key before `key`, which must be in the list.
+    pub fn prev(&self, key: usize) -> Option<usize> {
+        some(self.nodes[key].prev)
+    }
+
+    pub fn push_front(&mut self, key: usize) {
+        self.link(key, NIL, self.head);
+    }
+
+    pub fn push_back(&mut self, key: usize) {
+        self.link(key, self.tail, NIL);
+    }
+
+    /// Insert `key` after `at`, which must be in the list.
+    pub fn insert_after(&mut self, at: usize, key: usize) {
+        self.link(key, at, self.nodes[at].next);
+    }
+
+    /// Insert `key` before `at`, which must be in the list.
+    pub fn insert_before(&mut self, at: usize, key: usize) {
+        self.link(key, self.nodes[at].prev, at);
+    }
+
+    /// Remove `key`, which must be in the list.
+    pub fn unlink(&mut self, key: usize) {
+        let Node { prev, next } = self.nodes[key];
+        match prev {
+            NIL => self.head = next,
+            prev => self.nodes[prev].next = next,
+        }
+        match next {
+            NIL => self.tail = prev,
+            next => self.nodes[next].prev = prev,
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.head = NIL;
+        self.tail = NIL;
+    }
+
+    fn link(&mut self, key: usize, prev: usize, next: usize) {
+        if key >= self.nodes.len() {
+            self.nodes.resize(
+                key + 1,
+                Node {
+                    prev: NIL,
+                    next: NIL,
+                },
+            );
+        }
+        self.nodes[key] = Node { prev, next };
+        match prev {
+            NIL => self.head = key,
+            prev => self.nodes[prev].next = key,
+        }
+        match next {
+            NIL => self.tail = key,
+            next => self.nodes[next].prev = key,
+        }
+    }
+}
+
+fn some(key: usize) -> Option<usize> {
+    match key {
+        NIL => None,
+        key => Some(key),
+    }
+}
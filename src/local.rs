@@ -0,0 +1,299 @@
+//! Thread-local slabs which share a global key space.
+
+use crate::Slab;
+use std::fmt;
+use std::marker::PhantomData;
+use std::mem;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, PoisonError, RwLock};
+
+/// The number of high bits of a key which hold the shard of the value.
+const SHARD_BITS: u32 = 16;
+
+/// The number of low bits of a key which hold the key within the shard.
+const LOCAL_BITS: u32 = usize::BITS - SHARD_BITS;
+
+/// The frees which other threads queued for a `LocalSlab`.
+#[derive(Default)]
+struct Shard {
+    alive: AtomicBool,
+    frees: Mutex<Vec<usize>>,
+}
+
+/// A registry of `LocalSlab`s whose keys can be resolved from any thread.
+///
+/// Each thread inserts into its own [`LocalSlab`](struct.LocalSlab.html)
+/// without taking any locks, e.g. one per core of an executor. The shard of a
+/// value is encoded in the high bits of its key, so any thread can remove a
+/// value through the registry: the free is queued and the owning thread drops
+/// the value the next time it inserts or calls
+/// [`LocalSlab::process_remote_frees`](struct.LocalSlab.html#method.process_remote_frees).
+///
+/// Cloning a `GlobalRegistry` returns another handle to the same registry.
+///
+/// # Examples
+///
+/// ```
+/// # use pinned_slab::*;
+/// use std::thread;
+///
+/// let registry = GlobalRegistry::new();
+/// let mut local = registry.local();
+/// let key = local.insert(String::from("task"));
+///
+/// let remote = registry.clone();
+/// thread::spawn(move || assert!(remote.remove(key))).join().unwrap();
+///
+/// assert!(local.contains(key));
+/// assert_eq!(local.process_remote_frees(), 1);
+/// assert!(!local.contains(key));
+/// ```
+pub struct GlobalRegistry<T> {
+    shards: Arc<RwLock<Vec<Arc<Shard>>>>,
+    _marker: PhantomData<fn() -> T>,
+}
+
+impl<T> GlobalRegistry<T> {
+    /// Construct a new registry without any shards.
+    pub fn new() -> Self {
+        GlobalRegistry {
+            shards: Arc::default(),
+            _marker: PhantomData,
+        }
+    }
+
+    /// Register a new, empty `LocalSlab` with its own shard.
+    ///
+    /// Shards are never reused, even after their `LocalSlab` is dropped.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the registry runs out of shards.
+    pub fn local(&self) -> LocalSlab<T> {
+        let shard = Arc::new(Shard {
+            alive: AtomicBool::new(true),
+            frees: Mutex::default(),
+        });
+        let mut shards = self.shards.write().unwrap_or_else(PoisonError::into_inner);
+        let id = shards.len();
+        assert!(id < 1 << SHARD_BITS, "too many shards");
+        shards.push(shard.clone());
+        LocalSlab {
+            registry: self.clone(),
+            id,
+            shard,
+            slab: Slab::new(),
+        }
+    }
+
+    /// Return the number of shards which were registered.
+    pub fn shard_count(&self) -> usize {
+        self.shards
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .len()
+    }
+
+    /// Return the shard of the value associated with the given key.
+    pub fn shard_of(key: usize) -> usize {
+        key >> LOCAL_BITS
+    }
+
+    /// Queue the value associated with the given key to be removed by the
+    /// thread which owns its shard.
+    ///
+    /// Return `false` if the shard doesn't exist or its `LocalSlab` was
+    /// dropped. Queuing a key twice, or queuing a key which is removed and
+    /// reused before the free is processed, removes whatever value is
+    /// associated with the key at that time.
+    pub fn remove(&self, key: usize) -> bool {
+        let shards = self.shards.read().unwrap_or_else(PoisonError::into_inner);
+        match shards.get(Self::shard_of(key)) {
+            Some(shard) if shard.alive.load(Ordering::Acquire) => {
+                shard.queue(key);
+                true
+            }
+            _ => false,
+        }
+    }
+}
+
+impl Shard {
+    fn queue(&self, key: usize) {
+        self.frees
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .push(key);
+    }
+}
+
+impl<T> Clone for GlobalRegistry<T> {
+    fn clone(&self) -> Self {
+        GlobalRegistry {
+            shards: self.shards.clone(),
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<T> Default for GlobalRegistry<T> {
+    fn default() -> Self {
+        GlobalRegistry::new()
+    }
+}
+
+impl<T> fmt::Debug for GlobalRegistry<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("GlobalRegistry")
+            .field("shards", &self.shard_count())
+            .finish()
+    }
+}
+
+/// The shard of a [`GlobalRegistry`](struct.GlobalRegistry.html) which is
+/// owned by one thread.
+///
+/// Values are pinned just like in a [`Slab`](struct.Slab.html), and keys are
+/// those of the registry, with the shard in their high bits.
+pub struct LocalSlab<T> {
+    registry: GlobalRegistry<T>,
+    id: usize,
+    shard: Arc<Shard>,
+    slab: Slab<T>,
+}
+
+impl<T> LocalSlab<T> {
+    /// Return the shard of the slab.
+    pub fn shard(&self) -> usize {
+        self.id
+    }
+
+    /// Return the registry of the slab.
+    pub fn registry(&self) -> &GlobalRegistry<T> {
+        &self.registry
+    }
+
+    /// Return the number of stored values, including values whose removal
+    /// was queued but not yet processed.
+    pub fn len(&self) -> usize {
+        self.slab.len()
+    }
+
+    /// Return `true` if there are no values stored in the slab.
+    pub fn is_empty(&self) -> bool {
+        self.slab.is_empty()
+    }
+
+    /// Return `true` if a value of this shard is associated with the given
+    /// key.
+    pub fn contains(&self, key: usize) -> bool {
+        self.local_key(key)
+            .is_some_and(|key| self.slab.contains(key))
+    }
+
+    /// Insert a value in the slab, returning the key assigned to the value.
+    ///
+    /// This processes the queued frees first, so their keys can be reused.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the key doesn't fit in the low bits of a `usize`.
+    pub fn insert(&mut self, val: T) -> usize {
+        self.process_remote_frees();
+        let key = self.slab.insert(val).0;
+        assert!(key < 1 << LOCAL_BITS, "key overflow");
+        self.id << LOCAL_BITS | key
+    }
+
+    /// Return a reference to the value associated with the given key, or
+    /// `None` if the key belongs to another shard or there is no such value.
+    pub fn get(&self, key: usize) -> Option<&T> {
+        self.slab.get(self.local_key(key)?)
+    }
+
+    /// Return a pinned mutable reference to the value associated with the
+    /// given key, or `None` if the key belongs to another shard or there is no
+    /// such value.
+    pub fn get_pin_mut(&mut self, key: usize) -> Option<Pin<&mut T>> {
+        let key = self.local_key(key)?;
+        self.slab.get_pin_mut(key)
+    }
+
+    /// Remove and return the value associated with the given key.
+    ///
+    /// If the key belongs to another shard, its removal is queued like with
+    /// [`GlobalRegistry::remove`](struct.GlobalRegistry.html#method.remove)
+    /// and `None` is returned. The value is moved out, so it must be `Unpin`.
+    pub fn remove(&mut self, key: usize) -> Option<T>
+    where
+        T: Unpin,
+    {
+        match self.local_key(key) {
+            Some(key) => self.slab.take(key),
+            None => {
+                self.registry.remove(key);
+                None
+            }
+        }
+    }
+
+    /// Drop the value associated with the given key in place, returning
+    /// whether there was such a value.
+    ///
+    /// If the key belongs to another shard, its removal is queued like with
+    /// [`remove`](#method.remove) and `false` is returned.
+    pub fn remove_in_place(&mut self, key: usize) -> bool {
+        match self.local_key(key) {
+            Some(key) => self.slab.remove_in_place(key),
+            None => {
+                self.registry.remove(key);
+                false
+            }
+        }
+    }
+
+    /// Drop the values whose removal was queued by other threads, returning
+    /// how many were dropped.
+    pub fn process_remote_frees(&mut self) -> usize {
+        let frees = mem::take(
+            &mut *self
+                .shard
+                .frees
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner),
+        );
+        let mut count = 0;
+        for key in frees {
+            let key = key & ((1 << LOCAL_BITS) - 1);
+            if self.slab.remove_in_place(key) {
+                count += 1;
+            }
+        }
+        count
+    }
+
+    /// Return the key within the slab, if `key` belongs to this shard.
+    fn local_key(&self, key: usize) -> Option<usize> {
+        if GlobalRegistry::<T>::shard_of(key) == self.id {
+            Some(key & ((1 << LOCAL_BITS) - 1))
+        } else {
+            None
+        }
+    }
+}
+
+impl<T> Drop for LocalSlab<T> {
+    fn drop(&mut self) {
+        self.shard.alive.store(false, Ordering::Release);
+    }
+}
+
+impl<T> fmt::Debug for LocalSlab<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("LocalSlab")
+            .field("shard", &self.id)
+            .field("len", &self.slab.len())
+            .finish()
+    }
+}
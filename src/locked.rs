@@ -0,0 +1,183 @@
+//! A `Slab` whose entries are locked individually.
+
+use crate::{Keys, Slab};
+use std::fmt;
+use std::ops;
+use std::pin::Pin;
+use std::sync::{PoisonError, RwLock, RwLockReadGuard, RwLockWriteGuard};
+
+/// A slab with a read-write lock around each value, so that threads can
+/// modify different values at the same time through a shared reference.
+///
+/// Inserting and removing values still needs a mutable reference. The
+/// locks themselves are never exposed, since locking one for writing would
+/// un-pin its value. Poisoning is ignored, so a panic while a value is locked
+/// doesn't make it inaccessible.
+///
+/// # Examples
+///
+/// ```
+/// # use pinned_slab::*;
+/// use std::sync::Arc;
+/// use std::thread;
+///
+/// let mut slab = LockedSlab::new();
+/// let keys: Vec<usize> = (0..4).map(|_| slab.insert(0)).collect();
+/// let slab = Arc::new(slab);
+///
+/// thread::scope(|s| {
+///     for &key in &keys {
+///         let slab = slab.clone();
+///         s.spawn(move || *slab.write(key).unwrap() += key);
+///     }
+/// });
+///
+/// assert_eq!(*slab.read(3).unwrap(), 3);
+/// ```
+pub struct LockedSlab<T> {
+    slab: Slab<RwLock<T>>,
+}
+
+impl<T> LockedSlab<T> {
+    /// Construct a new, empty `LockedSlab`.
+    ///
+    /// Like [`Slab::new`](struct.Slab.html#method.new), this does not
+    /// allocate.
+    pub fn new() -> Self {
+        LockedSlab { slab: Slab::new() }
+    }
+
+    /// Return the number of stored values.
+    pub fn len(&self) -> usize {
+        self.slab.len()
+    }
+
+    /// Return `true` if there are no values stored in the slab.
+    pub fn is_empty(&self) -> bool {
+        self.slab.is_empty()
+    }
+
+    /// Return `true` if a value is associated with the given key.
+    pub fn contains(&self, key: usize) -> bool {
+        self.slab.contains(key)
+    }
+
+    /// Return an iterator over the keys of the slab, in ascending order.
+    pub fn keys(&self) -> Keys<'_, RwLock<T>> {
+        self.slab.keys()
+    }
+
+    /// Insert a value in the slab, returning the key assigned to the value.
+    pub fn insert(&mut self, val: T) -> usize {
+        self.slab.insert(RwLock::new(val)).0
+    }
+
+    /// Remove and return the value associated with the given key.
+    ///
+    /// The value is moved out, so it must be `Unpin`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `key` is not associated with a value.
+    pub fn remove(&mut self, key: usize) -> T
+    where
+        T: Unpin,
+    {
+        self.slab
+            .remove(key)
+            .into_inner()
+            .unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Drop the value associated with the given key in place, returning
+    /// whether there was such a value.
+    pub fn remove_in_place(&mut self, key: usize) -> bool {
+        self.slab.remove_in_place(key)
+    }
+
+    /// Lock the value associated with the given key for reading, blocking
+    /// until no writer holds its lock.
+    ///
+    /// If the given key is not associated with a value, then `None` is
+    /// returned.
+    pub fn read(&self, key: usize) -> Option<RwLockReadGuard<'_, T>> {
+        let lock = self.slab.get(key)?;
+        Some(lock.read().unwrap_or_else(PoisonError::into_inner))
+    }
+
+    /// Lock the value associated with the given key for writing, blocking
+    /// until no other thread holds its lock.
+    ///
+    /// If the given key is not associated with a value, then `None` is
+    /// returned.
+    pub fn write(&self, key: usize) -> Option<LockedWriteGuard<'_, T>> {
+        let lock = self.slab.get(key)?;
+        Some(LockedWriteGuard {
+            guard: lock.write().unwrap_or_else(PoisonError::into_inner),
+        })
+    }
+
+    /// Consume the `LockedSlab`, returning the underlying `Slab`.
+    ///
+    /// The locks of the returned slab hand out `&mut T`, so the values must
+    /// be `Unpin`.
+    pub fn into_inner(self) -> Slab<RwLock<T>>
+    where
+        T: Unpin,
+    {
+        self.slab
+    }
+}
+
+impl<T> Default for LockedSlab<T> {
+    fn default() -> Self {
+        LockedSlab::new()
+    }
+}
+
+impl<T> fmt::Debug for LockedSlab<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("LockedSlab")
+            .field("len", &self.slab.len())
+            .finish()
+    }
+}
+
+/// A write lock on a value of a `LockedSlab`.
+///
+/// The value is pinned, so it can only be modified through
+/// [`as_pin_mut`](#method.as_pin_mut) unless it is `Unpin`.
+///
+/// See [`LockedSlab::write`](struct.LockedSlab.html#method.write).
+pub struct LockedWriteGuard<'a, T> {
+    guard: RwLockWriteGuard<'a, T>,
+}
+
+impl<'a, T> LockedWriteGuard<'a, T> {
+    /// Return the value as a pinned mutable reference.
+    pub fn as_pin_mut(&mut self) -> Pin<&mut T> {
+        // SAFETY: The lock never moves its value, and the lock itself is
+        // pinned in the slab.
+        unsafe { Pin::new_unchecked(&mut *self.guard) }
+    }
+}
+
+impl<'a, T> ops::Deref for LockedWriteGuard<'a, T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.guard
+    }
+}
+
+impl<'a, T: Unpin> ops::DerefMut for LockedWriteGuard<'a, T> {
+    fn deref_mut(&mut self) -> &mut T {
+        &mut self.guard
+    }
+}
+
+impl<'a, T: fmt::Debug> fmt::Debug for LockedWriteGuard<'a, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&**self, f)
+    }
+}
@@ -0,0 +1,144 @@
+//! Counters of the operations on a `Slab`, enabled by the `stats` feature.
+//!
+//! Without the feature, `Counters` is zero-sized and counting is a no-op, so
+//! the slab doesn't have to `cfg` out every call site.
+
+#[cfg(feature = "stats")]
+use crate::{ChunkAllocator, Slab};
+
+/// Counts of the operations on a `Slab` since it was created or its metrics
+/// were last reset.
+///
+/// See [`Slab::metrics`](struct.Slab.html#method.metrics).
+#[cfg(feature = "stats")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[non_exhaustive]
+pub struct SlabMetrics {
+    /// The number of inserted values.
+    pub inserts: u64,
+    /// The number of removed values, including values dropped by `clear`.
+    pub removes: u64,
+    /// The number of inserts which reused a key that was handed out before.
+    pub reuses: u64,
+    /// The number of chunks added to the slab.
+    pub chunk_allocations: u64,
+    /// The largest number of values stored at once.
+    pub peak_len: usize,
+}
+
+#[cfg(feature = "stats")]
+#[derive(Debug, Clone)]
+pub(crate) struct Counters(SlabMetrics);
+
+#[cfg(not(feature = "stats"))]
+#[derive(Debug, Clone)]
+pub(crate) struct Counters;
+
+impl Counters {
+    pub(crate) fn new() -> Self {
+        #[cfg(feature = "stats")]
+        return Counters(SlabMetrics::default());
+        #[cfg(not(feature = "stats"))]
+        return Counters;
+    }
+
+    /// Count an insert, after which the slab holds `len` values.
+    #[inline]
+    pub(crate) fn insert(&mut self, reused: bool, len: usize) {
+        #[cfg(feature = "stats")]
+        {
+            self.0.inserts += 1;
+            self.0.reuses += reused as u64;
+            self.0.peak_len = self.0.peak_len.max(len);
+        }
+        #[cfg(not(feature = "stats"))]
+        let _ = (reused, len);
+    }
+
+    /// Count `n` removes.
+    #[inline]
+    pub(crate) fn remove(&mut self, n: usize) {
+        #[cfg(feature = "stats")]
+        {
+            self.0.removes += n as u64;
+        }
+        #[cfg(not(feature = "stats"))]
+        let _ = n;
+    }
+
+    /// Uncount an insert and a remove which only moved a value to another
+    /// key.
+    #[inline]
+    pub(crate) fn relocate(&mut self) {
+        #[cfg(feature = "stats")]
+        {
+            self.0.inserts -= 1;
+            self.0.removes -= 1;
+        }
+    }
+
+    /// Count a chunk allocation.
+    #[inline]
+    pub(crate) fn chunk(&mut self) {
+        #[cfg(feature = "stats")]
+        {
+            self.0.chunk_allocations += 1;
+        }
+    }
+}
+
+#[cfg(feature = "stats")]
+impl<T, const N: usize, A: ChunkAllocator> Slab<T, N, A> {
+    /// Return the counts of the operations on the slab since it was created
+    /// or [`reset_metrics`](#method.reset_metrics) was last called.
+    ///
+    /// Requires the `stats` feature.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use pinned_slab::*;
+    /// let mut slab = Slab::new();
+    ///
+    /// let (a, _) = slab.insert("a");
+    /// slab.insert("b");
+    /// slab.remove(a);
+    /// slab.insert("c");
+    ///
+    /// let metrics = slab.metrics();
+    /// assert_eq!(metrics.inserts, 3);
+    /// assert_eq!(metrics.removes, 1);
+    /// assert_eq!(metrics.reuses, 1);
+    /// assert_eq!(metrics.chunk_allocations, 1);
+    /// assert_eq!(metrics.peak_len, 2);
+    /// ```
+    pub fn metrics(&self) -> SlabMetrics {
+        self.counters.0
+    }
+
+    /// Reset every count to zero, and the peak length to the current length.
+    ///
+    /// Requires the `stats` feature.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use pinned_slab::*;
+    /// let mut slab = Slab::new();
+    /// slab.insert_many(0..10);
+    ///
+    /// slab.reset_metrics();
+    /// slab.remove(3);
+    ///
+    /// let metrics = slab.metrics();
+    /// assert_eq!(metrics.inserts, 0);
+    /// assert_eq!(metrics.removes, 1);
+    /// assert_eq!(metrics.peak_len, 10);
+    /// ```
+    pub fn reset_metrics(&mut self) {
+        self.counters.0 = SlabMetrics {
+            peak_len: self.len,
+            ..SlabMetrics::default()
+        };
+    }
+}
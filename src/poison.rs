@@ -0,0 +1,110 @@
+//! Poisoning of vacated entries, enabled by the `poison` feature.
+//!
+//! With the feature, every removed value's entry is overwritten with
+//! `POISON` and the caller which removed it is remembered, so that using a
+//! stale key panics with the place the entry was freed. Without the feature
+//! nothing is recorded, and the panics only say the key is invalid.
+//!
+//! With the `zeroize` feature too, vacated entries are left zeroed instead of
+//! being overwritten with `POISON`, and only the caller is remembered.
+
+use crate::{Chunk, ChunkAllocator, Slab};
+#[cfg(all(feature = "poison", not(feature = "zeroize")))]
+use core::mem::{self, MaybeUninit};
+use core::panic::Location;
+
+/// The byte vacated entries are filled with.
+#[cfg(all(feature = "poison", not(feature = "zeroize")))]
+const POISON: u8 = 0xa5;
+
+impl<T, A: ChunkAllocator> Chunk<T, A> {
+    /// Overwrite the vacated entry at `entry_key` with `POISON`, unless it is
+    /// zeroed, and remember who vacated it.
+    #[track_caller]
+    #[inline]
+    pub fn poison(&mut self, entry_key: usize) {
+        #[cfg(feature = "poison")]
+        {
+            // The entry has just been zeroed by `wipe`, which must not be
+            // undone.
+            #[cfg(not(feature = "zeroize"))]
+            {
+                // SAFETY: The entry is vacant, so nothing refers to it anymore.
+                let entry = unsafe { &mut self.entries_mut()[entry_key] };
+                *entry = MaybeUninit::uninit();
+                // SAFETY: The entry is valid for `size_of::<T>()` bytes.
+                unsafe {
+                    (entry.as_mut_ptr() as *mut u8).write_bytes(POISON, mem::size_of::<T>());
+                }
+            }
+            self.freed_at[entry_key] = Some(Location::caller());
+        }
+        #[cfg(not(feature = "poison"))]
+        let _ = entry_key;
+    }
+
+    /// Return where the entry at `entry_key` was last vacated, if it is
+    /// vacant and that was recorded.
+    pub fn freed_at(&self, entry_key: usize) -> Option<&'static Location<'static>> {
+        #[cfg(feature = "poison")]
+        if !self.is_occupied(entry_key) {
+            return self.freed_at[entry_key];
+        }
+        let _ = entry_key;
+        None
+    }
+}
+
+impl<T, const N: usize, A: ChunkAllocator> Slab<T, N, A> {
+    /// Return where the value which was associated with `key` was removed,
+    /// or `None` if `key` is occupied or has never been vacated.
+    ///
+    /// Requires the `poison` feature, which also makes stale keys passed to
+    /// [`Index`](#impl-Index%3Cusize%3E-for-Slab%3CT%2C%20N%2C%20A%3E),
+    /// [`remove`](#method.remove) or the `get_unchecked` methods panic with
+    /// "slot freed at ..." and the place the value was removed, instead of
+    /// returning whatever was reinserted there or reading freed memory.
+    /// [`get`](#method.get) still returns `None` for stale keys.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use pinned_slab::*;
+    /// let mut slab = Slab::new();
+    ///
+    /// let (key, _) = slab.insert("hello");
+    /// assert_eq!(slab.freed_at(key), None);
+    ///
+    /// slab.remove(key);
+    /// assert_eq!(slab.freed_at(key).unwrap().line(), line!() - 1);
+    /// ```
+    #[cfg(feature = "poison")]
+    pub fn freed_at(&self, key: usize) -> Option<&'static Location<'static>> {
+        let (slab_key, entry_key) = self.growth.locate(key);
+        self.chunk(slab_key)?.freed_at(entry_key)
+    }
+
+    /// Panic because `key` isn't associated with a value.
+    #[cold]
+    #[track_caller]
+    pub(crate) fn invalid_key(&self, key: usize) -> ! {
+        let (slab_key, entry_key) = self.growth.locate(key);
+        match self
+            .chunk(slab_key)
+            .and_then(|chunk| chunk.freed_at(entry_key))
+        {
+            Some(location) => panic!("invalid key: slot {} freed at {}", key, location),
+            None => panic!("invalid key"),
+        }
+    }
+
+    /// Panic if `key` isn't associated with a value, when the `poison`
+    /// feature is enabled.
+    #[track_caller]
+    #[inline]
+    pub(crate) fn check_poison(&self, key: usize) {
+        if cfg!(feature = "poison") && !self.contains(key) {
+            self.invalid_key(key);
+        }
+    }
+}
@@ -0,0 +1,174 @@
+//! A pool of pinned objects which are reset and reused instead of dropped.
+
+use crate::Slab;
+use alloc::{string::String, vec::Vec};
+use core::fmt;
+use core::pin::Pin;
+
+/// Objects which can be reset to a blank state, so a [`Pool`](struct.Pool.html)
+/// can reuse them.
+pub trait Reset {
+    /// Reset the object, keeping any allocations it wants to reuse.
+    fn reset(self: Pin<&mut Self>);
+}
+
+impl Reset for String {
+    fn reset(self: Pin<&mut Self>) {
+        self.get_mut().clear();
+    }
+}
+
+impl<T: Unpin> Reset for Vec<T> {
+    fn reset(self: Pin<&mut Self>) {
+        self.get_mut().clear();
+    }
+}
+
+/// A pool of objects which are expensive to construct, like parsers or
+/// buffers.
+///
+/// [`release`](#method.release) resets an object in place instead of dropping
+/// it, and [`acquire`](#method.acquire) hands out a released object before
+/// constructing a new one. Objects are stored pinned in a
+/// [`Slab`](struct.Slab.html), so they never move, even while they are idle.
+///
+/// # Examples
+///
+/// ```
+/// # use pinned_slab::*;
+/// let mut pool: Pool<String> = Pool::new();
+///
+/// let (key, mut buf) = pool.acquire();
+/// buf.as_mut().get_mut().push_str("request");
+/// let ptr = &*buf as *const String;
+/// pool.release(key);
+///
+/// // The buffer is reused, already cleared.
+/// let (_, buf) = pool.acquire();
+/// assert_eq!(&*buf as *const String, ptr);
+/// assert!(buf.is_empty());
+/// assert!(buf.capacity() >= "request".len());
+/// ```
+pub struct Pool<T> {
+    objects: Slab<T>,
+    /// Whether the object at each key is idle.
+    idle: Vec<bool>,
+    /// The keys of the idle objects, most recently released last.
+    free: Vec<usize>,
+}
+
+impl<T: Reset> Pool<T> {
+    /// Construct a new, empty `Pool`.
+    pub fn new() -> Self {
+        Pool {
+            objects: Slab::new(),
+            idle: Vec::new(),
+            free: Vec::new(),
+        }
+    }
+
+    /// Return the number of acquired objects.
+    pub fn len(&self) -> usize {
+        self.objects.len() - self.free.len()
+    }
+
+    /// Return `true` if no objects are acquired.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Return the number of idle objects.
+    pub fn idle_count(&self) -> usize {
+        self.free.len()
+    }
+
+    /// Return `true` if the object at the given key is acquired.
+    pub fn contains(&self, key: usize) -> bool {
+        self.objects.contains(key) && !self.idle[key]
+    }
+
+    /// Acquire an idle object, or construct a new one with `Default`,
+    /// returning its key.
+    pub fn acquire(&mut self) -> (usize, Pin<&mut T>)
+    where
+        T: Default,
+    {
+        self.acquire_with(T::default)
+    }
+
+    /// Acquire an idle object, or construct a new one with `f`, returning its
+    /// key.
+    pub fn acquire_with<F>(&mut self, f: F) -> (usize, Pin<&mut T>)
+    where
+        F: FnOnce() -> T,
+    {
+        let key = match self.free.pop() {
+            Some(key) => key,
+            None => {
+                let (key, _) = self.objects.insert(f());
+                if key >= self.idle.len() {
+                    self.idle.resize(key + 1, false);
+                }
+                key
+            }
+        };
+        self.idle[key] = false;
+        (key, self.objects.get_pin_mut(key).unwrap())
+    }
+
+    /// Reset the object at the given key and keep it for the next
+    /// [`acquire`](#method.acquire).
+    ///
+    /// # Panics
+    ///
+    /// Panics if no object is acquired at `key`.
+    pub fn release(&mut self, key: usize) {
+        assert!(self.contains(key), "invalid key");
+        self.objects.get_pin_mut(key).unwrap().reset();
+        self.idle[key] = true;
+        self.free.push(key);
+    }
+
+    /// Return a reference to the acquired object at the given key, or `None`
+    /// if there is no such object.
+    pub fn get(&self, key: usize) -> Option<&T> {
+        if self.contains(key) {
+            self.objects.get(key)
+        } else {
+            None
+        }
+    }
+
+    /// Return a pinned mutable reference to the acquired object at the given
+    /// key, or `None` if there is no such object.
+    pub fn get_pin_mut(&mut self, key: usize) -> Option<Pin<&mut T>> {
+        if self.contains(key) {
+            self.objects.get_pin_mut(key)
+        } else {
+            None
+        }
+    }
+
+    /// Drop every idle object.
+    pub fn clear_idle(&mut self) {
+        for key in self.free.drain(..) {
+            self.objects.remove_in_place(key);
+            self.idle[key] = false;
+        }
+    }
+}
+
+impl<T: Reset> Default for Pool<T> {
+    fn default() -> Self {
+        Pool::new()
+    }
+}
+
+impl<T> fmt::Debug for Pool<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Pool")
+            .field("acquired", &(self.objects.len() - self.free.len()))
+            .field("idle", &self.free.len())
+            .finish()
+    }
+}
@@ -0,0 +1,55 @@
+//! Encoding a `Slab` with `postcard`, available behind the `postcard`
+//! feature.
+//!
+//! This goes through the `serde` implementations, which `postcard` writes
+//! densely: the number of values as a varint, followed by each key as a
+//! varint and its value, in ascending key order. Neither direction needs
+//! `std`, and encoding doesn't allocate.
+
+use crate::{ChunkAllocator, Slab};
+use ::serde::{Deserialize, Serialize};
+
+impl<T: Serialize, const N: usize, A: ChunkAllocator> Slab<T, N, A> {
+    /// Encode the slab with `postcard` into `buf`, returning the part of
+    /// `buf` which was written.
+    ///
+    /// Fails if `buf` is too small, or if a value fails to serialize.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use pinned_slab::*;
+    /// let mut slab = Slab::new();
+    /// slab.insert_many([10u8, 20, 30]);
+    /// slab.remove(1);
+    ///
+    /// let mut buf = [0; 16];
+    /// let bytes = slab.to_postcard(&mut buf).unwrap();
+    /// assert_eq!(bytes, [2, 0, 10, 2, 30]);
+    /// ```
+    pub fn to_postcard<'b>(&self, buf: &'b mut [u8]) -> ::postcard::Result<&'b mut [u8]> {
+        ::postcard::to_slice(self, buf)
+    }
+}
+
+impl<T, const N: usize, A: ChunkAllocator + Default> Slab<T, N, A> {
+    /// Decode a slab which was encoded with
+    /// [`to_postcard`](#method.to_postcard).
+    ///
+    /// The keys are kept, and fail to decode under the same conditions as
+    /// with `Deserialize`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use pinned_slab::*;
+    /// let slab = Slab::<u8>::from_postcard(&[2, 0, 10, 2, 30]).unwrap();
+    /// assert_eq!(slab.iter().collect::<Vec<_>>(), [(0, &10), (2, &30)]);
+    /// ```
+    pub fn from_postcard<'de>(bytes: &'de [u8]) -> ::postcard::Result<Self>
+    where
+        T: Deserialize<'de>,
+    {
+        ::postcard::from_bytes(bytes)
+    }
+}
@@ -0,0 +1,61 @@
+//! A `proptest` strategy for `Slab`, available behind the `proptest`
+//! feature.
+
+use crate::Slab;
+use ::proptest::collection::{self, SizeRange};
+use ::proptest::prelude::*;
+use ::proptest::sample::Index;
+
+#[derive(Debug, Clone)]
+enum Op<T> {
+    Insert(T),
+    Remove(Index),
+}
+
+/// Return a strategy which generates slabs by replaying a sequence of
+/// inserts with values from `values` and removes of occupied keys.
+///
+/// The number of operations is drawn from `ops`. About one in four
+/// operations is a remove, so the generated slabs have vacant keys on their
+/// free list like a slab which has been in use for a while. Failing cases
+/// shrink towards fewer operations and simpler values.
+///
+/// # Examples
+///
+/// ```
+/// # use pinned_slab::*;
+/// use proptest::prelude::*;
+///
+/// proptest!(|(slab in slab_strategy(any::<u32>(), 0..100))| {
+///     let keys: Vec<usize> = slab.keys().collect();
+///     prop_assert!(keys.windows(2).all(|w| w[0] < w[1]));
+/// });
+/// ```
+pub fn slab_strategy<S>(
+    values: S,
+    ops: impl Into<SizeRange>,
+) -> impl Strategy<Value = Slab<S::Value>>
+where
+    S: Strategy + 'static,
+{
+    let op = prop_oneof![
+        3 => values.prop_map(Op::Insert),
+        1 => any::<Index>().prop_map(Op::Remove),
+    ];
+    collection::vec(op, ops).prop_map(|ops| {
+        let mut slab = Slab::new();
+        for op in ops {
+            match op {
+                Op::Insert(val) => {
+                    slab.insert(val);
+                }
+                Op::Remove(index) if !slab.is_empty() => {
+                    let key = slab.keys().nth(index.index(slab.len())).unwrap();
+                    slab.remove_in_place(key);
+                }
+                Op::Remove(_) => {}
+            }
+        }
+        slab
+    })
+}
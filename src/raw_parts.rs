@@ -0,0 +1,246 @@
+//! Taking a `Slab` apart into its chunks and bookkeeping, and putting it back
+//! together.
+
+use crate::allocator::RawArray;
+use crate::growth::Growth;
+use crate::metrics::Counters;
+use crate::{Chunk, ChunkAllocator, Global, GrowthPolicy, KeyPolicy, Link, Slab};
+use alloc::boxed::Box;
+use alloc::vec::Vec;
+use core::mem::{self, MaybeUninit};
+use core::ptr::NonNull;
+
+/// The parts of a `Slab`, as returned by
+/// [`Slab::into_raw_parts`](struct.Slab.html#method.into_raw_parts).
+///
+/// This is the layout contract of the slab, which stays the same between
+/// versions with the same major version:
+///
+/// - Keys are split into chunks by `growth_policy` and the chunk size `N` of
+///   the slab. Chunk `i` holds the keys starting at the sum of the sizes of
+///   the chunks before it, and is `None` if it isn't allocated.
+/// - Keys which refer to nothing, e.g. the `head` and `tail` of an empty
+///   slab, are `usize::MAX`.
+/// - The occupied keys form a doubly-linked list through the
+///   [`RawLink`](struct.RawLink.html)s of their entries, sorted by key, from
+///   `head` to `tail`.
+/// - The vacant entries of each chunk which have been handed out before are
+///   the ones below its `touched` count. With `KeyPolicy::Lifo`, they form
+///   the free list through the `next` field of their links, starting at
+///   `next_key`. Every vacant key at or above `touched` is free.
+/// - Keys reserved with [`Slab::reserve_key`](struct.Slab.html#method.reserve_key)
+///   are vacant, below `touched`, and not on the free list.
+
+#[derive(Debug)]
+pub struct RawParts<T, A: ChunkAllocator = Global> {
+    /// The chunks, or `None` for chunks which aren't allocated.
+    pub chunks: Vec<Option<RawChunk<T, A>>>,
+    /// The growth policy of the slab.
+    pub growth_policy: GrowthPolicy,
+    /// The key policy of the slab.
+    pub key_policy: KeyPolicy,
+    /// The allocator of the slab, which allocates new chunks.
+    pub alloc: A,
+    /// The number of stored values.
+    pub len: usize,
+    /// The key the next value is inserted at.
+    pub next_key: usize,
+    /// The smallest occupied key.
+    pub head: usize,
+    /// The largest occupied key.
+    pub tail: usize,
+    /// The stamp of the last inserted value, which the stamps of the entries
+    /// don't exceed. See [`Handle`](struct.Handle.html).
+    pub stamp: u64,
+}
+
+/// A chunk of a [`RawParts`](struct.RawParts.html).
+#[derive(Debug)]
+pub struct RawChunk<T, A: ChunkAllocator = Global> {
+    /// The entries, allocated with `alloc` and `Layout::array::<T>(size)`.
+    /// Only the occupied entries are initialized. This is dangling if the
+    /// layout is empty.
+    pub entries: NonNull<MaybeUninit<T>>,
+    /// The number of entries.
+    pub size: usize,
+    /// The allocator of `entries`.
+    pub alloc: A,
+    /// The number of occupied entries.
+    pub len: usize,
+    /// The number of entries at the start of `entries` which have been handed
+    /// out.
+    pub touched: usize,
+    /// A bitmap of the occupied entries: entry `j` is occupied if bit
+    /// `j % 64` of word `j / 64` is set. Bits past `size` are zero.
+    pub occupied: Box<[u64]>,
+    /// A bitmap of the reserved entries, like `occupied`. The stamp of a
+    /// reserved entry is the stamp of its reservation.
+    pub reserved: Box<[u64]>,
+    /// The link of each entry.
+    pub links: Box<[RawLink]>,
+    /// The stamp of each entry.
+    pub stamps: Box<[u64]>,
+    /// The tag of each entry. See [`Slab::tag`](struct.Slab.html#method.tag).
+    pub tags: Box<[u32]>,
+}
+
+/// The link of an entry of a [`RawChunk`](struct.RawChunk.html).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(C)]
+pub struct RawLink {
+    /// The previous occupied key, if the entry is occupied.
+    pub prev: usize,
+    /// The next occupied key if the entry is occupied, or the next key of the
+    /// free list if it is vacant.
+    pub next: usize,
+}
+
+impl<T, const N: usize, A: ChunkAllocator> Slab<T, N, A> {
+    /// Take the slab apart into its chunks and bookkeeping, without dropping
+    /// any values or freeing any memory.
+    ///
+    /// See [`RawParts`](struct.RawParts.html) for the layout. The name and
+    /// the metrics of the slab aren't included.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use pinned_slab::*;
+    /// let mut slab: Slab<u32, 64> = Slab::with_chunk_size();
+    /// slab.insert_many(0..100);
+    /// slab.remove(3);
+    ///
+    /// let parts = slab.into_raw_parts();
+    /// assert_eq!(parts.chunks.len(), 2);
+    /// assert_eq!(parts.len, 99);
+    /// assert_eq!(parts.next_key, 3);
+    ///
+    /// // SAFETY: The parts come from a slab with the same chunk size.
+    /// let slab: Slab<u32, 64> = unsafe { Slab::from_raw_parts(parts) };
+    /// assert_eq!(slab[99], 99);
+    /// ```
+    pub fn into_raw_parts(self) -> RawParts<T, A> {
+        let mut slab = mem::ManuallyDrop::new(self);
+        let chunks = mem::take(&mut slab.chunks)
+            .into_iter()
+            .map(|chunk| chunk.map(Chunk::into_raw_chunk))
+            .collect();
+        let growth_policy = slab.growth_policy();
+        // SAFETY: Every other field is `Copy` or has been taken, and `slab`
+        // is never used or dropped again.
+        let alloc = unsafe { core::ptr::read(&slab.alloc) };
+
+        RawParts {
+            chunks,
+            growth_policy,
+            key_policy: slab.key_policy,
+            alloc,
+            len: slab.len,
+            next_key: slab.next,
+            head: slab.head,
+            tail: slab.tail,
+            stamp: slab.stamp,
+        }
+    }
+
+    /// Put a slab taken apart with
+    /// [`into_raw_parts`](#method.into_raw_parts) back together.
+    ///
+    /// In builds with `debug_assertions`, the parts are checked with
+    /// [`assert_invariants`](#method.assert_invariants).
+    ///
+    /// # Safety
+    ///
+    /// The parts must come from `into_raw_parts` of a slab with the same `T`,
+    /// chunk size `N` and allocator type, or follow the layout contract of
+    /// [`RawParts`](struct.RawParts.html) for them. In particular, every
+    /// occupied entry must be initialized, every chunk must have been
+    /// allocated with its allocator, and values which were pinned must not
+    /// have moved.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the growth policy is invalid for `N`, or in builds with
+    /// `debug_assertions` if the parts are inconsistent.
+    pub unsafe fn from_raw_parts(parts: RawParts<T, A>) -> Self {
+        let chunks: Vec<_> = parts
+            .chunks
+            .into_iter()
+            .map(|chunk| chunk.map(|chunk| Chunk::from_raw_chunk(chunk)))
+            .collect();
+        let reserved = chunks
+            .iter()
+            .flatten()
+            .flat_map(|chunk: &Chunk<T, A>| chunk.reserved.iter())
+            .map(|word| word.count_ones() as usize)
+            .sum();
+        let mut slab = Slab {
+            chunks,
+            growth: Growth::new(parts.growth_policy),
+            key_policy: parts.key_policy,
+            alloc: parts.alloc,
+            len: parts.len,
+            next: parts.next_key,
+            head: parts.head,
+            tail: parts.tail,
+            issued: 0,
+            stamp: parts.stamp,
+            reserved,
+            counters: Counters::new(),
+            name: None,
+        };
+        // The parts don't record which keys have been handed out, so assume
+        // it was every key up to the last touched entry.
+        slab.issued = slab.touched_end();
+        if slab.key_policy == KeyPolicy::Monotonic {
+            slab.issued = slab.issued.max(slab.next);
+        }
+        if cfg!(debug_assertions) {
+            slab.assert_invariants();
+        }
+        slab
+    }
+}
+
+impl<T, A: ChunkAllocator> Chunk<T, A> {
+    fn into_raw_chunk(self) -> RawChunk<T, A> {
+        let mut chunk = mem::ManuallyDrop::new(self);
+        // SAFETY: Every field is read out exactly once, and `chunk` is never
+        // used or dropped again.
+        let (entries, size, alloc) = unsafe { core::ptr::read(&chunk.entries) }.into_raw_parts();
+        let links = mem::take(&mut chunk.links);
+        #[cfg(feature = "poison")]
+        drop(mem::take(&mut chunk.freed_at));
+
+        RawChunk {
+            entries,
+            size,
+            alloc,
+            len: chunk.len,
+            touched: chunk.touched,
+            occupied: mem::take(&mut chunk.occupied),
+            reserved: mem::take(&mut chunk.reserved),
+            // SAFETY: `Link` and `RawLink` are both `repr(C)` structs of two
+            // `usize`s.
+            links: unsafe { Box::from_raw(Box::into_raw(links) as *mut [RawLink]) },
+            stamps: mem::take(&mut chunk.stamps),
+            tags: mem::take(&mut chunk.tags),
+        }
+    }
+
+    /// See `Slab::from_raw_parts`.
+    unsafe fn from_raw_chunk(chunk: RawChunk<T, A>) -> Self {
+        Chunk {
+            entries: RawArray::from_raw_parts(chunk.entries, chunk.size, chunk.alloc),
+            touched: chunk.touched,
+            occupied: chunk.occupied,
+            reserved: chunk.reserved,
+            links: Box::from_raw(Box::into_raw(chunk.links) as *mut [Link]),
+            stamps: chunk.stamps,
+            tags: chunk.tags,
+            len: chunk.len,
+            #[cfg(feature = "poison")]
+            freed_at: alloc::vec![None; chunk.size].into_boxed_slice(),
+        }
+    }
+}
@@ -0,0 +1,204 @@
+//! Parallel iterators for `Slab`, available behind the `rayon` feature.
+//!
+//! Work is split at chunk boundaries, so each chunk is visited by a single
+//! thread.
+
+use crate::growth::Growth;
+use crate::{Chunk, ChunkAllocator, Global, IterMut, IterPinMut, Slab, DEFAULT_CHUNK_SIZE};
+use ::rayon::iter::plumbing::UnindexedConsumer;
+use ::rayon::prelude::*;
+use std::pin::Pin;
+
+/// A parallel iterator over the values stored in the `Slab`
+///
+/// See [`Slab::par_iter`](../struct.Slab.html#method.par_iter).
+pub struct ParIter<'a, T: 'a, const N: usize = DEFAULT_CHUNK_SIZE, A: ChunkAllocator = Global> {
+    slab: &'a Slab<T, N, A>,
+}
+
+/// A parallel iterator over pinned mutable references to the values stored in
+/// the `Slab`
+///
+/// See [`Slab::par_iter_pin_mut`](../struct.Slab.html#method.par_iter_pin_mut).
+pub struct ParIterPinMut<'a, T: 'a, const N: usize = DEFAULT_CHUNK_SIZE, A: ChunkAllocator = Global>
+{
+    chunks: &'a mut [Option<Chunk<T, A>>],
+    growth: Growth<N>,
+}
+
+impl<T: Sync, const N: usize, A: ChunkAllocator + Sync> Slab<T, N, A> {
+    /// Return a parallel iterator over the slab.
+    ///
+    /// This is also available through rayon's `par_iter`, since `&Slab<T>`
+    /// implements `IntoParallelIterator`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use pinned_slab::*;
+    /// use rayon::prelude::*;
+    ///
+    /// let mut slab = Slab::new();
+    ///
+    /// for i in 0..10_000 {
+    ///     slab.insert(i);
+    /// }
+    ///
+    /// let sum: usize = slab.par_iter().map(|(_, val)| *val).sum();
+    /// assert_eq!(sum, (0..10_000).sum());
+    /// ```
+    pub fn par_iter(&self) -> ParIter<'_, T, N, A> {
+        ParIter { slab: self }
+    }
+}
+
+impl<T: Send, const N: usize, A: ChunkAllocator + Send> Slab<T, N, A> {
+    /// Return a parallel iterator that yields pinned mutable references to
+    /// each value.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use pinned_slab::*;
+    /// use rayon::prelude::*;
+    ///
+    /// let mut slab = Slab::new();
+    ///
+    /// for i in 0..10_000 {
+    ///     slab.insert(i);
+    /// }
+    ///
+    /// slab.par_iter_pin_mut().for_each(|(key, mut val)| *val += key);
+    ///
+    /// assert!(slab.iter().all(|(key, val)| *val == 2 * key));
+    /// ```
+    pub fn par_iter_pin_mut(&mut self) -> ParIterPinMut<'_, T, N, A> {
+        ParIterPinMut {
+            chunks: &mut self.chunks,
+            growth: self.growth,
+        }
+    }
+
+    /// Retain only the elements specified by the predicate, evaluating the
+    /// predicate in parallel.
+    ///
+    /// The predicate is called for every entry in parallel first, and the
+    /// rejected entries are removed afterwards on the calling thread.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use pinned_slab::*;
+    /// let mut slab = Slab::new();
+    ///
+    /// for i in 0..10_000 {
+    ///     slab.insert(i);
+    /// }
+    ///
+    /// slab.par_retain(|_, val| *val % 2 == 0);
+    ///
+    /// assert_eq!(slab.len(), 5_000);
+    /// assert!(slab.values().all(|val| val % 2 == 0));
+    /// ```
+    pub fn par_retain<F>(&mut self, f: F)
+    where
+        F: Fn(usize, Pin<&mut T>) -> bool + Sync + Send,
+    {
+        let rejected: Vec<usize> = self
+            .par_iter_pin_mut()
+            .filter_map(|(key, val)| if f(key, val) { None } else { Some(key) })
+            .collect();
+
+        for key in rejected {
+            self.remove_in_place(key);
+        }
+    }
+
+    /// Insert every value produced by a parallel iterator, returning the keys
+    /// assigned to the values in the order the iterator produced them.
+    ///
+    /// The values are collected in parallel, then enough chunks are allocated
+    /// up front to hold all of them before they are inserted.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use pinned_slab::*;
+    /// use rayon::prelude::*;
+    ///
+    /// let mut slab = Slab::new();
+    ///
+    /// let keys = slab.par_extend((0..10_000).into_par_iter().map(|i| i * 2));
+    ///
+    /// assert_eq!(keys.len(), 10_000);
+    /// assert_eq!(slab[keys[42]], 84);
+    /// ```
+    pub fn par_extend<I>(&mut self, par_iter: I) -> Vec<usize>
+    where
+        I: IntoParallelIterator<Item = T>,
+    {
+        let vals: Vec<T> = par_iter.into_par_iter().collect();
+
+        self.reserve(vals.len());
+        vals.into_iter().map(|val| self.insert(val).0).collect()
+    }
+}
+
+impl<'a, T: Sync, const N: usize, A: ChunkAllocator + Sync> IntoParallelIterator
+    for &'a Slab<T, N, A>
+{
+    type Item = (usize, &'a T);
+    type Iter = ParIter<'a, T, N, A>;
+
+    fn into_par_iter(self) -> ParIter<'a, T, N, A> {
+        self.par_iter()
+    }
+}
+
+impl<'a, T: Send, const N: usize, A: ChunkAllocator + Send> IntoParallelIterator
+    for &'a mut Slab<T, N, A>
+{
+    type Item = (usize, Pin<&'a mut T>);
+    type Iter = ParIterPinMut<'a, T, N, A>;
+
+    fn into_par_iter(self) -> ParIterPinMut<'a, T, N, A> {
+        self.par_iter_pin_mut()
+    }
+}
+
+impl<'a, T: Sync, const N: usize, A: ChunkAllocator + Sync> ParallelIterator
+    for ParIter<'a, T, N, A>
+{
+    type Item = (usize, &'a T);
+
+    fn drive_unindexed<C>(self, consumer: C) -> C::Result
+    where
+        C: UnindexedConsumer<Self::Item>,
+    {
+        let slab = self.slab;
+        (0..slab.chunk_count())
+            .into_par_iter()
+            .flat_map_iter(move |index| slab.iter_chunk(index))
+            .drive_unindexed(consumer)
+    }
+}
+
+impl<'a, T: Send, const N: usize, A: ChunkAllocator + Send> ParallelIterator
+    for ParIterPinMut<'a, T, N, A>
+{
+    type Item = (usize, Pin<&'a mut T>);
+
+    fn drive_unindexed<C>(self, consumer: C) -> C::Result
+    where
+        C: UnindexedConsumer<Self::Item>,
+    {
+        let growth = self.growth;
+        self.chunks
+            .par_iter_mut()
+            .enumerate()
+            .flat_map_iter(move |(index, slot)| IterPinMut {
+                inner: IterMut::for_chunk(growth, index, slot),
+            })
+            .drive_unindexed(consumer)
+    }
+}
@@ -0,0 +1,188 @@
+//! Reference-counted handles to values stored in an `AtomicSlab`.
+
+use crate::AtomicSlab;
+use std::fmt;
+use std::ops;
+use std::pin::Pin;
+use std::sync::Arc;
+
+/// A handle like `Arc<T>`, whose value is stored in an
+/// [`AtomicSlab`](struct.AtomicSlab.html) instead of its own allocation.
+///
+/// The value is dropped and its slot reused once the last `SlabArc` to it is
+/// dropped. Values never move, so they can be handed out pinned. Like `Arc`,
+/// the methods of `SlabArc` are associated functions, so they don't shadow
+/// the methods of `T`.
+///
+/// # Examples
+///
+/// ```
+/// # use pinned_slab::*;
+/// use std::sync::Arc;
+///
+/// let slab = Arc::new(AtomicSlab::new());
+///
+/// let a = slab.insert_arc(String::from("hello"));
+/// let b = a.clone();
+/// assert_eq!(SlabArc::strong_count(&a), 2);
+/// assert_eq!(slab.len(), 1);
+///
+/// drop(a);
+/// assert_eq!(*b, "hello");
+/// drop(b);
+/// assert!(slab.is_empty());
+/// ```
+pub struct SlabArc<T> {
+    slab: Arc<AtomicSlab<T>>,
+    key: usize,
+}
+
+/// A weak handle to a value of a `SlabArc`, which doesn't keep the value
+/// alive.
+///
+/// See [`SlabArc::downgrade`](struct.SlabArc.html#method.downgrade).
+pub struct SlabWeak<T> {
+    slab: Arc<AtomicSlab<T>>,
+    key: usize,
+    generation: u32,
+}
+
+impl<T> AtomicSlab<T> {
+    /// Insert a value in the slab, returning the first handle to it.
+    ///
+    /// The value isn't owned by the slab itself, so it can't be looked up by
+    /// key or removed. See [`SlabArc`](struct.SlabArc.html).
+    pub fn insert_arc(self: &Arc<Self>, val: T) -> SlabArc<T> {
+        let (key, _) = self.insert_unowned(val);
+        SlabArc {
+            slab: self.clone(),
+            key,
+        }
+    }
+}
+
+impl<T> SlabArc<T> {
+    /// Return the key of the value in its slab.
+    pub fn key(this: &Self) -> usize {
+        this.key
+    }
+
+    /// Return the slab the value is stored in.
+    pub fn slab(this: &Self) -> &Arc<AtomicSlab<T>> {
+        &this.slab
+    }
+
+    /// Return the value as a pinned reference.
+    pub fn as_pin(this: &Self) -> Pin<&T> {
+        // SAFETY: Values never move until they are dropped.
+        unsafe { Pin::new_unchecked(&**this) }
+    }
+
+    /// Return the number of handles to the value.
+    pub fn strong_count(this: &Self) -> usize {
+        this.slab.ref_count(this.key)
+    }
+
+    /// Return `true` if both handles point to the same value.
+    pub fn ptr_eq(this: &Self, other: &Self) -> bool {
+        Arc::ptr_eq(&this.slab, &other.slab) && this.key == other.key
+    }
+
+    /// Create a weak handle to the value.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use pinned_slab::*;
+    /// use std::sync::Arc;
+    ///
+    /// let slab = Arc::new(AtomicSlab::new());
+    /// let strong = slab.insert_arc(5);
+    /// let weak = SlabArc::downgrade(&strong);
+    ///
+    /// assert_eq!(weak.upgrade().map(|val| *val), Some(5));
+    ///
+    /// drop(strong);
+    /// assert!(weak.upgrade().is_none());
+    ///
+    /// // The slot is reused, but the weak handle still doesn't upgrade.
+    /// let other = slab.insert_arc(6);
+    /// assert_eq!(SlabArc::key(&other), weak.key());
+    /// assert!(weak.upgrade().is_none());
+    /// ```
+    pub fn downgrade(this: &Self) -> SlabWeak<T> {
+        // The generation can't change while `this` keeps the value alive.
+        let generation = this.slab.generation(this.key);
+        SlabWeak {
+            slab: this.slab.clone(),
+            key: this.key,
+            generation,
+        }
+    }
+}
+
+impl<T> SlabWeak<T> {
+    /// Return the key the value had in its slab.
+    pub fn key(&self) -> usize {
+        self.key
+    }
+
+    /// Return a strong handle to the value, or `None` if it has been dropped.
+    pub fn upgrade(&self) -> Option<SlabArc<T>> {
+        if self.slab.acquire_generation(self.key, self.generation) {
+            Some(SlabArc {
+                slab: self.slab.clone(),
+                key: self.key,
+            })
+        } else {
+            None
+        }
+    }
+}
+
+impl<T> ops::Deref for SlabArc<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        // SAFETY: The handle keeps the value alive.
+        unsafe { self.slab.value(self.key) }
+    }
+}
+
+impl<T> Clone for SlabArc<T> {
+    fn clone(&self) -> Self {
+        self.slab.retain(self.key);
+        SlabArc {
+            slab: self.slab.clone(),
+            key: self.key,
+        }
+    }
+}
+
+impl<T> Drop for SlabArc<T> {
+    fn drop(&mut self) {
+        self.slab.release(self.key);
+    }
+}
+
+impl<T> Clone for SlabWeak<T> {
+    fn clone(&self) -> Self {
+        SlabWeak {
+            slab: self.slab.clone(),
+            key: self.key,
+            generation: self.generation,
+        }
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for SlabArc<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&**self, f)
+    }
+}
+
+impl<T> fmt::Debug for SlabWeak<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("(SlabWeak)")
+    }
+}
@@ -0,0 +1,151 @@
+//! Keys which are handed out before their value exists.
+
+use crate::{ChunkAllocator, Slab};
+use core::mem::MaybeUninit;
+use core::pin::Pin;
+
+/// A key taken off the free list of a slab before a value is stored under it.
+///
+/// Until the reservation is passed to
+/// [`Slab::fulfill`](struct.Slab.html#method.fulfill) or
+/// [`Slab::cancel`](struct.Slab.html#method.cancel), the key is neither
+/// occupied nor handed out to other values. Dropping a `ReservedKey` keeps
+/// the key reserved until the slab is cleared.
+///
+/// # Examples
+///
+/// ```
+/// # use pinned_slab::*;
+/// let mut slab = Slab::new();
+///
+/// let reserved = slab.reserve_key();
+/// let key = reserved.key();
+/// assert!(slab.is_reserved(key));
+/// assert!(!slab.contains(key));
+/// assert_ne!(slab.insert("other").0, key);
+///
+/// slab.fulfill(reserved, "hello");
+/// assert_eq!(slab[key], "hello");
+/// ```
+#[derive(Debug, PartialEq, Eq, Hash)]
+#[must_use = "the key stays reserved unless it is fulfilled or cancelled"]
+pub struct ReservedKey {
+    key: usize,
+    stamp: u64,
+}
+
+impl ReservedKey {
+    /// Return the reserved key.
+    pub fn key(&self) -> usize {
+        self.key
+    }
+}
+
+impl<T, const N: usize, A: ChunkAllocator> Slab<T, N, A> {
+    /// Reserve the key the next value would be inserted at, without storing
+    /// a value yet.
+    ///
+    /// The key isn't associated with a value, so [`contains`](#method.contains)
+    /// returns `false` and iterators skip it, but it isn't handed out to other
+    /// values either until the reservation is cancelled. Additional capacity
+    /// is allocated if needed, like for [`insert`](#method.insert).
+    /// [`clear`](#method.clear) releases every reserved key.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use pinned_slab::*;
+    /// let mut slab: Slab<&str> = Slab::new();
+    ///
+    /// let reserved = slab.reserve_key();
+    /// assert_eq!(reserved.key(), 0);
+    /// assert_eq!(slab.next_key(), 1);
+    ///
+    /// slab.cancel(reserved);
+    /// assert_eq!(slab.next_key(), 0);
+    /// ```
+    pub fn reserve_key(&mut self) -> ReservedKey {
+        let key = self.next;
+        let (slab_key, entry_key) = self.allocate_key(key);
+        let chunk = self.chunks[slab_key].as_mut().unwrap();
+
+        self.stamp += 1;
+        chunk.stamps[entry_key] = self.stamp;
+        chunk.set_reserved(entry_key, true);
+        self.reserved += 1;
+        self.advance_next(key);
+
+        ReservedKey {
+            key,
+            stamp: self.stamp,
+        }
+    }
+
+    /// Return `true` if the given key is reserved by
+    /// [`reserve_key`](#method.reserve_key).
+    pub fn is_reserved(&self, key: usize) -> bool {
+        let (slab_key, entry_key) = self.growth.locate(key);
+        self.chunk(slab_key)
+            .is_some_and(|chunk| chunk.is_reserved(entry_key))
+    }
+
+    /// Store a value under a reserved key, returning a pinned mutable
+    /// reference to it.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the key isn't reserved anymore, e.g. because the slab was
+    /// cleared, or was reserved in a different slab.
+    pub fn fulfill(&mut self, reserved: ReservedKey, val: T) -> Pin<&mut T> {
+        let key = reserved.key;
+        self.check_reserved(&reserved);
+
+        let (slab_key, entry_key) = self.growth.locate(key);
+        let chunk = self.chunks[slab_key].as_mut().unwrap();
+        chunk.set_reserved(entry_key, false);
+        // SAFETY: The entry is vacant, so this doesn't move any values.
+        unsafe { chunk.entries_mut()[entry_key] = MaybeUninit::new(val) };
+        chunk.set_occupied(entry_key, true);
+        chunk.tags[entry_key] = 0;
+        chunk.len += 1;
+
+        self.reserved -= 1;
+        self.len += 1;
+        self.counters.insert(false, self.len);
+        self.link(key);
+
+        // SAFETY: The value was just stored, and it is never moved out of the
+        // slab while it is occupied.
+        unsafe { self.get_unchecked_pin_mut(key) }
+    }
+
+    /// Release a reserved key without storing a value, making it available
+    /// again according to the key policy.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the key isn't reserved anymore, e.g. because the slab was
+    /// cleared, or was reserved in a different slab.
+    pub fn cancel(&mut self, reserved: ReservedKey) {
+        let key = reserved.key;
+        self.check_reserved(&reserved);
+
+        let (slab_key, entry_key) = self.growth.locate(key);
+        self.chunks[slab_key]
+            .as_mut()
+            .unwrap()
+            .set_reserved(entry_key, false);
+        self.reserved -= 1;
+        self.free_key(key);
+    }
+
+    /// Panic unless `reserved` still reserves its key in this slab.
+    #[track_caller]
+    fn check_reserved(&self, reserved: &ReservedKey) {
+        let (slab_key, entry_key) = self.growth.locate(reserved.key);
+        let valid = self.chunk(slab_key).is_some_and(|chunk| {
+            chunk.is_reserved(entry_key) && chunk.stamps[entry_key] == reserved.stamp
+        });
+        assert!(valid, "key {} is not reserved", reserved.key);
+    }
+}
@@ -0,0 +1,239 @@
+//! Zero-copy archiving for `Slab` with [`rkyv`](https://docs.rs/rkyv),
+//! available behind the `rkyv` feature.
+//!
+//! A slab is archived in a canonical dense form: the occupied keys in
+//! ascending order, and the values in the same order. The archive doesn't
+//! depend on the chunk size, growth policy or allocator of the slab.
+
+use crate::{is_plausible_key, ChunkAllocator, Slab};
+use ::rkyv::bytecheck::CheckBytes;
+use ::rkyv::ser::{ScratchSpace, Serializer};
+use ::rkyv::validation::ArchiveContext;
+use ::rkyv::vec::{ArchivedVec, VecResolver};
+use ::rkyv::{
+    out_field, Archive, Archived, Deserialize, Fallible, FixedUsize, Infallible, Serialize,
+};
+use alloc::boxed::Box;
+use core::convert::TryFrom;
+use core::error::Error;
+use core::fmt;
+use core::ptr;
+
+/// The archived form of a [`Slab`](struct.Slab.html).
+///
+/// Values can be looked up by key in `O(log len)` time without deserializing
+/// the slab.
+///
+/// # Examples
+///
+/// ```
+/// # use pinned_slab::*;
+/// use rkyv::{Deserialize, Infallible};
+///
+/// let mut slab: Slab<u32> = Slab::new();
+/// slab.insert_many(0..10);
+/// slab.remove(3);
+///
+/// let bytes = rkyv::to_bytes::<_, 256>(&slab).unwrap();
+/// let archived = rkyv::check_archived_root::<Slab<u32>>(&bytes).unwrap();
+///
+/// assert_eq!(archived.len(), 9);
+/// assert_eq!(archived.get(4), Some(&4));
+/// assert_eq!(archived.get(3), None);
+///
+/// let mut slab: Slab<u32> = archived.deserialize(&mut Infallible).unwrap();
+/// assert_eq!(slab[9], 9);
+/// assert_eq!(slab.insert(3).0, 3);
+/// ```
+#[repr(C)]
+pub struct ArchivedSlab<T> {
+    keys: ArchivedVec<Archived<usize>>,
+    values: ArchivedVec<T>,
+}
+
+impl<T> ArchivedSlab<T> {
+    /// Return the number of stored values.
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    /// Return `true` if there are no values stored in the slab.
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    /// Return `true` if a value is associated with the given key.
+    pub fn contains(&self, key: usize) -> bool {
+        self.index(key).is_some()
+    }
+
+    /// Return a reference to the value associated with the given key.
+    pub fn get(&self, key: usize) -> Option<&T> {
+        Some(&self.values[self.index(key)?])
+    }
+
+    /// Return an iterator over the keys and values of the slab, in ascending
+    /// key order.
+    pub fn iter(&self) -> impl ExactSizeIterator<Item = (usize, &T)> + '_ {
+        self.keys
+            .iter()
+            .map(|key| archived_key(key) as usize)
+            .zip(self.values.iter())
+    }
+
+    /// Return the archived values, in ascending key order.
+    pub fn values(&self) -> &[T] {
+        self.values.as_slice()
+    }
+
+    fn index(&self, key: usize) -> Option<usize> {
+        self.keys
+            .binary_search_by(|k| (archived_key(k) as usize).cmp(&key))
+            .ok()
+    }
+}
+
+/// Return the value of an archived key, whatever the endianness of the
+/// archive.
+fn archived_key(key: &Archived<usize>) -> FixedUsize {
+    let key: Result<FixedUsize, _> = key.deserialize(&mut Infallible);
+    match key {
+        Ok(key) => key,
+        Err(never) => match never {},
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for ArchivedSlab<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_map().entries(self.iter()).finish()
+    }
+}
+
+/// The resolver for an archived [`Slab`](struct.Slab.html).
+pub struct SlabResolver {
+    keys: VecResolver,
+    values: VecResolver,
+}
+
+impl<T: Archive, const N: usize, A: ChunkAllocator> Archive for Slab<T, N, A> {
+    type Archived = ArchivedSlab<T::Archived>;
+    type Resolver = SlabResolver;
+
+    unsafe fn resolve(&self, pos: usize, resolver: SlabResolver, out: *mut Self::Archived) {
+        let (fp, fo) = out_field!(out.keys);
+        ArchivedVec::resolve_from_len(self.len(), pos + fp, resolver.keys, fo);
+        let (fp, fo) = out_field!(out.values);
+        ArchivedVec::resolve_from_len(self.len(), pos + fp, resolver.values, fo);
+    }
+}
+
+impl<T, S, const N: usize, A> Serialize<S> for Slab<T, N, A>
+where
+    T: Serialize<S>,
+    S: ScratchSpace + Serializer + ?Sized,
+    A: ChunkAllocator,
+{
+    fn serialize(&self, serializer: &mut S) -> Result<SlabResolver, S::Error> {
+        Ok(SlabResolver {
+            keys: ArchivedVec::serialize_from_iter::<usize, _, _, _>(self.keys(), serializer)?,
+            values: ArchivedVec::serialize_from_iter::<T, _, _, _>(self.values(), serializer)?,
+        })
+    }
+}
+
+/// Stores every value at its key, and puts the keys which are left vacant on
+/// the free list in ascending order.
+///
+/// A validated archive has no duplicate or out of range keys. If the archive
+/// wasn't validated and has such keys anyway, their values are skipped: the
+/// error type of a `Fallible` deserializer can't be constructed here, e.g.
+/// it is uninhabited for `Infallible`, so validate archives from untrusted
+/// sources to have them rejected instead.
+impl<T, D, const N: usize, A> Deserialize<Slab<T, N, A>, D> for ArchivedSlab<T::Archived>
+where
+    T: Archive,
+    T::Archived: Deserialize<T, D>,
+    D: Fallible + ?Sized,
+    A: ChunkAllocator + Default,
+{
+    fn deserialize(&self, deserializer: &mut D) -> Result<Slab<T, N, A>, D::Error> {
+        let mut slab = Slab::default();
+        for (key, val) in self.iter() {
+            if slab.contains(key) || !is_plausible_key(slab.len(), key) {
+                continue;
+            }
+            slab.occupy(key, val.deserialize(deserializer)?);
+        }
+        slab.rebuild_free_list();
+        Ok(slab)
+    }
+}
+
+/// An error which occurred while validating an archived
+/// [`Slab`](struct.Slab.html).
+#[derive(Debug)]
+pub enum ArchivedSlabError {
+    /// The keys or the values are invalid.
+    Field(Box<dyn Error + Send + Sync>),
+    /// The number of keys and values differ.
+    LengthMismatch,
+    /// The keys aren't in strictly ascending order, e.g. because a key
+    /// appears twice.
+    UnsortedKeys,
+    /// A key is so large that there are far too few values for it.
+    KeyOutOfRange,
+}
+
+impl fmt::Display for ArchivedSlabError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ArchivedSlabError::Field(err) => write!(f, "invalid field: {}", err),
+            ArchivedSlabError::LengthMismatch => {
+                f.write_str("mismatched number of keys and values")
+            }
+            ArchivedSlabError::UnsortedKeys => f.write_str("keys are not in ascending order"),
+            ArchivedSlabError::KeyOutOfRange => f.write_str("key out of range"),
+        }
+    }
+}
+
+impl Error for ArchivedSlabError {}
+
+impl<T, C> CheckBytes<C> for ArchivedSlab<T>
+where
+    C: ArchiveContext + ?Sized,
+    C::Error: Error + Send + Sync + 'static,
+    ArchivedVec<T>: CheckBytes<C>,
+    <ArchivedVec<T> as CheckBytes<C>>::Error: Send + Sync + 'static,
+{
+    type Error = ArchivedSlabError;
+
+    unsafe fn check_bytes<'a>(
+        value: *const Self,
+        context: &mut C,
+    ) -> Result<&'a Self, Self::Error> {
+        let keys =
+            ArchivedVec::<Archived<usize>>::check_bytes(ptr::addr_of!((*value).keys), context)
+                .map_err(|err| ArchivedSlabError::Field(Box::new(err)))?;
+        let values = ArchivedVec::<T>::check_bytes(ptr::addr_of!((*value).values), context)
+            .map_err(|err| ArchivedSlabError::Field(Box::new(err)))?;
+        if keys.len() != values.len() {
+            return Err(ArchivedSlabError::LengthMismatch);
+        }
+        if keys
+            .windows(2)
+            .any(|w| archived_key(&w[0]) >= archived_key(&w[1]))
+        {
+            return Err(ArchivedSlabError::UnsortedKeys);
+        }
+        // The keys are ascending, so each one is stored after as many values
+        // as there are keys before it.
+        let in_range = keys.iter().enumerate().all(|(i, key)| {
+            usize::try_from(archived_key(key)).is_ok_and(|key| is_plausible_key(i, key))
+        });
+        if !in_range {
+            return Err(ArchivedSlabError::KeyOutOfRange);
+        }
+        Ok(&*value)
+    }
+}
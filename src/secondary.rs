@@ -0,0 +1,227 @@
+//! A map which attaches extra values to the keys of a `Slab`.
+
+use crate::growth::Growth;
+use crate::{GrowthPolicy, Handle, DEFAULT_CHUNK_SIZE};
+use alloc::{boxed::Box, vec::Vec};
+use core::fmt;
+use core::ops;
+
+/// A map from the keys of a [`Slab`](struct.Slab.html) to values of another
+/// type, e.g. metadata which only some values need.
+///
+/// Values are stored in chunks which are laid out like those of a slab with
+/// the same chunk size and [`GrowthPolicy`](enum.GrowthPolicy.html), so looking
+/// up a key is as fast as in the slab itself, and a chunk is only allocated
+/// once a key in it is inserted.
+///
+/// Values inserted with a [`Handle`](struct.Handle.html) are invalidated along
+/// with it: once the slab value the handle was created for is removed, and
+/// its key is reused, the old value is no longer returned by
+/// [`get_handle`](#method.get_handle) and is replaced by the next
+/// [`insert_handle`](#method.insert_handle) for the key.
+///
+/// # Examples
+///
+/// ```
+/// # use pinned_slab::*;
+/// let mut slab = Slab::new();
+/// let mut names = SecondaryMap::new();
+///
+/// let (key, _) = slab.insert(42);
+/// let handle = slab.handle(key).unwrap();
+/// names.insert_handle(handle, "answer");
+/// assert_eq!(names.get_handle(handle), Some(&"answer"));
+///
+/// slab.remove(key);
+/// let (reused, _) = slab.insert(7);
+/// let new_handle = slab.handle(reused).unwrap();
+/// assert_eq!(reused, key);
+/// assert_eq!(names.get_handle(new_handle), None);
+/// ```
+pub struct SecondaryMap<V, const N: usize = DEFAULT_CHUNK_SIZE> {
+    chunks: Vec<Option<SecondaryChunk<V>>>,
+    growth: Growth<N>,
+    len: usize,
+}
+
+/// The slots of a chunk, which are `None` where no value is stored.
+type SecondaryChunk<V> = Box<[Option<Slot<V>>]>;
+
+struct Slot<V> {
+    /// The stamp of the handle the value was inserted with, if any.
+    stamp: Option<u64>,
+    value: V,
+}
+
+impl<V> SecondaryMap<V> {
+    /// Construct a new, empty `SecondaryMap` for slabs with the default chunk
+    /// size.
+    pub fn new() -> Self {
+        SecondaryMap::with_growth_policy(GrowthPolicy::Fixed)
+    }
+}
+
+impl<V, const N: usize> SecondaryMap<V, N> {
+    /// Construct a new, empty `SecondaryMap` whose chunks grow like those of
+    /// a slab with the given policy.
+    pub fn with_growth_policy(policy: GrowthPolicy) -> Self {
+        SecondaryMap {
+            chunks: Vec::new(),
+            growth: Growth::new(policy),
+            len: 0,
+        }
+    }
+
+    /// Return the number of values in the map, including values whose handle
+    /// has been invalidated.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Return `true` if there are no values in the map.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Return `true` if a value is associated with the given key.
+    pub fn contains(&self, key: usize) -> bool {
+        self.slot(key).is_some()
+    }
+
+    /// Associate `value` with the given key, returning the previous value of
+    /// the key.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use pinned_slab::*;
+    /// let mut meta = SecondaryMap::new();
+    ///
+    /// assert_eq!(meta.insert(3, "three"), None);
+    /// assert_eq!(meta.insert(3, "drei"), Some("three"));
+    /// assert_eq!(meta[3], "drei");
+    /// assert_eq!(meta.get(4), None);
+    /// ```
+    pub fn insert(&mut self, key: usize, value: V) -> Option<V> {
+        self.insert_slot(key, Slot { stamp: None, value })
+    }
+
+    /// Associate `value` with the key of `handle`, returning the previous
+    /// value of the key, even if its handle has been invalidated.
+    pub fn insert_handle(&mut self, handle: Handle, value: V) -> Option<V> {
+        let slot = Slot {
+            stamp: Some(handle.stamp()),
+            value,
+        };
+        self.insert_slot(handle.key(), slot)
+    }
+
+    /// Return a reference to the value associated with the given key.
+    pub fn get(&self, key: usize) -> Option<&V> {
+        self.slot(key).map(|slot| &slot.value)
+    }
+
+    /// Return a mutable reference to the value associated with the given key.
+    pub fn get_mut(&mut self, key: usize) -> Option<&mut V> {
+        self.slot_mut(key).map(|slot| &mut slot.value)
+    }
+
+    /// Return a reference to the value inserted with `handle`, or `None` if
+    /// there is no such value.
+    pub fn get_handle(&self, handle: Handle) -> Option<&V> {
+        self.slot(handle.key())
+            .filter(|slot| slot.stamp == Some(handle.stamp()))
+            .map(|slot| &slot.value)
+    }
+
+    /// Return a mutable reference to the value inserted with `handle`, or
+    /// `None` if there is no such value.
+    pub fn get_handle_mut(&mut self, handle: Handle) -> Option<&mut V> {
+        self.slot_mut(handle.key())
+            .filter(|slot| slot.stamp == Some(handle.stamp()))
+            .map(|slot| &mut slot.value)
+    }
+
+    /// Remove and return the value associated with the given key.
+    pub fn remove(&mut self, key: usize) -> Option<V> {
+        let (index, entry) = self.growth.locate(key);
+        let slot = self.chunks.get_mut(index)?.as_mut()?[entry].take()?;
+        self.len -= 1;
+        Some(slot.value)
+    }
+
+    /// Remove every value, keeping the allocated chunks.
+    pub fn clear(&mut self) {
+        for chunk in self.chunks.iter_mut().flatten() {
+            chunk.iter_mut().for_each(|slot| *slot = None);
+        }
+        self.len = 0;
+    }
+
+    /// Return an iterator over the keys and values of the map, in ascending
+    /// key order.
+    pub fn iter(&self) -> impl Iterator<Item = (usize, &V)> + '_ {
+        let growth = self.growth;
+        self.chunks
+            .iter()
+            .enumerate()
+            .filter_map(move |(index, chunk)| Some((growth.chunk_start(index), chunk.as_ref()?)))
+            .flat_map(|(start, chunk)| {
+                chunk
+                    .iter()
+                    .enumerate()
+                    .filter_map(move |(i, slot)| Some((start + i, &slot.as_ref()?.value)))
+            })
+    }
+
+    fn slot(&self, key: usize) -> Option<&Slot<V>> {
+        let (index, entry) = self.growth.locate(key);
+        self.chunks.get(index)?.as_ref()?[entry].as_ref()
+    }
+
+    fn slot_mut(&mut self, key: usize) -> Option<&mut Slot<V>> {
+        let (index, entry) = self.growth.locate(key);
+        self.chunks.get_mut(index)?.as_mut()?[entry].as_mut()
+    }
+
+    fn insert_slot(&mut self, key: usize, slot: Slot<V>) -> Option<V> {
+        let (index, entry) = self.growth.locate(key);
+        if index >= self.chunks.len() {
+            self.chunks.resize_with(index + 1, || None);
+        }
+        let growth = self.growth;
+        let chunk = self.chunks[index]
+            .get_or_insert_with(|| (0..growth.chunk_len(index)).map(|_| None).collect());
+        let old = chunk[entry].replace(slot);
+        if old.is_none() {
+            self.len += 1;
+        }
+        old.map(|slot| slot.value)
+    }
+}
+
+impl<V, const N: usize> Default for SecondaryMap<V, N> {
+    fn default() -> Self {
+        SecondaryMap::with_growth_policy(GrowthPolicy::Fixed)
+    }
+}
+
+impl<V, const N: usize> ops::Index<usize> for SecondaryMap<V, N> {
+    type Output = V;
+
+    fn index(&self, key: usize) -> &V {
+        self.get(key).expect("invalid key")
+    }
+}
+
+impl<V, const N: usize> ops::IndexMut<usize> for SecondaryMap<V, N> {
+    fn index_mut(&mut self, key: usize) -> &mut V {
+        self.get_mut(key).expect("invalid key")
+    }
+}
+
+impl<V: fmt::Debug, const N: usize> fmt::Debug for SecondaryMap<V, N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_map().entries(self.iter()).finish()
+    }
+}
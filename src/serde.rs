@@ -0,0 +1,62 @@
+//! `Serialize` and `Deserialize` for `Slab`, available behind the `serde`
+//! feature.
+
+use crate::{is_plausible_key, ChunkAllocator, Slab};
+use ::serde::de::{self, Deserialize, Deserializer, MapAccess, Visitor};
+use ::serde::ser::{Serialize, Serializer};
+use core::fmt;
+use core::marker::PhantomData;
+
+/// Serializes the slab as a map from keys to values, in ascending key order.
+///
+/// Only occupied entries are written, so a sparse slab stays small.
+impl<T: Serialize, const N: usize, A: ChunkAllocator> Serialize for Slab<T, N, A> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_map(self.iter())
+    }
+}
+
+/// Deserializes a map from keys to values, storing every value at its key.
+///
+/// The keys which are left vacant are put on the free list in ascending
+/// order, so they are reused before any new keys. Deserializing fails if a
+/// key appears twice, or if a key is so large that there are far too few
+/// values for it, since reaching it would take a huge chunk table.
+impl<'de, T, const N: usize, A> Deserialize<'de> for Slab<T, N, A>
+where
+    T: Deserialize<'de>,
+    A: ChunkAllocator + Default,
+{
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_map(SlabVisitor(PhantomData))
+    }
+}
+
+struct SlabVisitor<T, const N: usize, A: ChunkAllocator>(PhantomData<fn() -> Slab<T, N, A>>);
+
+impl<'de, T, const N: usize, A> Visitor<'de> for SlabVisitor<T, N, A>
+where
+    T: Deserialize<'de>,
+    A: ChunkAllocator + Default,
+{
+    type Value = Slab<T, N, A>;
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("a map from keys to values")
+    }
+
+    fn visit_map<M: MapAccess<'de>>(self, mut map: M) -> Result<Self::Value, M::Error> {
+        let mut slab = Slab::default();
+        while let Some((key, val)) = map.next_entry::<usize, T>()? {
+            if !is_plausible_key(slab.len(), key) {
+                return Err(de::Error::custom(format_args!("key {} out of range", key)));
+            }
+            if slab.contains(key) {
+                return Err(de::Error::custom(format_args!("duplicate key {}", key)));
+            }
+            slab.occupy(key, val);
+        }
+        slab.rebuild_free_list();
+        Ok(slab)
+    }
+}
@@ -0,0 +1,303 @@
+//! A `Slab` which can be shared between threads.
+
+use crate::Slab;
+use std::cell::Cell;
+use std::fmt;
+use std::ops;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{PoisonError, RwLock, RwLockReadGuard, RwLockWriteGuard};
+use std::thread;
+
+/// Hands out a shard to each thread, so threads spread their inserts.
+static NEXT_THREAD: AtomicUsize = AtomicUsize::new(0);
+
+thread_local! {
+    static THREAD_INDEX: Cell<Option<usize>> = const { Cell::new(None) };
+}
+
+/// Return a small number which is unique to the current thread, at least
+/// among the threads which are alive at the same time.
+fn thread_index() -> usize {
+    THREAD_INDEX.with(|index| match index.get() {
+        Some(index) => index,
+        None => {
+            let new = NEXT_THREAD.fetch_add(1, Ordering::Relaxed);
+            index.set(Some(new));
+            new
+        }
+    })
+}
+
+/// A slab which is split into shards behind their own locks, so that it can
+/// be shared between threads, e.g. as a table of connections.
+///
+/// Each thread inserts into its own shard, so inserts from different threads
+/// rarely contend. Reads only take a shared lock on the shard of their key,
+/// and values are pinned just like in a [`Slab`](struct.Slab.html): they
+/// never move until they are removed.
+///
+/// The shard of a value is encoded in the low bits of its key, so keys are
+/// not dense.
+///
+/// # Examples
+///
+/// ```
+/// # use pinned_slab::*;
+/// use std::thread;
+///
+/// let slab = ShardedSlab::new();
+///
+/// let keys: Vec<usize> = thread::scope(|s| {
+///     let handles: Vec<_> = (0..4)
+///         .map(|i| {
+///             let slab = &slab;
+///             s.spawn(move || slab.insert(format!("connection {}", i)))
+///         })
+///         .collect();
+///     handles.into_iter().map(|h| h.join().unwrap()).collect()
+/// });
+///
+/// assert_eq!(slab.len(), 4);
+/// assert!(slab.get(keys[2]).unwrap().starts_with("connection"));
+/// assert!(slab.remove(keys[2]).is_some());
+/// assert!(slab.get(keys[2]).is_none());
+/// ```
+pub struct ShardedSlab<T> {
+    shards: Box<[RwLock<Slab<T>>]>,
+}
+
+impl<T> ShardedSlab<T> {
+    /// Construct a new, empty `ShardedSlab` with four shards per available
+    /// CPU.
+    ///
+    /// Like [`Slab::new`](struct.Slab.html#method.new), this doesn't allocate
+    /// any chunks.
+    pub fn new() -> Self {
+        let cpus = thread::available_parallelism().map_or(1, |n| n.get());
+        ShardedSlab::with_shards(cpus * 4)
+    }
+
+    /// Construct a new, empty `ShardedSlab` with `shards` shards.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `shards` is zero.
+    pub fn with_shards(shards: usize) -> Self {
+        assert!(shards > 0, "a sharded slab needs at least one shard");
+        ShardedSlab {
+            shards: (0..shards).map(|_| RwLock::new(Slab::new())).collect(),
+        }
+    }
+
+    /// Return the number of shards.
+    pub fn shard_count(&self) -> usize {
+        self.shards.len()
+    }
+
+    /// Return the number of stored values.
+    ///
+    /// Other threads may insert or remove values at the same time, so this is
+    /// only a snapshot.
+    pub fn len(&self) -> usize {
+        (0..self.shards.len()).map(|i| self.read(i).len()).sum()
+    }
+
+    /// Return `true` if there are no values stored in the slab.
+    pub fn is_empty(&self) -> bool {
+        (0..self.shards.len()).all(|i| self.read(i).is_empty())
+    }
+
+    /// Return `true` if a value is associated with the given key.
+    pub fn contains(&self, key: usize) -> bool {
+        let (shard, key) = self.split(key);
+        self.read(shard).contains(key)
+    }
+
+    /// Insert a value in the slab, returning the key assigned to the value.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the key doesn't fit in a `usize`.
+    pub fn insert(&self, val: T) -> usize {
+        let shard = thread_index() % self.shards.len();
+        let key = self.write(shard).insert(val).0;
+        self.join(shard, key)
+    }
+
+    /// Return a guard for the value associated with the given key, or `None`
+    /// if there is no such value.
+    ///
+    /// The shard of the key can't be modified while the guard is alive, but
+    /// other readers aren't blocked.
+    pub fn get(&self, key: usize) -> Option<ShardedRef<'_, T>> {
+        let (shard, key) = self.split(key);
+        let guard = self.read(shard);
+        let value = guard.get(key)? as *const T;
+        Some(ShardedRef {
+            _guard: guard,
+            value,
+        })
+    }
+
+    /// Return a guard for a pinned mutable reference to the value associated
+    /// with the given key, or `None` if there is no such value.
+    ///
+    /// The shard of the key is locked exclusively while the guard is alive.
+    pub fn get_pin_mut(&self, key: usize) -> Option<ShardedRefMut<'_, T>> {
+        let (shard, key) = self.split(key);
+        let mut guard = self.write(shard);
+        // SAFETY: The value is only handed out pinned, see `ShardedRefMut`.
+        let value = unsafe { guard.get_mut(key)? as *mut T };
+        Some(ShardedRefMut {
+            _guard: guard,
+            value,
+        })
+    }
+
+    /// Remove and return the value associated with the given key, or `None`
+    /// if there is no such value, e.g. because another thread removed it
+    /// first.
+    ///
+    /// The value is moved out, so it must be `Unpin`.
+    pub fn remove(&self, key: usize) -> Option<T>
+    where
+        T: Unpin,
+    {
+        let (shard, key) = self.split(key);
+        self.write(shard).take(key)
+    }
+
+    /// Drop the value associated with the given key in place, returning
+    /// whether there was such a value.
+    pub fn remove_in_place(&self, key: usize) -> bool {
+        let (shard, key) = self.split(key);
+        self.write(shard).remove_in_place(key)
+    }
+
+    /// Drop every value in the slab, one shard at a time.
+    pub fn clear(&self) {
+        for i in 0..self.shards.len() {
+            self.write(i).clear();
+        }
+    }
+
+    /// Return the shard of `key` and the key within that shard.
+    fn split(&self, key: usize) -> (usize, usize) {
+        (key % self.shards.len(), key / self.shards.len())
+    }
+
+    /// Return the key of the value at `key` within `shard`.
+    fn join(&self, shard: usize, key: usize) -> usize {
+        key.checked_mul(self.shards.len())
+            .and_then(|key| key.checked_add(shard))
+            .expect("key overflow")
+    }
+
+    // The slabs of the shards stay consistent even if a panic poisons their
+    // locks, so poisoning is ignored.
+
+    fn read(&self, shard: usize) -> RwLockReadGuard<'_, Slab<T>> {
+        self.shards[shard]
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+    }
+
+    fn write(&self, shard: usize) -> RwLockWriteGuard<'_, Slab<T>> {
+        self.shards[shard]
+            .write()
+            .unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+impl<T> Default for ShardedSlab<T> {
+    fn default() -> Self {
+        ShardedSlab::new()
+    }
+}
+
+impl<T> fmt::Debug for ShardedSlab<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ShardedSlab")
+            .field("len", &self.len())
+            .field("shards", &self.shards.len())
+            .finish()
+    }
+}
+
+/// A shared reference to a value of a `ShardedSlab`.
+///
+/// See [`ShardedSlab::get`](struct.ShardedSlab.html#method.get).
+pub struct ShardedRef<'a, T> {
+    _guard: RwLockReadGuard<'a, Slab<T>>,
+    value: *const T,
+}
+
+// SAFETY: `ShardedRef` behaves like a `&T`.
+unsafe impl<'a, T: Sync> Sync for ShardedRef<'a, T> {}
+
+impl<'a, T> ShardedRef<'a, T> {
+    /// Return the value as a pinned reference.
+    pub fn as_pin(&self) -> Pin<&T> {
+        // SAFETY: Values of a slab never move until they are removed.
+        unsafe { Pin::new_unchecked(&*self.value) }
+    }
+}
+
+impl<'a, T> ops::Deref for ShardedRef<'a, T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        // SAFETY: The shard is locked for reading, so the value can't be
+        // removed or modified.
+        unsafe { &*self.value }
+    }
+}
+
+impl<'a, T: fmt::Debug> fmt::Debug for ShardedRef<'a, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&**self, f)
+    }
+}
+
+/// A pinned mutable reference to a value of a `ShardedSlab`.
+///
+/// See [`ShardedSlab::get_pin_mut`](struct.ShardedSlab.html#method.get_pin_mut).
+pub struct ShardedRefMut<'a, T> {
+    _guard: RwLockWriteGuard<'a, Slab<T>>,
+    value: *mut T,
+}
+
+// SAFETY: `ShardedRefMut` behaves like a `&mut T`.
+unsafe impl<'a, T: Sync> Sync for ShardedRefMut<'a, T> {}
+
+impl<'a, T> ShardedRefMut<'a, T> {
+    /// Return the value as a pinned mutable reference.
+    pub fn as_pin_mut(&mut self) -> Pin<&mut T> {
+        // SAFETY: The shard is locked exclusively, and values of a slab never
+        // move until they are removed.
+        unsafe { Pin::new_unchecked(&mut *self.value) }
+    }
+}
+
+impl<'a, T> ops::Deref for ShardedRefMut<'a, T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        // SAFETY: The shard is locked exclusively.
+        unsafe { &*self.value }
+    }
+}
+
+impl<'a, T: Unpin> ops::DerefMut for ShardedRefMut<'a, T> {
+    fn deref_mut(&mut self) -> &mut T {
+        // SAFETY: The shard is locked exclusively, and `T` is `Unpin`.
+        unsafe { &mut *self.value }
+    }
+}
+
+impl<'a, T: fmt::Debug> fmt::Debug for ShardedRefMut<'a, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&**self, f)
+    }
+}
@@ -0,0 +1,55 @@
+//! Conversions between `Slab` and the [`slab`](https://docs.rs/slab) crate's
+//! `Slab`, available behind the `slab` feature.
+
+use crate::{ChunkAllocator, Slab};
+
+/// Moves every value of a `slab::Slab` into a new slab, keeping its key.
+///
+/// # Examples
+///
+/// ```
+/// # use pinned_slab::*;
+/// let mut old = slab::Slab::new();
+/// let hello = old.insert("hello");
+/// let world = old.insert("world");
+/// old.remove(hello);
+///
+/// let slab: Slab<&str> = Slab::from(old);
+/// assert_eq!(slab[world], "world");
+/// assert!(!slab.contains(hello));
+/// ```
+impl<T, const N: usize, A: ChunkAllocator + Default> From<::slab::Slab<T>> for Slab<T, N, A> {
+    fn from(old: ::slab::Slab<T>) -> Self {
+        let mut slab = Slab::default();
+        for (key, val) in old {
+            slab.occupy(key, val);
+        }
+        slab.rebuild_free_list();
+        slab
+    }
+}
+
+impl<T: Unpin, const N: usize, A: ChunkAllocator> Slab<T, N, A> {
+    /// Move every value into a new `slab::Slab`, keeping its key.
+    ///
+    /// The values of a `slab::Slab` aren't pinned, so this is only available
+    /// for `Unpin` types. The chunk size, growth policy and allocator of the
+    /// slab are lost.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use pinned_slab::*;
+    /// let mut slab = Slab::new();
+    /// slab.insert_many(0..3);
+    /// slab.remove(1);
+    ///
+    /// let old = slab.into_unpinned();
+    /// assert_eq!(old.len(), 2);
+    /// assert_eq!(old[2], 2);
+    /// assert!(!old.contains(1));
+    /// ```
+    pub fn into_unpinned(self) -> ::slab::Slab<T> {
+        self.into_iter().collect()
+    }
+}
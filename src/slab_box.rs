@@ -0,0 +1,106 @@
+//! An owning handle which removes its value from the `Slab` when dropped.
+
+use crate::{ChunkAllocator, Global, Slab, DEFAULT_CHUNK_SIZE};
+use core::fmt;
+use core::mem::ManuallyDrop;
+use core::ops;
+use core::pin::Pin;
+
+/// A value in a `Slab` which is removed and dropped along with the
+/// `SlabBox`, so the entry can't be leaked by an early return.
+///
+/// See [`Slab::insert_owned`](struct.Slab.html#method.insert_owned).
+pub struct SlabBox<'a, T, const N: usize = DEFAULT_CHUNK_SIZE, A: ChunkAllocator = Global> {
+    slab: &'a mut Slab<T, N, A>,
+    key: usize,
+}
+
+impl<T, const N: usize, A: ChunkAllocator> Slab<T, N, A> {
+    /// Insert a value in the slab, returning a `SlabBox` which removes it
+    /// again when dropped.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use pinned_slab::*;
+    /// fn process(slab: &mut Slab<String>, fail: bool) -> Result<usize, ()> {
+    ///     let mut request = slab.insert_owned(String::from("request"));
+    ///     request.as_pin_mut().get_mut().push_str(" body");
+    ///     if fail {
+    ///         return Err(());
+    ///     }
+    ///     Ok(SlabBox::leak(request))
+    /// }
+    ///
+    /// let mut slab = Slab::new();
+    /// assert!(process(&mut slab, true).is_err());
+    /// assert!(slab.is_empty());
+    ///
+    /// let key = process(&mut slab, false).unwrap();
+    /// assert_eq!(slab[key], "request body");
+    /// ```
+    pub fn insert_owned(&mut self, val: T) -> SlabBox<'_, T, N, A> {
+        let key = self.insert(val).0;
+        SlabBox { slab: self, key }
+    }
+}
+
+impl<'a, T, const N: usize, A: ChunkAllocator> SlabBox<'a, T, N, A> {
+    /// Return the key of the value.
+    pub fn key(&self) -> usize {
+        self.key
+    }
+
+    /// Return the value as a pinned mutable reference.
+    pub fn as_pin_mut(&mut self) -> Pin<&mut T> {
+        // SAFETY: The value stays occupied while the box is alive.
+        unsafe { self.slab.get_unchecked_pin_mut(self.key) }
+    }
+
+    /// Remove the value from the slab and return it.
+    ///
+    /// The value is moved out, so it must be `Unpin`.
+    pub fn into_inner(this: Self) -> T
+    where
+        T: Unpin,
+    {
+        let this = ManuallyDrop::new(this);
+        // SAFETY: `this` is never used or dropped again.
+        let slab = unsafe { core::ptr::read(&this.slab) };
+        slab.remove(this.key)
+    }
+
+    /// Keep the value in the slab and return its key.
+    pub fn leak(this: Self) -> usize {
+        ManuallyDrop::new(this).key
+    }
+}
+
+impl<'a, T, const N: usize, A: ChunkAllocator> ops::Deref for SlabBox<'a, T, N, A> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        // SAFETY: The value stays occupied while the box is alive.
+        unsafe { self.slab.get_unchecked(self.key) }
+    }
+}
+
+impl<'a, T: Unpin, const N: usize, A: ChunkAllocator> ops::DerefMut for SlabBox<'a, T, N, A> {
+    fn deref_mut(&mut self) -> &mut T {
+        // SAFETY: The value stays occupied while the box is alive, and it is
+        // `Unpin`.
+        unsafe { self.slab.get_unchecked_mut(self.key) }
+    }
+}
+
+impl<'a, T, const N: usize, A: ChunkAllocator> Drop for SlabBox<'a, T, N, A> {
+    fn drop(&mut self) {
+        self.slab.remove_in_place(self.key);
+    }
+}
+
+impl<'a, T: fmt::Debug, const N: usize, A: ChunkAllocator> fmt::Debug for SlabBox<'a, T, N, A> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&**self, f)
+    }
+}
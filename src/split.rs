@@ -0,0 +1,224 @@
+//! Splitting a `Slab` and merging slabs by moving whole chunks, so the values
+//! in them stay where they are.
+
+use crate::metrics::Counters;
+use crate::{ChunkAllocator, KeyPolicy, Slab, NIL};
+use alloc::vec::Vec;
+use core::mem;
+
+impl<T, const N: usize, A: ChunkAllocator> Slab<T, N, A> {
+    /// Split the slab in two at the key `at`, returning a slab with the
+    /// chunks holding the keys from `at` on.
+    ///
+    /// The chunks are moved as a whole, so the values in them keep both
+    /// their keys and their addresses, and stay pinned. Both slabs keep the
+    /// growth and key policies of this slab, and rebuild their free lists
+    /// like [`shrink_to_fit`](#method.shrink_to_fit). Each can reuse the
+    /// vacant keys of the other afterwards.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `at` isn't the first key of a chunk.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use pinned_slab::*;
+    /// let mut slab: Slab<usize, 4> = Slab::with_chunk_size();
+    /// slab.insert_many(0..10);
+    /// let addr = slab.as_ptr(9).unwrap();
+    ///
+    /// let upper = slab.split_off(8);
+    /// assert_eq!(slab.keys().collect::<Vec<_>>(), (0..8).collect::<Vec<_>>());
+    /// assert_eq!(upper.keys().collect::<Vec<_>>(), [8, 9]);
+    /// assert_eq!(upper.as_ptr(9), Some(addr));
+    /// ```
+    pub fn split_off(&mut self, at: usize) -> Self {
+        let (slab_key, entry_key) = self.growth.locate(at);
+        assert!(
+            entry_key == 0,
+            "split_off at {} isn't at the start of a chunk",
+            at
+        );
+
+        let last = self.prev_occupied(at);
+        let first = self.next_occupied(at);
+        let mut other = Slab {
+            chunks: Vec::new(),
+            growth: self.growth,
+            key_policy: self.key_policy,
+            alloc: self.alloc.clone(),
+            len: 0,
+            next: self.next.max(at),
+            head: NIL,
+            tail: NIL,
+            issued: self.issued,
+            stamp: self.stamp,
+            reserved: 0,
+            counters: Counters::new(),
+            name: self.name,
+        };
+        if slab_key < self.chunks.len() {
+            other.chunks.resize_with(slab_key, || None);
+            other.chunks.extend(self.chunks.drain(slab_key..));
+        }
+
+        for chunk in other.chunks.iter().flatten() {
+            let reserved: usize = chunk.reserved.iter().map(|w| w.count_ones() as usize).sum();
+            other.len += chunk.len;
+            other.reserved += reserved;
+        }
+        self.len -= other.len;
+        self.reserved -= other.reserved;
+
+        // Cut the list of occupied entries in two at `at`.
+        if let Some(first) = first {
+            other.head = first;
+            other.tail = self.tail;
+            other.links_mut(first).prev = NIL;
+        }
+        match last {
+            Some(last) => {
+                self.tail = last;
+                self.links_mut(last).next = NIL;
+            }
+            None => {
+                self.head = NIL;
+                self.tail = NIL;
+            }
+        }
+
+        self.rebuild_free_list();
+        other.rebuild_free_list();
+        other
+    }
+
+    /// Move every value of `other` into this slab, calling `remap` with the
+    /// old and the new key of each.
+    ///
+    /// Whole chunks of `other` are moved into this slab, so their values
+    /// keep their addresses and stay pinned. A chunk keeps its keys if this
+    /// slab has no chunk in its place, e.g. after
+    /// [`split_off`](#method.split_off), and is moved to the first free place
+    /// for a chunk of its size otherwise. With a
+    /// [`Geometric`](enum.GrowthPolicy.html#variant.Geometric) growth policy
+    /// there is only one place for each of the small first chunks, so if it
+    /// is taken, their values are inserted one by one instead, which moves
+    /// them. Since that can't be ruled out up front, `T` must be `Unpin`.
+    /// Keys reserved in `other` are released.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the growth policies of the slabs differ.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use pinned_slab::*;
+    /// let mut a: Slab<&str, 4> = Slab::with_chunk_size();
+    /// let mut b: Slab<&str, 4> = Slab::with_chunk_size();
+    /// a.insert_many(["a0", "a1"]);
+    /// b.insert_many(["b0", "b1", "b2"]);
+    /// let addr = b.as_ptr(2).unwrap();
+    ///
+    /// let mut remapped = Vec::new();
+    /// a.merge(b, |old, new| remapped.push((old, new)));
+    ///
+    /// assert_eq!(remapped, [(0, 4), (1, 5), (2, 6)]);
+    /// assert_eq!(a[6], "b2");
+    /// assert_eq!(a.as_ptr(6), Some(addr));
+    /// ```
+    pub fn merge<F>(&mut self, mut other: Slab<T, N, A>, mut remap: F)
+    where
+        T: Unpin,
+        F: FnMut(usize, usize),
+    {
+        assert_eq!(
+            self.growth_policy(),
+            other.growth_policy(),
+            "the growth policies of merged slabs must be the same"
+        );
+
+        let mut chunks: Vec<_> = mem::take(&mut other.chunks)
+            .into_iter()
+            .enumerate()
+            .filter_map(|(i, chunk)| Some((i, chunk.filter(|chunk| chunk.len > 0)?)))
+            .collect();
+
+        // Chunks which can keep their keys go first, so the others don't take
+        // their places.
+        chunks.sort_by_key(|&(i, _)| self.chunk(i).is_some());
+        let mut moved = Vec::new();
+        for (i, mut chunk) in chunks {
+            let index = match self.chunk_place(i) {
+                Some(index) => index,
+                None => {
+                    moved.push((i, chunk));
+                    continue;
+                }
+            };
+
+            chunk.reserved.fill(0);
+            for j in 0..chunk.touched {
+                if chunk.is_occupied(j) {
+                    self.stamp += 1;
+                    chunk.stamps[j] = self.stamp;
+                }
+            }
+            if index >= self.chunks.len() {
+                self.chunks.resize_with(index + 1, || None);
+            }
+            self.chunks[index] = Some(chunk);
+
+            let (old, new) = (other.growth.chunk_start(i), self.growth.chunk_start(index));
+            let mut next = self.chunk(index).unwrap().next_occupied(0);
+            while let Some(j) = next {
+                self.len += 1;
+                self.counters.insert(false, self.len);
+                self.link(new + j);
+                remap(old + j, new + j);
+                next = self.chunk(index).unwrap().next_occupied(j + 1);
+            }
+        }
+        if self.tail != NIL {
+            self.issued = self.issued.max(self.tail + 1);
+        }
+        if self.key_policy == KeyPolicy::Monotonic {
+            self.next = self.next.max(self.issued);
+        }
+        self.rebuild_free_list();
+
+        for (i, mut chunk) in moved {
+            let start = other.growth.chunk_start(i);
+            let mut next = chunk.next_occupied(0);
+            while let Some(j) = next {
+                // SAFETY: `T` is `Unpin`, so the value may be moved. The
+                // entry is marked vacant right after, so it won't be read or
+                // dropped again.
+                let val = unsafe { chunk.entries()[j].as_ptr().read() };
+                chunk.set_occupied(j, false);
+                chunk.len -= 1;
+
+                let (key, _) = self.insert(val);
+                remap(start + j, key);
+                next = chunk.next_occupied(j + 1);
+            }
+        }
+    }
+
+    /// Return where a chunk which was at `index` in another slab can be
+    /// moved to, if anywhere.
+    fn chunk_place(&self, index: usize) -> Option<usize> {
+        if self.chunk(index).is_none() {
+            return Some(index);
+        }
+
+        // Only chunks of `N` entries have more than one place.
+        let doublings = self.growth.doublings();
+        if index < doublings {
+            return None;
+        }
+        let free = (doublings..self.chunks.len()).find(|&k| self.chunks[k].is_none());
+        Some(free.unwrap_or(self.chunks.len().max(doublings)))
+    }
+}
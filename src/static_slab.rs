@@ -0,0 +1,308 @@
+//! A slab which stores its entries inline, without allocating.
+
+use core::fmt;
+use core::iter::FusedIterator;
+use core::marker::PhantomPinned;
+use core::mem::MaybeUninit;
+use core::ops;
+use core::pin::Pin;
+
+/// A slab with room for exactly `N` values, which stores them inline instead
+/// of in allocated chunks.
+///
+/// It never allocates, so it can be used without an allocator, on the stack,
+/// or in a `static`, since [`new`](#method.new) is a `const fn`. Keys are
+/// handed out like those of a [`Slab`](struct.Slab.html) with the default
+/// key policy, but [`insert`](#method.insert) hands the value back once all
+/// `N` entries are occupied.
+///
+/// The values are stored in the slab itself, so they are only pinned while
+/// the slab is: every method which hands out pinned values or which may
+/// drop them takes a `Pin<&mut StaticSlab>`. Use [`core::pin::pin!`] for a
+/// slab on the stack, or [`Pin::static_mut`] for a `&'static mut` slab.
+///
+/// # Examples
+///
+/// ```
+/// # use pinned_slab::*;
+/// use std::pin::pin;
+///
+/// let mut slab = pin!(StaticSlab::<_, 2>::new());
+///
+/// let (a, _) = slab.as_mut().insert("a").unwrap();
+/// slab.as_mut().insert("b").unwrap();
+/// assert_eq!(slab.as_mut().insert("c").err(), Some("c"));
+///
+/// assert_eq!(slab.as_mut().remove(a), "a");
+/// assert_eq!(slab.as_mut().insert("c").unwrap().0, a);
+/// assert_eq!(slab[a], "c");
+/// ```
+pub struct StaticSlab<T, const N: usize> {
+    entries: [MaybeUninit<T>; N],
+    occupied: [bool; N],
+    /// The next key of the free list, for each vacant entry below `touched`.
+    links: [usize; N],
+    /// The number of entries at the start of `entries` which have been
+    /// handed out.
+    touched: usize,
+    /// The key the next value is inserted at, or `N` if the slab is full.
+    next: usize,
+    len: usize,
+    _pinned: PhantomPinned,
+}
+
+impl<T, const N: usize> StaticSlab<T, N> {
+    /// Construct a new, empty `StaticSlab`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use pinned_slab::*;
+    /// static SLAB: StaticSlab<u32, 16> = StaticSlab::new();
+    ///
+    /// assert!(SLAB.is_empty());
+    /// assert_eq!(SLAB.capacity(), 16);
+    /// ```
+    pub const fn new() -> Self {
+        StaticSlab {
+            entries: [const { MaybeUninit::uninit() }; N],
+            occupied: [false; N],
+            links: [0; N],
+            touched: 0,
+            next: 0,
+            len: 0,
+            _pinned: PhantomPinned,
+        }
+    }
+
+    /// Return the number of stored values.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Return `true` if there are no values stored in the slab.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Return the number of values the slab can store, which is `N`.
+    pub fn capacity(&self) -> usize {
+        N
+    }
+
+    /// Return `true` if all `N` entries are occupied.
+    pub fn is_full(&self) -> bool {
+        self.len == N
+    }
+
+    /// Return `true` if a value is associated with the given key.
+    pub fn contains(&self, key: usize) -> bool {
+        key < N && self.occupied[key]
+    }
+
+    /// Return a reference to the value associated with the given key.
+    pub fn get(&self, key: usize) -> Option<&T> {
+        if !self.contains(key) {
+            return None;
+        }
+
+        // SAFETY: Occupied entries are initialized.
+        Some(unsafe { self.entries[key].assume_init_ref() })
+    }
+
+    /// Return a pinned mutable reference to the value associated with the
+    /// given key.
+    pub fn get_pin_mut(self: Pin<&mut Self>, key: usize) -> Option<Pin<&mut T>> {
+        if !self.contains(key) {
+            return None;
+        }
+
+        // SAFETY: The entry is initialized, and it is pinned since the slab
+        // is.
+        Some(unsafe { self.map_unchecked_mut(|slab| slab.entries[key].assume_init_mut()) })
+    }
+
+    /// Insert a value in the slab, returning the key assigned to the value
+    /// and a pinned mutable reference to it.
+    ///
+    /// If all `N` entries are occupied, the value is handed back instead.
+    pub fn insert(self: Pin<&mut Self>, val: T) -> Result<(usize, Pin<&mut T>), T> {
+        // SAFETY: Nothing is moved out of the slab.
+        let slab = unsafe { self.get_unchecked_mut() };
+        let key = slab.next;
+        if key == N {
+            return Err(val);
+        }
+
+        slab.next = if key == slab.touched {
+            slab.touched += 1;
+            slab.touched
+        } else {
+            slab.links[key]
+        };
+        slab.entries[key] = MaybeUninit::new(val);
+        slab.occupied[key] = true;
+        slab.len += 1;
+
+        // SAFETY: The value was just written, and it is pinned since the slab
+        // is.
+        Ok((key, unsafe {
+            Pin::new_unchecked(slab.entries[key].assume_init_mut())
+        }))
+    }
+
+    /// Remove and return the value associated with the given key.
+    ///
+    /// The key is then released and may be associated with future stored
+    /// values.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `key` is not associated with a value.
+    pub fn remove(self: Pin<&mut Self>, key: usize) -> T
+    where
+        T: Unpin,
+    {
+        if !self.contains(key) {
+            panic!("invalid key");
+        }
+
+        // SAFETY: Only the bookkeeping of the entry changes. No entries are
+        // moved.
+        let slab = unsafe { self.get_unchecked_mut() };
+        slab.release(key);
+
+        // SAFETY: The entry was occupied, and is now marked vacant so it
+        // won't be read again. `T` is `Unpin`, so the value may be moved out
+        // even though it was pinned.
+        unsafe { slab.entries[key].as_ptr().read() }
+    }
+
+    /// Drop the value associated with the given key in place, returning
+    /// whether there was such a value.
+    ///
+    /// Unlike [`remove`](#method.remove), this works for values which aren't
+    /// `Unpin`.
+    pub fn remove_in_place(self: Pin<&mut Self>, key: usize) -> bool {
+        if !self.contains(key) {
+            return false;
+        }
+
+        // SAFETY: The value is dropped in place, which doesn't move it.
+        let slab = unsafe { self.get_unchecked_mut() };
+        slab.release(key);
+
+        // SAFETY: The entry was occupied, and is marked vacant first.
+        unsafe { slab.entries[key].assume_init_drop() };
+        true
+    }
+
+    /// Drop every value in the slab.
+    pub fn clear(self: Pin<&mut Self>) {
+        // SAFETY: Values are dropped in place, which doesn't move them.
+        let slab = unsafe { self.get_unchecked_mut() };
+        slab.drop_values();
+        slab.touched = 0;
+        slab.next = 0;
+    }
+
+    /// Return an iterator over the keys and values of the slab.
+    pub fn iter(&self) -> StaticIter<'_, T, N> {
+        StaticIter {
+            slab: self,
+            key: 0,
+            len: self.len,
+        }
+    }
+
+    /// Mark an occupied entry vacant and push it on the free list.
+    fn release(&mut self, key: usize) {
+        self.occupied[key] = false;
+        self.len -= 1;
+        self.links[key] = self.next;
+        self.next = key;
+    }
+
+    /// Drop every occupied entry in place and mark it vacant, leaking the
+    /// rest if a value panics while being dropped.
+    fn drop_values(&mut self) {
+        let touched = self.touched;
+        self.len = 0;
+        for key in 0..touched {
+            if self.occupied[key] {
+                self.occupied[key] = false;
+                // SAFETY: The entry was occupied, and is marked vacant first.
+                unsafe { self.entries[key].assume_init_drop() };
+            }
+        }
+    }
+}
+
+impl<T, const N: usize> Drop for StaticSlab<T, N> {
+    fn drop(&mut self) {
+        self.drop_values();
+    }
+}
+
+impl<T, const N: usize> Default for StaticSlab<T, N> {
+    fn default() -> Self {
+        StaticSlab::new()
+    }
+}
+
+impl<T: fmt::Debug, const N: usize> fmt::Debug for StaticSlab<T, N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_map().entries(self.iter()).finish()
+    }
+}
+
+impl<T, const N: usize> ops::Index<usize> for StaticSlab<T, N> {
+    type Output = T;
+
+    fn index(&self, key: usize) -> &T {
+        self.get(key).expect("invalid key")
+    }
+}
+
+impl<'a, T, const N: usize> IntoIterator for &'a StaticSlab<T, N> {
+    type Item = (usize, &'a T);
+    type IntoIter = StaticIter<'a, T, N>;
+
+    fn into_iter(self) -> StaticIter<'a, T, N> {
+        self.iter()
+    }
+}
+
+/// An iterator over the values of a [`StaticSlab`](struct.StaticSlab.html).
+///
+/// See [`StaticSlab::iter`](struct.StaticSlab.html#method.iter).
+pub struct StaticIter<'a, T, const N: usize> {
+    slab: &'a StaticSlab<T, N>,
+    key: usize,
+    /// The number of values left to yield.
+    len: usize,
+}
+
+impl<'a, T, const N: usize> Iterator for StaticIter<'a, T, N> {
+    type Item = (usize, &'a T);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.len > 0 {
+            let key = self.key;
+            self.key += 1;
+            if let Some(val) = self.slab.get(key) {
+                self.len -= 1;
+                return Some((key, val));
+            }
+        }
+        None
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.len, Some(self.len))
+    }
+}
+
+impl<'a, T, const N: usize> ExactSizeIterator for StaticIter<'a, T, N> {}
+
+impl<'a, T, const N: usize> FusedIterator for StaticIter<'a, T, N> {}
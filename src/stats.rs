@@ -0,0 +1,212 @@
+//! Memory usage and fragmentation statistics for `Slab`.
+
+use crate::{Chunk, ChunkAllocator, Link, Slab};
+use alloc::vec::Vec;
+use core::fmt;
+use core::mem::{self, MaybeUninit};
+
+/// The number of buckets in [`SlabStats::histogram`](struct.SlabStats.html#structfield.histogram).
+pub const HISTOGRAM_BUCKETS: usize = 10;
+
+/// The width of the occupancy bar of each chunk in a
+/// [`SlabReport`](struct.SlabReport.html).
+const BAR_WIDTH: usize = 10;
+
+/// A snapshot of the memory usage of a `Slab`.
+///
+/// See [`Slab::stats`](struct.Slab.html#method.stats).
+#[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
+pub struct SlabStats {
+    /// The number of stored values.
+    pub len: usize,
+    /// The number of values the slab can store without allocating.
+    pub capacity: usize,
+    /// The number of chunk slots, including the slots of freed chunks.
+    pub chunk_count: usize,
+    /// The number of allocated chunks.
+    pub allocated_chunks: usize,
+    /// The number of bytes allocated for the chunks and the list of chunks.
+    pub bytes_allocated: usize,
+    /// The number of allocated chunks by occupancy. Bucket `i` counts the
+    /// chunks which are at least `i * 10` percent but less than
+    /// `(i + 1) * 10` percent occupied, except that the last bucket also
+    /// counts full chunks.
+    pub histogram: [usize; HISTOGRAM_BUCKETS],
+    /// The fraction of the capacity which is vacant, between `0.0` and `1.0`.
+    /// This is `0.0` if the slab has no capacity.
+    pub fragmentation: f64,
+}
+
+impl<T, const N: usize, A: ChunkAllocator> Slab<T, N, A> {
+    /// Return statistics about the memory usage of the slab.
+    ///
+    /// This takes `O(chunk_count)` time.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use pinned_slab::*;
+    /// let mut slab = Slab::new();
+    ///
+    /// for i in 0..DEFAULT_CHUNK_SIZE * 2 {
+    ///     slab.insert(i);
+    /// }
+    /// for key in 0..DEFAULT_CHUNK_SIZE {
+    ///     slab.remove(key);
+    /// }
+    ///
+    /// let stats = slab.stats();
+    /// assert_eq!(stats.len, DEFAULT_CHUNK_SIZE);
+    /// assert_eq!(stats.capacity, DEFAULT_CHUNK_SIZE * 2);
+    /// assert_eq!(stats.histogram[0], 1);
+    /// assert_eq!(stats.histogram[9], 1);
+    /// assert_eq!(stats.fragmentation, 0.5);
+    ///
+    /// if stats.fragmentation > 0.25 {
+    ///     slab.free_unused();
+    /// }
+    /// assert_eq!(slab.stats().fragmentation, 0.0);
+    /// ```
+    pub fn stats(&self) -> SlabStats {
+        let mut histogram = [0; HISTOGRAM_BUCKETS];
+        let mut allocated_chunks = 0;
+        let mut capacity = 0;
+        let mut bytes_allocated = self.chunks.capacity() * mem::size_of::<Option<Chunk<T, A>>>();
+        for chunk in self.chunks.iter().flatten() {
+            allocated_chunks += 1;
+            capacity += chunk.size();
+            bytes_allocated += chunk.heap_size();
+            let bucket = chunk.len * HISTOGRAM_BUCKETS / chunk.size();
+            histogram[bucket.min(HISTOGRAM_BUCKETS - 1)] += 1;
+        }
+
+        let fragmentation = match capacity {
+            0 => 0.0,
+            capacity => (capacity - self.len) as f64 / capacity as f64,
+        };
+
+        SlabStats {
+            len: self.len,
+            capacity,
+            chunk_count: self.chunks.len(),
+            allocated_chunks,
+            bytes_allocated,
+            histogram,
+            fragmentation,
+        }
+    }
+}
+
+/// A human-readable report on the memory usage of a `Slab`, e.g. for
+/// logging.
+///
+/// See [`Slab::report`](struct.Slab.html#method.report).
+#[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
+pub struct SlabReport {
+    /// The statistics of the slab.
+    pub stats: SlabStats,
+    /// The number of vacant keys which have been handed out before, and will
+    /// be reused before any keys which haven't.
+    pub free_list_len: usize,
+    /// The number of occupied entries and the size of each chunk, or `None`
+    /// for chunks which aren't allocated.
+    pub chunks: Vec<Option<(usize, usize)>>,
+}
+
+impl fmt::Display for SlabReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let stats = &self.stats;
+        writeln!(
+            f,
+            "{} values, capacity {} ({:.1}% vacant)",
+            stats.len,
+            stats.capacity,
+            stats.fragmentation * 100.0
+        )?;
+        writeln!(
+            f,
+            "{} of {} chunks allocated, {} bytes",
+            stats.allocated_chunks, stats.chunk_count, stats.bytes_allocated
+        )?;
+        write!(f, "{} keys on the free list", self.free_list_len)?;
+        for (i, chunk) in self.chunks.iter().enumerate() {
+            write!(f, "\nchunk {:>4} ", i)?;
+            match *chunk {
+                Some((len, size)) => {
+                    let filled = (len * BAR_WIDTH).div_ceil(size);
+                    write!(
+                        f,
+                        "[{:<width$}] {}/{}",
+                        "#".repeat(filled),
+                        len,
+                        size,
+                        width = BAR_WIDTH
+                    )?;
+                }
+                None => f.write_str("freed")?,
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<T, const N: usize, A: ChunkAllocator> Slab<T, N, A> {
+    /// Return a report on the memory usage of the slab which can be printed
+    /// with `Display`, e.g. to diagnose fragmentation.
+    ///
+    /// This takes `O(chunk_count)` time.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use pinned_slab::*;
+    /// let mut slab: Slab<u32, 16> = Slab::with_chunk_size();
+    ///
+    /// slab.insert_many(0..40);
+    /// for key in 0..20 {
+    ///     slab.remove(key);
+    /// }
+    /// slab.free_unused();
+    ///
+    /// // 20 values, capacity 32 (37.5% vacant)
+    /// // 2 of 3 chunks allocated, 1152 bytes
+    /// // 4 keys on the free list
+    /// // chunk    0 freed
+    /// // chunk    1 [########  ] 12/16
+    /// // chunk    2 [#####     ] 8/16
+    /// let report = slab.report().to_string();
+    /// println!("{}", report);
+    ///
+    /// assert!(report.starts_with("20 values, capacity 32 (37.5% vacant)\n"));
+    /// assert!(report.ends_with("chunk    1 [########  ] 12/16\nchunk    2 [#####     ] 8/16"));
+    /// ```
+    pub fn report(&self) -> SlabReport {
+        SlabReport {
+            stats: self.stats(),
+            free_list_len: self
+                .chunks
+                .iter()
+                .flatten()
+                .map(|chunk| chunk.touched - chunk.len)
+                .sum(),
+            chunks: self
+                .chunks
+                .iter()
+                .map(|chunk| chunk.as_ref().map(|chunk| (chunk.len, chunk.size())))
+                .collect(),
+        }
+    }
+}
+
+impl<T, A: ChunkAllocator> Chunk<T, A> {
+    /// Return the number of bytes the chunk allocates.
+    pub(crate) fn heap_size(&self) -> usize {
+        mem::size_of::<MaybeUninit<T>>() * self.size()
+            + mem::size_of::<u64>() * (self.occupied.len() + self.reserved.len())
+            + mem::size_of::<Link>() * self.size()
+            + mem::size_of::<u64>() * self.size()
+            + mem::size_of::<u32>() * self.size()
+    }
+}
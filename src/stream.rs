@@ -0,0 +1,85 @@
+//! Streams over `Slab` and `FuturesSlab`, available behind the `futures`
+//! feature.
+
+use crate::{ChunkAllocator, FuturesSlab, IntoIter, Iter, Slab};
+use ::futures_core::Stream;
+use std::future::Future;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+/// A stream which yields the items of an iterator, without ever returning
+/// `Poll::Pending`.
+///
+/// See [`Slab::stream`](struct.Slab.html#method.stream) and
+/// [`Slab::into_stream`](struct.Slab.html#method.into_stream).
+#[derive(Debug, Clone)]
+pub struct IterStream<I> {
+    iter: I,
+}
+
+// The iterator is never pinned.
+impl<I> Unpin for IterStream<I> {}
+
+impl<I: Iterator> Stream for IterStream<I> {
+    type Item = I::Item;
+
+    fn poll_next(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<Option<I::Item>> {
+        Poll::Ready(self.get_mut().iter.next())
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+impl<T, const N: usize, A: ChunkAllocator> Slab<T, N, A> {
+    /// Return a stream of the keys and values of the slab, in the same order
+    /// as [`iter`](#method.iter).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use pinned_slab::*;
+    /// use futures_core::Stream;
+    /// use std::pin::Pin;
+    /// use std::task::{Context, Poll, Waker};
+    ///
+    /// let mut slab = Slab::new();
+    /// let (key, _) = slab.insert("hello");
+    ///
+    /// let mut stream = slab.stream();
+    /// let mut cx = Context::from_waker(Waker::noop());
+    /// assert_eq!(
+    ///     Pin::new(&mut stream).poll_next(&mut cx),
+    ///     Poll::Ready(Some((key, &"hello")))
+    /// );
+    /// assert_eq!(Pin::new(&mut stream).poll_next(&mut cx), Poll::Ready(None));
+    /// ```
+    pub fn stream(&self) -> IterStream<Iter<'_, T, N, A>> {
+        IterStream { iter: self.iter() }
+    }
+
+    /// Return a stream which moves the keys and values out of the slab, in
+    /// the same order as [`into_iter`](#method.into_iter), so `T` must be
+    /// `Unpin`.
+    pub fn into_stream(self) -> IterStream<IntoIter<T, N, A>>
+    where
+        T: Unpin,
+    {
+        IterStream {
+            iter: self.into_iter(),
+        }
+    }
+}
+
+impl<F: Future> Stream for FuturesSlab<F> {
+    type Item = (usize, F::Output);
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        FuturesSlab::poll_next(self.get_mut(), cx)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.len(), Some(self.len()))
+    }
+}
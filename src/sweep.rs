@@ -0,0 +1,99 @@
+//! Visiting the values of a `Slab` a few at a time, across several calls.
+
+use crate::{ChunkAllocator, Slab, NIL};
+use core::pin::Pin;
+
+/// Where an incremental sweep over a slab will continue.
+///
+/// A `Sweep` is passed to [`Slab::sweep_n`](struct.Slab.html#method.sweep_n)
+/// on every call, and remembers the key the next call starts at. It doesn't
+/// borrow the slab, so the slab can be changed freely between calls.
+///
+/// # Examples
+///
+/// ```
+/// # use pinned_slab::*;
+/// let mut sweep = Sweep::new();
+/// assert_eq!(sweep.next_key(), 0);
+/// ```
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Sweep {
+    next: usize,
+}
+
+impl Sweep {
+    /// Construct a sweep which starts at the smallest key.
+    pub fn new() -> Self {
+        Sweep::default()
+    }
+
+    /// Return the key the next call to
+    /// [`Slab::sweep_n`](struct.Slab.html#method.sweep_n) starts at.
+    pub fn next_key(&self) -> usize {
+        self.next
+    }
+}
+
+impl<T, const N: usize, A: ChunkAllocator> Slab<T, N, A> {
+    /// Visit at most `budget` values, in ascending key order from where
+    /// `sweep` left off, removing those for which `f` returns `false`.
+    ///
+    /// This is [`retain`](#method.retain) split into steps of bounded work,
+    /// e.g. one per frame. Return `true` once the sweep has passed the
+    /// largest key, in which case `sweep` starts over at the smallest key on
+    /// the next call. Values inserted during a sweep are visited in the same
+    /// pass if their keys are after the point the sweep has reached, and in
+    /// the next pass otherwise.
+    ///
+    /// Finding where to continue takes `O(chunks)` time, and every visited
+    /// value `O(1)`, so a call takes `O(chunks + budget)` time at most.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use pinned_slab::*;
+    /// let mut slab = Slab::new();
+    /// slab.insert_many(0..10);
+    ///
+    /// let mut sweep = Sweep::new();
+    /// assert!(!slab.sweep_n(&mut sweep, 4, |_, val| *val % 2 == 0));
+    /// assert_eq!(slab.keys().collect::<Vec<_>>(), [0, 2, 4, 5, 6, 7, 8, 9]);
+    ///
+    /// assert!(!slab.sweep_n(&mut sweep, 4, |_, val| *val % 2 == 0));
+    /// assert!(slab.sweep_n(&mut sweep, 4, |_, val| *val % 2 == 0));
+    /// assert_eq!(slab.keys().collect::<Vec<_>>(), [0, 2, 4, 6, 8]);
+    /// assert_eq!(sweep.next_key(), 0);
+    /// ```
+    #[track_caller]
+    pub fn sweep_n<F>(&mut self, sweep: &mut Sweep, budget: usize, mut f: F) -> bool
+    where
+        F: FnMut(usize, Pin<&mut T>) -> bool,
+    {
+        let mut key = self.next_occupied(sweep.next).unwrap_or(NIL);
+        for _ in 0..budget {
+            if key == NIL {
+                break;
+            }
+
+            let next = self.links(key).next;
+            let (slab_key, entry_key) = self.growth.locate(key);
+            let chunk = self.chunk_mut(slab_key).unwrap();
+            // SAFETY: The key is in the list of occupied entries, and values
+            // never move while they are in the slab.
+            let val =
+                unsafe { Pin::new_unchecked(&mut *chunk.entries_mut()[entry_key].as_mut_ptr()) };
+            if !f(key, val) {
+                self.drop_entry(slab_key, entry_key);
+            }
+            key = next;
+        }
+
+        if key == NIL {
+            sweep.next = 0;
+            true
+        } else {
+            sweep.next = key;
+            false
+        }
+    }
+}
@@ -0,0 +1,57 @@
+//! Small user tags stored next to each value of a `Slab`.
+
+use crate::{ChunkAllocator, Slab};
+
+impl<T, const N: usize, A: ChunkAllocator> Slab<T, N, A> {
+    /// Return the tag of the value associated with the given key.
+    ///
+    /// Every entry has room for a `u32` tag, e.g. a class or generation id,
+    /// which is stored with the bookkeeping of the slab instead of in the
+    /// value, so it can be read and changed without touching the value. A
+    /// value's tag is `0` when it is inserted, or when it is replaced with
+    /// [`replace`](#method.replace).
+    ///
+    /// If the given key is not associated with a value, then `None` is
+    /// returned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use pinned_slab::*;
+    /// let mut slab = Slab::new();
+    /// let (key, _) = slab.insert("task");
+    /// assert_eq!(slab.tag(key), Some(0));
+    ///
+    /// assert_eq!(slab.set_tag(key, 3), Some(0));
+    /// assert_eq!(slab.tag(key), Some(3));
+    ///
+    /// slab.remove(key);
+    /// assert_eq!(slab.tag(key), None);
+    /// assert_eq!(slab.insert("next").0, key);
+    /// assert_eq!(slab.tag(key), Some(0));
+    /// ```
+    pub fn tag(&self, key: usize) -> Option<u32> {
+        let (slab_key, entry_key) = self.growth.locate(key);
+        let chunk = self.chunk(slab_key)?;
+        if !chunk.is_occupied(entry_key) {
+            return None;
+        }
+
+        Some(chunk.tags[entry_key])
+    }
+
+    /// Set the tag of the value associated with the given key, returning the
+    /// old tag.
+    ///
+    /// If the given key is not associated with a value, nothing is set and
+    /// `None` is returned. See [`tag`](#method.tag).
+    pub fn set_tag(&mut self, key: usize, tag: u32) -> Option<u32> {
+        let (slab_key, entry_key) = self.growth.locate(key);
+        let chunk = self.chunk_mut(slab_key)?;
+        if !chunk.is_occupied(entry_key) {
+            return None;
+        }
+
+        Some(core::mem::replace(&mut chunk.tags[entry_key], tag))
+    }
+}
@@ -0,0 +1,85 @@
+//! [`tracing`](https://docs.rs/tracing) events for the chunks of a `Slab`,
+//! enabled by the `tracing` feature.
+//!
+//! Every event has the target `pinned_slab` and a `slab` field with the name
+//! of the slab, if it has one. Without the feature these functions do
+//! nothing.
+
+use crate::{ChunkAllocator, Slab};
+use core::fmt;
+
+impl<T, const N: usize, A: ChunkAllocator> Slab<T, N, A> {
+    /// Give the slab a name, which identifies it in `tracing` events.
+    ///
+    /// With the `tracing` feature, the slab emits a `DEBUG` event whenever it
+    /// allocates or frees a chunk, and a `WARN` event whenever it refuses a
+    /// value or fails to reserve capacity. The name is also shown by `Debug`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use pinned_slab::*;
+    /// let slab: Slab<u32> = Slab::new().named("sessions");
+    /// assert_eq!(slab.name(), Some("sessions"));
+    /// ```
+    pub fn named(mut self, name: &'static str) -> Self {
+        self.name = Some(name);
+        self
+    }
+
+    /// Return the name of the slab, if it has one.
+    ///
+    /// See [`named`](#method.named).
+    pub fn name(&self) -> Option<&'static str> {
+        self.name
+    }
+}
+
+/// Emit an event for chunk `chunk` of `slab`, which has `size` entries and
+/// takes `bytes` bytes, being allocated.
+#[inline]
+pub(crate) fn chunk_allocated(slab: Option<&str>, chunk: usize, size: usize, bytes: usize) {
+    #[cfg(feature = "tracing")]
+    tracing::debug!(
+        target: "pinned_slab",
+        slab = slab,
+        chunk = chunk,
+        size = size,
+        bytes = bytes,
+        "chunk allocated"
+    );
+    #[cfg(not(feature = "tracing"))]
+    let _ = (slab, chunk, size, bytes);
+}
+
+/// Emit an event for chunk `chunk` of `slab`, which has `size` entries, being
+/// freed.
+#[inline]
+pub(crate) fn chunk_freed(slab: Option<&str>, chunk: usize, size: usize) {
+    #[cfg(feature = "tracing")]
+    tracing::debug!(
+        target: "pinned_slab",
+        slab = slab,
+        chunk = chunk,
+        size = size,
+        "chunk freed"
+    );
+    #[cfg(not(feature = "tracing"))]
+    let _ = (slab, chunk, size);
+}
+
+/// Emit an event for `slab` refusing a value or failing to grow, because of
+/// `reason`.
+#[inline]
+pub(crate) fn limit_reached(slab: Option<&str>, len: usize, reason: &dyn fmt::Display) {
+    #[cfg(feature = "tracing")]
+    tracing::warn!(
+        target: "pinned_slab",
+        slab = slab,
+        len = len,
+        reason = tracing::field::display(reason),
+        "slab capacity limit reached"
+    );
+    #[cfg(not(feature = "tracing"))]
+    let _ = (slab, len, reason);
+}
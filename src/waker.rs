@@ -0,0 +1,150 @@
+//! A slab of wakers for async executors and I/O drivers.
+
+use crate::AtomicSlab;
+use std::fmt;
+use std::sync::{Mutex, PoisonError};
+use std::task::Waker;
+
+/// A slab of [`Waker`](std::task::Waker)s which can be registered from `poll`
+/// and woken from any thread, e.g. by an I/O driver.
+///
+/// Each registration keeps its key until it is
+/// [`unregister`](#method.unregister)ed, so a task can update its waker on
+/// every poll without allocating a new key. Waking a key takes its waker, so
+/// each registration is woken at most once until it is updated again.
+///
+/// Registrations are stored in an [`AtomicSlab`](struct.AtomicSlab.html), so
+/// registering and unregistering never block each other.
+///
+/// # Examples
+///
+/// ```
+/// # use pinned_slab::*;
+/// use std::future::Future;
+/// use std::pin::Pin;
+/// use std::sync::Arc;
+/// use std::sync::atomic::{AtomicBool, Ordering};
+/// use std::task::{Context, Poll};
+///
+/// struct Readiness {
+///     wakers: Arc<WakerSlab>,
+///     ready: Arc<AtomicBool>,
+///     key: Option<usize>,
+/// }
+///
+/// impl Future for Readiness {
+///     type Output = ();
+///
+///     fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+///         if self.ready.load(Ordering::Acquire) {
+///             if let Some(key) = self.key.take() {
+///                 self.wakers.unregister(key);
+///             }
+///             return Poll::Ready(());
+///         }
+///         match self.key {
+///             Some(key) => {
+///                 self.wakers.update(key, cx.waker());
+///             }
+///             None => self.key = Some(self.wakers.register(cx.waker())),
+///         }
+///         Poll::Pending
+///     }
+/// }
+/// ```
+pub struct WakerSlab {
+    wakers: AtomicSlab<Mutex<Option<Waker>>>,
+}
+
+impl WakerSlab {
+    /// Construct a new, empty `WakerSlab`.
+    pub fn new() -> Self {
+        WakerSlab {
+            wakers: AtomicSlab::new(),
+        }
+    }
+
+    /// Return the number of registrations.
+    pub fn len(&self) -> usize {
+        self.wakers.len()
+    }
+
+    /// Return `true` if there are no registrations.
+    pub fn is_empty(&self) -> bool {
+        self.wakers.is_empty()
+    }
+
+    /// Return `true` if the given key is registered.
+    pub fn contains(&self, key: usize) -> bool {
+        self.wakers.contains(key)
+    }
+
+    /// Register a clone of `waker`, returning its key.
+    pub fn register(&self, waker: &Waker) -> usize {
+        self.wakers.insert(Mutex::new(Some(waker.clone())))
+    }
+
+    /// Replace the waker of the given key with a clone of `waker`, unless
+    /// they would wake the same task.
+    ///
+    /// Return `false` if the key isn't registered.
+    pub fn update(&self, key: usize, waker: &Waker) -> bool {
+        let slot = match self.wakers.get(key) {
+            Some(slot) => slot,
+            None => return false,
+        };
+        let mut current = slot.lock().unwrap_or_else(PoisonError::into_inner);
+        match &*current {
+            Some(current) if current.will_wake(waker) => {}
+            _ => *current = Some(waker.clone()),
+        }
+        true
+    }
+
+    /// Take the waker of the given key and wake it.
+    ///
+    /// Return `false` if the key isn't registered, or it was already woken
+    /// since its waker was last updated.
+    pub fn wake(&self, key: usize) -> bool {
+        let waker = match self.wakers.get(key) {
+            Some(slot) => slot.lock().unwrap_or_else(PoisonError::into_inner).take(),
+            None => return false,
+        };
+        match waker {
+            Some(waker) => {
+                waker.wake();
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Wake every registered waker, returning how many were woken.
+    ///
+    /// Keys which are registered while this runs may or may not be woken.
+    pub fn wake_all(&self) -> usize {
+        (0..self.wakers.key_bound())
+            .filter(|&key| self.wake(key))
+            .count()
+    }
+
+    /// Remove the registration of the given key, returning whether it was
+    /// registered.
+    pub fn unregister(&self, key: usize) -> bool {
+        self.wakers.remove(key)
+    }
+}
+
+impl Default for WakerSlab {
+    fn default() -> Self {
+        WakerSlab::new()
+    }
+}
+
+impl fmt::Debug for WakerSlab {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("WakerSlab")
+            .field("len", &self.len())
+            .finish()
+    }
+}
@@ -0,0 +1,72 @@
+//! Zeroing of vacated entries, enabled by the `zeroize` feature.
+//!
+//! With the feature, the memory of every entry is zeroed when its value is
+//! removed or dropped, and every chunk is zeroed before it is freed, so no
+//! copies of removed values are left behind in the slab's memory. Without the
+//! feature these functions do nothing. The zeroes take precedence over the
+//! `poison` feature, which then only records where entries were freed.
+
+#[cfg(feature = "zeroize")]
+use crate::Slab;
+use crate::{Chunk, ChunkAllocator};
+#[cfg(feature = "zeroize")]
+use zeroize::{Zeroize, ZeroizeOnDrop};
+
+impl<T, A: ChunkAllocator> Chunk<T, A> {
+    /// Zero the memory of the vacated entry at `entry_key`.
+    #[inline]
+    pub fn wipe(&mut self, entry_key: usize) {
+        #[cfg(feature = "zeroize")]
+        // SAFETY: The entry is vacant, so nothing refers to it anymore.
+        unsafe {
+            self.entries_mut()[entry_key].zeroize();
+        }
+        #[cfg(not(feature = "zeroize"))]
+        let _ = entry_key;
+    }
+
+    /// Zero the memory of every entry, which must all be vacant.
+    #[inline]
+    pub fn wipe_all(&mut self) {
+        #[cfg(feature = "zeroize")]
+        // SAFETY: Every entry is vacant.
+        unsafe {
+            self.entries_mut().zeroize();
+        }
+    }
+}
+
+/// Zeroize every value in place, then drop them all. The chunks stay
+/// allocated, like with [`Slab::clear`](struct.Slab.html#method.clear).
+///
+/// Some `Zeroize` implementations move the value out, e.g. the one for
+/// `Option` uses `take`, so the values must be `Unpin`.
+///
+/// Requires the `zeroize` feature.
+///
+/// # Examples
+///
+/// ```
+/// # use pinned_slab::*;
+/// use zeroize::Zeroize;
+///
+/// let mut slab = Slab::new();
+/// slab.insert([0x42u8; 32]);
+///
+/// slab.zeroize();
+/// assert!(slab.is_empty());
+/// ```
+#[cfg(feature = "zeroize")]
+impl<T: Zeroize + Unpin, const N: usize, A: ChunkAllocator> Zeroize for Slab<T, N, A> {
+    fn zeroize(&mut self) {
+        for (_, val) in self.iter_pin_mut() {
+            val.get_mut().zeroize();
+        }
+        self.clear();
+    }
+}
+
+/// The slab zeroes the memory of its values when it is dropped, so it
+/// zeroizes on drop if its values do.
+#[cfg(feature = "zeroize")]
+impl<T: ZeroizeOnDrop, const N: usize, A: ChunkAllocator> ZeroizeOnDrop for Slab<T, N, A> {}
@@ -0,0 +1,16 @@
+#![cfg(feature = "allocator-api2")]
+
+use allocator_api2::alloc::Global as Api2Global;
+use pinned_slab::*;
+
+#[test]
+fn allocator_api2() {
+    let mut slab = Slab::new_in(Api2Global);
+
+    for i in 0..DEFAULT_CHUNK_SIZE + 1 {
+        slab.insert(i);
+    }
+
+    assert_eq!(slab.capacity(), DEFAULT_CHUNK_SIZE * 2);
+    assert!(slab.iter().all(|(key, &val)| key == val));
+}
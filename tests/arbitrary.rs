@@ -0,0 +1,29 @@
+#![cfg(feature = "arbitrary")]
+
+use arbitrary::{Arbitrary, Unstructured};
+use pinned_slab::*;
+
+#[test]
+fn arbitrary_slab() {
+    let mut state = 1u32;
+    let data: Vec<u8> = (0..4096)
+        .map(|_| {
+            state = state.wrapping_mul(1_103_515_245).wrapping_add(12345);
+            (state >> 16) as u8
+        })
+        .collect();
+    let mut u = Unstructured::new(&data);
+    let mut fragmented = 0;
+    while !u.is_empty() {
+        let slab = Slab::<u8, 16>::arbitrary(&mut u).unwrap();
+        assert_eq!(slab.keys().count(), slab.len());
+        let last = slab.keys().next_back().unwrap_or(0);
+        if slab.vacant_keys().any(|key| key < last) {
+            fragmented += 1;
+        }
+    }
+    assert!(fragmented > 0);
+
+    let slab = Slab::<u8>::arbitrary(&mut Unstructured::new(&[])).unwrap();
+    assert!(slab.is_empty());
+}
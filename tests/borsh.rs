@@ -0,0 +1,29 @@
+#![cfg(feature = "borsh")]
+
+use pinned_slab::*;
+
+#[test]
+fn borsh_round_trip() {
+    let mut slab = Slab::new();
+    slab.insert_many((0..5).map(|i| i.to_string()));
+    slab.remove(1);
+
+    let bytes = borsh::to_vec(&slab).unwrap();
+    let mut copy: Slab<String> = borsh::from_slice(&bytes).unwrap();
+    assert!(copy.iter().eq(slab.iter()));
+    assert_eq!(copy.insert("1".to_string()).0, 1);
+
+    // One value with key 2, followed by one with key 1.
+    let mut unsorted = 2u32.to_le_bytes().to_vec();
+    for key in [2u64, 1] {
+        unsorted.extend_from_slice(&key.to_le_bytes());
+        unsorted.push(0);
+    }
+    assert!(borsh::from_slice::<Slab<u8>>(&unsorted).is_err());
+
+    // One value with the largest possible key.
+    let mut huge = 1u32.to_le_bytes().to_vec();
+    huge.extend_from_slice(&u64::MAX.to_le_bytes());
+    huge.push(0);
+    assert!(borsh::from_slice::<Slab<u8>>(&huge).is_err());
+}
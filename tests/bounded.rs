@@ -0,0 +1,26 @@
+use pinned_slab::*;
+
+#[test]
+fn bounded_slab() {
+    let mut slab = BoundedSlab::new(DEFAULT_CHUNK_SIZE + 1);
+
+    for i in 0..DEFAULT_CHUNK_SIZE + 1 {
+        assert!(slab.insert(i).is_ok());
+    }
+    assert!(slab.is_full());
+    assert_eq!(slab.insert(0), Err(0));
+    assert_eq!(slab.len(), DEFAULT_CHUNK_SIZE + 1);
+
+    slab.remove(10);
+    *slab.get_pin_mut(11).unwrap() = 0;
+    assert_eq!(slab.insert(1).unwrap().0, 10);
+    assert!(slab.is_full());
+
+    slab.set_limit(DEFAULT_CHUNK_SIZE * 2);
+    assert!(!slab.is_full());
+    assert!(slab.insert(2).is_ok());
+
+    let slab = slab.into_inner();
+    assert_eq!(slab.len(), DEFAULT_CHUNK_SIZE + 2);
+    assert_eq!(slab[11], 0);
+}
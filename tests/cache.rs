@@ -0,0 +1,56 @@
+#![cfg(feature = "cache")]
+
+use pinned_slab::*;
+use std::time::{Duration, Instant};
+
+#[test]
+fn lru_eviction() {
+    let mut cache = LruSlab::new(3);
+    let (a, _) = cache.insert('a');
+    let (b, _) = cache.insert('b');
+    let (c, _) = cache.insert('c');
+
+    assert_eq!(cache.get(a), Some(&'a'));
+    assert!(cache.touch(b));
+    assert_eq!(cache.peek(c), Some(&'c'));
+    let order: Vec<_> = cache.iter().map(|(key, _)| key).collect();
+    assert_eq!(order, [b, a, c]);
+
+    let (d, evicted) = cache.insert('d');
+    assert_eq!(evicted, Some((c, 'c')));
+    assert_eq!(d, c);
+    assert_eq!(cache.len(), 3);
+    assert_eq!(cache.lru(), Some(a));
+
+    assert_eq!(cache.remove(a), Some('a'));
+    assert_eq!(cache.remove(a), None);
+    let (_, evicted) = cache.insert('e');
+    assert_eq!(evicted, None);
+    assert_eq!(cache.lru(), Some(b));
+
+    cache.clear();
+    assert!(cache.is_empty());
+    assert_eq!(cache.lru(), None);
+}
+
+#[test]
+fn ttl_expiry() {
+    let ttl = Duration::from_secs(10);
+    let mut cache = LruSlab::with_ttl(8, ttl);
+    let (a, _) = cache.insert(1);
+    let (b, _) = cache.insert(2);
+    let (c, _) = cache.insert(3);
+    cache.touch(a);
+
+    let start = Instant::now();
+    assert!(cache.evict_expired(start).is_empty());
+    assert_eq!(
+        cache.evict_expired(start + ttl * 2),
+        [(b, 2), (c, 3), (a, 1)]
+    );
+    assert!(cache.is_empty());
+
+    let mut cache = LruSlab::new(1);
+    cache.insert(1);
+    assert!(cache.evict_expired(start + ttl * 2).is_empty());
+}
@@ -0,0 +1,104 @@
+#![cfg(feature = "epoch")]
+
+use pinned_slab::*;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::thread;
+
+struct Counted(usize, Arc<AtomicUsize>);
+
+impl Drop for Counted {
+    fn drop(&mut self) {
+        self.1.fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+/// Advance the epoch until `dropped` reaches `expected`.
+fn collect(dropped: &AtomicUsize, expected: usize) {
+    for _ in 0..100_000 {
+        if dropped.load(Ordering::Relaxed) == expected {
+            return;
+        }
+        crossbeam_epoch::pin().flush();
+    }
+    panic!(
+        "{} values were dropped, expected {}",
+        dropped.load(Ordering::Relaxed),
+        expected
+    );
+}
+
+#[test]
+fn epoch_slab() {
+    let dropped = Arc::new(AtomicUsize::new(0));
+    let slab = EpochSlab::new();
+
+    thread::scope(|s| {
+        for t in 0..8 {
+            let (slab, dropped) = (&slab, &dropped);
+            s.spawn(move || {
+                let mut keys = Vec::new();
+                for i in 0..2000 {
+                    let key = slab.insert(Counted(t * 10000 + i, dropped.clone()));
+                    let guard = crossbeam_epoch::pin();
+                    let value = slab.get(key, &guard).unwrap();
+                    assert_eq!(value.0, t * 10000 + i);
+                    if i % 3 == 0 {
+                        assert!(slab.remove(key));
+                        assert!(!slab.remove(key));
+                        assert!(slab.get(key, &guard).is_none());
+                        // The value is still alive while `guard` is pinned.
+                        assert_eq!(value.0, t * 10000 + i);
+                    } else {
+                        keys.push(key);
+                    }
+                }
+                let guard = crossbeam_epoch::pin();
+                for key in keys {
+                    assert!(slab.contains(key));
+                    assert!(slab.get(key, &guard).is_some());
+                }
+            });
+        }
+    });
+
+    collect(&dropped, 8 * 667);
+    assert_eq!(slab.len(), 8 * 1333);
+    drop(slab);
+    assert_eq!(dropped.load(Ordering::Relaxed), 8 * 2000);
+}
+
+#[test]
+fn epoch_slab_reclaims_after_unpin() {
+    let dropped = Arc::new(AtomicUsize::new(0));
+    let slab = EpochSlab::new();
+    let key = slab.insert(Counted(1, dropped.clone()));
+
+    let guard = crossbeam_epoch::pin();
+    let value = slab.get(key, &guard).unwrap();
+    assert!(slab.remove(key));
+    for _ in 0..1000 {
+        crossbeam_epoch::pin().flush();
+    }
+    assert_eq!(value.0, 1);
+    assert_eq!(dropped.load(Ordering::Relaxed), 0);
+    assert_eq!(slab.len(), 1);
+
+    drop(guard);
+    collect(&dropped, 1);
+    assert_eq!(slab.len(), 0);
+    // The slot is only reused once the value has been dropped.
+    assert_eq!(slab.insert(Counted(2, dropped.clone())), key);
+}
+
+#[test]
+#[should_panic(expected = "guard is not pinned")]
+fn epoch_slab_rejects_foreign_guards() {
+    let slab = EpochSlab::new();
+    let key = slab.insert(0);
+
+    let collector = crossbeam_epoch::Collector::new();
+    let handle = collector.register();
+    let guard = handle.pin();
+    slab.get(key, &guard);
+}
@@ -0,0 +1,35 @@
+#![cfg(feature = "ffi")]
+
+use pinned_slab::ffi::*;
+use std::ffi::c_void;
+use std::ptr;
+
+#[test]
+fn ffi() {
+    let mut values = [1u32, 2, 3];
+    unsafe {
+        let slab = pinned_slab_new();
+        let a = pinned_slab_insert(slab, &mut values[0] as *mut u32 as *mut c_void);
+        let b = pinned_slab_insert(slab, &mut values[1] as *mut u32 as *mut c_void);
+        let entry = pinned_slab_get(slab, a);
+
+        // The entry stays put while the slab changes around it.
+        for _ in 0..100 {
+            let key = pinned_slab_insert(slab, ptr::null_mut());
+            assert!(pinned_slab_remove(slab, key, ptr::null_mut()));
+        }
+        assert_eq!(pinned_slab_get(slab, a), entry);
+        *entry = &mut values[2] as *mut u32 as *mut c_void;
+
+        let mut removed = ptr::null_mut();
+        assert!(pinned_slab_remove(slab, a, &mut removed));
+        assert_eq!(*(removed as *mut u32), 3);
+        assert!(!pinned_slab_remove(slab, a, &mut removed));
+        assert!(pinned_slab_get(slab, a).is_null());
+        assert_eq!(pinned_slab_len(slab), 1);
+        assert_eq!(**(pinned_slab_get(slab, b) as *mut *mut u32), 2);
+
+        pinned_slab_free(slab);
+        pinned_slab_free(ptr::null_mut());
+    }
+}
@@ -0,0 +1,41 @@
+#![cfg(feature = "futures")]
+
+use futures_core::Stream;
+use pinned_slab::*;
+use std::future;
+use std::pin::Pin;
+use std::task::{Context, Poll, Waker};
+
+fn collect<S: Stream + Unpin>(mut stream: S) -> Vec<S::Item> {
+    let mut cx = Context::from_waker(Waker::noop());
+    let mut items = Vec::new();
+    while let Poll::Ready(Some(item)) = Pin::new(&mut stream).poll_next(&mut cx) {
+        items.push(item);
+    }
+    items
+}
+
+#[test]
+fn slab_stream() {
+    let mut slab = Slab::new();
+    slab.insert_many(0..10);
+    slab.remove(3);
+
+    let stream = slab.stream();
+    assert_eq!(stream.size_hint(), (9, Some(9)));
+    let expected: Vec<_> = slab.iter().collect();
+    assert_eq!(collect(stream), expected);
+
+    let expected: Vec<_> = slab.iter().map(|(key, val)| (key, *val)).collect();
+    assert_eq!(collect(slab.into_stream()), expected);
+}
+
+#[test]
+fn futures_slab_stream() {
+    let mut futures = FuturesSlab::new();
+    let a = futures.insert(future::ready(1));
+    let b = futures.insert(future::ready(2));
+
+    assert_eq!(futures.size_hint(), (2, Some(2)));
+    assert_eq!(collect(futures), [(a, 1), (b, 2)]);
+}
@@ -0,0 +1,84 @@
+#![cfg(loom)]
+
+// Model-checks the lock-free slabs. Run with
+// `RUSTFLAGS="--cfg loom" cargo test --release --test loom`.
+
+use loom::sync::Arc;
+use loom::thread;
+use pinned_slab::*;
+
+#[test]
+fn concurrent_inserts() {
+    loom::model(|| {
+        let slab = Arc::new(AtomicSlab::new());
+        let other = slab.clone();
+        let thread = thread::spawn(move || other.insert(1));
+        let key = slab.insert(2);
+        let other_key = thread.join().unwrap();
+
+        assert_ne!(key, other_key);
+        assert_eq!(*slab.get(key).unwrap(), 2);
+        assert_eq!(*slab.get(other_key).unwrap(), 1);
+        assert_eq!(slab.len(), 2);
+    });
+}
+
+#[test]
+fn get_while_removing() {
+    loom::model(|| {
+        let slab = Arc::new(AtomicSlab::new());
+        let key = slab.insert(String::from("value"));
+        let other = slab.clone();
+        let thread = thread::spawn(move || {
+            if let Some(value) = other.get(key) {
+                assert_eq!(*value, "value");
+            }
+        });
+        assert!(slab.remove(key));
+        thread.join().unwrap();
+
+        assert!(slab.get(key).is_none());
+        assert!(slab.is_empty());
+    });
+}
+
+#[test]
+fn remove_and_reuse() {
+    loom::model(|| {
+        let slab = Arc::new(AtomicSlab::new());
+        let first = slab.insert(1);
+        let second = slab.insert(2);
+        let other = slab.clone();
+        let thread = thread::spawn(move || {
+            assert!(other.remove(first));
+            other.insert(3)
+        });
+        assert!(slab.remove(second));
+        let key = slab.insert(4);
+        let other_key = thread.join().unwrap();
+
+        // Both removed slots are reused, each by exactly one insert.
+        assert_ne!(key, other_key);
+        assert!(key == first || key == second);
+        assert!(other_key == first || other_key == second);
+        assert_eq!(slab.len(), 2);
+    });
+}
+
+#[test]
+fn weak_upgrade_while_dropping() {
+    loom::model(|| {
+        let slab = std::sync::Arc::new(AtomicSlab::new());
+        let arc = slab.insert_arc(1);
+        let weak = SlabArc::downgrade(&arc);
+        let thread = thread::spawn(move || {
+            if let Some(arc) = weak.upgrade() {
+                assert_eq!(*arc, 1);
+            }
+        });
+        drop(arc);
+        thread.join().unwrap();
+
+        assert!(slab.is_empty());
+    });
+}
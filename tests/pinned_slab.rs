@@ -21,19 +21,19 @@ fn insert_get_remove_one() {
 fn insert_get_many() {
     let mut slab = Slab::new();
 
-    for i in 0..CHUNK_SIZE {
+    for i in 0..DEFAULT_CHUNK_SIZE {
         let (key, _) = slab.insert(i + 10);
         assert_eq!(slab[key], i + 10);
     }
 
-    assert_eq!(slab.capacity(), CHUNK_SIZE);
+    assert_eq!(slab.capacity(), DEFAULT_CHUNK_SIZE);
 
     // Storing another one grows the slab
     let (key, _) = slab.insert(20);
     assert_eq!(slab[key], 20);
 
     // Capacity grows by 2x
-    assert_eq!(slab.capacity(), 2 * CHUNK_SIZE);
+    assert_eq!(slab.capacity(), 2 * DEFAULT_CHUNK_SIZE);
 }
 
 #[test]
@@ -42,7 +42,7 @@ fn insert_get_remove_many() {
     let mut keys = vec![];
 
     for i in 0..10 {
-        for j in 0..CHUNK_SIZE {
+        for j in 0..DEFAULT_CHUNK_SIZE {
             let val = (i * 10) + j;
 
             let (key, _) = slab.insert(val);
@@ -55,7 +55,7 @@ fn insert_get_remove_many() {
         }
     }
 
-    assert_eq!(CHUNK_SIZE, slab.capacity());
+    assert_eq!(DEFAULT_CHUNK_SIZE, slab.capacity());
 }
 
 #[test]
@@ -81,6 +81,44 @@ fn invalid_remove_panics() {
     slab.remove(0);
 }
 
+#[test]
+fn remove_in_place() {
+    use std::cell::Cell;
+    use std::marker::PhantomPinned;
+
+    struct Node<'a> {
+        addr: usize,
+        dropped_at: &'a Cell<usize>,
+        _pin: PhantomPinned,
+    }
+
+    impl Drop for Node<'_> {
+        fn drop(&mut self) {
+            self.dropped_at.set(self as *const Self as usize);
+        }
+    }
+
+    let dropped_at = Cell::new(0);
+    let mut slab = Slab::new();
+    let (key, _) = slab.insert(Node {
+        addr: 0,
+        dropped_at: &dropped_at,
+        _pin: PhantomPinned,
+    });
+    // SAFETY: `addr` isn't structurally pinned.
+    unsafe {
+        let node = slab.get_pin_mut(key).unwrap().get_unchecked_mut();
+        node.addr = node as *const Node as usize;
+    }
+    let addr = slab[key].addr;
+
+    assert!(slab.remove_in_place(key));
+    assert_eq!(dropped_at.get(), addr);
+    assert!(!slab.contains(key));
+    assert!(slab.is_empty());
+    assert!(!slab.remove_in_place(key));
+}
+
 #[test]
 fn slab_get_mut() {
     let mut slab = Slab::new();
@@ -99,12 +137,10 @@ fn retain() {
     let (key1, _) = slab.insert(0);
     let (key2, _) = slab.insert(1);
 
-    unsafe {
-        slab.retain(|key, x| {
-            assert_eq!(key, *x);
-            *x % 2 == 0
-        })
-    };
+    slab.retain(|key, x| {
+        assert_eq!(key, *x);
+        *x % 2 == 0
+    });
 
     assert_eq!(slab.len(), 1);
     assert_eq!(slab[key1], 0);
@@ -116,6 +152,211 @@ fn retain() {
     assert_eq!(2, slab.len());
 }
 
+#[test]
+fn retain_sparse() {
+    use std::rc::Rc;
+
+    let mut slab = Slab::new();
+    let counter = Rc::new(());
+
+    for _ in 0..DEFAULT_CHUNK_SIZE * 8 {
+        slab.insert(counter.clone());
+    }
+    for key in 0..DEFAULT_CHUNK_SIZE * 8 {
+        if key % 100 != 0 {
+            slab.remove(key);
+        }
+    }
+    let addr = slab.as_ptr(300).unwrap();
+
+    let mut visited = Vec::new();
+    slab.retain(|key, _| {
+        visited.push(key);
+        key % 200 != 0
+    });
+
+    let live: Vec<_> = (0..DEFAULT_CHUNK_SIZE * 8).step_by(100).collect();
+    assert_eq!(visited, live);
+    assert_eq!(slab.len(), Rc::strong_count(&counter) - 1);
+    assert_eq!(
+        slab.len(),
+        live.iter().filter(|&&key| key % 200 != 0).count()
+    );
+    assert_eq!(slab.as_ptr(300), Some(addr));
+    assert!(!slab.contains(200));
+
+    // The freed keys are handed out again.
+    let keys: Vec<_> = (0..live.len() - slab.len())
+        .map(|_| slab.insert(counter.clone()).0)
+        .collect();
+    assert!(keys.iter().all(|key| live.contains(key) && key % 200 == 0));
+}
+
+#[test]
+fn first_and_last() {
+    let mut slab = Slab::new();
+    assert_eq!(slab.first(), None);
+    assert_eq!(slab.max_key(), None);
+
+    slab.insert_many(0..DEFAULT_CHUNK_SIZE * 3);
+    for key in (DEFAULT_CHUNK_SIZE * 2 + 7..DEFAULT_CHUNK_SIZE * 3).chain(0..100) {
+        slab.remove(key);
+    }
+    assert_eq!(slab.first(), Some((100, &100)));
+    assert_eq!(
+        slab.last(),
+        Some((DEFAULT_CHUNK_SIZE * 2 + 6, &(DEFAULT_CHUNK_SIZE * 2 + 6)))
+    );
+
+    *slab.first_pin_mut().unwrap().1 = 0;
+    *slab.last_pin_mut().unwrap().1 = 0;
+    assert_eq!(slab[100], 0);
+    assert_eq!(slab[DEFAULT_CHUNK_SIZE * 2 + 6], 0);
+
+    // A key inserted below the smallest one becomes the first.
+    let (key, _) = slab.insert(1);
+    assert_eq!(slab.min_key(), Some(key));
+    assert_eq!(key, 99);
+
+    slab.clear();
+    assert_eq!(slab.min_key(), None);
+    assert_eq!(slab.last(), None);
+}
+
+#[test]
+fn find() {
+    let mut slab = Slab::new();
+    slab.insert_many(0..DEFAULT_CHUNK_SIZE * 4);
+    slab.retain(|key, _| key % 1000 == 999);
+
+    let mut visited = Vec::new();
+    let found = slab.find(|key, &val| {
+        visited.push(key);
+        val > DEFAULT_CHUNK_SIZE
+    });
+    let expected = (999..)
+        .step_by(1000)
+        .find(|&key| key > DEFAULT_CHUNK_SIZE)
+        .unwrap();
+    assert_eq!(found, Some((expected, &expected)));
+    assert_eq!(visited, (999..=expected).step_by(1000).collect::<Vec<_>>());
+
+    assert_eq!(slab.position(|_, &val| val == 1999), Some(1999));
+    assert_eq!(slab.position(|_, &val| val == 2000), None);
+    assert_eq!(slab.find_map(|key, &val| Some(key + val)), Some(999 * 2));
+    assert_eq!(Slab::<u8>::new().find_map(|_, _| Some(())), None);
+}
+
+#[test]
+fn iteration_order() {
+    let mut slab = Slab::new();
+    slab.insert_many(0..DEFAULT_CHUNK_SIZE * 3);
+    slab.retain(|key, _| key % 5 != 0);
+    // Reused keys go in the middle, new ones at the end.
+    for _ in 0..DEFAULT_CHUNK_SIZE {
+        slab.insert(0);
+    }
+
+    let mut sorted: Vec<_> = slab.keys().collect();
+    sorted.sort_unstable();
+    assert_eq!(slab.keys().collect::<Vec<_>>(), sorted);
+    assert_eq!(slab.iter().map(|(key, _)| key).collect::<Vec<_>>(), sorted);
+    assert_eq!(
+        slab.iter_pin_mut().map(|(key, _)| key).collect::<Vec<_>>(),
+        sorted
+    );
+    let mut rev = sorted.clone();
+    rev.reverse();
+    assert_eq!(slab.keys().rev().collect::<Vec<_>>(), rev);
+
+    // Paging through with `iter_from` sees every key once.
+    let mut paged = Vec::new();
+    let mut from = 0;
+    loop {
+        let page: Vec<_> = slab.iter_from(from).take(100).map(|(key, _)| key).collect();
+        match page.last() {
+            Some(&last) => from = last + 1,
+            None => break,
+        }
+        paged.extend(page);
+    }
+    assert_eq!(paged, sorted);
+    assert_eq!(slab.iter_from(slab.max_key().unwrap() + 1).next(), None);
+}
+
+#[test]
+fn sweep_n() {
+    let mut slab = Slab::new();
+    slab.insert_many(0..DEFAULT_CHUNK_SIZE * 2);
+    slab.retain(|key, _| key % 10 == 0);
+    let live = slab.len();
+
+    let mut sweep = Sweep::new();
+    let mut visited = Vec::new();
+    let mut calls = 0;
+    loop {
+        calls += 1;
+        let done = slab.sweep_n(&mut sweep, 7, |key, _| {
+            visited.push(key);
+            key % 20 == 0
+        });
+        // Keys inserted behind the sweep wait for the next pass.
+        slab.insert(usize::MAX);
+        if done {
+            break;
+        }
+        assert!(visited.len() <= calls * 7);
+    }
+
+    let expected: Vec<_> = (0..DEFAULT_CHUNK_SIZE * 2).step_by(10).collect();
+    assert_eq!(visited, expected);
+    assert_eq!(calls, live.div_ceil(7));
+    assert_eq!(sweep.next_key(), 0);
+    assert!(slab
+        .iter()
+        .all(|(key, &val)| key % 20 == 0 || val == usize::MAX));
+
+    // The next pass visits the values inserted during the first.
+    visited.clear();
+    while !slab.sweep_n(&mut sweep, 100, |key, _| {
+        visited.push(key);
+        true
+    }) {}
+    assert_eq!(visited, slab.keys().collect::<Vec<_>>());
+}
+
+#[test]
+fn lending_iter_mut() {
+    use std::marker::PhantomPinned;
+
+    struct Node {
+        val: usize,
+        _pin: PhantomPinned,
+    }
+
+    let mut slab = Slab::new();
+    for val in 0..DEFAULT_CHUNK_SIZE + 10 {
+        slab.insert(Node {
+            val,
+            _pin: PhantomPinned,
+        });
+    }
+    slab.retain(|key, _| key % 3 != 0);
+
+    let mut keys = Vec::new();
+    let mut iter = slab.lending_iter_mut();
+    while let Some((key, node)) = iter.next() {
+        // SAFETY: `val` isn't structurally pinned.
+        unsafe { node.get_unchecked_mut().val *= 2 };
+        keys.push(key);
+        assert_eq!(iter.slab()[key].val, key * 2);
+    }
+    assert!(iter.next().is_none());
+
+    assert_eq!(keys, slab.keys().collect::<Vec<_>>());
+    assert!(slab.iter().all(|(key, node)| node.val == key * 2));
+}
+
 #[test]
 fn iter() {
     let mut slab = Slab::new();
@@ -141,6 +382,7 @@ fn iter() {
 }
 
 #[test]
+#[allow(deprecated)]
 fn iter_mut() {
     let mut slab = Slab::new();
 
@@ -150,7 +392,7 @@ fn iter_mut() {
 
     for (i, (key, e)) in unsafe { slab.iter_mut() }.enumerate() {
         assert_eq!(i, key);
-        *e = *e + 1;
+        *e += 1;
     }
 
     let vals: Vec<_> = slab.iter().map(|(_, r)| *r).collect();
@@ -159,9 +401,2462 @@ fn iter_mut() {
     slab.remove(2);
 
     for (_, e) in unsafe { slab.iter_mut() } {
-        *e = *e + 1;
+        *e += 1;
     }
 
     let vals: Vec<_> = slab.iter().map(|(_, r)| *r).collect();
     assert_eq!(vals, vec![2, 3, 5]);
 }
+
+#[test]
+fn key_of_ptr() {
+    let mut slab = Slab::new();
+    let mut ptrs = vec![];
+
+    for i in 0..DEFAULT_CHUNK_SIZE + 10 {
+        let (key, val) = slab.insert(i);
+        ptrs.push((key, val as *const usize));
+    }
+
+    for &(key, ptr) in &ptrs {
+        assert_eq!(slab.key_of_ptr(ptr), Some(key));
+        assert!(slab.contains_ptr(ptr));
+    }
+
+    let (key, ptr) = ptrs[DEFAULT_CHUNK_SIZE + 3];
+    slab.remove(key);
+    assert_eq!(slab.key_of_ptr(ptr), None);
+
+    // Pointers into the middle of an entry are rejected
+    let misaligned = (ptrs[0].1 as *const u8).wrapping_add(1) as *const usize;
+    assert_eq!(slab.key_of_ptr(misaligned), None);
+}
+
+#[test]
+fn get_key_value() {
+    let mut slab = Slab::new();
+    let (key, _) = slab.insert(10);
+
+    assert_eq!(slab.get_key_value(key), Some((key, &10)));
+
+    {
+        let (k, mut v) = slab.get_key_value_pin_mut(key).unwrap();
+        assert_eq!(k, key);
+        *v = 11;
+    }
+    assert_eq!(slab[key], 11);
+
+    slab.remove(key);
+    assert_eq!(slab.get_key_value(key), None);
+    assert!(slab.get_key_value_pin_mut(key).is_none());
+}
+
+#[test]
+fn get_unchecked() {
+    let mut slab = Slab::new();
+    let keys: Vec<_> = (0..DEFAULT_CHUNK_SIZE + 2)
+        .map(|i| slab.insert(i).0)
+        .collect();
+
+    for &key in &keys {
+        assert_eq!(unsafe { *slab.get_unchecked(key) }, key);
+        unsafe {
+            *slab.get_unchecked_pin_mut(key) += 1;
+        }
+        assert_eq!(slab[key], key + 1);
+    }
+}
+
+#[test]
+fn get_disjoint_pin_mut() {
+    let mut slab = Slab::new();
+    let keys: Vec<_> = (0..DEFAULT_CHUNK_SIZE + 2)
+        .map(|i| slab.insert(i).0)
+        .collect();
+    let (a, b) = (keys[1], keys[DEFAULT_CHUNK_SIZE + 1]);
+
+    {
+        let (mut x, mut y) = slab.get2_pin_mut(a, b).unwrap();
+        *x += 100;
+        *y += 100;
+    }
+    assert_eq!(slab[a], 101);
+    assert_eq!(slab[b], DEFAULT_CHUNK_SIZE + 101);
+
+    // Keys in the same chunk.
+    {
+        let [mut x, mut y, mut z] = slab.get_disjoint_pin_mut([0, 2, a]).unwrap();
+        *x += 10;
+        *y += 10;
+        *z += 10;
+    }
+    assert_eq!(slab[0], 10);
+    assert_eq!(slab[2], 12);
+    assert_eq!(slab[a], 111);
+
+    assert!(slab.get2_pin_mut(a, a).is_none());
+    assert!(slab.get_disjoint_pin_mut([a, b, 10_000]).is_none());
+
+    slab.remove(a);
+    assert!(slab.get2_pin_mut(a, b).is_none());
+    assert_eq!(slab.get_disjoint_pin_mut::<0>([]).map(|v| v.len()), Some(0));
+}
+
+#[test]
+fn cursor_mut() {
+    let mut slab = Slab::new();
+
+    for i in 0..DEFAULT_CHUNK_SIZE * 2 {
+        slab.insert(i);
+    }
+
+    let mut cursor = slab.cursor_mut();
+    let mut visited = 0;
+    while let Some(mut val) = cursor.current() {
+        visited += 1;
+        if *val % 3 == 0 {
+            assert_eq!(cursor.remove_current().map(|v| v % 3), Some(0));
+        } else {
+            *val += 1;
+            cursor.move_next();
+        }
+    }
+    assert_eq!(visited, DEFAULT_CHUNK_SIZE * 2);
+
+    // Moving past the end is a no-op
+    cursor.move_next();
+    assert_eq!(cursor.key(), None);
+    assert_eq!(cursor.remove_current(), None);
+
+    assert_eq!(
+        slab.len(),
+        DEFAULT_CHUNK_SIZE * 2 - (DEFAULT_CHUNK_SIZE * 2).div_ceil(3)
+    );
+    for (key, val) in slab.iter() {
+        assert_eq!(key + 1, *val);
+    }
+
+    let mut empty = Slab::<usize>::new();
+    assert!(empty.cursor_mut().current().is_none());
+}
+
+#[test]
+fn drain() {
+    let mut slab = Slab::new();
+
+    for i in 0..DEFAULT_CHUNK_SIZE + 10 {
+        slab.insert(i);
+    }
+    slab.remove(5);
+
+    let drained: Vec<_> = slab.drain().collect();
+    assert_eq!(drained.len(), DEFAULT_CHUNK_SIZE + 9);
+    assert!(drained.iter().all(|&(key, val)| key == val));
+    assert!(slab.is_empty());
+    assert_eq!(slab.capacity(), 2 * DEFAULT_CHUNK_SIZE);
+
+    // Keys are handed out from the start again
+    let (key, _) = slab.insert(0);
+    assert_eq!(key, 0);
+}
+
+#[test]
+fn drain_dropped_midway() {
+    let mut slab = Slab::new();
+
+    for i in 0..10 {
+        slab.insert(i);
+    }
+
+    {
+        let mut drain = slab.drain();
+        assert_eq!(drain.size_hint(), (10, Some(10)));
+        assert_eq!(drain.next(), Some((0, 0)));
+        assert_eq!(drain.size_hint(), (9, Some(9)));
+    }
+
+    assert!(slab.is_empty());
+    assert!(slab.iter().next().is_none());
+}
+
+#[test]
+fn drain_leaked_midway() {
+    let mut slab = Slab::new();
+
+    for i in 0..10 {
+        slab.insert(i);
+    }
+
+    let mut drain = slab.drain();
+    drain.next();
+    drain.next();
+    std::mem::forget(drain);
+
+    assert_eq!(slab.len(), 8);
+    for i in 0..2 {
+        let (key, _) = slab.insert(i);
+        assert_eq!(slab[key], i);
+    }
+    assert_eq!(slab.len(), 10);
+    assert_eq!(slab.insert(10).0, 10);
+}
+
+#[test]
+fn extract_if() {
+    let mut slab = Slab::new();
+
+    for i in 0..DEFAULT_CHUNK_SIZE * 2 {
+        slab.insert(i);
+    }
+
+    let extracted: Vec<_> = slab
+        .extract_if(|key, mut val| {
+            *val += 1;
+            key % 4 == 0
+        })
+        .collect();
+
+    assert_eq!(extracted.len(), DEFAULT_CHUNK_SIZE / 2);
+    assert!(extracted
+        .iter()
+        .all(|&(key, val)| key % 4 == 0 && val == key + 1));
+    assert_eq!(slab.len(), DEFAULT_CHUNK_SIZE * 2 - DEFAULT_CHUNK_SIZE / 2);
+    assert!(slab
+        .iter()
+        .all(|(key, val)| key % 4 != 0 && *val == key + 1));
+
+    // Dropping the iterator early retains the rest
+    let first = slab.extract_if(|_, _| true).next();
+    assert_eq!(first, Some((1, 2)));
+    assert_eq!(
+        slab.len(),
+        DEFAULT_CHUNK_SIZE * 2 - DEFAULT_CHUNK_SIZE / 2 - 1
+    );
+}
+
+#[test]
+fn iter_pin_mut() {
+    let mut slab = Slab::new();
+
+    for i in 0..4 {
+        slab.insert(i);
+    }
+    slab.remove(2);
+
+    for (key, mut val) in &mut slab {
+        assert_eq!(key, *val);
+        *val += 1;
+    }
+
+    let vals: Vec<_> = slab.iter().map(|(_, r)| *r).collect();
+    assert_eq!(vals, vec![1, 2, 4]);
+}
+
+#[test]
+fn keys_values() {
+    let mut slab = Slab::new();
+
+    for i in 0..4 {
+        slab.insert(i * 10);
+    }
+    slab.remove(1);
+
+    assert_eq!(slab.keys().collect::<Vec<_>>(), vec![0, 2, 3]);
+    assert_eq!(slab.values().copied().collect::<Vec<_>>(), vec![0, 20, 30]);
+
+    for mut val in slab.values_pin_mut() {
+        *val += 1;
+    }
+    assert_eq!(slab.values().copied().collect::<Vec<_>>(), vec![1, 21, 31]);
+}
+
+#[test]
+#[allow(deprecated)]
+fn iter_sparse() {
+    let mut slab = Slab::new();
+
+    for i in 0..DEFAULT_CHUNK_SIZE * 3 {
+        slab.insert(i);
+    }
+
+    let kept = [
+        0,
+        63,
+        64,
+        65,
+        127,
+        DEFAULT_CHUNK_SIZE - 1,
+        DEFAULT_CHUNK_SIZE * 2 + 100,
+    ];
+    slab.retain(|key, _| kept.contains(&key));
+
+    assert_eq!(slab.keys().collect::<Vec<_>>(), kept);
+
+    for (key, val) in unsafe { slab.iter_mut() } {
+        assert_eq!(key, *val);
+        *val += 1;
+    }
+    assert!(slab.iter().all(|(key, val)| key + 1 == *val));
+}
+
+#[test]
+#[allow(deprecated)]
+fn iter_order_after_churn() {
+    let mut slab = Slab::new();
+    let mut model = std::collections::BTreeMap::new();
+    let mut rng = 0x2545_f491_4f6c_dd1du64;
+
+    for i in 0..20_000 {
+        rng ^= rng << 13;
+        rng ^= rng >> 7;
+        rng ^= rng << 17;
+
+        if rng.is_multiple_of(3) && !model.is_empty() {
+            let nth = (rng >> 8) as usize % model.len();
+            let key = *model.keys().nth(nth).unwrap();
+            assert_eq!(slab.remove(key), model.remove(&key).unwrap());
+        } else {
+            let (key, _) = slab.insert(i);
+            model.insert(key, i);
+        }
+    }
+
+    let expected: Vec<_> = model.iter().map(|(&k, &v)| (k, v)).collect();
+    let actual: Vec<_> = slab.iter().map(|(k, &v)| (k, v)).collect();
+    assert_eq!(actual, expected);
+
+    let actual: Vec<_> = unsafe { slab.iter_mut() }.map(|(k, v)| (k, *v)).collect();
+    assert_eq!(actual, expected);
+}
+
+#[test]
+#[allow(deprecated)]
+fn exact_size() {
+    let mut slab = Slab::new();
+
+    for i in 0..DEFAULT_CHUNK_SIZE + 5 {
+        slab.insert(i);
+    }
+    slab.remove(3);
+
+    let mut iter = slab.iter();
+    assert_eq!(iter.len(), DEFAULT_CHUNK_SIZE + 4);
+    iter.next();
+    assert_eq!(
+        iter.size_hint(),
+        (DEFAULT_CHUNK_SIZE + 3, Some(DEFAULT_CHUNK_SIZE + 3))
+    );
+    assert_eq!(iter.count(), DEFAULT_CHUNK_SIZE + 3);
+
+    assert_eq!(slab.keys().len(), DEFAULT_CHUNK_SIZE + 4);
+    assert_eq!(slab.values().len(), DEFAULT_CHUNK_SIZE + 4);
+    assert_eq!(slab.values_pin_mut().len(), DEFAULT_CHUNK_SIZE + 4);
+    assert_eq!(unsafe { slab.iter_mut() }.skip(4).len(), DEFAULT_CHUNK_SIZE);
+    assert_eq!(slab.iter_pin_mut().len(), DEFAULT_CHUNK_SIZE + 4);
+
+    let mut drain = slab.drain();
+    drain.next();
+    assert_eq!(drain.len(), DEFAULT_CHUNK_SIZE + 3);
+}
+
+#[test]
+#[allow(deprecated)]
+fn double_ended() {
+    let mut slab = Slab::new();
+
+    for i in 0..DEFAULT_CHUNK_SIZE * 3 {
+        slab.insert(i);
+    }
+    slab.retain(|key, _| key % 7 == 0);
+    let expected: Vec<_> = (0..DEFAULT_CHUNK_SIZE * 3).filter(|k| k % 7 == 0).collect();
+
+    let rev: Vec<_> = slab.keys().rev().collect();
+    assert_eq!(rev, expected.iter().rev().copied().collect::<Vec<_>>());
+
+    // Alternate ends so both meet in the middle
+    let mut iter = unsafe { slab.iter_mut() };
+    let mut front = vec![];
+    let mut back = vec![];
+    while let Some((key, val)) = iter.next() {
+        front.push((key, *val));
+        match iter.next_back() {
+            Some((key, val)) => back.push((key, *val)),
+            None => break,
+        }
+    }
+    assert!(iter.next().is_none());
+    assert!(iter.next_back().is_none());
+    front.extend(back.into_iter().rev());
+    assert_eq!(front.iter().map(|&(k, _)| k).collect::<Vec<_>>(), expected);
+
+    // Mostly from the back, ending in the first chunk
+    let mut iter = slab.iter_pin_mut();
+    let first = iter.next().map(|(k, _)| k);
+    let rest: Vec<_> = iter.rev().map(|(k, _)| k).collect();
+    assert_eq!(first, Some(0));
+    assert_eq!(rest.len(), expected.len() - 1);
+    assert_eq!(rest.last(), Some(&7));
+
+    let mut drain = slab.drain();
+    assert_eq!(drain.next_back(), expected.last().map(|&k| (k, k)));
+    drop(drain);
+    assert!(slab.is_empty());
+}
+
+#[test]
+fn into_iter() {
+    let mut slab = Slab::new();
+
+    for i in 0..4 {
+        slab.insert(i);
+    }
+    slab.remove(2);
+
+    let mut iter = slab.into_iter();
+    assert_eq!(iter.len(), 3);
+    assert_eq!(iter.next_back(), Some((3, 3)));
+    assert_eq!(iter.next(), Some((0, 0)));
+    assert_eq!(iter.next(), Some((1, 1)));
+    assert_eq!(iter.next(), None);
+}
+
+#[test]
+#[allow(deprecated)]
+fn fused() {
+    fn assert_fused<I: std::iter::FusedIterator>(_: I) {}
+
+    let mut slab = Slab::new();
+    slab.insert(0);
+
+    assert_fused(slab.iter());
+    assert_fused(unsafe { slab.iter_mut() });
+    assert_fused(slab.iter_pin_mut());
+    assert_fused(slab.keys());
+    assert_fused(slab.values());
+    assert_fused(slab.values_pin_mut());
+    assert_fused(slab.extract_if(|_, _| false));
+    assert_fused(slab.clone().into_iter());
+
+    let mut iter = slab.iter();
+    assert!(iter.next().is_some());
+    assert!(iter.next().is_none());
+    assert!(iter.next().is_none());
+    assert!(iter.next_back().is_none());
+
+    assert_fused(slab.drain());
+}
+
+#[test]
+fn iter_range() {
+    let mut slab = Slab::new();
+
+    for i in 0..DEFAULT_CHUNK_SIZE * 4 {
+        slab.insert(i);
+    }
+    slab.retain(|key, _| key % 3 != 0);
+
+    let ranges = [
+        (0, 0),
+        (0, 1),
+        (0, 3),
+        (1, 2),
+        (5, 200),
+        (63, 129),
+        (DEFAULT_CHUNK_SIZE - 2, DEFAULT_CHUNK_SIZE * 3 + 5),
+        (DEFAULT_CHUNK_SIZE * 2, DEFAULT_CHUNK_SIZE * 2 + 64),
+        (DEFAULT_CHUNK_SIZE * 4 - 10, DEFAULT_CHUNK_SIZE * 10),
+        (DEFAULT_CHUNK_SIZE * 5, DEFAULT_CHUNK_SIZE * 6),
+        (10, 5),
+    ];
+
+    for &(a, b) in &ranges {
+        let expected: Vec<_> = (a..b.min(DEFAULT_CHUNK_SIZE * 4))
+            .filter(|k| k % 3 != 0)
+            .collect();
+
+        let iter = slab.iter_range(a..b);
+        assert_eq!(iter.len(), expected.len(), "{}..{}", a, b);
+        assert_eq!(iter.map(|(k, _)| k).collect::<Vec<_>>(), expected);
+
+        let rev: Vec<_> = slab
+            .iter_range_pin_mut(a..b)
+            .rev()
+            .map(|(k, _)| k)
+            .collect();
+        assert_eq!(rev, expected.iter().rev().copied().collect::<Vec<_>>());
+    }
+
+    assert_eq!(slab.iter_range(..).len(), slab.len());
+    assert_eq!(
+        slab.iter_range(..=4).map(|(k, _)| k).collect::<Vec<_>>(),
+        vec![1, 2, 4]
+    );
+    assert_eq!(slab.iter_range(usize::MAX..).len(), 0);
+    assert_eq!(Slab::<usize>::new().iter_range(0..10).len(), 0);
+}
+
+#[test]
+fn iter_chunk() {
+    let mut slab = Slab::new();
+
+    for i in 0..DEFAULT_CHUNK_SIZE * 3 {
+        slab.insert(i);
+    }
+    for key in DEFAULT_CHUNK_SIZE..DEFAULT_CHUNK_SIZE * 2 {
+        slab.remove(key);
+    }
+    slab.remove(0);
+
+    assert_eq!(slab.chunk_count(), 3);
+    assert_eq!(slab.iter_chunk(0).len(), DEFAULT_CHUNK_SIZE - 1);
+    assert_eq!(slab.iter_chunk(1).len(), 0);
+    assert_eq!(slab.iter_chunk(2).len(), DEFAULT_CHUNK_SIZE);
+    assert_eq!(slab.iter_chunk(3).len(), 0);
+    assert_eq!(slab.iter_chunk(usize::MAX).len(), 0);
+
+    for (key, mut val) in slab.iter_chunk_pin_mut(2) {
+        assert!(key >= DEFAULT_CHUNK_SIZE * 2);
+        *val = 0;
+    }
+    assert_eq!(
+        slab.values().filter(|&&v| v == 0).count(),
+        DEFAULT_CHUNK_SIZE
+    );
+}
+
+#[test]
+fn vacant_keys() {
+    let mut slab = Slab::new();
+    assert_eq!(slab.vacant_keys().len(), 0);
+    assert_eq!(slab.next_key(), 0);
+
+    for i in 0..DEFAULT_CHUNK_SIZE + 1 {
+        slab.insert(i);
+    }
+    for &key in &[0, 63, 64, DEFAULT_CHUNK_SIZE] {
+        slab.remove(key);
+    }
+
+    let vacant: Vec<_> = slab.vacant_keys().collect();
+    let mut expected = vec![0, 63, 64];
+    expected.extend(DEFAULT_CHUNK_SIZE..DEFAULT_CHUNK_SIZE * 2);
+    assert_eq!(vacant, expected);
+    assert_eq!(slab.vacant_keys().len(), slab.capacity() - slab.len());
+
+    let key = slab.next_key();
+    assert_eq!(slab.insert(0).0, key);
+    assert!(!slab.vacant_keys().any(|k| k == key));
+}
+
+#[test]
+fn chunk_slices() {
+    let mut slab = Slab::new();
+    assert_eq!(slab.as_chunk_slices().unwrap().len(), 0);
+
+    for i in 0..DEFAULT_CHUNK_SIZE * 2 + 10 {
+        slab.insert(i);
+    }
+
+    let slices: Vec<_> = slab.as_chunk_slices().unwrap().collect();
+    assert_eq!(slices.len(), 3);
+    assert_eq!(slices[0].len(), DEFAULT_CHUNK_SIZE);
+    assert_eq!(slices[2].len(), 10);
+    assert!(slices
+        .concat()
+        .into_iter()
+        .eq(0..DEFAULT_CHUNK_SIZE * 2 + 10));
+
+    slab.remove(DEFAULT_CHUNK_SIZE + 1);
+    assert!(slab.as_chunk_slices().is_none());
+    assert!(slab.chunk_slice(0).is_some());
+    assert!(slab.chunk_slice(1).is_none());
+    assert!(slab.chunk_slice(3).is_none());
+
+    // Reusing the vacant key makes the slab dense again
+    slab.insert(0);
+    assert_eq!(slab.chunk_slice(1).unwrap()[1], 0);
+    assert!(slab.as_chunk_slices().is_some());
+}
+
+#[test]
+fn drop_and_clone() {
+    use std::rc::Rc;
+
+    let val = Rc::new(());
+    let mut slab = Slab::new();
+
+    for _ in 0..DEFAULT_CHUNK_SIZE + 1 {
+        slab.insert(val.clone());
+    }
+    for key in (0..DEFAULT_CHUNK_SIZE).step_by(3) {
+        slab.remove(key);
+    }
+
+    let copy = slab.clone();
+    assert_eq!(Rc::strong_count(&val), 2 * slab.len() + 1);
+    assert!(copy.iter().eq(slab.iter()));
+
+    drop(slab);
+    drop(copy);
+    assert_eq!(Rc::strong_count(&val), 1);
+}
+
+#[test]
+fn reserve() {
+    let mut slab = Slab::with_capacity(DEFAULT_CHUNK_SIZE + 1);
+    assert_eq!(slab.capacity(), DEFAULT_CHUNK_SIZE * 2);
+    assert_eq!(slab.chunk_count(), 2);
+
+    // Reserving what's already there doesn't allocate
+    slab.reserve(DEFAULT_CHUNK_SIZE * 2);
+    assert_eq!(slab.capacity(), DEFAULT_CHUNK_SIZE * 2);
+
+    for i in 0..DEFAULT_CHUNK_SIZE * 2 {
+        slab.insert(i);
+    }
+    assert_eq!(slab.capacity(), DEFAULT_CHUNK_SIZE * 2);
+
+    slab.remove(3);
+    slab.reserve_exact(DEFAULT_CHUNK_SIZE);
+    assert_eq!(slab.capacity(), DEFAULT_CHUNK_SIZE * 3);
+
+    // The vacant entry and the reserved chunk are used before allocating
+    let keys: Vec<_> = (0..DEFAULT_CHUNK_SIZE + 1)
+        .map(|i| slab.insert(i).0)
+        .collect();
+    assert_eq!(keys[0], 3);
+    assert_eq!(keys[DEFAULT_CHUNK_SIZE], DEFAULT_CHUNK_SIZE * 3 - 1);
+    assert_eq!(slab.capacity(), DEFAULT_CHUNK_SIZE * 3);
+    assert!(keys.iter().enumerate().all(|(i, &key)| slab[key] == i));
+}
+
+#[test]
+#[should_panic(expected = "capacity overflow")]
+fn reserve_overflow() {
+    let mut slab = Slab::new();
+    slab.insert(0);
+    slab.reserve(usize::MAX);
+}
+
+#[test]
+fn try_reserve() {
+    let mut slab = Slab::new();
+    assert_eq!(slab.try_reserve(DEFAULT_CHUNK_SIZE + 1), Ok(()));
+    assert_eq!(slab.capacity(), DEFAULT_CHUNK_SIZE * 2);
+
+    let (key, mut val) = slab.try_insert(String::from("a")).unwrap();
+    val.push('b');
+    assert_eq!(slab[key], "ab");
+
+    assert_eq!(
+        slab.try_reserve(usize::MAX),
+        Err(TryReserveError::CapacityOverflow)
+    );
+    let err = slab.try_reserve(usize::MAX - 1).unwrap_err();
+    assert!(!err.to_string().is_empty());
+    assert_eq!(slab.capacity(), DEFAULT_CHUNK_SIZE * 2);
+    assert_eq!(slab.len(), 1);
+}
+
+#[test]
+fn insert_within_capacity() {
+    let mut slab = Slab::with_capacity(DEFAULT_CHUNK_SIZE);
+
+    for i in 0..DEFAULT_CHUNK_SIZE {
+        assert_eq!(slab.insert_within_capacity(i).unwrap().0, i);
+    }
+    assert_eq!(slab.insert_within_capacity(0).err(), Some(0));
+    assert_eq!(slab.chunk_count(), 1);
+
+    slab.remove(7);
+    let (key, mut val) = slab.insert_within_capacity(0).unwrap();
+    *val = 100;
+    assert_eq!(key, 7);
+    assert_eq!(slab[7], 100);
+    assert_eq!(slab.capacity(), DEFAULT_CHUNK_SIZE);
+
+    // Vacant entries are found after freeing a chunk in the middle.
+    for policy in [KeyPolicy::Lifo, KeyPolicy::LowestFirst] {
+        let mut slab = Slab::<usize, 8>::with_chunk_size();
+        slab.set_key_policy(policy);
+        slab.insert_many(0..24);
+        for key in (8..16).chain([3, 20]) {
+            slab.remove(key);
+        }
+        slab.free_unused();
+        assert_eq!(slab.capacity(), 16);
+
+        let mut keys: Vec<_> = (0..2)
+            .map(|i| slab.insert_within_capacity(i).unwrap().0)
+            .collect();
+        keys.sort();
+        assert_eq!(keys, [3, 20]);
+        assert_eq!(slab.insert_within_capacity(0).err(), Some(0));
+    }
+
+    // `Monotonic` doesn't go back to vacant entries.
+    let mut slab = Slab::<usize, 8>::with_chunk_size();
+    slab.set_key_policy(KeyPolicy::Monotonic);
+    slab.insert_many(0..8);
+    slab.remove(3);
+    assert_eq!(slab.insert_within_capacity(0).err(), Some(0));
+}
+
+#[test]
+fn clear() {
+    use std::rc::Rc;
+
+    let val = Rc::new(());
+    let mut slab = Slab::new();
+
+    for _ in 0..DEFAULT_CHUNK_SIZE * 2 {
+        slab.insert(val.clone());
+    }
+    slab.remove(5);
+    slab.remove(DEFAULT_CHUNK_SIZE + 5);
+
+    slab.clear();
+    assert!(slab.is_empty());
+    assert_eq!(slab.iter().count(), 0);
+    assert_eq!(slab.capacity(), DEFAULT_CHUNK_SIZE * 2);
+    assert_eq!(Rc::strong_count(&val), 1);
+
+    // The chunks are reused in order
+    for i in 0..DEFAULT_CHUNK_SIZE + 1 {
+        assert_eq!(slab.insert(val.clone()).0, i);
+    }
+    assert_eq!(slab.capacity(), DEFAULT_CHUNK_SIZE * 2);
+
+    slab.clear_and_free();
+    assert_eq!(slab.capacity(), 0);
+    assert_eq!(Rc::strong_count(&val), 1);
+}
+
+#[test]
+fn clear_panicking_drop() {
+    use std::cell::Cell;
+    use std::panic::{catch_unwind, AssertUnwindSafe};
+
+    struct Bomb<'a>(&'a Cell<usize>, bool);
+
+    impl<'a> Drop for Bomb<'a> {
+        fn drop(&mut self) {
+            self.0.set(self.0.get() + 1);
+            if self.1 {
+                panic!("boom");
+            }
+        }
+    }
+
+    let drops = Cell::new(0);
+    let mut slab = Slab::new();
+    for i in 0..DEFAULT_CHUNK_SIZE * 3 {
+        slab.insert(Bomb(&drops, i == 10));
+    }
+
+    assert!(catch_unwind(AssertUnwindSafe(|| slab.clear())).is_err());
+
+    // The other chunks were still cleared, and the slab is usable
+    assert!(slab.is_empty());
+    assert!(drops.get() >= DEFAULT_CHUNK_SIZE * 2);
+    assert_eq!(slab.insert(Bomb(&drops, false)).0, 0);
+    assert_eq!(slab.iter().count(), 1);
+}
+
+#[test]
+fn shrink_to_fit() {
+    let mut slab = Slab::new();
+
+    for i in 0..DEFAULT_CHUNK_SIZE * 4 {
+        slab.insert(i);
+    }
+    // Empty the second and the last chunk, and all but one entry of the third
+    for key in (DEFAULT_CHUNK_SIZE..DEFAULT_CHUNK_SIZE * 4).rev() {
+        if key != DEFAULT_CHUNK_SIZE * 2 {
+            slab.remove(key);
+        }
+    }
+    slab.remove(7);
+
+    slab.shrink_to_fit();
+    assert_eq!(slab.capacity(), DEFAULT_CHUNK_SIZE * 3);
+    assert_eq!(slab.len(), DEFAULT_CHUNK_SIZE);
+    assert!(slab.iter().all(|(key, &val)| key == val));
+
+    // Every remaining vacant key is reused before allocating
+    let mut keys: Vec<_> = (0..DEFAULT_CHUNK_SIZE * 2)
+        .map(|i| slab.insert(i).0)
+        .collect();
+    keys.sort_unstable();
+    let mut expected = vec![7];
+    expected.extend(DEFAULT_CHUNK_SIZE..DEFAULT_CHUNK_SIZE * 2);
+    expected.extend(DEFAULT_CHUNK_SIZE * 2 + 1..DEFAULT_CHUNK_SIZE * 3);
+    assert_eq!(keys, expected);
+    assert_eq!(slab.capacity(), DEFAULT_CHUNK_SIZE * 3);
+
+    assert_eq!(slab.insert(0).0, DEFAULT_CHUNK_SIZE * 3);
+
+    slab.clear();
+    slab.free_unused();
+    assert_eq!(slab.capacity(), 0);
+    assert_eq!(slab.insert(0).0, 0);
+}
+
+#[test]
+#[allow(deprecated)]
+fn free_unused() {
+    let mut slab = Slab::new();
+
+    for i in 0..DEFAULT_CHUNK_SIZE * 4 {
+        slab.insert(i);
+    }
+    for key in (DEFAULT_CHUNK_SIZE..DEFAULT_CHUNK_SIZE * 2)
+        .chain(DEFAULT_CHUNK_SIZE * 3..DEFAULT_CHUNK_SIZE * 4)
+    {
+        slab.remove(key);
+    }
+    slab.remove(5);
+
+    slab.free_unused();
+    assert_eq!(slab.capacity(), DEFAULT_CHUNK_SIZE * 2);
+    assert_eq!(slab.chunk_count(), 3);
+    assert_eq!(slab.vacant_keys().collect::<Vec<_>>(), vec![5]);
+
+    // Keys above the freed chunk keep their meaning
+    assert!(slab.iter().all(|(key, &val)| key == val));
+    assert!(slab.get(DEFAULT_CHUNK_SIZE).is_none());
+    assert_eq!(slab.iter_chunk(1).len(), 0);
+    assert_eq!(
+        slab.iter_range(DEFAULT_CHUNK_SIZE - 1..).count(),
+        DEFAULT_CHUNK_SIZE + 1
+    );
+    assert_eq!(slab.iter().rev().count(), slab.len());
+    assert_eq!(unsafe { slab.iter_mut() }.rev().count(), slab.len());
+
+    // The vacant entry is used first, then the free slot is filled again
+    assert!(slab.insert_within_capacity(0).is_ok());
+    assert!(slab.insert_within_capacity(0).is_err());
+    assert_eq!(slab.insert(0).0, DEFAULT_CHUNK_SIZE);
+    assert_eq!(slab.capacity(), DEFAULT_CHUNK_SIZE * 3);
+    assert_eq!(slab.chunk_count(), 3);
+
+    let keys: Vec<_> = (0..DEFAULT_CHUNK_SIZE).map(|_| slab.insert(0).0).collect();
+    assert_eq!(keys[DEFAULT_CHUNK_SIZE - 2], DEFAULT_CHUNK_SIZE * 2 - 1);
+    assert_eq!(keys[DEFAULT_CHUNK_SIZE - 1], DEFAULT_CHUNK_SIZE * 3);
+
+    let mut other = slab.clone();
+    other.clear();
+    assert_eq!(other.insert(0).0, 0);
+}
+
+#[test]
+fn reserve_fills_free_slots() {
+    let mut slab = Slab::new();
+
+    for i in 0..DEFAULT_CHUNK_SIZE * 3 {
+        slab.insert(i);
+    }
+    for key in 0..DEFAULT_CHUNK_SIZE {
+        slab.remove(key);
+    }
+    slab.free_unused();
+    assert_eq!(slab.capacity(), DEFAULT_CHUNK_SIZE * 2);
+
+    slab.reserve(1);
+    assert_eq!(slab.chunk_count(), 3);
+    assert_eq!(slab.capacity(), DEFAULT_CHUNK_SIZE * 3);
+    assert_eq!(slab.insert_within_capacity(0).unwrap().0, 0);
+}
+
+#[test]
+fn free_unused_churn() {
+    let mut slab = Slab::new();
+    let mut model = std::collections::BTreeMap::new();
+    let mut rng = 0x9e37_79b9_7f4a_7c15u64;
+
+    for i in 0..40_000 {
+        rng ^= rng << 13;
+        rng ^= rng >> 7;
+        rng ^= rng << 17;
+
+        // Alternate between growing and shrinking phases
+        let removing = (i / 5_000) % 2 == 1;
+        if (removing || rng.is_multiple_of(4)) && !model.is_empty() {
+            let nth = (rng >> 8) as usize % model.len();
+            let key = *model.keys().nth(nth).unwrap();
+            assert_eq!(slab.remove(key), model.remove(&key).unwrap());
+        } else {
+            let (key, _) = slab.insert(i);
+            assert!(model.insert(key, i).is_none());
+        }
+
+        if i % 1_000 == 0 {
+            slab.free_unused();
+            assert!(slab.capacity() - slab.len() < slab.chunk_count() * DEFAULT_CHUNK_SIZE);
+        }
+    }
+
+    let expected: Vec<_> = model.iter().map(|(&k, &v)| (k, v)).collect();
+    let actual: Vec<_> = slab.iter().map(|(k, &v)| (k, v)).collect();
+    assert_eq!(actual, expected);
+    assert_eq!(slab.vacant_keys().len(), slab.capacity() - slab.len());
+}
+
+#[test]
+fn compact() {
+    let mut slab = Slab::new();
+    let mut rng = 0x2545_f491_4f6c_dd1du64;
+
+    for i in 0..DEFAULT_CHUNK_SIZE * 8 {
+        slab.insert(i);
+    }
+    slab.extract_if(|_, _| {
+        rng ^= rng << 13;
+        rng ^= rng >> 7;
+        rng ^= rng << 17;
+        !rng.is_multiple_of(20)
+    })
+    .for_each(drop);
+
+    // Track where every value lives, like an external index would
+    let mut index: Vec<_> = slab.iter().map(|(key, &val)| (val, key)).collect();
+    let len = slab.len();
+
+    slab.compact(|old, new, val| {
+        let entry = index.iter_mut().find(|(v, _)| v == val).unwrap();
+        assert_eq!(entry.1, old);
+        assert!(new < old);
+        entry.1 = new;
+    });
+
+    assert_eq!(slab.len(), len);
+    assert!(slab.keys().eq(0..len));
+    assert!(index.iter().all(|&(val, key)| slab[key] == val));
+    assert_eq!(
+        slab.capacity(),
+        len.div_ceil(DEFAULT_CHUNK_SIZE) * DEFAULT_CHUNK_SIZE
+    );
+
+    // The slab keeps working after compaction
+    assert_eq!(slab.insert(0).0, len);
+    assert!(slab.as_chunk_slices().is_some());
+}
+
+#[test]
+fn compact_panicking_callback() {
+    use std::panic::{catch_unwind, AssertUnwindSafe};
+
+    let mut slab = Slab::new();
+    slab.insert_many(0..10);
+    for key in [1, 2, 4] {
+        slab.remove(key);
+    }
+
+    let mut moves = 0;
+    let result = catch_unwind(AssertUnwindSafe(|| {
+        slab.compact(|_, _, _| {
+            moves += 1;
+            if moves == 2 {
+                panic!();
+            }
+        })
+    }));
+    assert!(result.is_err());
+    slab.assert_invariants();
+
+    // The moved values are where they were moved to, and the free list only
+    // holds vacant keys.
+    assert_eq!(slab.len(), 7);
+    let mut keys: Vec<_> = (0..3).map(|i| slab.insert(10 + i).0).collect();
+    keys.sort();
+    assert!(keys.iter().all(|&key| slab[key] >= 10));
+    assert_eq!(slab.keys().count(), 10);
+    let mut values: Vec<i32> = slab.values().copied().collect();
+    values.sort();
+    assert_eq!(values, [0, 3, 5, 6, 7, 8, 9, 10, 11, 12]);
+    slab.assert_invariants();
+}
+
+#[test]
+fn stats() {
+    let mut slab = Slab::new();
+
+    let stats = slab.stats();
+    assert_eq!(stats.capacity, 0);
+    assert_eq!(stats.bytes_allocated, 0);
+    assert_eq!(stats.fragmentation, 0.0);
+
+    for i in 0..DEFAULT_CHUNK_SIZE * 4 {
+        slab.insert(i);
+    }
+    for key in DEFAULT_CHUNK_SIZE..DEFAULT_CHUNK_SIZE * 2 {
+        slab.remove(key);
+    }
+    for key in DEFAULT_CHUNK_SIZE * 2..DEFAULT_CHUNK_SIZE * 2 + DEFAULT_CHUNK_SIZE / 2 {
+        slab.remove(key);
+    }
+
+    let before = slab.stats();
+    assert_eq!(before.len, DEFAULT_CHUNK_SIZE * 2 + DEFAULT_CHUNK_SIZE / 2);
+    assert_eq!(before.chunk_count, 4);
+    assert_eq!(before.allocated_chunks, 4);
+    assert_eq!(before.histogram, [1, 0, 0, 0, 0, 1, 0, 0, 0, 2]);
+    assert_eq!(before.fragmentation, 0.375);
+    assert!(before.bytes_allocated >= DEFAULT_CHUNK_SIZE * 4 * std::mem::size_of::<usize>());
+
+    slab.free_unused();
+    let after = slab.stats();
+    assert_eq!(after.chunk_count, 4);
+    assert_eq!(after.allocated_chunks, 3);
+    assert_eq!(after.histogram.iter().sum::<usize>(), 3);
+    assert!(after.bytes_allocated < before.bytes_allocated);
+    assert!(after.fragmentation < before.fragmentation);
+}
+
+fn check_chunk_size<const N: usize>() {
+    let mut slab = Slab::<usize, N>::with_chunk_size();
+    for i in 0..N * 3 + 1 {
+        assert_eq!(slab.insert(i).0, i);
+    }
+    assert_eq!(slab.capacity(), N * 4);
+    assert_eq!(slab.chunk_count(), 4);
+
+    for key in (0..N * 3 + 1).step_by(2) {
+        assert_eq!(slab.remove(key), key);
+    }
+    assert!(slab.iter().all(|(key, &val)| key == val && key % 2 == 1));
+    assert!(slab.vacant_keys().all(|key| key % 2 == 0 || key > N * 3));
+    assert_eq!(slab.vacant_keys().count(), slab.capacity() - slab.len());
+    assert_eq!(slab.chunk_slice(0), None);
+
+    let mut clone = slab.clone();
+    clone.compact(|_, _, _| {});
+    assert_eq!(clone.len(), slab.len());
+    assert!(clone.chunk_count() <= slab.chunk_count());
+    assert_eq!(clone.values().sum::<usize>(), slab.values().sum());
+
+    assert_eq!(slab.insert(0).0, N * 3 / 2 * 2);
+    slab.clear();
+    assert!(slab.is_empty());
+}
+
+#[test]
+fn chunk_sizes() {
+    check_chunk_size::<1>();
+    check_chunk_size::<4>();
+    check_chunk_size::<64>();
+    check_chunk_size::<100>();
+    check_chunk_size::<DEFAULT_CHUNK_SIZE>();
+}
+
+#[test]
+fn geometric_growth() {
+    let mut slab = Slab::<usize, 100>::with_growth_policy(GrowthPolicy::Geometric { first: 3 });
+    assert_eq!(slab.growth_policy(), GrowthPolicy::Geometric { first: 3 });
+
+    // The chunks hold 3, 6, 12, 24, 48, and 96 entries, then 100 entries each.
+    let sizes = [3, 6, 12, 24, 48, 96, 100];
+    for i in 0..sizes.iter().sum() {
+        assert_eq!(slab.insert(i).0, i);
+    }
+    assert_eq!(slab.chunk_count(), sizes.len());
+    assert_eq!(slab.capacity(), slab.len());
+
+    let mut start = 0;
+    for (index, &size) in sizes.iter().enumerate() {
+        assert_eq!(slab.chunk_range(index), start..start + size);
+        assert_eq!(
+            slab.chunk_slice(index),
+            Some(&(start..start + size).collect::<Vec<_>>()[..])
+        );
+        assert!(slab
+            .iter_chunk(index)
+            .map(|(key, _)| key)
+            .eq(start..start + size));
+        start += size;
+    }
+
+    for key in (0..slab.len()).filter(|key| key % 3 != 0) {
+        slab.remove(key);
+    }
+    assert!(slab.iter().all(|(key, &val)| key == val && key % 3 == 0));
+    assert!(slab
+        .iter()
+        .rev()
+        .map(|(key, _)| key)
+        .eq((0..start).step_by(3).rev()));
+    assert!(slab
+        .iter_range(40..50)
+        .map(|(key, _)| key)
+        .eq((42..50).step_by(3)));
+    assert_eq!(slab.vacant_keys().count(), slab.capacity() - slab.len());
+    assert!(slab.vacant_keys().all(|key| key % 3 != 0));
+
+    slab.extract_if(|key, _| (9..93).contains(&key))
+        .for_each(drop);
+    slab.free_unused();
+    assert_eq!(slab.capacity(), 3 + 6 + 96 + 100);
+    assert_eq!(slab.get(93), Some(&93));
+
+    // Free slots are refilled in order, with the sizes they had before.
+    slab.reserve(150);
+    assert_eq!(slab.capacity(), 3 + 6 + 12 + 24 + 96 + 100);
+    let key = slab.insert(1000).0;
+    assert_eq!(slab[key], 1000);
+
+    slab.compact(|_, _, _| {});
+    assert!(slab.as_chunk_slices().is_some());
+    assert_eq!(slab.stats().capacity, slab.capacity());
+}
+
+#[test]
+fn chunk_pool() {
+    let mut pool = ChunkPool::new();
+    let mut slab = Slab::with_chunks(3);
+    for i in 0..DEFAULT_CHUNK_SIZE * 2 {
+        slab.insert(i);
+    }
+    slab.clear_into_pool(&mut pool);
+    assert_eq!(pool.len(), 3);
+    assert_eq!(slab.capacity(), 0);
+    assert_eq!(slab.insert(7).0, 0);
+
+    // Only chunks of the right size are reused.
+    let mut small = Slab::<usize, 16>::with_chunk_size();
+    small.reserve_chunks_from(&mut pool, 2);
+    assert_eq!(pool.len(), 3);
+    assert_eq!(small.capacity(), 32);
+
+    let mut other = Slab::new();
+    other.reserve_chunks_from(&mut pool, 4);
+    assert!(pool.is_empty());
+    assert_eq!(other.capacity(), DEFAULT_CHUNK_SIZE * 4);
+    for i in 0..DEFAULT_CHUNK_SIZE * 4 {
+        assert_eq!(other.insert(i).0, i);
+    }
+    assert!(other.iter().all(|(key, &val)| key == val));
+
+    small.clear_into_pool(&mut pool);
+    assert_eq!(pool.len(), 2);
+    pool.clear();
+    assert!(pool.is_empty());
+}
+
+#[test]
+fn custom_allocator() {
+    use std::alloc::Layout;
+    use std::cell::Cell;
+    use std::ptr::NonNull;
+    use std::rc::Rc;
+
+    /// Allocates at most `limit` arrays at a time.
+    #[derive(Clone)]
+    struct Limited {
+        live: Rc<Cell<usize>>,
+        limit: usize,
+    }
+
+    unsafe impl ChunkAllocator for Limited {
+        fn allocate(&self, layout: Layout) -> Option<NonNull<u8>> {
+            if self.live.get() == self.limit {
+                return None;
+            }
+            self.live.set(self.live.get() + 1);
+            Global.allocate(layout)
+        }
+
+        unsafe fn deallocate(&self, ptr: NonNull<u8>, layout: Layout) {
+            self.live.set(self.live.get() - 1);
+            Global.deallocate(ptr, layout)
+        }
+    }
+
+    let live = Rc::new(Cell::new(0));
+    let alloc = Limited {
+        live: live.clone(),
+        limit: 2,
+    };
+    let mut slab = Slab::<String, 4, _>::with_growth_policy_in(GrowthPolicy::Fixed, alloc);
+
+    for i in 0..8 {
+        slab.insert(i.to_string());
+    }
+    assert_eq!(live.get(), 2);
+    assert!(matches!(
+        slab.try_reserve(1),
+        Err(TryReserveError::AllocError { .. })
+    ));
+    assert_eq!(slab.try_insert("8".to_string()).err().as_deref(), Some("8"));
+
+    slab.remove(3);
+    slab.remove(5);
+    slab.compact(|_, _, _| {});
+    assert_eq!(live.get(), 2);
+    slab.drain().for_each(drop);
+    slab.free_unused();
+    assert_eq!(live.get(), 0);
+    assert_eq!(slab.allocator().limit, 2);
+}
+
+#[test]
+fn page_aligned_chunks() {
+    let mut slab = Slab::<u64, 512, _>::with_growth_policy_in(
+        GrowthPolicy::Geometric { first: 8 },
+        PageAligned::new(),
+    );
+    for i in 0..2000 {
+        slab.insert(i);
+    }
+    for i in 0..slab.chunk_count() {
+        let first = &slab[slab.chunk_range(i).start];
+        assert_eq!(first as *const u64 as usize % PAGE_SIZE, 0);
+    }
+
+    let mut slab =
+        Slab::<[u8; 64], 1024, _>::with_growth_policy_in(GrowthPolicy::Fixed, PageAligned::huge());
+    let (key, _) = slab.insert([1; 64]);
+    assert_eq!(slab.allocator().alignment(), HUGE_PAGE_SIZE);
+    assert_eq!(&slab[key] as *const [u8; 64] as usize % HUGE_PAGE_SIZE, 0);
+    assert_eq!(slab.remove(key), [1; 64]);
+}
+
+#[test]
+fn region_backed_chunks() {
+    use std::ptr::NonNull;
+
+    let mut memory = vec![0u8; 1000];
+    let range = memory.as_ptr_range();
+    let region =
+        unsafe { Region::from_raw_parts(NonNull::new(memory.as_mut_ptr()).unwrap(), 1000) };
+    assert_eq!(region.capacity(), 1000);
+
+    let mut slab =
+        Slab::<u32, 64, _>::with_growth_policy_in(GrowthPolicy::Geometric { first: 16 }, &region);
+    let mut count = 0;
+    while slab.try_insert(count).is_ok() {
+        count += 1;
+    }
+    // Chunks of 16, 32, 64, 64 and 64 entries fill 960 bytes, and the next
+    // chunk doesn't fit.
+    assert_eq!(count, 240);
+    assert_eq!(region.remaining(), 40);
+    for (_, value) in &slab {
+        assert!(range.contains(&(value as *const u32).cast()));
+    }
+
+    // Freeing the last chunk makes room for another one.
+    for key in slab.chunk_range(4) {
+        slab.remove(key);
+    }
+    slab.free_unused();
+    assert_eq!(region.remaining(), 1000 - 176 * 4);
+    assert!(slab.try_insert(0).is_ok());
+
+    drop(slab);
+    assert_eq!(region.remaining(), 1000);
+}
+
+#[test]
+fn dense_entries() {
+    let mut slab = Slab::<u8, 64>::with_chunk_size();
+    for i in 0..64 {
+        slab.insert(i);
+    }
+    slab.remove(10);
+
+    let first = &slab[0] as *const u8 as usize;
+    for (key, value) in &slab {
+        assert_eq!(value as *const u8 as usize, first + key);
+    }
+    assert_eq!(slab.vacant_keys().collect::<Vec<_>>(), vec![10]);
+}
+
+#[test]
+fn cache_padded() {
+    use std::mem;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::thread;
+
+    assert_eq!(mem::align_of::<CachePadded<u8>>(), CACHE_LINE_SIZE);
+    assert_eq!(mem::size_of::<CachePadded<[u8; 65]>>(), CACHE_LINE_SIZE * 2);
+
+    let mut slab = Slab::<_, 16>::with_chunk_size();
+    for _ in 0..4 {
+        slab.insert(CachePadded::new(AtomicUsize::new(0)));
+    }
+    for (_, counter) in &slab {
+        assert_eq!(counter as *const _ as usize % CACHE_LINE_SIZE, 0);
+    }
+
+    thread::scope(|s| {
+        for (_, counter) in &slab {
+            s.spawn(move || {
+                for _ in 0..1000 {
+                    counter.fetch_add(1, Ordering::Relaxed);
+                }
+            });
+        }
+    });
+    assert!(slab
+        .values()
+        .all(|counter| counter.load(Ordering::Relaxed) == 1000));
+
+    let (key, _) = slab.insert(CachePadded::new(AtomicUsize::new(7)));
+    let value = slab.get_pin_mut(key).unwrap().as_pin_mut();
+    assert_eq!(value.load(Ordering::Relaxed), 7);
+    assert_eq!(slab.remove(key).into_inner().into_inner(), 7);
+}
+
+#[test]
+fn zero_sized_values() {
+    use std::alloc::Layout;
+    use std::cell::Cell;
+    use std::ptr::NonNull;
+    use std::rc::Rc;
+
+    #[derive(Clone, Default)]
+    struct Counting(Rc<Cell<usize>>);
+
+    unsafe impl ChunkAllocator for Counting {
+        fn allocate(&self, layout: Layout) -> Option<NonNull<u8>> {
+            self.0.set(self.0.get() + 1);
+            Global.allocate(layout)
+        }
+
+        unsafe fn deallocate(&self, ptr: NonNull<u8>, layout: Layout) {
+            Global.deallocate(ptr, layout)
+        }
+    }
+
+    let counting = Counting::default();
+    let mut slab = Slab::new_in(counting.clone());
+    for i in 0..DEFAULT_CHUNK_SIZE * 3 {
+        assert_eq!(slab.insert(()).0, i);
+    }
+    slab.remove(5);
+    assert_eq!(slab.insert(()).0, 5);
+    assert_eq!(slab.len(), DEFAULT_CHUNK_SIZE * 3);
+    assert_eq!(slab.iter().count(), DEFAULT_CHUNK_SIZE * 3);
+    assert_eq!(slab.key_of_ptr(&()), None);
+    assert_eq!(counting.0.get(), 0);
+}
+
+#[test]
+fn key_policies() {
+    let mut slab = Slab::<usize, 8>::with_chunk_size();
+    assert_eq!(slab.key_policy(), KeyPolicy::Lifo);
+    for i in 0..20 {
+        slab.insert(i);
+    }
+    for key in [2, 9, 5] {
+        slab.remove(key);
+    }
+    assert_eq!(slab.insert(0).0, 5);
+    assert_eq!(slab.insert(0).0, 9);
+
+    slab.set_key_policy(KeyPolicy::LowestFirst);
+    slab.remove(12);
+    slab.remove(0);
+    assert_eq!(slab.insert(0).0, 0);
+    assert_eq!(slab.insert(0).0, 2);
+    assert_eq!(slab.insert(0).0, 12);
+    // The last chunk still has vacant entries, so no chunk is allocated.
+    assert_eq!(slab.insert(0).0, 20);
+    while slab.len() < 24 {
+        slab.insert(0);
+    }
+    assert_eq!(slab.next_key(), 24);
+    slab.remove(1);
+    assert_eq!(slab.insert(0).0, 1);
+    assert_eq!(slab.insert(0).0, 24);
+
+    slab.set_key_policy(KeyPolicy::Monotonic);
+    for key in 0..10 {
+        slab.remove(key);
+    }
+    assert_eq!(slab.insert(0).0, 25);
+    assert_eq!(slab.try_insert(0).map(|(key, _)| key), Ok(26));
+    for _ in 0..5 {
+        slab.insert(0);
+    }
+    assert_eq!(slab.insert_within_capacity(0).err(), Some(0));
+    assert_eq!(slab.insert(0).0, 32);
+
+    slab.free_unused();
+    assert_eq!(slab.chunk_count(), 5);
+    assert_eq!(slab.insert(0).0, 33);
+    assert_eq!(slab.capacity(), 32);
+
+    slab.set_key_policy(KeyPolicy::Lifo);
+    assert_eq!(slab.insert(0).0, 8);
+    slab.clear();
+    assert_eq!(slab.insert(0).0, 8);
+}
+
+#[test]
+fn monotonic_continues_after_issued_keys() {
+    let mut slab = Slab::<usize, 8>::with_chunk_size();
+    slab.insert_many(0..10);
+    for key in 5..10 {
+        slab.remove(key);
+    }
+    slab.set_key_policy(KeyPolicy::Monotonic);
+    assert_eq!(slab.insert(0).0, 10);
+
+    // Clearing the slab starts over.
+    slab.clear();
+    slab.set_key_policy(KeyPolicy::Lifo);
+    slab.set_key_policy(KeyPolicy::Monotonic);
+    assert_eq!(slab.insert(0).0, 0);
+
+    // Keys handed out one by one count as well.
+    let mut slab = Slab::<usize, 8>::with_chunk_size();
+    for i in 0..3 {
+        slab.insert(i);
+    }
+    for key in 0..3 {
+        slab.remove(key);
+    }
+    slab.set_key_policy(KeyPolicy::Monotonic);
+    assert_eq!(slab.insert(0).0, 3);
+}
+
+#[test]
+fn insert_many() {
+    let mut slab = Slab::<usize, 8>::with_chunk_size();
+    assert_eq!(slab.insert_many(0..5), 0..5);
+    slab.remove(1);
+    slab.remove(3);
+
+    // The free list ends at key 5, which is handed out here.
+    assert_eq!(slab.insert_many(5..20), 5..20);
+    assert_eq!(slab.insert(0).0, 3);
+    assert_eq!(slab.insert(0).0, 1);
+    assert_eq!(slab.insert(0).0, 20);
+    assert_eq!(slab.keys().collect::<Vec<_>>(), (0..21).collect::<Vec<_>>());
+    assert_eq!(slab[19], 19);
+    assert_eq!(slab.iter().rev().nth(1), Some((19, &19)));
+
+    assert_eq!(slab.insert_many(std::iter::empty()), 21..21);
+    assert_eq!(slab.capacity(), 24);
+
+    let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+        slab.insert_many((0..10).map(|i| if i == 5 { panic!() } else { i }));
+    }));
+    assert!(result.is_err());
+    assert_eq!(slab.len(), 26);
+    assert_eq!(slab.iter().count(), 26);
+    assert_eq!(slab.insert(0).0, 26);
+}
+
+#[test]
+fn remove_many() {
+    let mut slab = Slab::<String, 8>::with_chunk_size();
+    slab.insert_many((0..30).map(|i| i.to_string()));
+
+    let removed = slab.remove_many(vec![29, 3, 17, 16, 0, 8]);
+    let keys: Vec<_> = removed.iter().map(|(key, _)| *key).collect();
+    assert_eq!(keys, [0, 3, 8, 16, 17, 29]);
+    assert!(removed.iter().all(|(key, val)| *val == key.to_string()));
+    assert_eq!(slab.len(), 24);
+    assert_eq!(slab.iter().count(), 24);
+    assert_eq!(slab.iter().next_back().unwrap().0, 28);
+    assert_eq!(slab.insert("a".to_string()).0, 0);
+    assert_eq!(slab.insert("b".to_string()).0, 3);
+
+    let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+        slab.remove_many([1, 2, 1]);
+    }));
+    assert!(result.is_err());
+    assert!(slab.contains(1) && slab.contains(2));
+    assert!(slab.remove_many(std::iter::empty()).is_empty());
+}
+
+#[test]
+fn freeze_and_thaw() {
+    use std::thread;
+
+    let mut slab = Slab::<String, 16>::with_chunk_size();
+    let keys = slab.insert_many((0..100).map(|i| i.to_string()));
+    let frozen = slab.freeze();
+    assert!(frozen.is_unique());
+
+    let sums: Vec<usize> = thread::scope(|s| {
+        let handles: Vec<_> = (0..4)
+            .map(|_| {
+                let frozen = frozen.clone();
+                s.spawn(move || {
+                    frozen
+                        .values()
+                        .map(|val| val.parse::<usize>().unwrap())
+                        .sum()
+                })
+            })
+            .collect();
+        handles.into_iter().map(|h| h.join().unwrap()).collect()
+    });
+    assert_eq!(sums, [4950; 4]);
+    assert_eq!(frozen.len(), 100);
+    assert_eq!(&*frozen.get_pin(7).unwrap(), "7");
+
+    let other = frozen.clone();
+    assert!(!frozen.is_unique());
+    let frozen = frozen.thaw().unwrap_err();
+    drop(other);
+    let mut slab = frozen.thaw().unwrap();
+    for key in keys {
+        slab.remove(key);
+    }
+    assert!(slab.is_empty());
+}
+
+#[test]
+fn handles() {
+    let mut slab = Slab::<String, 4>::with_chunk_size();
+    let keys = slab.insert_many((0..8).map(|i| i.to_string()));
+    let handles: Vec<Handle> = keys.map(|key| slab.handle(key).unwrap()).collect();
+    assert!(slab.handle(100).is_none());
+
+    slab.remove(1);
+    assert!(!slab.contains_handle(handles[1]));
+    assert!(slab.contains_handle(handles[2]));
+    slab.insert("reused".to_string());
+    assert!(slab.upgrade(handles[1]).is_none());
+    slab.upgrade_pin_mut(handles[2])
+        .unwrap()
+        .get_mut()
+        .push('!');
+    assert_eq!(&*slab.upgrade(handles[2]).unwrap(), "2!");
+
+    // Freeing a chunk and allocating it again doesn't revive handles.
+    for key in 4..8 {
+        slab.remove(key);
+    }
+    slab.free_unused();
+    slab.insert_many((4..8).map(|i| i.to_string()));
+    assert!(handles[4..]
+        .iter()
+        .all(|&handle| slab.upgrade(handle).is_none()));
+
+    // Neither does compacting.
+    slab.remove(0);
+    slab.compact(|_, _, _| {});
+    assert!(slab.upgrade(handles[3]).is_some());
+    assert!(slab.upgrade(handles[0]).is_none());
+
+    let clone = slab.clone();
+    assert!(clone.contains_handle(handles[3]));
+    slab.clear();
+    assert!(!slab.contains_handle(handles[3]));
+}
+
+#[test]
+fn slab_box() {
+    use std::marker::PhantomPinned;
+
+    let mut slab = Slab::new();
+    let mut boxed = slab.insert_owned(vec![1]);
+    let key = boxed.key();
+    boxed.push(2);
+    assert_eq!(*boxed, [1, 2]);
+    drop(boxed);
+    assert!(!slab.contains(key));
+
+    let boxed = slab.insert_owned(vec![3]);
+    assert_eq!(SlabBox::into_inner(boxed), [3]);
+    assert!(slab.is_empty());
+
+    let boxed = slab.insert_owned(vec![4]);
+    let key = SlabBox::leak(boxed);
+    assert_eq!(slab[key], [4]);
+
+    let mut slab = Slab::new();
+    let mut boxed = slab.insert_owned((5, PhantomPinned));
+    unsafe { boxed.as_pin_mut().get_unchecked_mut().0 += 1 };
+    assert_eq!(boxed.0, 6);
+}
+
+#[test]
+fn pool() {
+    use std::marker::PhantomPinned;
+    use std::pin::Pin;
+
+    #[derive(Default)]
+    struct Parser {
+        state: Vec<u8>,
+        resets: usize,
+        _pinned: PhantomPinned,
+    }
+
+    impl Reset for Parser {
+        fn reset(self: Pin<&mut Self>) {
+            let this = unsafe { self.get_unchecked_mut() };
+            this.state.clear();
+            this.resets += 1;
+        }
+    }
+
+    let mut pool: Pool<Parser> = Pool::new();
+    let (a, mut parser) = pool.acquire();
+    unsafe { parser.as_mut().get_unchecked_mut().state.push(1) };
+    let ptr = &*parser as *const Parser;
+    let (b, _) = pool.acquire_with(Parser::default);
+    assert_eq!(pool.len(), 2);
+
+    pool.release(a);
+    assert!(!pool.contains(a));
+    assert!(pool.get(a).is_none());
+    assert_eq!(pool.idle_count(), 1);
+
+    let (key, parser) = pool.acquire_with(|| panic!("should reuse"));
+    assert_eq!(key, a);
+    assert_eq!(&*parser as *const Parser, ptr);
+    assert!(parser.state.is_empty());
+    assert_eq!(parser.resets, 1);
+
+    pool.release(a);
+    pool.release(b);
+    pool.clear_idle();
+    assert!(pool.is_empty());
+    assert_eq!(pool.idle_count(), 0);
+    let (_, parser) = pool.acquire();
+    assert_eq!(parser.resets, 0);
+}
+
+#[test]
+fn secondary_map() {
+    let mut slab: Slab<u32, 8> = Slab::with_growth_policy(GrowthPolicy::Geometric { first: 2 });
+    let mut meta: SecondaryMap<&str, 8> =
+        SecondaryMap::with_growth_policy(GrowthPolicy::Geometric { first: 2 });
+
+    let keys: Vec<usize> = (0..20).map(|i| slab.insert(i).0).collect();
+    for &key in keys.iter().step_by(3) {
+        meta.insert(key, "third");
+    }
+    assert_eq!(meta.len(), 7);
+    assert_eq!(meta.get(keys[3]), Some(&"third"));
+    assert_eq!(meta.get(keys[4]), None);
+    let stored: Vec<usize> = meta.iter().map(|(key, _)| key).collect();
+    assert_eq!(stored, [0, 3, 6, 9, 12, 15, 18]);
+
+    meta[keys[3]] = "changed";
+    assert_eq!(meta.remove(keys[3]), Some("changed"));
+    assert_eq!(meta.remove(keys[3]), None);
+    assert_eq!(meta.len(), 6);
+
+    let handle = slab.handle(keys[5]).unwrap();
+    assert_eq!(meta.insert_handle(handle, "five"), None);
+    *meta.get_handle_mut(handle).unwrap() = "FIVE";
+    slab.remove(keys[5]);
+    let (reused, _) = slab.insert(55);
+    let new_handle = slab.handle(reused).unwrap();
+    assert_eq!(meta.get_handle(handle), Some(&"FIVE"));
+    assert_eq!(meta.get_handle(new_handle), None);
+    assert_eq!(meta.insert_handle(new_handle, "new"), Some("FIVE"));
+    assert_eq!(meta.get_handle(handle), None);
+
+    meta.clear();
+    assert!(meta.is_empty());
+    assert!(!meta.contains(0));
+}
+
+#[test]
+fn arena() {
+    use std::rc::Rc;
+
+    let counter = Rc::new(());
+    let mut arena: Arena<Rc<()>, 4> = Arena::new_in(Global);
+    assert!(arena.is_empty());
+
+    let first = arena.alloc(counter.clone());
+    let first = &*first as *const Rc<()>;
+    let values = arena.alloc_iter((0..3).map(|_| counter.clone()));
+    assert_eq!(values.len(), 3);
+    assert_eq!(arena.capacity(), 4);
+    let big = arena.alloc_iter((0..6).map(|_| counter.clone()));
+    assert_eq!(big.len(), 6);
+    assert!(arena.alloc_iter(std::iter::empty()).is_empty());
+    assert_eq!(arena.len(), 10);
+    assert_eq!(Rc::strong_count(&counter), 11);
+
+    arena.clear();
+    assert_eq!(Rc::strong_count(&counter), 1);
+    assert!(arena.is_empty());
+    assert_eq!(arena.capacity(), 10);
+
+    // The chunks are reused from the front.
+    let reused = arena.alloc(counter.clone());
+    assert_eq!(&*reused as *const Rc<()>, first);
+    drop(arena);
+    assert_eq!(Rc::strong_count(&counter), 1);
+}
+
+#[test]
+fn linked_slab() {
+    let mut list = LinkedSlab::new();
+    let keys: Vec<usize> = (0..5).map(|i| list.push_back(i)).collect();
+    let first = list.push_front(-1);
+    let middle = list.insert_before(keys[3], 25);
+    list.insert_after(keys[4], 5);
+
+    let order = |list: &LinkedSlab<i32>| list.iter_in_order().map(|(_, v)| *v).collect::<Vec<_>>();
+    assert_eq!(order(&list), [-1, 0, 1, 2, 25, 3, 4, 5]);
+    let reversed: Vec<i32> = list.iter_in_order().rev().map(|(_, v)| *v).collect();
+    assert_eq!(reversed, [5, 4, 3, 25, 2, 1, 0, -1]);
+    let mut iter = list.iter_in_order();
+    assert_eq!(iter.next().map(|(k, _)| k), Some(first));
+    assert_eq!(iter.next_back().map(|(_, v)| *v), Some(5));
+    assert_eq!(iter.len(), 6);
+
+    assert_eq!(list.prev(middle), Some(keys[2]));
+    assert_eq!(list.next(middle), Some(keys[3]));
+    list.move_to_front(middle);
+    list.move_to_back(first);
+    assert_eq!(list.unlink(keys[2]), Some(2));
+    assert_eq!(list.unlink(keys[2]), None);
+    assert_eq!(order(&list), [25, 0, 1, 3, 4, 5, -1]);
+
+    assert_eq!(list.pop_front(), Some(25));
+    assert_eq!(list.pop_back(), Some(-1));
+    assert_eq!(list.front(), Some(keys[0]));
+    assert_eq!(list.len(), 5);
+
+    // Reused keys get linked afresh.
+    let reused = list.push_front(100);
+    assert!(reused == keys[2] || reused == first || reused == middle);
+    assert_eq!(order(&list), [100, 0, 1, 3, 4, 5]);
+
+    list.clear();
+    assert!(list.is_empty());
+    assert_eq!(list.front(), None);
+    assert_eq!(list.iter_in_order().next(), None);
+}
+
+#[test]
+fn dyn_slab() {
+    use std::cell::Cell;
+    use std::rc::Rc;
+
+    trait Shape {
+        fn area(&self) -> u64;
+    }
+
+    struct Square(u64, Rc<Cell<usize>>);
+    struct Big([u64; 16], Rc<Cell<usize>>);
+
+    impl Shape for Square {
+        fn area(&self) -> u64 {
+            self.0 * self.0
+        }
+    }
+
+    impl Shape for Big {
+        fn area(&self) -> u64 {
+            self.0.iter().sum()
+        }
+    }
+
+    impl Drop for Square {
+        fn drop(&mut self) {
+            self.1.set(self.1.get() + 1);
+        }
+    }
+
+    impl Drop for Big {
+        fn drop(&mut self) {
+            self.1.set(self.1.get() + 1);
+        }
+    }
+
+    let drops = Rc::new(Cell::new(0));
+    let mut slab: DynSlab<dyn Shape> = DynSlab::new();
+    let square = dyn_insert!(slab, Square(3, drops.clone()));
+    let big = dyn_insert!(slab, Big([1; 16], drops.clone()));
+    let keys: Vec<usize> = (0..100)
+        .map(|i| dyn_insert!(slab, Square(i, drops.clone())))
+        .collect();
+
+    assert_eq!(slab.is_inline(square), Some(true));
+    assert_eq!(slab.is_inline(big), Some(false));
+    assert_eq!(slab.get(square).unwrap().area(), 9);
+    assert_eq!(slab.get(big).unwrap().area(), 16);
+    assert_eq!(slab.get_pin_mut(keys[10]).unwrap().area(), 100);
+    assert_eq!(slab.iter().count(), 102);
+
+    assert!(slab.remove(square));
+    assert!(!slab.remove(square));
+    assert!(slab.remove(big));
+    assert_eq!(drops.get(), 2);
+
+    // Reused entries hold new values.
+    let reused = dyn_insert!(slab, Square(5, drops.clone()));
+    assert_eq!(slab.get(reused).unwrap().area(), 25);
+
+    drop(slab);
+    assert_eq!(drops.get(), 103);
+}
+
+#[test]
+fn dense_slab() {
+    let mut slab = DenseSlab::new();
+    let keys: Vec<usize> = (0..10).map(|i| slab.insert(i)).collect();
+    assert_eq!(keys, (0..10).collect::<Vec<_>>());
+
+    assert_eq!(slab.remove(keys[2]), Some(2));
+    assert_eq!(slab.remove(keys[2]), None);
+    assert_eq!(slab.remove(keys[5]), Some(5));
+    assert_eq!(slab.len(), 8);
+    assert_eq!(slab.values(), [0, 1, 9, 3, 4, 8, 6, 7]);
+    assert_eq!(slab.keys(), [0, 1, 9, 3, 4, 8, 6, 7]);
+    for (key, val) in &slab {
+        assert_eq!(key, *val);
+    }
+
+    // Vacated keys are reused, most recent first.
+    assert_eq!(slab.insert(50), keys[5]);
+    assert_eq!(slab.insert(20), keys[2]);
+    assert_eq!(slab.insert(10), 10);
+    assert_eq!(slab[keys[5]], 50);
+
+    for (_, val) in slab.iter_mut() {
+        *val += 1;
+    }
+    slab.values_mut()[0] += 1;
+    assert_eq!(slab.get(0), Some(&2));
+    assert_eq!(slab.iter().next_back(), Some((10, &11)));
+    assert!(!slab.contains(11));
+
+    slab.clear();
+    assert!(slab.is_empty());
+    assert_eq!(slab.insert(0), 0);
+}
+
+#[test]
+fn compat_slab() {
+    use pinned_slab::compat;
+
+    let mut slab = compat::Slab::with_capacity(4);
+    let a = slab.insert(1);
+    let b = slab.insert(2);
+    let entry = slab.vacant_entry();
+    let c = entry.key();
+    *entry.insert(3) += 10;
+    assert_eq!(slab[c], 13);
+    assert_eq!(slab.key_of(&slab[b]), b);
+
+    for (_, val) in &mut slab {
+        *val *= 2;
+    }
+    slab[a] += 1;
+    {
+        let (x, y) = slab.get2_mut(a, b).unwrap();
+        std::mem::swap(x, y);
+    }
+    assert_eq!(slab.iter().map(|(_, v)| *v).collect::<Vec<_>>(), [4, 3, 26]);
+    assert!(slab.get2_mut(a, a).is_none());
+
+    assert_eq!(slab.try_remove(b), Some(3));
+    assert_eq!(slab.try_remove(b), None);
+    slab.retain(|_, val| *val > 10);
+    assert_eq!(slab.len(), 1);
+    assert_eq!(slab.drain().collect::<Vec<_>>(), [26]);
+    assert!(slab.is_empty());
+
+    let slab: compat::Slab<_> = vec![(3, "d"), (1, "b"), (3, "e")].into_iter().collect();
+    assert_eq!(slab.len(), 2);
+    assert_eq!(slab[3], "e");
+    let mut slab = slab.into_inner();
+    assert_eq!(slab.insert("a").0, 0);
+    assert_eq!(slab.insert("c").0, 2);
+}
+
+#[test]
+fn vec_conversions() {
+    let mut slab: Slab<String, 4> = Slab::from((0..10).map(|i| i.to_string()).collect::<Vec<_>>());
+    assert_eq!(slab.len(), 10);
+    assert_eq!(slab.keys().collect::<Vec<_>>(), (0..10).collect::<Vec<_>>());
+    assert_eq!(slab.capacity(), 12);
+
+    slab.remove(0);
+    slab.remove(9);
+    let vec = slab.into_vec();
+    assert_eq!(vec.len(), 9);
+    assert_eq!(vec[0], None);
+    assert_eq!(vec[8].as_deref(), Some("8"));
+
+    assert!(Slab::<i32>::new().into_vec().is_empty());
+}
+
+#[test]
+fn slab_equality() {
+    use std::collections::hash_map::DefaultHasher;
+    use std::hash::{Hash, Hasher};
+
+    fn hash<T: Hash>(val: &T) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        val.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    let mut a: Slab<String> = Slab::new();
+    let mut b: Slab<String, 4> = Slab::with_chunk_size();
+    for i in 0..10 {
+        a.insert(i.to_string());
+    }
+    b.insert_many((0..10).rev().map(|i| i.to_string()));
+    assert_ne!(a, b);
+
+    let mut b: Slab<String, 4> = Slab::with_chunk_size();
+    b.insert_many((0..12).map(|i| i.to_string()));
+    b.remove(11);
+    b.remove(10);
+    b.remove(5);
+    a.remove(5);
+    assert_eq!(a, b);
+    assert_eq!(b, a);
+
+    let mut c: Slab<String> = Slab::new();
+    c.insert_many((0..10).map(|i| i.to_string()));
+    c.remove(5);
+    c.insert("5".to_string());
+    c.remove(5);
+    assert_eq!(a, c);
+    assert_eq!(hash(&a), hash(&c));
+
+    let pairs: Vec<(usize, &str)> = vec![(0, "0"), (1, "1")];
+    let mut d: Slab<String> = Slab::new();
+    d.insert("0".to_string());
+    d.insert("1".to_string());
+    assert!(d == pairs[..]);
+    assert!(d != pairs[..1]);
+    assert_eq!(Slab::<i32>::new(), [(0, 0i32); 0]);
+}
+
+#[test]
+fn slab_debug() {
+    let mut slab: Slab<i32, 4> = Slab::with_chunk_size();
+    slab.insert_many(0..6);
+    for key in 0..4 {
+        slab.remove(key);
+    }
+    slab.free_unused();
+
+    assert_eq!(
+        format!("{:?}", slab),
+        "Slab { len: 2, capacity: 4, entries: {4: 4, 5: 5} }"
+    );
+    let verbose = format!("{:#?}", slab);
+    assert!(verbose.contains("next_key: 6,"), "{}", verbose);
+    assert!(
+        verbose.contains("None,\n        Some(\n            2/4,"),
+        "{}",
+        verbose
+    );
+    assert!(verbose.contains("4: 4,"), "{}", verbose);
+}
+
+#[test]
+fn report() {
+    let slab: Slab<u8> = Slab::new();
+    assert_eq!(
+        slab.report().to_string(),
+        "0 values, capacity 0 (0.0% vacant)\n0 of 0 chunks allocated, 0 bytes\n0 keys on the free list"
+    );
+
+    let mut slab: Slab<u8, 8> = Slab::with_chunk_size();
+    slab.insert_many(0..12);
+    slab.remove(3);
+    slab.remove(11);
+    let report = slab.report();
+    assert_eq!(report.free_list_len, 2);
+    assert_eq!(report.chunks, [Some((7, 8)), Some((3, 8))]);
+    assert_eq!(report.stats, slab.stats());
+    assert!(report
+        .to_string()
+        .ends_with("chunk    0 [######### ] 7/8\nchunk    1 [####      ] 3/8"));
+}
+
+#[test]
+fn invariants() {
+    for policy in [
+        KeyPolicy::Lifo,
+        KeyPolicy::LowestFirst,
+        KeyPolicy::Monotonic,
+    ] {
+        for growth in [GrowthPolicy::Fixed, GrowthPolicy::Geometric { first: 2 }] {
+            let mut slab: Slab<u32, 16> = Slab::with_growth_policy(growth);
+            slab.set_key_policy(policy);
+            slab.assert_invariants();
+
+            let mut state = 7u32;
+            for i in 0..2000 {
+                state = state.wrapping_mul(1_103_515_245).wrapping_add(12345);
+                let r = state >> 16;
+                match r % 10 {
+                    0..=4 => {
+                        slab.insert(i);
+                    }
+                    5..=7 => {
+                        if let Some(key) = slab.keys().nth(r as usize % (slab.len() + 1)) {
+                            slab.remove(key);
+                        }
+                    }
+                    8 => {
+                        slab.insert_many(i..i + r % 20);
+                    }
+                    _ => match r % 7 {
+                        0 => slab.free_unused(),
+                        1 => slab.shrink_to_fit(),
+                        2 => slab.extract_if(|key, _| key % 3 == 0).for_each(drop),
+                        3 => slab.compact(|_, _, _| {}),
+                        4 => slab.reserve(r as usize % 50),
+                        5 => slab.set_key_policy(policy),
+                        _ => slab.clear(),
+                    },
+                }
+                slab.assert_invariants();
+            }
+        }
+    }
+}
+
+#[test]
+fn static_slab() {
+    use std::cell::Cell;
+    use std::pin::pin;
+
+    static EMPTY: StaticSlab<u32, 8> = StaticSlab::new();
+    assert!(EMPTY.is_empty());
+    assert_eq!(EMPTY.iter().count(), 0);
+
+    struct Dropper<'a>(&'a Cell<usize>);
+
+    impl Drop for Dropper<'_> {
+        fn drop(&mut self) {
+            self.0.set(self.0.get() + 1);
+        }
+    }
+
+    let drops = Cell::new(0);
+    {
+        let mut slab = pin!(StaticSlab::<_, 3>::new());
+        for i in 0..3 {
+            assert_eq!(slab.as_mut().insert(Dropper(&drops)).ok().unwrap().0, i);
+        }
+        assert!(slab.is_full());
+        assert!(slab.as_mut().insert(Dropper(&drops)).is_err());
+        assert_eq!(drops.get(), 1);
+
+        drop(slab.as_mut().remove(2));
+        drop(slab.as_mut().remove(0));
+        assert_eq!(slab.iter().map(|(key, _)| key).collect::<Vec<_>>(), [1]);
+        assert!(slab.as_mut().get_pin_mut(0).is_none());
+
+        assert_eq!(slab.as_mut().insert(Dropper(&drops)).ok().unwrap().0, 0);
+        assert_eq!(slab.as_mut().insert(Dropper(&drops)).ok().unwrap().0, 2);
+        assert_eq!(drops.get(), 3);
+
+        slab.as_mut().clear();
+        assert_eq!(drops.get(), 6);
+        slab.as_mut().insert(Dropper(&drops)).ok().unwrap();
+    }
+    assert_eq!(drops.get(), 7);
+}
+
+#[test]
+fn leak() {
+    use std::pin::Pin;
+
+    struct Registered(&'static str);
+
+    impl Drop for Registered {
+        fn drop(&mut self) {
+            panic!("leaked value {} was dropped", self.0);
+        }
+    }
+
+    let mut slab: Slab<_, 2> = Slab::with_chunk_size();
+    slab.insert_many([Registered("a"), Registered("b"), Registered("c")]);
+    std::mem::forget(slab.remove(1));
+
+    let leaked: Vec<(usize, Pin<&'static Registered>)> = slab.leak().collect();
+    let names: Vec<_> = leaked.iter().map(|(key, val)| (*key, val.0)).collect();
+    assert_eq!(names, [(0, "a"), (2, "c")]);
+}
+
+#[test]
+fn raw_parts() {
+    let mut slab: Slab<String, 4> = Slab::with_growth_policy(GrowthPolicy::Geometric { first: 2 });
+    slab.insert_many((0..10).map(|i| i.to_string()));
+    slab.remove(8);
+    slab.remove(1);
+    slab.free_unused();
+    let addr = &slab[5] as *const String;
+
+    let parts = slab.into_raw_parts();
+    assert_eq!(parts.len, 8);
+    assert_eq!((parts.head, parts.tail), (0, 9));
+    let chunk = parts.chunks[0].as_ref().unwrap();
+    assert_eq!((chunk.size, chunk.len, chunk.occupied[0]), (2, 1, 0b01));
+    assert_eq!(
+        chunk.links[0],
+        RawLink {
+            prev: usize::MAX,
+            next: 2
+        }
+    );
+
+    // SAFETY: The parts come from a slab with the same chunk size.
+    let mut slab: Slab<String, 4> = unsafe { Slab::from_raw_parts(parts) };
+    assert_eq!(&slab[5] as *const String, addr);
+    assert_eq!(slab.insert("a".to_string()).0, 8);
+    assert_eq!(slab.insert("b".to_string()).0, 1);
+    assert_eq!(
+        slab.iter().map(|(_, val)| val.as_str()).collect::<String>(),
+        "0b234567a9"
+    );
+}
+
+#[test]
+fn as_ptr() {
+    let mut slab: Slab<[u8; 64], 4> = Slab::with_chunk_size();
+    let (a, _) = slab.insert([1; 64]);
+    let ptr = slab.as_mut_ptr(a).unwrap();
+
+    for i in 0..20 {
+        slab.insert([i; 64]);
+    }
+    slab.remove(3);
+    assert_eq!(slab.as_ptr(a), Some(ptr as *const _));
+    unsafe {
+        (*ptr)[0] = 42;
+        assert_eq!(*slab.as_ptr(a).unwrap(), {
+            let mut val = [1; 64];
+            val[0] = 42;
+            val
+        });
+    }
+
+    slab.remove(a);
+    assert_eq!(slab.as_ptr(a), None);
+    assert_eq!(slab.as_mut_ptr(a), None);
+}
+
+#[test]
+fn insert_with() {
+    let mut slab: Slab<String, 4> = Slab::with_chunk_size();
+    slab.insert_many(["a".to_string(), "b".to_string()]);
+    slab.remove(0);
+
+    let (key, val) = slab.insert_with(|entry| entry.write("c".to_string()));
+    assert_eq!((key, val.as_str()), (0, "c"));
+
+    let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+        slab.insert_with(|_| Box::leak(Box::new("d".to_string())));
+    }));
+    assert!(result.is_err());
+    assert_eq!(slab.len(), 2);
+    assert_eq!(slab.next_key(), 2);
+    slab.assert_invariants();
+
+    let mut buffers: Slab<[u32; 1024]> = Slab::new();
+    // SAFETY: Zero is a valid `u32`.
+    let (key, _) = unsafe { buffers.insert_zeroed() };
+    assert_eq!(buffers[key], [0; 1024]);
+}
+
+#[test]
+fn reserve_key() {
+    for policy in [
+        KeyPolicy::Lifo,
+        KeyPolicy::LowestFirst,
+        KeyPolicy::Monotonic,
+    ] {
+        let mut slab: Slab<u32, 4> = Slab::with_chunk_size();
+        slab.set_key_policy(policy);
+        slab.insert_many(0..6);
+        slab.remove(1);
+        slab.remove(2);
+
+        let reserved = slab.reserve_key();
+        let key = reserved.key();
+        assert!(slab.is_reserved(key));
+        assert!(!slab.contains(key));
+        assert!(slab.keys().all(|k| k != key));
+        assert!(slab.vacant_keys().all(|k| k != key));
+        let others: Vec<_> = (0..4).map(|i| slab.insert(10 + i).0).collect();
+        assert!(!others.contains(&key));
+        slab.assert_invariants();
+
+        // Neither freeing chunks nor compacting touches the reserved key.
+        for &other in &others {
+            slab.remove(other);
+        }
+        slab.insert_many(20..30);
+        for k in slab.keys().collect::<Vec<_>>() {
+            slab.remove(k);
+        }
+        slab.free_unused();
+        slab.compact(|_, new, _| assert_ne!(new, key));
+        assert!(slab.is_reserved(key));
+        slab.assert_invariants();
+
+        slab.fulfill(reserved, 42);
+        assert_eq!(slab[key], 42);
+        assert!(!slab.is_reserved(key));
+        slab.assert_invariants();
+
+        let reserved = slab.reserve_key();
+        let key = reserved.key();
+        slab.cancel(reserved);
+        assert!(!slab.is_reserved(key));
+        slab.assert_invariants();
+        if policy != KeyPolicy::Monotonic {
+            assert_eq!(slab.next_key(), key);
+        }
+
+        let reserved = slab.reserve_key();
+        slab.clear();
+        slab.assert_invariants();
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            slab.fulfill(reserved, 0);
+        }));
+        assert!(result.is_err());
+    }
+}
+
+#[test]
+fn reserve_key_across_policies() {
+    let policies = [
+        KeyPolicy::Lifo,
+        KeyPolicy::LowestFirst,
+        KeyPolicy::Monotonic,
+    ];
+    for from in policies {
+        for to in policies {
+            let mut slab: Slab<u32, 4> = Slab::with_chunk_size();
+            slab.set_key_policy(from);
+            let reserved = slab.reserve_key();
+            let key = reserved.key();
+
+            slab.set_key_policy(to);
+            for i in 0..6 {
+                assert_ne!(slab.insert(i).0, key);
+            }
+            slab.fulfill(reserved, 42);
+            assert_eq!(slab[key], 42);
+            slab.assert_invariants();
+        }
+    }
+}
+
+#[test]
+fn take_and_replace() {
+    let mut slab = Slab::new();
+    let (a, _) = slab.insert("a".to_string());
+    let (b, _) = slab.insert("b".to_string());
+    let handle = slab.handle(a).unwrap();
+    let ptr = slab.as_ptr(a).unwrap();
+
+    assert_eq!(slab.replace(a, "c".to_string()).as_deref(), Ok("a"));
+    assert_eq!(slab.as_ptr(a), Some(ptr));
+    assert_eq!(slab[a], "c");
+    assert!(!slab.contains_handle(handle));
+
+    assert_eq!(slab.take(b).as_deref(), Some("b"));
+    assert_eq!(slab.take(b), None);
+    assert_eq!(slab.replace(b, "d".to_string()), Err("d".to_string()));
+    assert_eq!(slab.len(), 1);
+    slab.assert_invariants();
+}
+
+#[test]
+fn map() {
+    let mut slab: Slab<String, 4> = Slab::with_chunk_size();
+    slab.insert_many((0..10).map(|i| i.to_string()));
+    slab.remove(5);
+    slab.remove(2);
+    slab.remove(6);
+    slab.free_unused();
+    let reserved = slab.reserve_key();
+    let next = slab.next_key();
+
+    let mut mapped = slab.map(|key, val| (key, val.parse::<usize>().unwrap()));
+    assert!(mapped.iter().all(|(key, &(k, v))| key == k && k == v));
+    assert_eq!(mapped.len(), 7);
+    assert_eq!(mapped.next_key(), next);
+    mapped.fulfill(reserved, (0, 0));
+    mapped.assert_invariants();
+
+    let dropped = std::rc::Rc::new(());
+    let mut slab = Slab::new();
+    slab.insert_many((0..4).map(|_| dropped.clone()));
+    let result = slab.try_map(|key, val| if key == 2 { Err(key) } else { Ok(val) });
+    assert_eq!(result.unwrap_err(), 2);
+    assert_eq!(std::rc::Rc::strong_count(&dropped), 1);
+}
+
+#[test]
+fn split_off_and_merge() {
+    let mut slab: Slab<usize, 4> = Slab::with_chunk_size();
+    slab.insert_many(0..14);
+    slab.remove(5);
+    slab.remove(9);
+    let reserved = slab.reserve_key();
+    assert_eq!(reserved.key(), 9);
+    let ptrs: Vec<_> = (8..14).map(|key| slab.as_ptr(key)).collect();
+
+    let mut upper = slab.split_off(8);
+    assert_eq!(slab.len(), 7);
+    assert_eq!(upper.len(), 5);
+    assert_eq!(
+        (8..14).map(|key| upper.as_ptr(key)).collect::<Vec<_>>(),
+        ptrs
+    );
+    assert_eq!(upper.iter().next_back(), Some((13, &13)));
+    slab.assert_invariants();
+    upper.assert_invariants();
+    upper.fulfill(reserved, 9);
+
+    // The upper chunks are free again in the lower slab, so merging back
+    // keeps every key.
+    let mut remapped = Vec::new();
+    slab.merge(upper, |old, new| remapped.push((old, new)));
+    assert!(remapped.iter().all(|(old, new)| old == new));
+    assert_eq!(remapped.len(), 6);
+    assert!(slab.iter().all(|(key, &val)| key == val));
+    slab.assert_invariants();
+
+    // Taken places move the chunk, and a geometric policy's small chunks are
+    // inserted value by value.
+    let mut a: Slab<usize, 4> = Slab::with_growth_policy(GrowthPolicy::Geometric { first: 2 });
+    let mut b: Slab<usize, 4> = Slab::with_growth_policy(GrowthPolicy::Geometric { first: 2 });
+    a.insert_many(0..6);
+    b.insert_many(100..110);
+    let addr = b.as_ptr(5).unwrap();
+    let mut remapped = Vec::new();
+    a.merge(b, |old, new| remapped.push((old, new)));
+    assert_eq!(a.len(), 16);
+    assert!(remapped.contains(&(5, 13)));
+    assert_eq!(a.as_ptr(13), Some(addr as *const _));
+    for (old, new) in remapped {
+        assert_eq!(a[new], 100 + old);
+    }
+    a.assert_invariants();
+}
+
+#[test]
+fn cow_slab() {
+    let mut slab: CowSlab<Vec<u32>, 4> = CowSlab::new();
+    for i in 0..10 {
+        slab.insert(vec![i]);
+    }
+    let untouched = slab.get(9).unwrap() as *const Vec<u32>;
+
+    let snapshot = slab.clone();
+    assert_eq!(slab.shared_chunks(), 3);
+    slab.get_pin_mut(1).unwrap().push(100);
+    slab.remove(2);
+    assert_eq!(slab.insert(vec![42]), 2);
+    assert_eq!(slab.shared_chunks(), 2);
+
+    assert_eq!(
+        snapshot.iter().map(|(_, v)| v[0]).collect::<Vec<_>>(),
+        (0..10).collect::<Vec<_>>()
+    );
+    assert_eq!(slab[1], [1, 100]);
+    assert_eq!(slab[2], [42]);
+    assert_eq!(slab.len(), 10);
+    assert!(std::ptr::eq(slab.get(9).unwrap(), untouched));
+    assert!(std::ptr::eq(snapshot.get(9).unwrap(), untouched));
+
+    drop(snapshot);
+    assert_eq!(slab.shared_chunks(), 0);
+    assert_eq!(slab.insert(vec![10]), 10);
+}
+
+#[test]
+fn cow_slab_snapshot() {
+    let mut slab: CowSlab<String, 4> = CowSlab::new();
+    for i in 0..10 {
+        slab.insert(i.to_string());
+    }
+
+    let snapshot = slab.snapshot();
+    assert_eq!(slab.shared_chunks(), 3);
+    for (key, _) in snapshot.iter().filter(|(key, _)| key % 3 == 0) {
+        slab.remove(key);
+    }
+    slab.insert("new".to_string());
+    *slab.get_pin_mut(1).unwrap() = "changed".to_string();
+
+    let frozen: Vec<_> = (0..10).map(|i| i.to_string()).collect();
+    let handle = {
+        let snapshot = snapshot.clone();
+        std::thread::spawn(move || {
+            snapshot
+                .iter()
+                .map(|(_, val)| val.clone())
+                .collect::<Vec<_>>()
+        })
+    };
+    assert_eq!(handle.join().unwrap(), frozen);
+    assert_eq!(snapshot.len(), 10);
+    assert_eq!(snapshot[1], "1");
+    assert!(snapshot.contains(3));
+    assert!(!slab.contains(6));
+    assert_eq!(slab[3], "new");
+    assert_eq!(slab.len(), 7);
+
+    drop(snapshot);
+    assert_eq!(slab.shared_chunks(), 0);
+}
+
+#[test]
+fn clone_from() {
+    let mut source: Slab<String, 4> = Slab::with_chunk_size();
+    source.insert_many((0..10).map(|i| i.to_string()));
+    source.remove(3);
+
+    let mut copy: Slab<String, 4> = Slab::with_chunk_size();
+    copy.insert_many((0..6).map(|i| format!("old {}", i)));
+    let reused = copy.as_ptr(1).unwrap();
+
+    copy.clone_from(&source);
+    assert_eq!(copy.as_ptr(1), Some(reused));
+    assert_eq!(
+        copy.iter().collect::<Vec<_>>(),
+        source.iter().collect::<Vec<_>>()
+    );
+    assert_eq!(copy.next_key(), 3);
+    copy.assert_invariants();
+
+    source.clear_and_free();
+    copy.clone_from(&source);
+    assert!(copy.is_empty());
+    assert_eq!(copy.capacity(), 0);
+    copy.assert_invariants();
+}
+
+#[test]
+fn tags() {
+    let mut slab: Slab<u32, 4> = Slab::with_chunk_size();
+    slab.insert_many(0..6);
+    for key in 0..6 {
+        slab.set_tag(key, key as u32 * 10);
+    }
+    slab.remove(2);
+    assert_eq!(slab.tag(2), None);
+    assert_eq!(slab.set_tag(2, 1), None);
+
+    let copy = slab.clone();
+    assert_eq!(copy.tag(5), Some(50));
+    let mapped = copy.map(|_, val| val + 1);
+    assert_eq!(mapped.tag(4), Some(40));
+
+    assert_eq!(slab.insert(2).0, 2);
+    assert_eq!(slab.tag(2), Some(0));
+    slab.replace(3, 7).unwrap();
+    assert_eq!(slab.tag(3), Some(0));
+    assert_eq!(slab.tag(1), Some(10));
+}
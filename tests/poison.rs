@@ -0,0 +1,21 @@
+#![cfg(feature = "poison")]
+
+use pinned_slab::*;
+
+#[test]
+#[should_panic(expected = "invalid key: slot 1 freed at tests/poison.rs:")]
+fn stale_key() {
+    let mut slab = Slab::new();
+    slab.insert_many(["a", "b", "c"]);
+
+    slab.remove(1);
+    assert_eq!(slab.freed_at(1).unwrap().file(), "tests/poison.rs");
+    assert_eq!(slab.get(1), None);
+
+    slab.clear();
+    let freed = slab.freed_at(0).unwrap();
+    assert_eq!(freed.line(), line!() - 2);
+    assert_eq!(slab.freed_at(1).unwrap().line(), freed.line() - 4);
+
+    let _ = slab[1];
+}
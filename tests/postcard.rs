@@ -0,0 +1,19 @@
+#![cfg(feature = "postcard")]
+
+use pinned_slab::*;
+
+#[test]
+fn postcard_round_trip() {
+    let mut slab = Slab::new();
+    slab.insert_many(0..300u32);
+    slab.retain(|key, _| key % 3 != 1);
+
+    let mut buf = [0; 1024];
+    let bytes = slab.to_postcard(&mut buf).unwrap();
+    let mut copy = Slab::<u32>::from_postcard(bytes).unwrap();
+    assert!(copy.iter().eq(slab.iter()));
+    assert_eq!(copy.insert(1).0, 1);
+
+    assert!(slab.to_postcard(&mut [0; 8]).is_err());
+    assert!(Slab::<u32>::from_postcard(&bytes[..bytes.len() - 1]).is_err());
+}
@@ -0,0 +1,14 @@
+#![cfg(feature = "proptest")]
+
+use pinned_slab::*;
+use proptest::prelude::*;
+
+proptest! {
+    #[test]
+    fn generated_slabs_are_consistent(slab in slab_strategy(any::<u16>(), 0..200)) {
+        prop_assert_eq!(slab.iter().count(), slab.len());
+        for key in slab.vacant_keys().take(10) {
+            prop_assert!(!slab.contains(key));
+        }
+    }
+}
@@ -0,0 +1,79 @@
+#![cfg(feature = "rayon")]
+
+use pinned_slab::*;
+use rayon::prelude::*;
+
+#[test]
+fn par_iter() {
+    let mut slab = Slab::new();
+
+    for i in 0..DEFAULT_CHUNK_SIZE * 4 {
+        slab.insert(i);
+    }
+    slab.retain(|key, _| key % 3 == 0);
+
+    let mut keys: Vec<_> = slab
+        .par_iter()
+        .map(|(key, val)| {
+            assert_eq!(key, *val);
+            key
+        })
+        .collect();
+    keys.sort();
+    assert_eq!(keys, slab.keys().collect::<Vec<_>>());
+
+    let count = (&slab).into_par_iter().count();
+    assert_eq!(count, slab.len());
+}
+
+#[test]
+fn par_iter_pin_mut() {
+    let mut slab = Slab::new();
+
+    for i in 0..DEFAULT_CHUNK_SIZE * 4 {
+        slab.insert(i);
+    }
+    slab.remove(DEFAULT_CHUNK_SIZE);
+
+    slab.par_iter_pin_mut().for_each(|(_, mut val)| *val += 1);
+    slab.par_iter_mut().for_each(|(_, mut val)| *val += 1);
+
+    assert!(slab.iter().all(|(key, val)| key + 2 == *val));
+}
+
+#[test]
+fn par_retain() {
+    let mut slab = Slab::new();
+
+    for i in 0..DEFAULT_CHUNK_SIZE * 4 {
+        slab.insert(i);
+    }
+
+    slab.par_retain(|key, mut val| {
+        *val += 1;
+        key % 5 == 0
+    });
+
+    assert_eq!(slab.len(), (DEFAULT_CHUNK_SIZE * 4).div_ceil(5));
+    assert!(slab
+        .iter()
+        .all(|(key, val)| key % 5 == 0 && key + 1 == *val));
+
+    // The free list is still consistent
+    let key = slab.insert(0).0;
+    assert_eq!(slab[key], 0);
+}
+
+#[test]
+fn par_extend() {
+    let mut slab = Slab::new();
+    slab.insert(usize::MAX);
+
+    let keys = slab.par_extend((0..DEFAULT_CHUNK_SIZE * 3).into_par_iter());
+
+    assert_eq!(slab.len(), DEFAULT_CHUNK_SIZE * 3 + 1);
+    assert_eq!(slab.capacity(), DEFAULT_CHUNK_SIZE * 4);
+    for (i, key) in keys.into_iter().enumerate() {
+        assert_eq!(slab[key], i);
+    }
+}
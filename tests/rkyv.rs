@@ -0,0 +1,38 @@
+#![cfg(feature = "rkyv")]
+
+use pinned_slab::*;
+use rkyv::{Deserialize, Infallible};
+
+#[test]
+fn rkyv_round_trip() {
+    let mut slab: Slab<u32, 16> = Slab::with_chunk_size();
+    slab.insert_many(0..40);
+    for key in (0..40).filter(|key| key % 3 == 0) {
+        slab.remove(key);
+    }
+
+    let bytes = rkyv::to_bytes::<_, 256>(&slab).unwrap();
+    let archived = rkyv::check_archived_root::<Slab<u32, 16>>(&bytes).unwrap();
+    assert_eq!(archived.len(), slab.len());
+    assert!(archived.iter().eq(slab.iter()));
+    assert!(!archived.contains(39));
+    assert_eq!(archived.get(38), Some(&38));
+
+    let mut copy: Slab<u32> = archived.deserialize(&mut Infallible).unwrap();
+    assert!(copy.iter().eq(slab.iter()));
+    // The vacant keys are reused in ascending order, before any new key.
+    for key in (0..40).step_by(3) {
+        assert_eq!(copy.insert(key as u32).0, key);
+    }
+    assert_eq!(copy.insert(40).0, 40);
+}
+
+#[test]
+fn rkyv_rejects_huge_keys() {
+    let mut slab: Slab<u32> = Slab::new();
+    slab.insert_many(0..1 << 20);
+    slab.retain(|key, _| key == (1 << 20) - 1);
+
+    let bytes = rkyv::to_bytes::<_, 256>(&slab).unwrap();
+    assert!(rkyv::check_archived_root::<Slab<u32>>(&bytes).is_err());
+}
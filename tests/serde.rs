@@ -0,0 +1,39 @@
+#![cfg(feature = "serde")]
+
+use pinned_slab::*;
+
+#[test]
+fn serde_round_trip() {
+    let mut slab = Slab::new();
+    slab.insert_many(0..10);
+    slab.remove(3);
+    slab.remove(7);
+
+    let json = serde_json::to_string(&slab).unwrap();
+    assert_eq!(json, r#"{"0":0,"1":1,"2":2,"4":4,"5":5,"6":6,"8":8,"9":9}"#);
+
+    let mut copy: Slab<i32> = serde_json::from_str(&json).unwrap();
+    assert_eq!(copy.len(), 8);
+    assert!(copy.iter().eq(slab.iter()));
+    assert_eq!(copy.insert(3).0, 3);
+    assert_eq!(copy.insert(7).0, 7);
+    assert_eq!(copy.insert(10).0, 10);
+
+    let sparse: Slab<String> = serde_json::from_str(r#"{"5000":"a","2":"b"}"#).unwrap();
+    assert_eq!(sparse.keys().collect::<Vec<_>>(), [2, 5000]);
+    assert_eq!(sparse[5000], "a");
+
+    assert!(serde_json::from_str::<Slab<i32>>(r#"{"1":1,"1":2}"#).is_err());
+}
+
+#[test]
+fn serde_rejects_huge_keys() {
+    let max = format!(r#"{{"{}":1}}"#, usize::MAX);
+    assert!(serde_json::from_str::<Slab<i32>>(&max).is_err());
+
+    let huge = format!(r#"{{"0":0,"{}":1}}"#, usize::MAX / 2);
+    assert!(serde_json::from_str::<Slab<i32>>(&huge).is_err());
+
+    let far = format!(r#"{{"{}":1}}"#, 1000 * DEFAULT_CHUNK_SIZE);
+    assert!(serde_json::from_str::<Slab<i32>>(&far).is_err());
+}
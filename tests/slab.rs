@@ -0,0 +1,23 @@
+#![cfg(feature = "slab")]
+
+use pinned_slab::*;
+
+#[test]
+fn slab_conversions() {
+    let mut old = slab::Slab::new();
+    for i in 0..10 {
+        old.insert(i);
+    }
+    old.remove(2);
+    old.remove(9);
+
+    let mut slab: Slab<i32, 4> = Slab::from(old);
+    assert_eq!(slab.len(), 8);
+    assert_eq!(slab.keys().collect::<Vec<_>>(), [0, 1, 3, 4, 5, 6, 7, 8]);
+    assert_eq!(slab.insert(20).0, 2);
+
+    let old = slab.into_unpinned();
+    assert_eq!(old.len(), 9);
+    assert_eq!(old[2], 20);
+    assert_eq!(old.iter().map(|(key, _)| key).max(), Some(8));
+}
@@ -0,0 +1,37 @@
+#![cfg(feature = "stats")]
+
+use pinned_slab::*;
+
+#[test]
+fn metrics() {
+    let mut slab: Slab<u32, 4> = Slab::with_chunk_size();
+    slab.insert_many(0..6);
+    let (key, _) = slab.insert(6);
+    slab.remove(key);
+    slab.remove(0);
+    slab.insert(7);
+
+    let metrics = slab.metrics();
+    assert_eq!(metrics.inserts, 8);
+    assert_eq!(metrics.removes, 2);
+    assert_eq!(metrics.reuses, 1);
+    assert_eq!(metrics.chunk_allocations, 2);
+    assert_eq!(metrics.peak_len, 7);
+
+    // Moving values doesn't count as inserts or removes, and allocating
+    // capacity counts the chunks.
+    slab.compact(|_, _, _| {});
+    slab.reserve(8);
+    let metrics = slab.metrics();
+    assert_eq!((metrics.inserts, metrics.removes), (8, 2));
+    assert_eq!(metrics.chunk_allocations, 4);
+
+    slab.clear();
+    assert_eq!(slab.metrics().removes, 8);
+    assert_eq!(slab.metrics().peak_len, 7);
+
+    slab.reset_metrics();
+    assert_eq!(slab.metrics(), SlabMetrics::default());
+    slab.insert(0);
+    assert_eq!(slab.metrics().peak_len, 1);
+}
@@ -0,0 +1,445 @@
+#![cfg(feature = "std")]
+
+use pinned_slab::*;
+
+#[test]
+fn sharded_slab() {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::thread;
+
+    let slab = ShardedSlab::with_shards(3);
+    assert_eq!(slab.shard_count(), 3);
+
+    let keys: Vec<Vec<usize>> = thread::scope(|s| {
+        let handles: Vec<_> = (0..8)
+            .map(|t| {
+                let slab = &slab;
+                s.spawn(move || {
+                    let keys: Vec<_> = (0..500).map(|i| slab.insert(t * 1000 + i)).collect();
+                    for &key in keys.iter().step_by(2) {
+                        assert!(slab.remove(key).is_some());
+                    }
+                    keys
+                })
+            })
+            .collect();
+        handles.into_iter().map(|h| h.join().unwrap()).collect()
+    });
+
+    assert_eq!(slab.len(), 8 * 250);
+    for (t, keys) in keys.iter().enumerate() {
+        // The removed keys may have been reused by other threads.
+        for (i, &key) in keys.iter().enumerate().skip(1).step_by(2) {
+            assert_eq!(*slab.get(key).unwrap(), t * 1000 + i);
+        }
+    }
+    slab.clear();
+    assert_eq!(slab.remove(keys[0][1]), None);
+
+    let slab = ShardedSlab::with_shards(2);
+    let key = slab.insert(AtomicUsize::new(1));
+    {
+        let a = slab.get(key).unwrap();
+        let b = slab.get(key).unwrap();
+        a.fetch_add(1, Ordering::Relaxed);
+        assert_eq!(b.as_pin().load(Ordering::Relaxed), 2);
+    }
+    let mut value = slab.get_pin_mut(key).unwrap();
+    *value.as_pin_mut().get_mut().get_mut() += 1;
+    *value = AtomicUsize::new(10);
+    drop(value);
+    assert_eq!(slab.remove(key).unwrap().into_inner(), 10);
+    assert!(slab.is_empty());
+}
+
+#[test]
+fn atomic_slab() {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::thread;
+
+    struct Counted<'a>(usize, &'a AtomicUsize);
+
+    impl Drop for Counted<'_> {
+        fn drop(&mut self) {
+            self.1.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    let dropped = AtomicUsize::new(0);
+    let slab = AtomicSlab::new();
+
+    thread::scope(|s| {
+        for t in 0..8 {
+            let (slab, dropped) = (&slab, &dropped);
+            s.spawn(move || {
+                let mut keys = Vec::new();
+                for i in 0..2000 {
+                    let key = slab.insert(Counted(t * 10000 + i, dropped));
+                    let value = slab.get(key).unwrap();
+                    assert_eq!(value.0, t * 10000 + i);
+                    assert_eq!(value.key(), key);
+                    if i % 3 == 0 {
+                        assert!(slab.remove(key));
+                        assert!(!slab.remove(key));
+                        // The value is still alive through `value`.
+                        assert_eq!(value.clone().0, t * 10000 + i);
+                    } else {
+                        keys.push(key);
+                    }
+                }
+                for key in keys {
+                    assert!(slab.contains(key));
+                }
+            });
+        }
+    });
+
+    assert_eq!(slab.len(), 8 * 1333);
+    assert_eq!(dropped.load(Ordering::Relaxed), 8 * 667);
+    assert!(slab.get(usize::MAX).is_none());
+    drop(slab);
+    assert_eq!(dropped.load(Ordering::Relaxed), 8 * 2000);
+}
+
+#[test]
+fn locked_slab() {
+    use std::marker::PhantomPinned;
+    use std::sync::Arc;
+    use std::thread;
+
+    let mut slab = LockedSlab::new();
+    let keys: Vec<usize> = (0..8).map(|_| slab.insert(Vec::new())).collect();
+    let slab = Arc::new(slab);
+
+    thread::scope(|s| {
+        for t in 0..8 {
+            let (slab, keys) = (slab.clone(), &keys);
+            s.spawn(move || {
+                for &key in keys {
+                    slab.write(key).unwrap().push(t);
+                }
+            });
+        }
+    });
+
+    for &key in &keys {
+        let mut values = slab.read(key).unwrap().clone();
+        values.sort();
+        assert_eq!(values, (0..8).collect::<Vec<_>>());
+    }
+    assert!(slab.read(100).is_none());
+    assert!(slab.write(100).is_none());
+
+    let mut slab = Arc::try_unwrap(slab).unwrap();
+    assert_eq!(slab.remove(keys[0]).len(), 8);
+    assert_eq!(slab.len(), 7);
+    assert!(!slab.contains(keys[0]) && slab.contains(keys[1]));
+    assert_eq!(slab.keys().collect::<Vec<_>>(), keys[1..]);
+
+    let mut slab = LockedSlab::new();
+    let key = slab.insert((0, PhantomPinned));
+    slab.write(key).unwrap().as_pin_mut();
+    assert_eq!(slab.read(key).unwrap().0, 0);
+}
+
+#[test]
+fn slab_arc() {
+    use std::sync::Arc;
+    use std::thread;
+
+    let slab = Arc::new(AtomicSlab::new());
+    let handles: Vec<_> = (0..100).map(|i| slab.insert_arc(i)).collect();
+    let weak: Vec<_> = handles.iter().map(SlabArc::downgrade).collect();
+    assert_eq!(slab.len(), 100);
+    assert!(!slab.contains(SlabArc::key(&handles[0])));
+    assert!(!slab.remove(SlabArc::key(&handles[0])));
+
+    thread::scope(|s| {
+        for chunk in handles.chunks(10) {
+            let chunk: Vec<_> = chunk.to_vec();
+            s.spawn(move || {
+                for handle in &chunk {
+                    assert_eq!(SlabArc::strong_count(handle), 2);
+                    assert_eq!(**handle, SlabArc::key(handle));
+                }
+            });
+        }
+    });
+
+    assert!(SlabArc::ptr_eq(&weak[3].upgrade().unwrap(), &handles[3]));
+    drop(handles);
+    assert!(slab.is_empty());
+    assert!(weak.iter().all(|weak| weak.upgrade().is_none()));
+
+    let again = slab.insert_arc(1000);
+    assert!(weak.iter().all(|weak| weak.upgrade().is_none()));
+    assert_eq!(*SlabArc::as_pin(&again), 1000);
+}
+
+#[test]
+fn local_slabs() {
+    use std::thread;
+
+    let registry = GlobalRegistry::new();
+    let mut a = registry.local();
+    let mut b = registry.local();
+    assert_eq!(registry.shard_count(), 2);
+
+    let key_a = a.insert(1);
+    let key_b = b.insert(2);
+    assert_ne!(key_a, key_b);
+    assert_eq!(GlobalRegistry::<i32>::shard_of(key_b), b.shard());
+    assert_eq!(a.get(key_b), None);
+    assert_eq!(b.get(key_b), Some(&2));
+
+    // Removing a key of another shard queues it.
+    assert_eq!(a.remove(key_b), None);
+    assert!(b.contains(key_b));
+    let key = b.insert(3);
+    assert_eq!(key, key_b);
+    assert_eq!(b.get(key), Some(&3));
+    assert_eq!(b.len(), 1);
+
+    let remote = registry.clone();
+    thread::spawn(move || {
+        assert!(remote.remove(key));
+    })
+    .join()
+    .unwrap();
+    assert_eq!(b.process_remote_frees(), 1);
+    assert!(b.is_empty());
+
+    assert_eq!(a.remove(key_a), Some(1));
+    drop(a);
+    assert!(!registry.remove(key_a));
+}
+
+#[test]
+fn waker_slab() {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+    use std::task::{Wake, Waker};
+    use std::thread;
+
+    struct Counter(AtomicUsize);
+
+    impl Wake for Counter {
+        fn wake(self: Arc<Self>) {
+            self.0.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    let counter = Arc::new(Counter(AtomicUsize::new(0)));
+    let waker = Waker::from(counter.clone());
+    let wakers = Arc::new(WakerSlab::new());
+
+    let a = wakers.register(&waker);
+    let b = wakers.register(&waker);
+    assert_eq!(wakers.len(), 2);
+
+    let remote = wakers.clone();
+    thread::spawn(move || assert!(remote.wake(a)))
+        .join()
+        .unwrap();
+    assert_eq!(counter.0.load(Ordering::Relaxed), 1);
+
+    // A woken key stays registered, but isn't woken again until updated.
+    assert!(!wakers.wake(a));
+    assert!(wakers.contains(a));
+    assert!(wakers.update(a, &waker));
+    assert_eq!(wakers.wake_all(), 2);
+    assert_eq!(counter.0.load(Ordering::Relaxed), 3);
+
+    assert!(wakers.unregister(b));
+    assert!(!wakers.update(b, &waker));
+    assert!(!wakers.wake(b));
+    assert_eq!(wakers.len(), 1);
+}
+
+#[test]
+fn futures_slab() {
+    use std::future::Future;
+    use std::marker::PhantomPinned;
+    use std::pin::Pin;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::{Arc, Mutex};
+    use std::task::{Context, Poll, Wake, Waker};
+
+    // Completes on its second poll, and counts how often it was polled.
+    struct Twice {
+        polls: Arc<AtomicUsize>,
+        waker: Arc<Mutex<Option<Waker>>>,
+        _pinned: PhantomPinned,
+    }
+
+    impl Future for Twice {
+        type Output = usize;
+
+        fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<usize> {
+            let polls = self.polls.fetch_add(1, Ordering::Relaxed) + 1;
+            if polls == 2 {
+                Poll::Ready(polls)
+            } else {
+                *self.waker.lock().unwrap() = Some(cx.waker().clone());
+                Poll::Pending
+            }
+        }
+    }
+
+    struct Counter(AtomicUsize);
+
+    impl Wake for Counter {
+        fn wake(self: Arc<Self>) {
+            self.0.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    let counter = Arc::new(Counter(AtomicUsize::new(0)));
+    let waker = Waker::from(counter.clone());
+    let mut cx = Context::from_waker(&waker);
+
+    let mut futures = FuturesSlab::new();
+    let twice = |polls: &Arc<AtomicUsize>, waker: &Arc<Mutex<Option<Waker>>>| Twice {
+        polls: polls.clone(),
+        waker: waker.clone(),
+        _pinned: PhantomPinned,
+    };
+    let (polls_a, waker_a) = (Arc::default(), Arc::default());
+    let (polls_b, waker_b) = (Arc::default(), Arc::default());
+    let a = futures.insert(twice(&polls_a, &waker_a));
+    let b = futures.insert(twice(&polls_b, &waker_b));
+
+    assert_eq!(futures.poll_next(&mut cx), Poll::Pending);
+    assert_eq!(polls_a.load(Ordering::Relaxed), 1);
+    assert_eq!(polls_b.load(Ordering::Relaxed), 1);
+
+    // Nothing was woken, so nothing is polled.
+    assert_eq!(futures.poll_next(&mut cx), Poll::Pending);
+    assert_eq!(polls_a.load(Ordering::Relaxed), 1);
+
+    waker_b.lock().unwrap().take().unwrap().wake();
+    assert_eq!(counter.0.load(Ordering::Relaxed), 1);
+    assert_eq!(futures.poll_next(&mut cx), Poll::Ready(Some((b, 2))));
+    assert_eq!(polls_a.load(Ordering::Relaxed), 1);
+    assert!(!futures.contains(b));
+
+    assert!(futures.get_pin_mut(a).is_some());
+    assert!(futures.cancel(a));
+    assert!(!futures.cancel(a));
+    waker_a.lock().unwrap().take().unwrap().wake();
+    assert_eq!(futures.poll_next(&mut cx), Poll::Ready(None));
+}
+
+#[test]
+fn async_slab() {
+    use std::future::Future;
+    use std::pin::pin;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+    use std::task::{Context, Poll, Wake, Waker};
+
+    struct Counter(AtomicUsize);
+
+    impl Wake for Counter {
+        fn wake(self: Arc<Self>) {
+            self.0.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    let counter = Arc::new(Counter(AtomicUsize::new(0)));
+    let waker = Waker::from(counter.clone());
+    let mut cx = Context::from_waker(&waker);
+
+    let slab = AsyncSlab::new(1);
+    let first = slab.try_insert(1).unwrap();
+
+    let mut second = pin!(slab.insert(2));
+    let mut third = Box::pin(slab.insert(3));
+    assert!(second.as_mut().poll(&mut cx).is_pending());
+    assert!(third.as_mut().poll(&mut cx).is_pending());
+    // Waiting inserts are served first.
+    slab.set_limit(2);
+    assert_eq!(counter.0.load(Ordering::Relaxed), 1);
+    assert_eq!(slab.try_insert(4), Err(4));
+    assert!(third.as_mut().poll(&mut cx).is_pending());
+
+    let second = match second.poll(&mut cx) {
+        Poll::Ready(key) => key,
+        Poll::Pending => panic!("second insert should be ready"),
+    };
+    assert_eq!(slab.len(), 2);
+
+    // A cancelled insert passes its slot on.
+    let mut fourth = Box::pin(slab.insert(4));
+    assert!(fourth.as_mut().poll(&mut cx).is_pending());
+    assert_eq!(slab.remove(first), Some(1));
+    assert_eq!(counter.0.load(Ordering::Relaxed), 2);
+    drop(third);
+    assert_eq!(counter.0.load(Ordering::Relaxed), 3);
+    let fourth = match fourth.as_mut().poll(&mut cx) {
+        Poll::Ready(key) => key,
+        Poll::Pending => panic!("fourth insert should be ready"),
+    };
+
+    *slab.get(second).unwrap() += 10;
+    assert_eq!(*slab.get(second).unwrap(), 12);
+    assert_eq!(slab.remove(fourth), Some(4));
+    assert_eq!(slab.remove(fourth), None);
+    assert_eq!(slab.len(), 1);
+}
+
+#[test]
+fn interner() {
+    let interner = Interner::new();
+    assert!(interner.is_empty());
+
+    let words: Vec<&str> = (0..2000)
+        .map(|i| interner.intern_str(&format!("word{}", i % 500)))
+        .collect();
+    assert_eq!(interner.len(), 500);
+    assert_eq!(words[3], "word3");
+    // Equal strings share the same copy.
+    assert!(std::ptr::eq(words[3], words[503]));
+
+    let long = "x".repeat(10_000);
+    let symbol = interner.intern(&long);
+    assert_eq!(symbol.index(), 500);
+    assert_eq!(interner.resolve(symbol), long);
+    assert_eq!(interner.get("word42").map(Symbol::index), Some(42));
+    assert_eq!(interner.get("missing"), None);
+    assert_eq!(interner.intern(""), interner.intern(""));
+    assert_eq!(interner.resolve(interner.intern("")), "");
+}
+
+#[test]
+fn any_slab() {
+    use std::marker::PhantomPinned;
+
+    let mut slab = AnySlab::new();
+    assert!(slab.is_empty());
+    assert!(slab.slab::<u8>().is_none());
+
+    let a = slab.insert(1u8);
+    let b = slab.insert(2u16);
+    let c = slab.insert((3u8, PhantomPinned));
+    let d = slab.insert(4u8);
+    assert_eq!(slab.len(), 4);
+    // Every type has its own keys.
+    assert_eq!((a.key(), b.key(), c.key(), d.key()), (0, 0, 0, 1));
+    assert_eq!(slab.slab::<u8>().unwrap().len(), 2);
+
+    assert_eq!(*slab.get(a).unwrap(), 1);
+    assert_eq!(*slab.get(b).unwrap(), 2);
+    assert_eq!(slab.get(c).unwrap().0, 3);
+    *slab.get_pin_mut(d).unwrap() += 1;
+    assert_eq!(*slab.get(d).unwrap(), 5);
+
+    assert_eq!(slab.remove(a), Some(1));
+    assert_eq!(slab.remove(a), None);
+    assert!(!slab.contains(a));
+    assert!(slab.contains(b));
+
+    slab.clear();
+    assert!(slab.is_empty());
+    assert!(slab.get(b).is_none());
+}
@@ -0,0 +1,88 @@
+#![cfg(feature = "tracing")]
+
+use pinned_slab::*;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::sync::{Arc, Mutex};
+use tracing::field::{Field, Visit};
+use tracing::span::{Attributes, Id, Record};
+use tracing::{Event, Level, Metadata, Subscriber};
+
+type Fields = BTreeMap<&'static str, String>;
+
+/// Records the level and fields of every event.
+#[derive(Clone, Default)]
+struct Recorder(Arc<Mutex<Vec<(Level, Fields)>>>);
+
+struct FieldVisitor(Fields);
+
+impl Visit for FieldVisitor {
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        self.0.insert(field.name(), format!("{:?}", value));
+    }
+
+    fn record_str(&mut self, field: &Field, value: &str) {
+        self.0.insert(field.name(), value.to_string());
+    }
+}
+
+impl Subscriber for Recorder {
+    fn enabled(&self, metadata: &Metadata<'_>) -> bool {
+        metadata.target() == "pinned_slab"
+    }
+
+    fn new_span(&self, _: &Attributes<'_>) -> Id {
+        Id::from_u64(1)
+    }
+
+    fn record(&self, _: &Id, _: &Record<'_>) {}
+
+    fn record_follows_from(&self, _: &Id, _: &Id) {}
+
+    fn event(&self, event: &Event<'_>) {
+        let mut visitor = FieldVisitor(Fields::new());
+        event.record(&mut visitor);
+        let level = *event.metadata().level();
+        self.0.lock().unwrap().push((level, visitor.0));
+    }
+
+    fn enter(&self, _: &Id) {}
+
+    fn exit(&self, _: &Id) {}
+}
+
+#[test]
+fn chunk_events() {
+    let recorder = Recorder::default();
+    tracing::subscriber::with_default(recorder.clone(), || {
+        let mut slab: Slab<u32, 4> = Slab::with_chunk_size().named("sessions");
+        slab.insert_many(0..6);
+        slab.clear_and_free();
+        assert!(slab.try_reserve(usize::MAX).is_err());
+    });
+
+    let events = recorder.0.lock().unwrap();
+    let summary: Vec<_> = events
+        .iter()
+        .map(|(level, fields)| {
+            assert_eq!(fields["slab"], "sessions");
+            (
+                *level,
+                fields["message"].as_str(),
+                fields.get("chunk").map(String::as_str),
+            )
+        })
+        .collect();
+    assert_eq!(
+        summary,
+        [
+            (Level::DEBUG, "chunk allocated", Some("0")),
+            (Level::DEBUG, "chunk allocated", Some("1")),
+            (Level::DEBUG, "chunk freed", Some("0")),
+            (Level::DEBUG, "chunk freed", Some("1")),
+            (Level::WARN, "slab capacity limit reached", None),
+        ]
+    );
+    assert_eq!(events[0].1["size"], "4");
+    assert_eq!(events[4].1["reason"], "capacity overflow");
+}
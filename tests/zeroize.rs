@@ -0,0 +1,40 @@
+#![cfg(feature = "zeroize")]
+
+use pinned_slab::*;
+use zeroize::Zeroize;
+
+#[test]
+fn zeroed_entries() {
+    let mut slab = Slab::new();
+    let (a, val) = slab.insert([0x42u8; 32]);
+    let a_ptr = val as *const [u8; 32];
+    let (_, val) = slab.insert([0x43u8; 32]);
+    let b_ptr = val as *const [u8; 32];
+
+    // SAFETY: The chunk stays allocated, and the vacated entries are zeroed
+    // rather than uninitialized.
+    slab.remove(a);
+    assert_eq!(unsafe { a_ptr.read_volatile() }, [0; 32]);
+    assert_eq!(unsafe { b_ptr.read_volatile() }, [0x43; 32]);
+    slab.clear();
+    assert_eq!(unsafe { b_ptr.read_volatile() }, [0; 32]);
+
+    slab.insert([0x44u8; 32]);
+    slab.zeroize();
+    assert!(slab.is_empty());
+    assert_eq!(unsafe { a_ptr.read_volatile() }, [0; 32]);
+}
+
+#[test]
+#[cfg(feature = "poison")]
+fn zeroed_entries_with_poison() {
+    let mut slab = Slab::new();
+    let (a, val) = slab.insert([0x42u8; 32]);
+    let a_ptr = val as *const [u8; 32];
+
+    // SAFETY: The chunk stays allocated, and the vacated entry is zeroed
+    // rather than uninitialized.
+    slab.remove(a);
+    assert_eq!(unsafe { a_ptr.read_volatile() }, [0; 32]);
+    assert_eq!(slab.freed_at(a).unwrap().line(), line!() - 2);
+}